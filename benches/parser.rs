@@ -0,0 +1,99 @@
+//! Compares the incremental `RequestParser` with `RespHandler::parse_request`, which
+//! restarts from the first byte on every read.
+//!
+//! Input arrives in fixed-size chunks, the way a connection reads it from the socket.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use hexagondb::network::limits::Limits;
+use hexagondb::network::parser::RequestParser;
+use hexagondb::network::resp::RespHandler;
+use std::hint::black_box;
+
+const READ_SIZE: usize = 16 * 1024;
+
+fn command(args: &[&[u8]]) -> Vec<u8> {
+    let mut frame = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        frame.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        frame.extend_from_slice(arg);
+        frame.extend_from_slice(b"\r\n");
+    }
+    frame
+}
+
+/// `count` pipelined `SET key:i value` commands
+fn pipeline(count: usize) -> Vec<u8> {
+    (0..count)
+        .flat_map(|i| command(&[b"SET", format!("key:{}", i).as_bytes(), b"value"]))
+        .collect()
+}
+
+fn resp_handler(input: &[u8]) -> usize {
+    let mut buffer = Vec::new();
+    let mut parsed = 0;
+    for chunk in input.chunks(READ_SIZE) {
+        buffer.extend_from_slice(chunk);
+        while let Ok(Some((request, len))) = RespHandler::parse_request(&buffer) {
+            black_box(request);
+            buffer.drain(0..len);
+            parsed += 1;
+        }
+    }
+    parsed
+}
+
+fn request_parser(input: &[u8], limits: &Limits) -> usize {
+    let mut parser = RequestParser::new();
+    let mut parsed = 0;
+    for chunk in input.chunks(READ_SIZE) {
+        parser.extend(chunk);
+        while let Ok(Some(request)) = parser.next_request(limits) {
+            black_box(request);
+            parsed += 1;
+        }
+    }
+    parsed
+}
+
+fn bench_pipeline(c: &mut Criterion) {
+    let limits = Limits::default();
+    let mut group = c.benchmark_group("pipeline");
+    for count in [16, 1024, 16 * 1024] {
+        let input = pipeline(count);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("resp_handler", count),
+            &input,
+            |b, input| b.iter(|| resp_handler(input)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("request_parser", count),
+            &input,
+            |b, input| b.iter(|| request_parser(input, &limits)),
+        );
+    }
+    group.finish();
+}
+
+fn bench_large_value(c: &mut Criterion) {
+    let limits = Limits::default();
+    let mut group = c.benchmark_group("large_value");
+    for size in [64 * 1024, 1024 * 1024, 8 * 1024 * 1024] {
+        let input = command(&[b"SET", b"key", &vec![b'x'; size]]);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("resp_handler", size),
+            &input,
+            |b, input| b.iter(|| resp_handler(input)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("request_parser", size),
+            &input,
+            |b, input| b.iter(|| request_parser(input, &limits)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_pipeline, bench_large_value);
+criterion_main!(benches);
@@ -0,0 +1,48 @@
+//! Feeds arbitrary bytes to `RequestParser` in one piece and in small chunks. The parser
+//! must not panic, and the chunk size must not change the requests it returns.
+
+#![no_main]
+
+use hexagondb::network::limits::Limits;
+use hexagondb::network::parser::RequestParser;
+use libfuzzer_sys::fuzz_target;
+
+/// Requests parsed until the input runs out or the first protocol error
+fn parse<'a>(chunks: impl Iterator<Item = &'a [u8]>, limits: &Limits) -> (Vec<Vec<Vec<u8>>>, bool) {
+    let mut parser = RequestParser::new();
+    let mut requests = Vec::new();
+    for chunk in chunks {
+        parser.extend(chunk);
+        loop {
+            match parser.next_request(limits) {
+                Ok(Some(request)) => {
+                    assert!(!request.is_empty());
+                    requests.push(request.args().map(<[u8]>::to_vec).collect());
+                }
+                Ok(None) => break,
+                Err(_) => return (requests, true),
+            }
+        }
+    }
+    (requests, false)
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&chunk, data)) = data.split_first() else {
+        return;
+    };
+    let limits = Limits {
+        max_bulk_len: 1024,
+        max_multibulk_len: 64,
+        ..Default::default()
+    };
+    let (whole, whole_failed) = parse(std::iter::once(data), &limits);
+    let (chunked, chunked_failed) = parse(data.chunks(chunk as usize % 16 + 1), &limits);
+    if whole_failed || chunked_failed {
+        // A line that is too long can be rejected before its newline arrives
+        let shorter = whole.len().min(chunked.len());
+        assert_eq!(whole[..shorter], chunked[..shorter]);
+    } else {
+        assert_eq!(whole, chunked);
+    }
+});
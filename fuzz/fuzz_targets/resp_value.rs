@@ -0,0 +1,21 @@
+//! Feeds arbitrary bytes to `parse_value`. Parsing must not panic, and a parsed value must
+//! come back unchanged after `serialize` and another parse.
+
+#![no_main]
+
+use hexagondb::network::parser::parse_value;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(Some((value, len))) = parse_value(data) else {
+        return;
+    };
+    assert!(len <= data.len());
+
+    let serialized = value.serialize();
+    let (again, again_len) = parse_value(serialized.as_bytes())
+        .expect("serialized value is valid RESP")
+        .expect("serialized value is complete");
+    assert_eq!(again, value);
+    assert_eq!(again_len, serialized.len());
+});
@@ -0,0 +1,68 @@
+//! ACL komut kategorileri. Kural olarak `+@kategori` / `-@kategori` yazılır.
+//!
+//! Veri tipi kategorileri ve `admin`/`dangerous`/`pubsub`/`connection` komut tablosundaki
+//! tanımdan gelir; `read` ve `write` ise komutun `write` bayrağından türetilir.
+
+use crate::commands::registry;
+
+/// Bilinen kategoriler (`@all` her komutu kapsar)
+pub const CATEGORIES: &[&str] = &[
+    "all",
+    "read",
+    "write",
+    "keyspace",
+    "string",
+    "list",
+    "hash",
+    "set",
+    "geo",
+    "stream",
+    "json",
+    "probabilistic",
+    "timeseries",
+    "search",
+    "pubsub",
+    "connection",
+    "admin",
+    "dangerous",
+];
+
+/// Okuma/yazma ayrımı yapılan veri kategorileri
+const DATA_CATEGORIES: &[&str] = &[
+    "keyspace",
+    "string",
+    "list",
+    "hash",
+    "set",
+    "geo",
+    "stream",
+    "json",
+    "probabilistic",
+    "timeseries",
+    "search",
+];
+
+pub fn is_category(name: &str) -> bool {
+    CATEGORIES.contains(&name)
+}
+
+/// Komutun kategorileri (`cmd` büyük harfle). `@all` listede yer almaz.
+pub fn command_categories(cmd: &str) -> Vec<&'static str> {
+    let Some(command) = registry::lookup(cmd) else {
+        return Vec::new();
+    };
+    let mut result = command.categories.to_vec();
+    if result.iter().any(|c| DATA_CATEGORIES.contains(c)) {
+        result.push(if command.is_write() { "write" } else { "read" });
+    }
+    result
+}
+
+/// Kategorideki komutlar (küçük harfle)
+pub fn category_commands(category: &str) -> Vec<String> {
+    registry::COMMANDS
+        .iter()
+        .filter(|command| category == "all" || command_categories(command.name).contains(&category))
+        .map(|command| command.name.to_lowercase())
+        .collect()
+}
@@ -0,0 +1,471 @@
+//! Kullanıcılar ve erişim kontrolü (ACL).
+//!
+//! Her bağlantı bir kullanıcı adına komut çalıştırır. Bağlantı açıldığında `default`
+//! kullanıcısı şifresizse istemci doğrudan onunla giriş yapmış sayılır; değilse `AUTH`
+//! gelene kadar hiçbir komut çalışmaz. Komut, anahtarları ve kanalları kullanıcının
+//! kurallarıyla dispatch'ten önce kontrol edilir; reddedilen istekler `ACL LOG`'a yazılır.
+
+pub mod category;
+pub mod user;
+
+use crate::cluster::keys::command_keys;
+use crate::network::resp::RespValue;
+use crate::observability::metrics::METRIC_ACL_DENIED_TOTAL;
+use metrics::counter;
+use parking_lot::{Mutex, RwLock};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use user::User;
+
+/// `ACL LOG`'da tutulan en fazla kayıt
+const LOG_MAX_LEN: usize = 128;
+/// Aynı ret bu süre içinde tekrar ederse yeni kayıt açılmaz, sayaç artar
+const LOG_MERGE_WINDOW: Duration = Duration::from_secs(60);
+
+pub const NOAUTH: &str = "NOAUTH Authentication required.";
+pub const WRONGPASS: &str = "WRONGPASS invalid username-password pair or user is disabled.";
+
+#[derive(Debug, Clone)]
+struct LogEntry {
+    id: u64,
+    count: u64,
+    /// command, key, channel ya da auth
+    reason: &'static str,
+    /// Reddedilen komut, anahtar ya da kanal
+    object: String,
+    username: String,
+    created: Instant,
+    created_ms: u64,
+    updated_ms: u64,
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+pub struct Acl {
+    users: RwLock<BTreeMap<String, User>>,
+    log: Mutex<VecDeque<LogEntry>>,
+    next_log_id: Mutex<u64>,
+    /// `ACL LOAD` ve `ACL SAVE`'in kullandığı dosya
+    file: Option<PathBuf>,
+}
+
+impl Acl {
+    pub fn new(file: Option<PathBuf>) -> Self {
+        let mut users = BTreeMap::new();
+        users.insert("default".to_string(), User::default_user());
+        Acl {
+            users: RwLock::new(users),
+            log: Mutex::new(VecDeque::new()),
+            next_log_id: Mutex::new(0),
+            file,
+        }
+    }
+
+    /// Kullanıcıları ACL dosyasından yükler. Dosyanın tamamı geçerli değilse mevcut
+    /// kullanıcılar değişmez.
+    pub fn open(file: PathBuf) -> Result<Self, String> {
+        let acl = Acl::new(Some(file));
+        acl.load()?;
+        Ok(acl)
+    }
+
+    fn load(&self) -> Result<(), String> {
+        let Some(path) = &self.file else {
+            return Err("this instance is not configured to use an ACL file".to_string());
+        };
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read ACL file {}: {}", path.display(), e))?;
+        let users = parse_file(&text)?;
+        *self.users.write() = users;
+        Ok(())
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.file else {
+            return Err("this instance is not configured to use an ACL file".to_string());
+        };
+        let mut text = String::new();
+        for user in self.users.read().values() {
+            text.push_str(&user.describe());
+            text.push('\n');
+        }
+        write_file(path, &text).map_err(|e| format!("failed to write ACL file: {}", e))
+    }
+
+    /// Yeni bağlantının otomatik giriş yaptığı kullanıcı: şifresiz ve açık `default`.
+    pub fn default_login(&self) -> Option<String> {
+        let users = self.users.read();
+        let user = users.get("default")?;
+        (user.enabled && user.nopass).then(|| user.name.clone())
+    }
+
+    /// Doğrulanmış istemci sertifikasının CN'i açık bir kullanıcıya karşılık geliyor mu?
+    /// Sertifika şifrenin yerini tutar.
+    pub fn certificate_login(&self, name: &str) -> bool {
+        self.users.read().get(name).is_some_and(|user| user.enabled)
+    }
+
+    /// `AUTH`: kullanıcı açık ve şifre doğruysa `Ok`.
+    pub fn authenticate(&self, username: &str, password: &str) -> Result<(), String> {
+        let ok = self
+            .users
+            .read()
+            .get(username)
+            .is_some_and(|user| user.enabled && user.check_password(password));
+        if ok {
+            return Ok(());
+        }
+        self.deny("auth", "AUTH", username);
+        Err(WRONGPASS.to_string())
+    }
+
+    /// Kullanıcının komutu (`cmd` büyük harfle) çalıştırma, anahtarlarına ve kanallarına
+    /// erişme yetkisi var mı?
+    pub fn check(&self, username: &str, cmd: &str, args: &[String]) -> Result<(), String> {
+        let users = self.users.read();
+        // Bağlantı açıkken silinen ya da kapatılan kullanıcı yeniden giriş yapmalıdır
+        let Some(user) = users.get(username).filter(|user| user.enabled) else {
+            return Err(NOAUTH.to_string());
+        };
+
+        if !user.can_run(cmd) {
+            let name = cmd.to_lowercase();
+            drop(users);
+            self.deny("command", &name, username);
+            return Err(format!(
+                "NOPERM User {} has no permissions to run the '{}' command",
+                username, name
+            ));
+        }
+
+        if let Some(key) = command_keys(cmd, args)
+            .into_iter()
+            .find(|key| !user.can_access_key(key))
+        {
+            let key = key.to_string();
+            drop(users);
+            self.deny("key", &key, username);
+            return Err("NOPERM No permissions to access a key".to_string());
+        }
+
+        let channels = match cmd {
+            "PUBLISH" => &args[..args.len().min(1)],
+            "SUBSCRIBE" => args,
+            _ => &[],
+        };
+        if let Some(channel) = channels
+            .iter()
+            .find(|channel| !user.can_access_channel(channel))
+        {
+            let channel = channel.clone();
+            drop(users);
+            self.deny("channel", &channel, username);
+            return Err("NOPERM No permissions to access a channel".to_string());
+        }
+        Ok(())
+    }
+
+    /// Reddi metriklere ve `ACL LOG`'a yazar.
+    fn deny(&self, reason: &'static str, object: &str, username: &str) {
+        counter!(METRIC_ACL_DENIED_TOTAL, "reason" => reason).increment(1);
+
+        let now = unix_ms();
+        let mut log = self.log.lock();
+        if let Some(pos) = log.iter().position(|entry| {
+            entry.reason == reason
+                && entry.object == object
+                && entry.username == username
+                && entry.created.elapsed() < LOG_MERGE_WINDOW
+        }) {
+            let mut entry = log.remove(pos).expect("position is valid");
+            entry.count += 1;
+            entry.updated_ms = now;
+            log.push_front(entry);
+            return;
+        }
+
+        let mut next_id = self.next_log_id.lock();
+        log.push_front(LogEntry {
+            id: *next_id,
+            count: 1,
+            reason,
+            object: object.to_string(),
+            username: username.to_string(),
+            created: Instant::now(),
+            created_ms: now,
+            updated_ms: now,
+        });
+        *next_id += 1;
+        log.truncate(LOG_MAX_LEN);
+    }
+
+    /// ACL komutu; `current` bağlantının kullanıcısıdır.
+    pub fn command(&self, current: &str, args: &[String]) -> RespValue {
+        match self.try_command(current, args) {
+            Ok(reply) => reply,
+            Err(e) => RespValue::Error(e),
+        }
+    }
+
+    fn try_command(&self, current: &str, args: &[String]) -> Result<RespValue, String> {
+        let Some(subcommand) = args.first().map(|s| s.to_uppercase()) else {
+            return Err("wrong number of arguments for 'ACL' command".to_string());
+        };
+        let bulk = |s: &str| RespValue::BulkString(Some(s.to_string()));
+        let ok = || RespValue::SimpleString("OK".to_string());
+        let arity = || format!("wrong number of arguments for 'ACL {}' command", subcommand);
+
+        match subcommand.as_str() {
+            "WHOAMI" => Ok(bulk(current)),
+            "USERS" => Ok(RespValue::Array(Some(
+                self.users.read().keys().map(|name| bulk(name)).collect(),
+            ))),
+            "LIST" => Ok(RespValue::Array(Some(
+                self.users
+                    .read()
+                    .values()
+                    .map(|user| bulk(&user.describe()))
+                    .collect(),
+            ))),
+            "SETUSER" => {
+                let name = args.get(1).ok_or_else(arity)?;
+                let mut users = self.users.write();
+                // Kurallar kopya üzerinde uygulanır; biri hatalıysa kullanıcı değişmez
+                let mut user = users.get(name).cloned().unwrap_or_else(|| User::new(name));
+                for rule in &args[2..] {
+                    user.apply_rule(rule)
+                        .map_err(|e| format!("error in ACL SETUSER modifier '{}': {}", rule, e))?;
+                }
+                users.insert(name.clone(), user);
+                Ok(ok())
+            }
+            "GETUSER" => {
+                let name = args.get(1).ok_or_else(arity)?;
+                let users = self.users.read();
+                let Some(user) = users.get(name) else {
+                    return Ok(RespValue::BulkString(None));
+                };
+                let mut flags = vec![bulk(if user.enabled { "on" } else { "off" })];
+                if user.nopass {
+                    flags.push(bulk("nopass"));
+                }
+                Ok(RespValue::Array(Some(vec![
+                    bulk("flags"),
+                    RespValue::Array(Some(flags)),
+                    bulk("passwords"),
+                    RespValue::Array(Some(user.passwords.iter().map(|h| bulk(h)).collect())),
+                    bulk("commands"),
+                    bulk(&user.command_rules()),
+                    bulk("keys"),
+                    bulk(&user.key_rules()),
+                    bulk("channels"),
+                    bulk(&user.channel_rules()),
+                ])))
+            }
+            "DELUSER" => {
+                if args.len() < 2 {
+                    return Err(arity());
+                }
+                if args[1..].iter().any(|name| name == "default") {
+                    return Err("the 'default' user cannot be removed".to_string());
+                }
+                let mut users = self.users.write();
+                let removed = args[1..]
+                    .iter()
+                    .filter(|name| users.remove(*name).is_some())
+                    .count();
+                Ok(RespValue::Integer(removed as i64))
+            }
+            "CAT" => match args.get(1) {
+                None => Ok(RespValue::Array(Some(
+                    category::CATEGORIES.iter().map(|c| bulk(c)).collect(),
+                ))),
+                Some(name) => {
+                    let name = name.to_lowercase();
+                    if !category::is_category(&name) {
+                        return Err(format!("unknown category '{}'", name));
+                    }
+                    Ok(RespValue::Array(Some(
+                        category::category_commands(&name)
+                            .iter()
+                            .map(|cmd| bulk(cmd))
+                            .collect(),
+                    )))
+                }
+            },
+            "LOG" => match args.get(1) {
+                Some(arg) if arg.eq_ignore_ascii_case("RESET") => {
+                    self.log.lock().clear();
+                    Ok(ok())
+                }
+                arg => {
+                    let count = match arg {
+                        Some(n) => n
+                            .parse::<usize>()
+                            .map_err(|_| "value is not an integer or out of range".to_string())?,
+                        None => 10,
+                    };
+                    let log = self.log.lock();
+                    Ok(RespValue::Array(Some(
+                        log.iter().take(count).map(log_entry_resp).collect(),
+                    )))
+                }
+            },
+            "LOAD" => {
+                self.load()?;
+                Ok(ok())
+            }
+            "SAVE" => {
+                self.save()?;
+                Ok(ok())
+            }
+            _ => Err(format!("unknown ACL subcommand '{}'", args[0])),
+        }
+    }
+}
+
+fn log_entry_resp(entry: &LogEntry) -> RespValue {
+    let bulk = |s: &str| RespValue::BulkString(Some(s.to_string()));
+    RespValue::Array(Some(vec![
+        bulk("count"),
+        RespValue::Integer(entry.count as i64),
+        bulk("reason"),
+        bulk(entry.reason),
+        bulk("context"),
+        bulk("toplevel"),
+        bulk("object"),
+        bulk(&entry.object),
+        bulk("username"),
+        bulk(&entry.username),
+        bulk("age-seconds"),
+        bulk(&format!("{:.3}", entry.created.elapsed().as_secs_f64())),
+        bulk("entry-id"),
+        RespValue::Integer(entry.id as i64),
+        bulk("timestamp-created"),
+        RespValue::Integer(entry.created_ms as i64),
+        bulk("timestamp-last-updated"),
+        RespValue::Integer(entry.updated_ms as i64),
+    ]))
+}
+
+/// ACL dosyası: her satırda `user <ad> <kurallar...>`; boş satırlar ve `#` ile başlayan
+/// satırlar atlanır. Dosyada `default` yoksa varsayılan hali kullanılır.
+fn parse_file(text: &str) -> Result<BTreeMap<String, User>, String> {
+    let mut users = BTreeMap::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |e: String| format!("ACL file line {}: {}", number + 1, e);
+        let mut words = line.split_whitespace();
+        let (Some("user"), Some(name)) = (words.next(), words.next()) else {
+            return Err(error("expected 'user <name> <rules...>'".to_string()));
+        };
+        if users.contains_key(name) {
+            return Err(error(format!("duplicate user '{}'", name)));
+        }
+        let mut user = User::new(name);
+        for rule in words {
+            user.apply_rule(rule)
+                .map_err(|e| error(format!("rule '{}': {}", rule, e)))?;
+        }
+        users.insert(name.to_string(), user);
+    }
+    users
+        .entry("default".to_string())
+        .or_insert_with(User::default_user);
+    Ok(users)
+}
+
+fn write_file(path: &Path, text: &str) -> std::io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, text)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_check_and_log() {
+        let acl = Acl::new(None);
+        acl.command(
+            "default",
+            &args(&["SETUSER", "alice", "on", ">pw", "~cache:*", "+@read"]),
+        );
+        assert!(acl.authenticate("alice", "pw").is_ok());
+        assert_eq!(acl.authenticate("alice", "no"), Err(WRONGPASS.to_string()));
+
+        assert!(acl.check("alice", "GET", &args(&["cache:1"])).is_ok());
+        assert!(acl
+            .check("alice", "GET", &args(&["user:1"]))
+            .unwrap_err()
+            .starts_with("NOPERM"));
+        assert!(acl
+            .check("alice", "SET", &args(&["cache:1", "v"]))
+            .unwrap_err()
+            .contains("'set'"));
+        assert!(acl.check("alice", "GET", &args(&["user:1"])).is_err());
+        assert_eq!(
+            acl.check("bob", "GET", &args(&["k"])),
+            Err(NOAUTH.to_string())
+        );
+
+        let log = acl.log.lock();
+        assert_eq!(log.len(), 3);
+        assert_eq!((log[0].reason, log[0].count), ("key", 2));
+        assert_eq!(log[1].reason, "command");
+        assert_eq!(log[2].reason, "auth");
+    }
+
+    #[test]
+    fn test_check_migrate_keys() {
+        let acl = Acl::new(None);
+        acl.command(
+            "default",
+            &args(&["SETUSER", "alice", "on", "nopass", "~cache:*", "+migrate"]),
+        );
+        // MIGRATE anahtarı kopyalayıp sildiği için anahtar kuralları ona da uygulanır
+        let migrate = |keys: &[&str]| {
+            let mut argv = args(&["127.0.0.1", "6380", keys[0], "0", "1000"]);
+            if keys.len() > 1 {
+                argv.push("KEYS".to_string());
+                argv.extend(args(&keys[1..]));
+            }
+            acl.check("alice", "MIGRATE", &argv)
+        };
+        assert!(migrate(&["cache:1"]).is_ok());
+        assert!(migrate(&["", "cache:1", "cache:2"]).is_ok());
+        assert!(migrate(&["user:1"]).is_err());
+        assert!(migrate(&["", "cache:1", "user:1"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_file() {
+        let users = parse_file(
+            "# users\nuser default on >admin ~* &* +@all\nuser reader on nopass ~* -@all +@read\n",
+        )
+        .unwrap();
+        assert_eq!(users.len(), 2);
+        assert!(!users["default"].nopass);
+        assert!(users["reader"].can_run("GET"));
+
+        let users = parse_file("user reader on nopass\n").unwrap();
+        assert!(users["default"].nopass);
+
+        assert!(parse_file("user a on\nuser a off\n").is_err());
+        assert!(parse_file("usr a on\n").unwrap_err().contains("line 1"));
+    }
+}
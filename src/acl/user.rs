@@ -0,0 +1,327 @@
+//! ACL kullanıcısı ve `ACL SETUSER` kuralları.
+
+use super::category;
+use sha2::{Digest, Sha256};
+
+/// Şifrenin SHA-256 özeti (hex). Şifreler yalnızca bu haliyle saklanır.
+pub fn hash_password(password: &str) -> String {
+    Sha256::digest(password.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// `*`, `?`, `[abc]`, `[^a-z]` ve `\` kaçışını destekleyen glob eşleştirme.
+pub fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| glob_match(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && glob_match(rest, &text[1..]),
+        Some((b'[', rest)) => {
+            let Some((&c, text_rest)) = text.split_first() else {
+                return false;
+            };
+            let (negate, rest) = match rest.split_first() {
+                Some((b'^', rest)) => (true, rest),
+                _ => (false, rest),
+            };
+            let Some(end) = rest.iter().position(|&b| b == b']') else {
+                return false;
+            };
+            let (class, rest) = (&rest[..end], &rest[end + 1..]);
+            let mut matched = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == b'-' {
+                    matched |= class[i] <= c && c <= class[i + 2];
+                    i += 3;
+                } else {
+                    matched |= class[i] == c;
+                    i += 1;
+                }
+            }
+            matched != negate && glob_match(rest, text_rest)
+        }
+        Some((b'\\', rest)) if !rest.is_empty() => {
+            text.first() == Some(&rest[0]) && glob_match(&rest[1..], &text[1..])
+        }
+        Some((&p, rest)) => text.first() == Some(&p) && glob_match(rest, &text[1..]),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct User {
+    pub name: String,
+    pub enabled: bool,
+    /// Şifre sorulmadan giriş yapılabilir
+    pub nopass: bool,
+    /// Şifrelerin SHA-256 özetleri
+    pub passwords: Vec<String>,
+    /// Sırayla uygulanan `+`/`-` komut kuralları; komuta uyan son kural geçerlidir.
+    /// Hedef `@kategori` ya da küçük harfle komut adıdır.
+    pub commands: Vec<(bool, String)>,
+    /// Erişilebilen anahtar desenleri (`~desen`)
+    pub keys: Vec<String>,
+    /// Erişilebilen kanal desenleri (`&desen`)
+    pub channels: Vec<String>,
+}
+
+impl User {
+    /// Hiçbir yetkisi olmayan, kapalı kullanıcı
+    pub fn new(name: &str) -> Self {
+        User {
+            name: name.to_string(),
+            enabled: false,
+            nopass: false,
+            passwords: Vec::new(),
+            commands: Vec::new(),
+            keys: Vec::new(),
+            channels: Vec::new(),
+        }
+    }
+
+    /// Her şeye izinli, şifresiz `default` kullanıcısı
+    pub fn default_user() -> Self {
+        let mut user = User::new("default");
+        for rule in ["on", "nopass", "~*", "&*", "+@all"] {
+            user.apply_rule(rule).expect("valid default rule");
+        }
+        user
+    }
+
+    /// Tek bir kuralı uygular (`on`, `>şifre`, `~desen`, `+@read`, `-flushdb` ...).
+    pub fn apply_rule(&mut self, rule: &str) -> Result<(), String> {
+        let lower = rule.to_lowercase();
+        match lower.as_str() {
+            "on" => self.enabled = true,
+            "off" => self.enabled = false,
+            "nopass" => {
+                self.nopass = true;
+                self.passwords.clear();
+            }
+            "resetpass" => {
+                self.nopass = false;
+                self.passwords.clear();
+            }
+            "allkeys" => self.keys = vec!["*".to_string()],
+            "resetkeys" => self.keys.clear(),
+            "allchannels" => self.channels = vec!["*".to_string()],
+            "resetchannels" => self.channels.clear(),
+            "allcommands" => self.commands = vec![(true, "@all".to_string())],
+            "nocommands" => self.commands.clear(),
+            "reset" => *self = User::new(&self.name),
+            _ => return self.apply_pattern_rule(rule, &lower),
+        }
+        Ok(())
+    }
+
+    fn apply_pattern_rule(&mut self, rule: &str, lower: &str) -> Result<(), String> {
+        let (prefix, value) = rule.split_at(rule.chars().next().map_or(0, char::len_utf8));
+        match prefix {
+            ">" => {
+                let hash = hash_password(value);
+                if !self.passwords.contains(&hash) {
+                    self.passwords.push(hash);
+                }
+                self.nopass = false;
+            }
+            "<" => {
+                let hash = hash_password(value);
+                self.passwords.retain(|h| *h != hash);
+            }
+            "#" | "!" => {
+                let hash = value.to_lowercase();
+                if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(
+                        "the password hash must be exactly 64 hexadecimal characters".to_string(),
+                    );
+                }
+                if prefix == "!" {
+                    self.passwords.retain(|h| *h != hash);
+                } else if !self.passwords.contains(&hash) {
+                    self.passwords.push(hash);
+                    self.nopass = false;
+                }
+            }
+            "~" => self.keys.push(value.to_string()),
+            "&" => self.channels.push(value.to_string()),
+            "+" | "-" => {
+                let target = &lower[1..];
+                if target.is_empty() {
+                    return Err("syntax error".to_string());
+                }
+                if let Some(name) = target.strip_prefix('@') {
+                    if !category::is_category(name) {
+                        return Err(format!("unknown category '{}'", name));
+                    }
+                    // +@all / -@all önceki kuralların hepsini geçersiz kılar
+                    if name == "all" {
+                        self.commands.clear();
+                    }
+                }
+                self.commands.push((prefix == "+", target.to_string()));
+            }
+            _ => return Err("syntax error".to_string()),
+        }
+        Ok(())
+    }
+
+    /// Kullanıcının şifresi doğru mu?
+    pub fn check_password(&self, password: &str) -> bool {
+        self.nopass || self.passwords.contains(&hash_password(password))
+    }
+
+    /// `cmd` büyük harfle verilir.
+    pub fn can_run(&self, cmd: &str) -> bool {
+        let name = cmd.to_lowercase();
+        let categories = category::command_categories(cmd);
+        self.commands
+            .iter()
+            .rev()
+            .find(|(_, target)| match target.strip_prefix('@') {
+                Some("all") => true,
+                Some(category) => categories.contains(&category),
+                None => *target == name,
+            })
+            .is_some_and(|(allowed, _)| *allowed)
+    }
+
+    pub fn can_access_key(&self, key: &str) -> bool {
+        self.keys
+            .iter()
+            .any(|pattern| glob_match(pattern.as_bytes(), key.as_bytes()))
+    }
+
+    pub fn can_access_channel(&self, channel: &str) -> bool {
+        self.channels
+            .iter()
+            .any(|pattern| glob_match(pattern.as_bytes(), channel.as_bytes()))
+    }
+
+    /// Komut kuralları, `ACL LIST` ve `ACL GETUSER` biçiminde
+    pub fn command_rules(&self) -> String {
+        if self.commands.is_empty() {
+            return "-@all".to_string();
+        }
+        self.commands
+            .iter()
+            .map(|(allowed, target)| format!("{}{}", if *allowed { '+' } else { '-' }, target))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn key_rules(&self) -> String {
+        patterns_rule('~', &self.keys)
+    }
+
+    pub fn channel_rules(&self) -> String {
+        patterns_rule('&', &self.channels)
+    }
+
+    /// Kullanıcıyı yeniden oluşturan kurallar; ACL dosyasına da bu satır yazılır.
+    pub fn describe(&self) -> String {
+        let mut parts = vec![
+            "user".to_string(),
+            self.name.clone(),
+            if self.enabled { "on" } else { "off" }.to_string(),
+        ];
+        if self.nopass {
+            parts.push("nopass".to_string());
+        }
+        parts.extend(self.passwords.iter().map(|hash| format!("#{}", hash)));
+        parts.push(self.key_rules());
+        parts.push(self.channel_rules());
+        parts.push(self.command_rules());
+        parts.retain(|part| !part.is_empty());
+        parts.join(" ")
+    }
+}
+
+fn patterns_rule(prefix: char, patterns: &[String]) -> String {
+    patterns
+        .iter()
+        .map(|pattern| format!("{}{}", prefix, pattern))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(rules: &[&str]) -> User {
+        let mut user = User::new("alice");
+        for rule in rules {
+            user.apply_rule(rule).unwrap();
+        }
+        user
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match(b"cache:*", b"cache:user:1"));
+        assert!(!glob_match(b"cache:*", b"session:1"));
+        assert!(glob_match(b"h?llo", b"hello"));
+        assert!(glob_match(b"h[ae]llo", b"hallo"));
+        assert!(!glob_match(b"h[^e]llo", b"hello"));
+        assert!(glob_match(b"key[0-9]", b"key7"));
+        assert!(glob_match(b"a\\*", b"a*"));
+        assert!(!glob_match(b"a\\*", b"ab"));
+    }
+
+    #[test]
+    fn test_command_rules() {
+        let alice = user(&["on", "+@all", "-@dangerous", "+info"]);
+        assert!(alice.can_run("GET"));
+        assert!(alice.can_run("INFO"));
+        assert!(!alice.can_run("KEYS"));
+        assert!(!alice.can_run("SAVE"));
+
+        let reader = user(&["+@read", "+ping"]);
+        assert!(reader.can_run("GET"));
+        assert!(reader.can_run("HGETALL"));
+        assert!(reader.can_run("PING"));
+        assert!(!reader.can_run("SET"));
+        assert!(!reader.can_run("ACL"));
+
+        assert!(User::new("bob").apply_rule("+@nope").is_err());
+        assert!(User::new("bob").apply_rule("%x").is_err());
+    }
+
+    #[test]
+    fn test_passwords_and_patterns() {
+        let mut alice = user(&["on", ">secret", "~cache:*", "&news.*"]);
+        assert!(alice.check_password("secret"));
+        assert!(!alice.check_password("wrong"));
+        assert!(alice.can_access_key("cache:1"));
+        assert!(!alice.can_access_key("user:1"));
+        assert!(alice.can_access_channel("news.sport"));
+        assert!(!alice.can_access_channel("chat"));
+        assert!(!alice.describe().contains("secret"));
+
+        alice.apply_rule("<secret").unwrap();
+        assert!(!alice.check_password("secret"));
+
+        let hash = hash_password("pw");
+        alice.apply_rule(&format!("#{}", hash)).unwrap();
+        assert!(alice.check_password("pw"));
+    }
+
+    #[test]
+    fn test_describe_round_trip() {
+        let alice = user(&[
+            "on",
+            ">pw",
+            "~cache:*",
+            "allchannels",
+            "-@all",
+            "+@read",
+            "-keys",
+        ]);
+        let mut copy = User::new("alice");
+        for rule in alice.describe().split(' ').skip(2) {
+            copy.apply_rule(rule).unwrap();
+        }
+        assert_eq!(copy, alice);
+    }
+}
@@ -0,0 +1,264 @@
+//! Değişiklik yakalama (CDC): yazma komutlarının değiştirdiği her anahtar için sıralı
+//! bir olay akışı. Olaylar AOF'a yazılan komutlardan üretilir; dış tüketiciler
+//! `CDC READ` ile son işledikleri offset'ten devam ederek okur.
+//!
+//! Akış bellekte tutulur ve en fazla `max_len` olay saklanır. Sunucu yeniden
+//! başladığında akış yeni bir kimlikle sıfırdan başlar; tüketici kimlik değiştiyse
+//! verinin tamamını yeniden okumalıdır.
+
+use crate::network::resp::RespValue;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Notify;
+
+/// Varsayılan olarak saklanan en fazla olay sayısı
+pub const DEFAULT_CDC_BACKLOG: usize = 100_000;
+
+/// Bir anahtarın değişikliği.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub offset: u64,
+    /// Değişikliği yapan komut (küçük harfle)
+    pub op: String,
+    pub key: String,
+    /// Anahtarın yeni değeri DUMP biçiminde (RESTORE ile yüklenebilir); silindiyse `None`
+    pub value: Option<String>,
+    /// Kalan yaşam süresi (ms); süresizse `None`
+    pub ttl_ms: Option<u64>,
+    /// Olayın oluştuğu an (unix ms)
+    pub time_ms: u64,
+}
+
+impl Event {
+    fn to_resp(&self) -> RespValue {
+        let bulk = |s: &str| RespValue::BulkString(Some(s.to_string()));
+        RespValue::Array(Some(vec![
+            RespValue::Integer(self.offset as i64),
+            RespValue::Array(Some(vec![
+                bulk("op"),
+                bulk(&self.op),
+                bulk("key"),
+                bulk(&self.key),
+                bulk("value"),
+                RespValue::BulkString(self.value.clone()),
+                bulk("ttl"),
+                RespValue::Integer(self.ttl_ms.map_or(-1, |ttl| ttl as i64)),
+                bulk("time"),
+                RespValue::Integer(self.time_ms as i64),
+            ])),
+        ]))
+    }
+}
+
+struct State {
+    /// Sıradaki olayın offset'i; offset'ler 1'den başlar
+    next_offset: u64,
+    events: VecDeque<Event>,
+}
+
+pub struct Cdc {
+    /// Akışın kimliği; her başlatmada değişir
+    id: String,
+    state: Mutex<State>,
+    max_len: usize,
+    /// Yeni olay eklenince bekleyen okumaları uyandırır
+    appended: Notify,
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+impl Cdc {
+    pub fn new(max_len: usize) -> Self {
+        Cdc {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            state: Mutex::new(State {
+                next_offset: 1,
+                events: VecDeque::new(),
+            }),
+            max_len: max_len.max(1),
+            appended: Notify::new(),
+        }
+    }
+
+    /// Olayı akışa ekler ve offset'ini döndürür.
+    pub fn append(&self, op: &str, key: &str, value: Option<String>, ttl_ms: Option<u64>) -> u64 {
+        let mut state = self.state.lock();
+        let offset = state.next_offset;
+        state.next_offset += 1;
+        state.events.push_back(Event {
+            offset,
+            op: op.to_string(),
+            key: key.to_string(),
+            value,
+            ttl_ms,
+            time_ms: unix_ms(),
+        });
+        if state.events.len() > self.max_len {
+            state.events.pop_front();
+        }
+        drop(state);
+        self.appended.notify_waiters();
+        offset
+    }
+
+    /// `after`'dan sonraki en fazla `count` olay. Aradaki olaylar artık saklanmıyorsa
+    /// tüketici değişiklik kaçırır; bu durumda hata döner.
+    pub fn read(&self, after: u64, count: usize) -> Result<Vec<Event>, String> {
+        let state = self.state.lock();
+        let first = state
+            .events
+            .front()
+            .map_or(state.next_offset, |event| event.offset);
+        if after > 0 && after + 1 < first {
+            return Err(format!(
+                "CDCTRIMMED offset {} is no longer retained, the oldest retained offset is {}",
+                after, first
+            ));
+        }
+        let skip = (after + 1).saturating_sub(first) as usize;
+        Ok(state
+            .events
+            .iter()
+            .skip(skip)
+            .take(count)
+            .cloned()
+            .collect())
+    }
+
+    /// `read` gibi; yeni olay yoksa `timeout` boyunca (`None` ise süresiz) bekler.
+    pub async fn read_blocking(
+        &self,
+        after: u64,
+        count: usize,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<Event>, String> {
+        let deadline = timeout.map(|t| tokio::time::Instant::now() + t);
+        loop {
+            let appended = self.appended.notified();
+            let events = self.read(after, count)?;
+            if !events.is_empty() {
+                return Ok(events);
+            }
+            match deadline {
+                Some(deadline) => {
+                    if tokio::time::timeout_at(deadline, appended).await.is_err() {
+                        return Ok(Vec::new());
+                    }
+                }
+                None => appended.await,
+            }
+        }
+    }
+
+    pub fn info(&self) -> String {
+        let state = self.state.lock();
+        let first = state
+            .events
+            .front()
+            .map_or(state.next_offset, |event| event.offset);
+        format!(
+            "# CDC\r\ncdc_id:{}\r\ncdc_first_offset:{}\r\ncdc_last_offset:{}\r\ncdc_events:{}\r\ncdc_backlog_size:{}\r\n",
+            self.id,
+            first,
+            state.next_offset - 1,
+            state.events.len(),
+            self.max_len
+        )
+    }
+
+    /// CDC komutu: `CDC READ id offset [COUNT count] [BLOCK ms]` ve `CDC INFO`.
+    pub async fn command(&self, args: &[String]) -> RespValue {
+        match self.try_command(args).await {
+            Ok(reply) => reply,
+            Err(e) => RespValue::Error(e),
+        }
+    }
+
+    async fn try_command(&self, args: &[String]) -> Result<RespValue, String> {
+        let Some(subcommand) = args.first().map(|s| s.to_uppercase()) else {
+            return Err("wrong number of arguments for 'CDC' command".to_string());
+        };
+        match subcommand.as_str() {
+            "INFO" => Ok(RespValue::BulkString(Some(self.info()))),
+            "READ" => {
+                let (Some(id), Some(offset)) = (args.get(1), args.get(2)) else {
+                    return Err("wrong number of arguments for 'CDC READ' command".to_string());
+                };
+                let not_integer = || "value is not an integer or out of range".to_string();
+                let offset = offset.parse::<u64>().map_err(|_| not_integer())?;
+                if id != "*" && *id != self.id {
+                    return Err(format!(
+                        "CDCRESET the change stream was restarted, the new stream id is {}",
+                        self.id
+                    ));
+                }
+
+                let mut count = 1000;
+                let mut block = None;
+                let mut i = 3;
+                while i < args.len() {
+                    let value = args.get(i + 1).ok_or_else(|| "syntax error".to_string())?;
+                    match args[i].to_uppercase().as_str() {
+                        "COUNT" => count = value.parse::<usize>().map_err(|_| not_integer())?,
+                        "BLOCK" => block = Some(value.parse::<u64>().map_err(|_| not_integer())?),
+                        _ => return Err("syntax error".to_string()),
+                    }
+                    i += 2;
+                }
+
+                let events = match block {
+                    // BLOCK 0 süresiz bekler
+                    Some(ms) => {
+                        let timeout = (ms > 0).then(|| Duration::from_millis(ms));
+                        self.read_blocking(offset, count, timeout).await?
+                    }
+                    None => self.read(offset, count)?,
+                };
+                Ok(RespValue::Array(Some(vec![
+                    RespValue::BulkString(Some(self.id.clone())),
+                    RespValue::Array(Some(events.iter().map(Event::to_resp).collect())),
+                ])))
+            }
+            _ => Err(format!("unknown CDC subcommand '{}'", args[0])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_resumes_after_offset() {
+        let cdc = Cdc::new(10);
+        assert!(cdc.read(0, 10).unwrap().is_empty());
+        for i in 0..3 {
+            cdc.append("set", &format!("k{}", i), Some("v".to_string()), None);
+        }
+        let offsets = |events: Vec<Event>| events.iter().map(|e| e.offset).collect::<Vec<_>>();
+        assert_eq!(offsets(cdc.read(0, 10).unwrap()), vec![1, 2, 3]);
+        assert_eq!(offsets(cdc.read(1, 10).unwrap()), vec![2, 3]);
+        assert_eq!(offsets(cdc.read(0, 2).unwrap()), vec![1, 2]);
+        assert!(cdc.read(3, 10).unwrap().is_empty());
+        assert!(cdc.read(7, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_trimmed_offsets_are_reported() {
+        let cdc = Cdc::new(2);
+        for _ in 0..5 {
+            cdc.append("del", "k", None, None);
+        }
+        // 4 ve 5 saklanıyor; 3'ü işlemiş tüketici kaldığı yerden devam edebilir
+        assert_eq!(cdc.read(3, 10).unwrap().len(), 2);
+        assert!(cdc.read(2, 10).unwrap_err().starts_with("CDCTRIMMED"));
+        // 0, saklanan en eski olaydan başlamak demektir
+        assert_eq!(cdc.read(0, 10).unwrap()[0].offset, 4);
+    }
+}
@@ -0,0 +1,1010 @@
+//! Bağlı istemcilerin kaydı ve `CLIENT` komutu. Her bağlantı kayıt olur ve bir kimlik
+//! alır; adres, ad, kütüphane bilgisi, son komut ve buffer boyutları burada tutulur.
+//! `CLIENT KILL` bağlantıyı kapatır, `CLIENT PAUSE` komutları bir süre bekletir.
+//! Çıkış buffer'ı sınıfının sınırını aşan bağlantı da kapatılır.
+
+pub mod resp3;
+pub mod tracking;
+
+use crate::network::limits::Limits;
+use crate::network::resp::RespValue;
+use parking_lot::{Mutex, RwLock};
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch, Notify};
+use tracing::warn;
+use tracking::{Table, TrackingOptions};
+
+/// Bağlantının uç noktaları.
+#[derive(Debug, Clone)]
+pub struct Peer {
+    /// İstemcinin IP adresi; Unix socket bağlantılarında boştur
+    pub ip: String,
+    /// `ip:port`; Unix socket bağlantılarında `yol:0`
+    pub addr: String,
+    /// Bağlantının kabul edildiği yerel adres
+    pub laddr: String,
+    pub unix_socket: bool,
+}
+
+impl Peer {
+    pub fn tcp(addr: SocketAddr, local: Option<SocketAddr>) -> Self {
+        Peer {
+            ip: addr.ip().to_string(),
+            addr: addr.to_string(),
+            laddr: local.map(|local| local.to_string()).unwrap_or_default(),
+            unix_socket: false,
+        }
+    }
+
+    pub fn unix(path: &str) -> Self {
+        Peer {
+            ip: String::new(),
+            addr: format!("{}:0", path),
+            laddr: format!("{}:0", path),
+            unix_socket: true,
+        }
+    }
+}
+
+/// Bağlantının türü; `CLIENT LIST TYPE` ve `CLIENT KILL TYPE` buna göre süzer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClientKind {
+    Normal,
+    PubSub,
+    /// PSYNC ile replikasyon akışına geçmiş bağlantı
+    Replica,
+}
+
+impl ClientKind {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "normal" => Ok(ClientKind::Normal),
+            "pubsub" => Ok(ClientKind::PubSub),
+            "replica" | "slave" => Ok(ClientKind::Replica),
+            _ => Err(format!("Unknown client type '{}'", name)),
+        }
+    }
+}
+
+struct ClientState {
+    name: String,
+    lib_name: String,
+    lib_ver: String,
+    /// Giriş yapılan kullanıcı; `AUTH` bekleniyorsa `None`
+    user: Option<String>,
+    kind: ClientKind,
+    no_evict: bool,
+    /// Son çalıştırılan (ya da çalışmakta olan) komut, küçük harfle
+    cmd: Option<String>,
+    commands: u64,
+    last_interaction: Instant,
+    /// Okunmuş ama henüz işlenmemiş baytlar ve buffer'da kalan boş yer
+    query_buffer: usize,
+    query_buffer_free: usize,
+    /// Gönderilmeyi bekleyen cevapların boyutu
+    output_buffer: usize,
+    /// Çıkış buffer'ı ne zamandan beri yumuşak sınırın üstünde
+    soft_limit_since: Option<Instant>,
+    /// `HELLO` ile seçilen protokol sürümü (2 ya da 3)
+    resp: u8,
+    /// `CLIENT TRACKING` kapalıysa `None`
+    tracking: Option<TrackingOptions>,
+    /// `CLIENT CACHING yes|no`; yalnızca sıradaki komut için geçerlidir
+    caching: Option<bool>,
+}
+
+/// Kayıtlı bir bağlantı.
+pub struct Client {
+    pub id: u64,
+    pub peer: Peer,
+    created: Instant,
+    state: Mutex<ClientState>,
+    killed: AtomicBool,
+    kill: Notify,
+    /// Bağlantıya cevaplar dışında yazılacak çerçeveler (geçersiz kılma mesajları)
+    pushes: mpsc::UnboundedSender<String>,
+    limits: Arc<RwLock<Limits>>,
+}
+
+impl Client {
+    pub fn set_user(&self, user: Option<String>) {
+        self.state.lock().user = user;
+    }
+
+    pub fn set_kind(&self, kind: ClientKind) {
+        self.state.lock().kind = kind;
+    }
+
+    pub fn set_name(&self, name: &str) -> Result<(), String> {
+        validate_name("Client names", name)?;
+        self.state.lock().name = name.to_string();
+        Ok(())
+    }
+
+    pub fn resp(&self) -> u8 {
+        self.state.lock().resp
+    }
+
+    pub fn set_resp(&self, resp: u8) {
+        self.state.lock().resp = resp;
+    }
+
+    /// Bağlantıya bir çerçeve gönderir; bağlantı kapanmışsa ya da çıkış sınırını aştıysa
+    /// çerçeve atılır.
+    pub fn push(&self, frame: String) {
+        if self.queue(frame.len()) {
+            let _ = self.pushes.send(frame);
+        }
+    }
+
+    pub fn limits(&self) -> Limits {
+        self.limits.read().clone()
+    }
+
+    /// Bağlantıya yazılacak `len` bayt kuyruğa girdi. Bekleyen çıktı bağlantının sınıfı
+    /// için belirlenen sınırı aşarsa bağlantı kapatılır ve `false` döner.
+    pub fn queue(&self, len: usize) -> bool {
+        if self.killed.load(Ordering::SeqCst) {
+            return false;
+        }
+        let mut state = self.state.lock();
+        state.output_buffer += len;
+        let limit = {
+            let limits = self.limits.read();
+            match state.kind {
+                ClientKind::Normal => limits.output.normal.clone(),
+                ClientKind::PubSub => limits.output.pubsub.clone(),
+                ClientKind::Replica => limits.output.replica.clone(),
+            }
+        };
+        let pending = state.output_buffer;
+        let Some(reason) = limit.check(pending, &mut state.soft_limit_since, Instant::now()) else {
+            return true;
+        };
+        drop(state);
+        warn!(
+            client_id = self.id,
+            addr = %self.peer.addr,
+            "Closing client: {}",
+            reason
+        );
+        self.kill();
+        false
+    }
+
+    /// Kuyruktaki `len` bayt bağlantıya yazıldı.
+    pub fn sent(&self, len: usize) {
+        let mut state = self.state.lock();
+        state.output_buffer = state.output_buffer.saturating_sub(len);
+    }
+
+    /// Komutun okuduğu anahtarlar varsayılan modda hatırlanmalı mı? `CLIENT CACHING`
+    /// bayrağı `CLIENT` dışındaki ilk komutta tüketilir.
+    pub fn tracks_command(&self, cmd: &str) -> bool {
+        let mut state = self.state.lock();
+        if cmd == "CLIENT" {
+            return false;
+        }
+        let caching = state.caching.take();
+        match &state.tracking {
+            Some(options) if !options.bcast => {
+                if options.optin {
+                    caching == Some(true)
+                } else {
+                    !(options.optout && caching == Some(false))
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Komut çalışmaya başladı (`cmd` büyük harfle)
+    pub fn begin_command(&self, cmd: &str) {
+        let mut state = self.state.lock();
+        state.cmd = Some(cmd.to_lowercase());
+        state.commands += 1;
+    }
+
+    /// Soketten veri okundu; `len` buffer'da bekleyen bayt sayısıdır.
+    pub fn received(&self, len: usize, capacity: usize) {
+        let mut state = self.state.lock();
+        state.query_buffer = len;
+        state.query_buffer_free = capacity - len;
+        state.last_interaction = Instant::now();
+    }
+
+    /// Bağlantının kapatılmasını ister. Bağlantı o an bir komut çalıştırıyorsa komut
+    /// bitip cevabı gönderildikten sonra kapanır.
+    pub fn kill(&self) {
+        self.killed.store(true, Ordering::SeqCst);
+        self.kill.notify_waiters();
+    }
+
+    /// Bağlantı `CLIENT KILL` ile kapatılana kadar bekler. Aynı bağlantıyı birden çok
+    /// görev bekleyebilir (abonelikte mesaj aktaran görev ve yazan döngü); hepsi uyanır.
+    pub async fn killed(&self) {
+        loop {
+            // Bayrağa bakmadan önce kayıt olunur; arada gelen `kill` kaçırılmaz
+            let notified = self.kill.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.killed.load(Ordering::SeqCst) {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// `CLIENT LIST` satırı
+    pub fn describe(&self) -> String {
+        let state = self.state.lock();
+        let mut flags = String::new();
+        match state.kind {
+            ClientKind::Normal => {}
+            ClientKind::PubSub => flags.push('P'),
+            ClientKind::Replica => flags.push('S'),
+        }
+        if state.no_evict {
+            flags.push('e');
+        }
+        if let Some(tracking) = &state.tracking {
+            flags.push('t');
+            if tracking.bcast {
+                flags.push('B');
+            }
+        }
+        if self.peer.unix_socket {
+            flags.push('U');
+        }
+        if flags.is_empty() {
+            flags.push('N');
+        }
+        format!(
+            "id={} addr={} laddr={} name={} age={} idle={} flags={} qbuf={} qbuf-free={} omem={} cmd={} user={} resp={} lib-name={} lib-ver={} tot-cmds={}",
+            self.id,
+            self.peer.addr,
+            self.peer.laddr,
+            state.name,
+            self.created.elapsed().as_secs(),
+            state.last_interaction.elapsed().as_secs(),
+            flags,
+            state.query_buffer,
+            state.query_buffer_free,
+            state.output_buffer,
+            state.cmd.as_deref().unwrap_or("NULL"),
+            state.user.as_deref().unwrap_or(""),
+            state.resp,
+            state.lib_name,
+            state.lib_ver,
+            state.commands
+        )
+    }
+}
+
+/// Kayıt silinene kadar bağlantıyı listede tutar.
+pub struct Registration {
+    clients: Arc<Clients>,
+    client: Arc<Client>,
+}
+
+impl std::ops::Deref for Registration {
+    type Target = Arc<Client>;
+
+    fn deref(&self) -> &Arc<Client> {
+        &self.client
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        self.clients.clients.write().remove(&self.client.id);
+        self.clients.stop_tracking(&self.client);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Pause {
+    until: Instant,
+    /// `false` ise yalnızca yazma komutları bekletilir
+    all: bool,
+}
+
+/// `CLIENT KILL` süzgeçleri; verilenlerin hepsine uyan bağlantılar kapatılır.
+#[derive(Default)]
+struct KillFilter {
+    id: Option<u64>,
+    addr: Option<String>,
+    laddr: Option<String>,
+    user: Option<String>,
+    kind: Option<ClientKind>,
+    skip_me: bool,
+}
+
+impl KillFilter {
+    fn matches(&self, client: &Client, current: u64) -> bool {
+        let state = client.state.lock();
+        !(self.skip_me && client.id == current)
+            && self.id.is_none_or(|id| id == client.id)
+            && self
+                .addr
+                .as_ref()
+                .is_none_or(|addr| *addr == client.peer.addr)
+            && self
+                .laddr
+                .as_ref()
+                .is_none_or(|laddr| *laddr == client.peer.laddr)
+            && self
+                .user
+                .as_ref()
+                .is_none_or(|user| state.user.as_ref() == Some(user))
+            && self.kind.is_none_or(|kind| kind == state.kind)
+    }
+}
+
+pub struct Clients {
+    next_id: AtomicU64,
+    /// Bağlantılar bu sınırları paylaşır; değişiklik açık bağlantılara da uygulanır
+    limits: Arc<RwLock<Limits>>,
+    clients: RwLock<BTreeMap<u64, Arc<Client>>>,
+    pause: watch::Sender<Option<Pause>>,
+    tracking: Mutex<Table>,
+    /// `CLIENT TRACKING` açık bağlantı sayısı; sıfırsa yazmalar izlenmez
+    tracking_clients: AtomicUsize,
+    /// Zamanlayıcıya yeni bir süre eklendi
+    expiry_added: Notify,
+}
+
+impl Default for Clients {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clients {
+    pub fn new() -> Self {
+        Clients {
+            next_id: AtomicU64::new(1),
+            limits: Arc::new(RwLock::new(Limits::default())),
+            clients: RwLock::new(BTreeMap::new()),
+            pause: watch::channel(None).0,
+            tracking: Mutex::new(Table::default()),
+            tracking_clients: AtomicUsize::new(0),
+            expiry_added: Notify::new(),
+        }
+    }
+
+    pub fn set_limits(&self, limits: Limits) {
+        *self.limits.write() = limits;
+    }
+
+    /// Yeni bağlantıyı kaydeder; dönen kayıt bırakılınca bağlantı listeden çıkar. Alıcı,
+    /// bağlantıya yazılacak geçersiz kılma mesajlarını taşır.
+    pub fn register(
+        self: &Arc<Self>,
+        peer: Peer,
+        user: Option<String>,
+    ) -> (Registration, mpsc::UnboundedReceiver<String>) {
+        let (pushes, receiver) = mpsc::unbounded_channel();
+        let client = Arc::new(Client {
+            id: self.next_id.fetch_add(1, Ordering::SeqCst),
+            peer,
+            created: Instant::now(),
+            state: Mutex::new(ClientState {
+                name: String::new(),
+                lib_name: String::new(),
+                lib_ver: String::new(),
+                user,
+                kind: ClientKind::Normal,
+                no_evict: false,
+                cmd: None,
+                commands: 0,
+                last_interaction: Instant::now(),
+                query_buffer: 0,
+                query_buffer_free: 0,
+                output_buffer: 0,
+                soft_limit_since: None,
+                resp: 2,
+                tracking: None,
+                caching: None,
+            }),
+            killed: AtomicBool::new(false),
+            kill: Notify::new(),
+            pushes,
+            limits: Arc::clone(&self.limits),
+        });
+        self.clients.write().insert(client.id, Arc::clone(&client));
+        let registration = Registration {
+            clients: Arc::clone(self),
+            client,
+        };
+        (registration, receiver)
+    }
+
+    pub fn len(&self) -> usize {
+        self.clients.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// INFO'nun `# Clients` bölümü. Redis'teki gibi replikalar `connected_clients`'a
+    /// sayılmaz; buffer alanları şu anki en büyük girdi ve çıkış buffer'larıdır.
+    pub fn info(&self, max_clients: usize) -> String {
+        let (mut connected, mut pubsub, mut max_input, mut max_output) = (0, 0, 0, 0);
+        for client in self.clients.read().values() {
+            let state = client.state.lock();
+            match state.kind {
+                ClientKind::Normal => connected += 1,
+                ClientKind::PubSub => {
+                    connected += 1;
+                    pubsub += 1;
+                }
+                ClientKind::Replica => {}
+            }
+            max_input = max_input.max(state.query_buffer);
+            max_output = max_output.max(state.output_buffer);
+        }
+        format!(
+            "# Clients\r\nconnected_clients:{}\r\nmaxclients:{}\r\nclient_recent_max_input_buffer:{}\r\nclient_recent_max_output_buffer:{}\r\ntracking_clients:{}\r\npubsub_clients:{}\r\n",
+            connected,
+            max_clients,
+            max_input,
+            max_output,
+            self.tracking_clients.load(Ordering::SeqCst),
+            pubsub
+        )
+    }
+
+    /// `CLIENT PAUSE` sürüyorsa komut çalışmadan önce bekler. `write` yazma komutları için
+    /// verilir; `CLIENT PAUSE ... WRITE` yalnızca onları bekletir.
+    pub async fn wait_unpaused(&self, write: bool) {
+        let mut pause = self.pause.subscribe();
+        loop {
+            let until = match *pause.borrow_and_update() {
+                Some(Pause { until, all }) if all || write => until,
+                _ => return,
+            };
+            if Instant::now() >= until {
+                return;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep_until(until.into()) => {}
+                changed = pause.changed() => {
+                    if changed.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Süresi dolan izlenmiş anahtarlar için geçersiz kılma mesajı gönderen görevi başlatır.
+    pub fn start_cron(self: &Arc<Self>) {
+        let clients = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                let next = clients.tracking.lock().next_expiry();
+                let added = clients.expiry_added.notified();
+                match next {
+                    Some(at) => tokio::select! {
+                        _ = tokio::time::sleep_until(at.into()) => {}
+                        _ = added => {}
+                    },
+                    None => added.await,
+                }
+                let expired = clients.tracking.lock().expired(Instant::now());
+                if !expired.is_empty() {
+                    clients.invalidate(&expired, None);
+                }
+            }
+        });
+    }
+
+    /// En az bir bağlantı `CLIENT TRACKING` kullanıyor mu?
+    pub fn tracking_active(&self) -> bool {
+        self.tracking_clients.load(Ordering::SeqCst) > 0
+    }
+
+    /// Bağlantının okuduğu anahtarları hatırlar. `expires_at` anahtarın süresidir; süre
+    /// dolunca istemciye mesaj gider.
+    pub fn remember(&self, id: u64, keys: &[(&str, Option<Instant>)]) {
+        let mut table = self.tracking.lock();
+        for (key, expires_at) in keys {
+            table.remember(id, key);
+            if let Some(at) = expires_at {
+                table.expire_at(key, *at);
+                self.expiry_added.notify_one();
+            }
+        }
+    }
+
+    /// Süresi olan bir anahtar yazıldı; BCAST aboneleri de süre dolunca mesaj alır.
+    pub fn expire_at(&self, key: &str, at: Instant) {
+        self.tracking.lock().expire_at(key, at);
+        self.expiry_added.notify_one();
+    }
+
+    /// Değişen anahtarları izleyen bağlantılara geçersiz kılma mesajı gönderir. `origin`
+    /// anahtarları yazan bağlantıdır; NOLOOP ile izleyen bağlantı kendi yazdıkları için
+    /// mesaj almaz.
+    pub fn invalidate(&self, keys: &[String], origin: Option<u64>) {
+        let mut targets: BTreeMap<u64, Vec<String>> = BTreeMap::new();
+        {
+            let mut table = self.tracking.lock();
+            for key in keys {
+                for id in table.take(key) {
+                    targets.entry(id).or_default().push(key.clone());
+                }
+            }
+        }
+        for (id, keys) in targets {
+            self.send_invalidation(id, Some(&keys), origin);
+        }
+    }
+
+    /// Veri setinin tamamı değişti: izleyen her bağlantıya bütün anahtarlar için mesaj gider.
+    pub fn invalidate_all(&self, origin: Option<u64>) {
+        let ids = self.tracking.lock().take_all();
+        for id in ids {
+            self.send_invalidation(id, None, origin);
+        }
+    }
+
+    fn send_invalidation(&self, id: u64, keys: Option<&[String]>, origin: Option<u64>) {
+        let clients = self.clients.read();
+        let Some(client) = clients.get(&id) else {
+            return;
+        };
+        let (redirect, resp) = {
+            let state = client.state.lock();
+            match &state.tracking {
+                Some(options) if !(options.noloop && origin == Some(id)) => {
+                    (options.redirect, state.resp)
+                }
+                _ => return,
+            }
+        };
+        match redirect {
+            None if resp == 3 => client.push(tracking::invalidate_frame(keys, true)),
+            // RESP2 bağlantısı aynı bağlantıda mesaj alamaz
+            None => {}
+            Some(target) => match clients.get(&target) {
+                Some(target) => {
+                    let (resp, kind) = {
+                        let state = target.state.lock();
+                        (state.resp, state.kind)
+                    };
+                    if resp == 3 {
+                        target.push(tracking::invalidate_frame(keys, true));
+                    } else if kind == ClientKind::PubSub {
+                        target.push(tracking::invalidate_frame(keys, false));
+                    }
+                }
+                None if resp == 3 => client.push(resp3::push(
+                    "tracking-redir-broken",
+                    &RespValue::Integer(target as i64).serialize(),
+                )),
+                None => {}
+            },
+        }
+    }
+
+    fn start_tracking(&self, client: &Client, options: TrackingOptions) {
+        self.stop_tracking(client);
+        if options.bcast {
+            self.tracking.lock().subscribe(client.id, &options.prefixes);
+        }
+        client.state.lock().tracking = Some(options);
+        self.tracking_clients.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Varsayılan moddaki kayıtlar silinmez; bağlantı artık izlemediği için mesaj gitmez.
+    fn stop_tracking(&self, client: &Client) {
+        let options = {
+            let mut state = client.state.lock();
+            state.caching = None;
+            state.tracking.take()
+        };
+        if let Some(options) = options {
+            if options.bcast {
+                self.tracking.lock().unsubscribe(client.id);
+            }
+            self.tracking_clients.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    fn kill(&self, filter: &KillFilter, current: u64) -> usize {
+        let clients = self.clients.read();
+        let mut killed = 0;
+        for client in clients.values().filter(|c| filter.matches(c, current)) {
+            client.kill();
+            killed += 1;
+        }
+        killed
+    }
+
+    /// CLIENT komutu; `current` komutu gönderen bağlantıdır.
+    pub fn command(&self, current: &Client, args: &[String]) -> RespValue {
+        match self.try_command(current, args) {
+            Ok(reply) => reply,
+            Err(e) => RespValue::Error(e),
+        }
+    }
+
+    fn try_command(&self, current: &Client, args: &[String]) -> Result<RespValue, String> {
+        let Some(subcommand) = args.first().map(|s| s.to_uppercase()) else {
+            return Err("wrong number of arguments for 'CLIENT' command".to_string());
+        };
+        let ok = || Ok(RespValue::SimpleString("OK".to_string()));
+        let arity = |valid: bool| {
+            if valid {
+                Ok(())
+            } else {
+                Err(format!(
+                    "wrong number of arguments for 'CLIENT|{}' command",
+                    subcommand
+                ))
+            }
+        };
+        match subcommand.as_str() {
+            "ID" => {
+                arity(args.len() == 1)?;
+                Ok(RespValue::Integer(current.id as i64))
+            }
+            "INFO" => {
+                arity(args.len() == 1)?;
+                Ok(RespValue::BulkString(Some(format!(
+                    "{}\n",
+                    current.describe()
+                ))))
+            }
+            "LIST" => {
+                let mut kind = None;
+                let mut ids = Vec::new();
+                let mut i = 1;
+                while i < args.len() {
+                    match args[i].to_uppercase().as_str() {
+                        "TYPE" if i + 1 < args.len() => {
+                            kind = Some(ClientKind::parse(&args[i + 1])?);
+                            i += 2;
+                        }
+                        "ID" if i + 1 < args.len() => {
+                            for id in &args[i + 1..] {
+                                ids.push(id.parse::<u64>().map_err(|_| "Invalid client ID")?);
+                            }
+                            i = args.len();
+                        }
+                        _ => return Err("syntax error".to_string()),
+                    }
+                }
+                let list: String = self
+                    .clients
+                    .read()
+                    .values()
+                    .filter(|c| kind.is_none_or(|kind| c.state.lock().kind == kind))
+                    .filter(|c| ids.is_empty() || ids.contains(&c.id))
+                    .map(|c| format!("{}\n", c.describe()))
+                    .collect();
+                Ok(RespValue::BulkString(Some(list)))
+            }
+            "SETNAME" => {
+                arity(args.len() == 2)?;
+                current.set_name(&args[1])?;
+                ok()
+            }
+            "GETNAME" => {
+                arity(args.len() == 1)?;
+                let name = current.state.lock().name.clone();
+                Ok(RespValue::BulkString((!name.is_empty()).then_some(name)))
+            }
+            "SETINFO" => {
+                arity(args.len() == 3)?;
+                let value = args[2].clone();
+                match args[1].to_uppercase().as_str() {
+                    "LIB-NAME" => {
+                        validate_name("lib-name", &value)?;
+                        current.state.lock().lib_name = value;
+                    }
+                    "LIB-VER" => {
+                        validate_name("lib-ver", &value)?;
+                        current.state.lock().lib_ver = value;
+                    }
+                    _ => return Err(format!("Unrecognized option '{}'", args[1])),
+                }
+                ok()
+            }
+            "KILL" => match args.len() {
+                // Eski biçim: CLIENT KILL ip:port
+                2 => {
+                    let filter = KillFilter {
+                        addr: Some(args[1].clone()),
+                        ..Default::default()
+                    };
+                    if self.kill(&filter, current.id) == 0 {
+                        return Err("No such client".to_string());
+                    }
+                    ok()
+                }
+                n if n >= 3 && n % 2 == 1 => {
+                    let filter = parse_kill_filter(&args[1..])?;
+                    Ok(RespValue::Integer(self.kill(&filter, current.id) as i64))
+                }
+                _ => Err("syntax error".to_string()),
+            },
+            "PAUSE" => {
+                arity(args.len() == 2 || args.len() == 3)?;
+                let timeout = args[1]
+                    .parse::<i64>()
+                    .map_err(|_| "timeout is not an integer or out of range")?;
+                if timeout < 0 {
+                    return Err("timeout is negative".to_string());
+                }
+                let all = match args.get(2).map(|mode| mode.to_uppercase()).as_deref() {
+                    None | Some("ALL") => true,
+                    Some("WRITE") => false,
+                    Some(_) => return Err("syntax error".to_string()),
+                };
+                let until = Instant::now() + Duration::from_millis(timeout as u64);
+                self.pause.send_replace(Some(Pause { until, all }));
+                ok()
+            }
+            "UNPAUSE" => {
+                arity(args.len() == 1)?;
+                self.pause.send_replace(None);
+                ok()
+            }
+            "NO-EVICT" => {
+                arity(args.len() == 2)?;
+                let on = match args[1].to_uppercase().as_str() {
+                    "ON" => true,
+                    "OFF" => false,
+                    _ => return Err("syntax error".to_string()),
+                };
+                current.state.lock().no_evict = on;
+                ok()
+            }
+            "TRACKING" => {
+                arity(args.len() >= 2)?;
+                match args[1].to_uppercase().as_str() {
+                    "ON" => {
+                        let options = TrackingOptions::parse(&args[2..])?;
+                        if let Some(target) = options.redirect {
+                            if !self.clients.read().contains_key(&target) {
+                                return Err(
+                                    "The client ID you want redirect to does not exist".to_string()
+                                );
+                            }
+                        }
+                        self.start_tracking(current, options);
+                    }
+                    "OFF" => self.stop_tracking(current),
+                    _ => return Err("syntax error".to_string()),
+                }
+                ok()
+            }
+            "CACHING" => {
+                arity(args.len() == 2)?;
+                let yes = match args[1].to_uppercase().as_str() {
+                    "YES" => true,
+                    "NO" => false,
+                    _ => return Err("syntax error".to_string()),
+                };
+                let mut state = current.state.lock();
+                match &state.tracking {
+                    Some(options) if yes && !options.optin => {
+                        return Err("CLIENT CACHING YES is only valid when tracking is enabled in OPTIN mode.".to_string())
+                    }
+                    Some(options) if !yes && !options.optout => {
+                        return Err("CLIENT CACHING NO is only valid when tracking is enabled in OPTOUT mode.".to_string())
+                    }
+                    Some(_) => state.caching = Some(yes),
+                    None => {
+                        return Err("CLIENT CACHING can be called only when the client is in tracking mode with OPTIN or OPTOUT mode enabled".to_string())
+                    }
+                }
+                ok()
+            }
+            "GETREDIR" => {
+                arity(args.len() == 1)?;
+                let redirect = match &current.state.lock().tracking {
+                    Some(options) => options.redirect.map_or(0, |id| id as i64),
+                    None => -1,
+                };
+                Ok(RespValue::Integer(redirect))
+            }
+            "TRACKINGINFO" => {
+                arity(args.len() == 1)?;
+                // Kilit sırası: önce bağlantı listesi, sonra bağlantının durumu
+                let redirect = current
+                    .state
+                    .lock()
+                    .tracking
+                    .as_ref()
+                    .and_then(|o| o.redirect);
+                let broken = redirect.is_some_and(|id| !self.clients.read().contains_key(&id));
+                let state = current.state.lock();
+                let bulk = |s: &str| RespValue::BulkString(Some(s.to_string()));
+                let (mut flags, redirect, prefixes) = match &state.tracking {
+                    Some(options) => (
+                        options.flags(),
+                        options.redirect.map_or(0, |id| id as i64),
+                        options.prefixes.iter().map(|p| bulk(p)).collect(),
+                    ),
+                    None => (vec!["off"], -1, Vec::new()),
+                };
+                match state.caching {
+                    Some(true) => flags.push("caching-yes"),
+                    Some(false) => flags.push("caching-no"),
+                    None => {}
+                }
+                if broken {
+                    flags.push("broken_redirect");
+                }
+                Ok(RespValue::Array(Some(vec![
+                    bulk("flags"),
+                    RespValue::Array(Some(flags.into_iter().map(bulk).collect())),
+                    bulk("redirect"),
+                    RespValue::Integer(redirect),
+                    bulk("prefixes"),
+                    RespValue::Array(Some(prefixes)),
+                ])))
+            }
+            _ => Err(format!(
+                "unknown subcommand '{}'. Try CLIENT HELP.",
+                args[0]
+            )),
+        }
+    }
+}
+
+/// Adlar boşluk ve kontrol karakteri içeremez; listede `alan=değer` biçimini bozarlar.
+fn validate_name(field: &str, value: &str) -> Result<(), String> {
+    if value.bytes().all(|b| (b'!'..=b'~').contains(&b)) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} cannot contain spaces, newlines or special characters.",
+            field
+        ))
+    }
+}
+
+/// `CLIENT KILL <süzgeç> <değer> ...`; `SKIPME` verilmezse komutu gönderen bağlantı
+/// kapatılmaz.
+fn parse_kill_filter(args: &[String]) -> Result<KillFilter, String> {
+    let mut filter = KillFilter {
+        skip_me: true,
+        ..Default::default()
+    };
+    for pair in args.chunks(2) {
+        let value = pair[1].clone();
+        match pair[0].to_uppercase().as_str() {
+            "ID" => {
+                let id = value.parse::<u64>().ok().filter(|id| *id > 0);
+                filter.id = Some(id.ok_or("client-id should be greater than 0")?);
+            }
+            "ADDR" => filter.addr = Some(value),
+            "LADDR" => filter.laddr = Some(value),
+            "USER" => filter.user = Some(value),
+            "TYPE" => filter.kind = Some(ClientKind::parse(&value)?),
+            "SKIPME" => {
+                filter.skip_me = match value.to_lowercase().as_str() {
+                    "yes" => true,
+                    "no" => false,
+                    _ => return Err("syntax error".to_string()),
+                }
+            }
+            _ => return Err("syntax error".to_string()),
+        }
+    }
+    Ok(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::limits::OutputLimit;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn register(clients: &Arc<Clients>, port: u16, user: &str) -> Registration {
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        clients
+            .register(Peer::tcp(addr, None), Some(user.to_string()))
+            .0
+    }
+
+    #[test]
+    fn test_list_and_names() {
+        let clients = Arc::new(Clients::new());
+        let a = register(&clients, 5000, "default");
+        let b = register(&clients, 5001, "alice");
+        assert_eq!(clients.len(), 2);
+
+        let reply = clients.command(&a, &args(&["SETNAME", "worker-1"]));
+        assert_eq!(reply, RespValue::SimpleString("OK".to_string()));
+        assert!(matches!(
+            clients.command(&a, &args(&["SETNAME", "has space"])),
+            RespValue::Error(_)
+        ));
+        assert_eq!(
+            clients.command(&a, &args(&["GETNAME"])),
+            RespValue::BulkString(Some("worker-1".to_string()))
+        );
+        assert_eq!(
+            clients.command(&b, &args(&["GETNAME"])),
+            RespValue::BulkString(None)
+        );
+
+        let RespValue::BulkString(Some(list)) = clients.command(&a, &args(&["LIST"])) else {
+            panic!("CLIENT LIST must return a bulk string");
+        };
+        let lines: Vec<&str> = list.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(&format!("id={} addr=127.0.0.1:5000 ", a.id)));
+        assert!(lines[0].contains(" name=worker-1 "));
+        assert!(lines[1].contains(" user=alice "));
+
+        drop(b);
+        assert_eq!(clients.len(), 1);
+    }
+
+    #[test]
+    fn test_output_limits() {
+        let clients = Arc::new(Clients::new());
+        let mut limits = Limits::default();
+        limits.output.pubsub = OutputLimit {
+            hard: 10,
+            ..Default::default()
+        };
+        clients.set_limits(limits);
+        let a = register(&clients, 5000, "default");
+
+        // Normal bağlantıların varsayılan sınırı yok
+        assert!(a.queue(1000));
+        a.sent(1000);
+
+        a.set_kind(ClientKind::PubSub);
+        assert!(a.queue(8));
+        a.sent(8);
+        assert!(a.queue(8));
+        assert!(!a.queue(8));
+        assert!(a.killed.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_kill_filters() {
+        let clients = Arc::new(Clients::new());
+        let me = register(&clients, 5000, "alice");
+        let other = register(&clients, 5001, "alice");
+        let bob = register(&clients, 5002, "bob");
+
+        // Komutu gönderen bağlantı varsayılan olarak atlanır
+        let reply = clients.command(&me, &args(&["KILL", "USER", "alice"]));
+        assert_eq!(reply, RespValue::Integer(1));
+        assert!(other.killed.load(Ordering::SeqCst));
+        assert!(!me.killed.load(Ordering::SeqCst));
+
+        let reply = clients.command(&me, &args(&["KILL", "ID", &bob.id.to_string()]));
+        assert_eq!(reply, RespValue::Integer(1));
+        assert!(bob.killed.load(Ordering::SeqCst));
+
+        assert!(matches!(
+            clients.command(&me, &args(&["KILL", "127.0.0.1:9999"])),
+            RespValue::Error(_)
+        ));
+        let reply = clients.command(
+            &me,
+            &args(&["KILL", "ADDR", "127.0.0.1:5000", "SKIPME", "no"]),
+        );
+        assert_eq!(reply, RespValue::Integer(1));
+        assert!(me.killed.load(Ordering::SeqCst));
+    }
+}
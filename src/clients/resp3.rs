@@ -0,0 +1,28 @@
+//! `RespValue`'nun karşılığı olmayan RESP3 tipleri. `HELLO 3` ile RESP3'e geçen
+//! bağlantılar map ve push çerçevelerini buradan alır; diğer cevaplar RESP2 tipleriyle
+//! gönderilir, bunlar RESP3'te de geçerlidir.
+
+use crate::network::resp::RespValue;
+
+/// `%` map çerçevesi
+pub fn map(pairs: &[(&str, RespValue)]) -> String {
+    let mut frame = format!("%{}\r\n", pairs.len());
+    for (key, value) in pairs {
+        frame.push_str(&RespValue::BulkString(Some(key.to_string())).serialize());
+        frame.push_str(&value.serialize());
+    }
+    frame
+}
+
+/// `>` push çerçevesi: bağlantıya istemci istemeden gönderilen mesaj. `payload`
+/// serileştirilmiş değerdir.
+pub fn push(kind: &str, payload: &str) -> String {
+    format!(
+        ">2\r\n{}{}",
+        RespValue::BulkString(Some(kind.to_string())).serialize(),
+        payload
+    )
+}
+
+/// RESP3 null
+pub const NULL: &str = "_\r\n";
@@ -0,0 +1,251 @@
+//! İstemci tarafı önbellek desteği (`CLIENT TRACKING`). Varsayılan modda sunucu her
+//! istemcinin okuduğu anahtarları hatırlar; BCAST modunda istemci önek aboneliği yapar ve
+//! öneke uyan her anahtar değişikliğini alır. Anahtar değiştiğinde ya da süresi dolduğunda
+//! istemciye geçersiz kılma mesajı gönderilir ve varsayılan moddaki kayıt silinir; anahtar
+//! yeniden okununca yeniden kaydedilir.
+
+use super::resp3;
+use crate::network::resp::RespValue;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::time::Instant;
+
+/// RESP2 istemcilerinin geçersiz kılma mesajlarını yönlendirme bağlantısında aldığı kanal
+pub const INVALIDATE_CHANNEL: &str = "__redis__:invalidate";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackingOptions {
+    /// Mesajların gönderileceği bağlantı; `None` ise mesajlar bu bağlantıya push edilir
+    pub redirect: Option<u64>,
+    pub bcast: bool,
+    /// BCAST önekleri; boşsa bütün anahtarlar
+    pub prefixes: Vec<String>,
+    /// Yalnızca `CLIENT CACHING yes` sonrasındaki komutun okudukları izlenir
+    pub optin: bool,
+    /// `CLIENT CACHING no` sonrasındaki komutun okudukları izlenmez
+    pub optout: bool,
+    /// Bağlantı kendi yazdığı anahtarlar için mesaj almaz
+    pub noloop: bool,
+}
+
+impl TrackingOptions {
+    /// `CLIENT TRACKING ON` sonrasındaki seçenekler
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = TrackingOptions::default();
+        let mut i = 0;
+        while i < args.len() {
+            match args[i].to_uppercase().as_str() {
+                "REDIRECT" if i + 1 < args.len() => {
+                    let id = args[i + 1]
+                        .parse::<u64>()
+                        .map_err(|_| "value is not an integer or out of range")?;
+                    options.redirect = Some(id);
+                    i += 1;
+                }
+                "PREFIX" if i + 1 < args.len() => {
+                    options.prefixes.push(args[i + 1].clone());
+                    i += 1;
+                }
+                "BCAST" => options.bcast = true,
+                "OPTIN" => options.optin = true,
+                "OPTOUT" => options.optout = true,
+                "NOLOOP" => options.noloop = true,
+                _ => return Err("syntax error".to_string()),
+            }
+            i += 1;
+        }
+        if !options.prefixes.is_empty() && !options.bcast {
+            return Err("PREFIX option requires BCAST mode to be enabled".to_string());
+        }
+        if options.optin && options.optout {
+            return Err("You can't use both OPTIN and OPTOUT".to_string());
+        }
+        if options.bcast && (options.optin || options.optout) {
+            return Err("OPTIN and OPTOUT are not compatible with BCAST".to_string());
+        }
+        Ok(options)
+    }
+
+    /// `CLIENT TRACKINGINFO` bayrakları
+    pub fn flags(&self) -> Vec<&'static str> {
+        let mut flags = vec!["on"];
+        for (set, flag) in [
+            (self.bcast, "bcast"),
+            (self.optin, "optin"),
+            (self.optout, "optout"),
+            (self.noloop, "noloop"),
+        ] {
+            if set {
+                flags.push(flag);
+            }
+        }
+        flags
+    }
+}
+
+/// İzlenen anahtarlar ve BCAST abonelikleri.
+#[derive(Default)]
+pub struct Table {
+    /// Varsayılan modda okunan anahtar → okuyan bağlantılar
+    keys: HashMap<String, HashSet<u64>>,
+    /// BCAST öneki → abone bağlantılar
+    prefixes: BTreeMap<String, HashSet<u64>>,
+    /// Süresi dolunca geçersiz kılınacak anahtarlar
+    expiries: BTreeSet<(Instant, String)>,
+}
+
+impl Table {
+    pub fn remember(&mut self, id: u64, key: &str) {
+        self.keys.entry(key.to_string()).or_default().insert(id);
+    }
+
+    /// BCAST aboneliği; önek verilmezse bütün anahtarlar
+    pub fn subscribe(&mut self, id: u64, prefixes: &[String]) {
+        if prefixes.is_empty() {
+            self.prefixes.entry(String::new()).or_default().insert(id);
+        }
+        for prefix in prefixes {
+            self.prefixes.entry(prefix.clone()).or_default().insert(id);
+        }
+    }
+
+    pub fn unsubscribe(&mut self, id: u64) {
+        self.prefixes.retain(|_, ids| {
+            ids.remove(&id);
+            !ids.is_empty()
+        });
+    }
+
+    /// Anahtarı izleyen bağlantılar. Varsayılan moddaki kayıt silinir; istemci anahtarı
+    /// yeniden okuyana kadar başka mesaj almaz.
+    pub fn take(&mut self, key: &str) -> HashSet<u64> {
+        let mut ids = self.keys.remove(key).unwrap_or_default();
+        for (prefix, subscribers) in &self.prefixes {
+            if key.starts_with(prefix.as_str()) {
+                ids.extend(subscribers);
+            }
+        }
+        ids
+    }
+
+    /// Veri seti değişti: bütün kayıtlar silinir, izleyen herkes mesaj alır.
+    pub fn take_all(&mut self) -> HashSet<u64> {
+        let mut ids: HashSet<u64> = self.keys.drain().flat_map(|(_, ids)| ids).collect();
+        ids.extend(self.prefixes.values().flatten());
+        self.expiries.clear();
+        ids
+    }
+
+    pub fn expire_at(&mut self, key: &str, at: Instant) {
+        self.expiries.insert((at, key.to_string()));
+    }
+
+    pub fn next_expiry(&self) -> Option<Instant> {
+        self.expiries.first().map(|(at, _)| *at)
+    }
+
+    /// Süresi dolmuş anahtarları zamanlayıcıdan çıkarır.
+    pub fn expired(&mut self, now: Instant) -> Vec<String> {
+        let mut keys = Vec::new();
+        while let Some((at, _)) = self.expiries.first() {
+            if *at > now {
+                break;
+            }
+            let (_, key) = self.expiries.pop_first().expect("checked above");
+            keys.push(key);
+        }
+        keys
+    }
+}
+
+/// Geçersiz kılma mesajı; `keys` `None` ise bütün anahtarlar.
+/// RESP3 bağlantısına push, RESP2 yönlendirme bağlantısına pub/sub mesajı olarak gider.
+pub fn invalidate_frame(keys: Option<&[String]>, resp3: bool) -> String {
+    let keys = RespValue::Array(keys.map(|keys| {
+        keys.iter()
+            .map(|key| RespValue::BulkString(Some(key.clone())))
+            .collect()
+    }));
+    if resp3 {
+        let payload = match keys {
+            RespValue::Array(None) => resp3::NULL.to_string(),
+            keys => keys.serialize(),
+        };
+        return resp3::push("invalidate", &payload);
+    }
+    RespValue::Array(Some(vec![
+        RespValue::BulkString(Some("message".to_string())),
+        RespValue::BulkString(Some(INVALIDATE_CHANNEL.to_string())),
+        keys,
+    ]))
+    .serialize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options = TrackingOptions::parse(&args(&["BCAST", "PREFIX", "user:", "NOLOOP"]));
+        assert_eq!(
+            options.unwrap(),
+            TrackingOptions {
+                bcast: true,
+                prefixes: vec!["user:".to_string()],
+                noloop: true,
+                ..Default::default()
+            }
+        );
+        assert!(TrackingOptions::parse(&args(&["PREFIX", "user:"])).is_err());
+        assert!(TrackingOptions::parse(&args(&["OPTIN", "OPTOUT"])).is_err());
+        assert!(TrackingOptions::parse(&args(&["BCAST", "OPTIN"])).is_err());
+        assert!(TrackingOptions::parse(&args(&["REDIRECT"])).is_err());
+    }
+
+    #[test]
+    fn test_table() {
+        let mut table = Table::default();
+        table.remember(1, "user:1");
+        table.remember(2, "user:1");
+        table.subscribe(3, &["user:".to_string()]);
+        table.subscribe(4, &[]);
+
+        let ids = table.take("user:1");
+        assert_eq!(ids, HashSet::from([1, 2, 3, 4]));
+        // Kayıt silindi; yalnızca aboneler kaldı
+        assert_eq!(table.take("user:1"), HashSet::from([3, 4]));
+        assert_eq!(table.take("order:1"), HashSet::from([4]));
+
+        table.unsubscribe(4);
+        assert!(table.take("order:1").is_empty());
+
+        let now = Instant::now();
+        table.expire_at("b", now + Duration::from_secs(5));
+        table.expire_at("a", now);
+        assert_eq!(table.next_expiry(), Some(now));
+        assert_eq!(table.expired(now), vec!["a".to_string()]);
+        assert_eq!(table.next_expiry(), Some(now + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_invalidate_frames() {
+        let keys = ["k".to_string()];
+        assert_eq!(
+            invalidate_frame(Some(&keys), true),
+            ">2\r\n$10\r\ninvalidate\r\n*1\r\n$1\r\nk\r\n"
+        );
+        assert_eq!(
+            invalidate_frame(None, true),
+            ">2\r\n$10\r\ninvalidate\r\n_\r\n"
+        );
+        assert_eq!(
+            invalidate_frame(Some(&keys), false),
+            "*3\r\n$7\r\nmessage\r\n$20\r\n__redis__:invalidate\r\n*1\r\n$1\r\nk\r\n"
+        );
+    }
+}
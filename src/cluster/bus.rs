@@ -0,0 +1,175 @@
+//! Cluster bus: node'lar arasında PING/PONG ile durum ve slot bilgisi alışverişi.
+//! Mesajlar RESP dizisi olarak gönderilir; her PING/MEET'e alıcının PONG'u cevap olur.
+
+use crate::cluster::{Cluster, Message, MessageKind};
+use crate::network::client::Connection;
+use crate::network::parser::parse_value;
+use crate::network::resp::RespValue;
+use crate::replication::command_args;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinSet;
+use tracing::{debug, error, info};
+
+/// Her node'a bu aralıkla PING gönderilir
+const GOSSIP_PERIOD: Duration = Duration::from_millis(1000);
+/// Bağlantı kurma ve PONG bekleme süresi sınırı
+const BUS_TIMEOUT: Duration = Duration::from_millis(1000);
+
+fn reply_bytes(message: &Message) -> Vec<u8> {
+    let reply = RespValue::Array(Some(
+        message
+            .to_args()
+            .into_iter()
+            .map(|arg| RespValue::BulkString(Some(arg)))
+            .collect(),
+    ));
+    reply.serialize().into_bytes()
+}
+
+/// Bus bağlantılarını kabul eder; dinleme sonlanmaz.
+pub async fn serve(listener: TcpListener, cluster: Arc<Cluster>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, addr)) => {
+                let cluster = Arc::clone(&cluster);
+                tokio::spawn(async move {
+                    debug!("Cluster bus connection from {}", addr);
+                    handle_peer(stream, &cluster).await;
+                });
+            }
+            Err(e) => error!("Cluster bus connection error: {}", e),
+        }
+    }
+}
+
+async fn handle_peer(mut stream: TcpStream, cluster: &Cluster) {
+    let peer_ip = match stream.peer_addr() {
+        Ok(addr) => addr.ip().to_string(),
+        Err(_) => return,
+    };
+    // Kendi adresimizi bilmiyorsak bize ulaşılan adresi kullanırız
+    if let Ok(local) = stream.local_addr() {
+        let mut state = cluster.state.lock();
+        let myself = state.myself.clone();
+        if let Some(node) = state
+            .nodes
+            .get_mut(&myself)
+            .filter(|node| node.ip.is_empty())
+        {
+            node.ip = local.ip().to_string();
+        }
+    }
+
+    let mut buffer = Vec::new();
+    let mut temp_buf = [0u8; 4096];
+    loop {
+        match stream.read(&mut temp_buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => buffer.extend_from_slice(&temp_buf[..n]),
+        }
+        loop {
+            let (request, len) = match parse_value(&buffer) {
+                Ok(Some(parsed)) => parsed,
+                Ok(None) => break,
+                Err(e) => {
+                    error!("Invalid cluster bus message from {}: {}", peer_ip, e);
+                    return;
+                }
+            };
+            buffer.drain(..len);
+            let Some(message) = Message::parse(&command_args(&request)) else {
+                error!("Invalid cluster bus message from {}", peer_ip);
+                return;
+            };
+            let pong = {
+                let mut state = cluster.state.lock();
+                let known = state.nodes.contains_key(&message.id);
+                state.process(&message, &peer_ip, None, Instant::now());
+                if !known && state.nodes.contains_key(&message.id) {
+                    info!("Node {} joined the cluster", message.id);
+                }
+                state.message(MessageKind::Pong)
+            };
+            if stream.write_all(&reply_bytes(&pong)).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Tek bir node'a PING (ya da MEET) gönderir ve PONG'unu işler.
+async fn ping(
+    cluster: &Cluster,
+    ip: &str,
+    bus_port: u16,
+    message: Message,
+    handshake_id: Option<String>,
+) {
+    let result = async {
+        let mut connection = Connection::connect(ip, bus_port, BUS_TIMEOUT).await?;
+        let args = message.to_args();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        connection.request(&args).await
+    }
+    .await;
+
+    let pong = match result {
+        Ok(reply) => Message::parse(&command_args(&reply)),
+        Err(e) => {
+            debug!("Cluster bus ping to {}:{} failed: {}", ip, bus_port, e);
+            return;
+        }
+    };
+    if let Some(pong) = pong {
+        let mut state = cluster.state.lock();
+        if handshake_id.is_some() {
+            info!("Handshake with {}:{} completed ({})", ip, bus_port, pong.id);
+        }
+        state.process(&pong, ip, handshake_id.as_deref(), Instant::now());
+    }
+}
+
+/// Gossip döngüsünü başlatır.
+pub fn start(cluster: Arc<Cluster>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(GOSSIP_PERIOD);
+        loop {
+            interval.tick().await;
+
+            let targets: Vec<_> = {
+                let mut state = cluster.state.lock();
+                state.remove_stale_handshakes(Instant::now());
+                state
+                    .nodes
+                    .values()
+                    .filter(|node| node.id != state.myself)
+                    .map(|node| {
+                        let kind = if node.handshake {
+                            MessageKind::Meet
+                        } else {
+                            MessageKind::Ping
+                        };
+                        (
+                            node.ip.clone(),
+                            node.bus_port,
+                            state.message(kind),
+                            node.handshake.then(|| node.id.clone()),
+                        )
+                    })
+                    .collect()
+            };
+
+            let mut pings = JoinSet::new();
+            for (ip, bus_port, message, handshake_id) in targets {
+                let cluster = Arc::clone(&cluster);
+                pings.spawn(async move {
+                    ping(&cluster, &ip, bus_port, message, handshake_id).await;
+                });
+            }
+            while pings.join_next().await.is_some() {}
+        }
+    });
+}
@@ -0,0 +1,45 @@
+//! Komutların hangi argümanlarının anahtar olduğu. Cluster yönlendirmesi bu anahtarların
+//! slot'una bakar; anahtar içermeyen komutlar her node'da yerel olarak çalışır. Anahtar
+//! konumları komut tablosunda tanımlıdır.
+
+use crate::commands::registry;
+
+/// Komutun anahtar argümanları (`cmd` büyük harfle, `args` komut adını içermez).
+/// Bilinmeyen komutlar anahtar içermez.
+pub fn command_keys<'a>(cmd: &str, args: &'a [String]) -> Vec<&'a str> {
+    registry::lookup(cmd).map_or_else(Vec::new, |command| command.keys(args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_command_keys() {
+        assert_eq!(command_keys("GET", &args(&["k"])), vec!["k"]);
+        assert!(command_keys("GET", &[]).is_empty());
+        assert!(command_keys("FT.SEARCH", &args(&["idx", "*"])).is_empty());
+        assert_eq!(
+            command_keys(
+                "XREAD",
+                &args(&["COUNT", "2", "STREAMS", "a", "b", "0", "0"])
+            ),
+            vec!["a", "b"]
+        );
+        assert_eq!(
+            command_keys(
+                "CMS.MERGE",
+                &args(&["d", "2", "s1", "s2", "WEIGHTS", "1", "2"])
+            ),
+            vec!["d", "s1", "s2"]
+        );
+        assert_eq!(
+            command_keys("TS.MADD", &args(&["a", "1", "5", "b", "1", "6"])),
+            vec!["a", "b"]
+        );
+    }
+}
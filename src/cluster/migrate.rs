@@ -0,0 +1,124 @@
+//! MIGRATE: anahtarları DUMP biçiminde başka bir node'a taşır.
+
+use crate::network::client::Connection;
+use crate::network::resp::RespValue;
+use std::time::Duration;
+
+/// Taşınacak tek bir anahtar.
+pub struct DumpedKey {
+    pub key: String,
+    pub payload: String,
+    /// Kalan TTL (ms); `None` ise süresiz
+    pub ttl_ms: Option<u64>,
+}
+
+/// MIGRATE argümanları: `host port key|"" db timeout [COPY] [REPLACE] [KEYS key ...]`.
+#[derive(Debug, PartialEq)]
+pub struct MigrateOptions {
+    pub host: String,
+    pub port: u16,
+    pub timeout: Duration,
+    pub copy: bool,
+    pub replace: bool,
+    pub keys: Vec<String>,
+}
+
+impl MigrateOptions {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let [host, port, key, db, timeout, options @ ..] = args else {
+            return Err("wrong number of arguments for 'MIGRATE' command".to_string());
+        };
+        let not_integer = || "value is not an integer or out of range".to_string();
+        let port = port.parse::<u16>().map_err(|_| not_integer())?;
+        if db.parse::<u64>().map_err(|_| not_integer())? != 0 {
+            return Err("invalid DB index".to_string());
+        }
+        let timeout = timeout.parse::<u64>().map_err(|_| not_integer())?;
+
+        let mut migrate = MigrateOptions {
+            host: host.clone(),
+            port,
+            // 0, Redis'te olduğu gibi varsayılan süre demektir
+            timeout: Duration::from_millis(if timeout == 0 { 1000 } else { timeout }),
+            copy: false,
+            replace: false,
+            keys: Vec::new(),
+        };
+        let mut i = 0;
+        while i < options.len() {
+            match options[i].to_uppercase().as_str() {
+                "COPY" => migrate.copy = true,
+                "REPLACE" => migrate.replace = true,
+                "KEYS" => {
+                    if !key.is_empty() {
+                        return Err(
+                            "When using MIGRATE KEYS option, the key argument must be set to the empty string"
+                                .to_string(),
+                        );
+                    }
+                    migrate.keys = options[i + 1..].to_vec();
+                    break;
+                }
+                _ => return Err("syntax error".to_string()),
+            }
+            i += 1;
+        }
+        if migrate.keys.is_empty() {
+            migrate.keys.push(key.clone());
+        }
+        Ok(migrate)
+    }
+}
+
+/// Anahtarları hedefe RESTORE eder. Hedef slot'u içe aktarıyor olabileceği için her
+/// RESTORE'dan önce ASKING gönderilir.
+pub async fn send(options: &MigrateOptions, keys: &[DumpedKey]) -> Result<(), String> {
+    let io_error = |e: std::io::Error| format!("IOERR error or timeout migrating keys: {}", e);
+    let mut target = Connection::connect(&options.host, options.port, options.timeout)
+        .await
+        .map_err(io_error)?;
+
+    for dumped in keys {
+        let ttl = dumped.ttl_ms.unwrap_or(0).to_string();
+        let mut restore = vec!["RESTORE", dumped.key.as_str(), &ttl, &dumped.payload];
+        if options.replace {
+            restore.push("REPLACE");
+        }
+        target.send(&["ASKING"]).await.map_err(io_error)?;
+        target.send(&restore).await.map_err(io_error)?;
+        // Cluster modunda olmayan hedef ASKING'i reddeder; önemli olan RESTORE'un cevabı
+        target.read_timeout().await.map_err(io_error)?;
+        if let RespValue::Error(e) = target.read_timeout().await.map_err(io_error)? {
+            return Err(format!("Target instance replied with error: {}", e));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let options = MigrateOptions::parse(&args(&[
+            "h", "7001", "", "0", "500", "COPY", "KEYS", "a", "b",
+        ]))
+        .unwrap();
+        assert_eq!(options.keys, vec!["a", "b"]);
+        assert!(options.copy && !options.replace);
+        assert_eq!(options.timeout, Duration::from_millis(500));
+
+        let options =
+            MigrateOptions::parse(&args(&["h", "7001", "k", "0", "0", "REPLACE"])).unwrap();
+        assert_eq!(options.keys, vec!["k"]);
+        assert!(options.replace);
+
+        assert!(MigrateOptions::parse(&args(&["h", "7001", "k", "1", "0"])).is_err());
+        assert!(MigrateOptions::parse(&args(&["h", "7001", "k", "0", "0", "KEYS", "a"])).is_err());
+    }
+}
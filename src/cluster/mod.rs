@@ -0,0 +1,951 @@
+//! Cluster modu: anahtar uzayı 16384 hash slot'a bölünür ve her slot bir node'a aittir.
+//!
+//! - Anahtarın slot'u `CRC16(anahtar) mod 16384`'tür. Anahtarda `{...}` varsa yalnızca
+//!   süslü parantez içi (hash tag) hesaba katılır; böylece ilişkili anahtarlar aynı slot'a düşer.
+//! - Başka bir node'a ait slot'a gelen istek `MOVED slot ip:port` ile yönlendirilir.
+//! - Taşınmakta olan (MIGRATING) slot'ta anahtar artık bizde değilse istemci
+//!   `ASK slot ip:port` ile hedefe gönderilir; hedef, önce ASKING gönderen istemciye
+//!   içe aktardığı (IMPORTING) slot'u bir seferlik açar.
+//! - Node'lar cluster bus üzerinden (`port + 10000`) birbirine PING/PONG gönderir. Her mesaj
+//!   gönderenin slot'larını ve bildiği diğer node'ları taşır; slot sahipliğindeki çakışmalarda
+//!   `config_epoch`'u büyük olan kazanır.
+
+pub mod bus;
+pub mod keys;
+pub mod migrate;
+
+use crate::network::resp::RespValue;
+use crate::replication::random_id;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub const CLUSTER_SLOTS: usize = 16384;
+/// Cluster bus portu, istemci portuna bu kadar eklenerek bulunur
+pub const BUS_PORT_OFFSET: u16 = 10000;
+/// Bu süre boyunca PONG göndermeyen node erişilemez (`fail?`) sayılır
+pub const NODE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// CRC16-CCITT (XMODEM), Redis Cluster ile aynı slot dağılımını verir.
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Anahtarın hash slot'u. İlk `{` ile ondan sonraki ilk `}` arası boş değilse
+/// yalnızca o kısım hash'lenir.
+pub fn key_slot(key: &str) -> u16 {
+    let bytes = key.as_bytes();
+    let tagged = bytes.iter().position(|&b| b == b'{').and_then(|start| {
+        let len = bytes[start + 1..].iter().position(|&b| b == b'}')?;
+        (len > 0).then(|| &bytes[start + 1..start + 1 + len])
+    });
+    crc16(tagged.unwrap_or(bytes)) % CLUSTER_SLOTS as u16
+}
+
+/// Anahtarların ortak slot'u; farklı slot'lara düşüyorlarsa CROSSSLOT hatası.
+pub fn keys_slot(keys: &[&str]) -> Result<Option<u16>, String> {
+    let mut slots = keys.iter().map(|key| key_slot(key));
+    let Some(slot) = slots.next() else {
+        return Ok(None);
+    };
+    if slots.any(|other| other != slot) {
+        return Err("CROSSSLOT Keys in request don't hash to the same slot".to_string());
+    }
+    Ok(Some(slot))
+}
+
+fn parse_slot(value: &str) -> Result<u16, String> {
+    value
+        .parse::<u16>()
+        .ok()
+        .filter(|slot| (*slot as usize) < CLUSTER_SLOTS)
+        .ok_or_else(|| "Invalid or out of range slot".to_string())
+}
+
+/// Sıralı slot listesini `(başlangıç, bitiş)` aralıklarına çevirir.
+fn to_ranges(slots: impl Iterator<Item = u16>) -> Vec<(u16, u16)> {
+    let mut ranges: Vec<(u16, u16)> = Vec::new();
+    for slot in slots {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == slot => *end = slot,
+            _ => ranges.push((slot, slot)),
+        }
+    }
+    ranges
+}
+
+fn unix_ms(at: Instant) -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    now.saturating_sub(at.elapsed()).as_millis() as u64
+}
+
+/// Cluster'daki bir node.
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub id: String,
+    /// Boşsa henüz bilinmiyor (kendi adresimizi ilk gelen bus bağlantısından öğreniriz)
+    pub ip: String,
+    pub port: u16,
+    pub bus_port: u16,
+    /// Slot'larını en son hangi dönemde aldığı; çakışmada büyük olan kazanır
+    pub config_epoch: u64,
+    pub last_pong: Option<Instant>,
+    pub added: Instant,
+    /// CLUSTER MEET ile eklendi, henüz gerçek kimliğini bildirmedi
+    pub handshake: bool,
+}
+
+impl Node {
+    fn new(id: String, ip: String, port: u16, bus_port: u16, now: Instant) -> Self {
+        Node {
+            id,
+            ip,
+            port,
+            bus_port,
+            config_epoch: 0,
+            last_pong: None,
+            added: now,
+            handshake: false,
+        }
+    }
+
+    pub fn addr(&self) -> String {
+        format!("{}:{}", self.ip, self.port)
+    }
+
+    /// `NODE_TIMEOUT` boyunca haber alınamadı mı?
+    pub fn failing(&self, now: Instant) -> bool {
+        let since = self.last_pong.unwrap_or(self.added);
+        now.duration_since(since) > NODE_TIMEOUT
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    Ping,
+    Pong,
+    /// Alıcı, göndereni tanımasa da node listesine ekler
+    Meet,
+}
+
+impl MessageKind {
+    fn name(self) -> &'static str {
+        match self {
+            MessageKind::Ping => "PING",
+            MessageKind::Pong => "PONG",
+            MessageKind::Meet => "MEET",
+        }
+    }
+}
+
+/// Gossip ile tanıtılan başka bir node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GossipEntry {
+    pub id: String,
+    pub ip: String,
+    pub port: u16,
+    pub bus_port: u16,
+}
+
+/// Cluster bus mesajı: gönderenin durumu ve bildiği diğer node'lar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub kind: MessageKind,
+    pub id: String,
+    pub ip: String,
+    pub port: u16,
+    pub bus_port: u16,
+    pub current_epoch: u64,
+    pub config_epoch: u64,
+    pub slots: Vec<(u16, u16)>,
+    pub gossip: Vec<GossipEntry>,
+}
+
+impl Message {
+    /// `kind id ip port bus_port current_epoch config_epoch slots [id ip port bus_port ...]`;
+    /// slot'lar `0-100,205` biçimindedir.
+    pub fn to_args(&self) -> Vec<String> {
+        let slots = self
+            .slots
+            .iter()
+            .map(|&(start, end)| {
+                if start == end {
+                    start.to_string()
+                } else {
+                    format!("{}-{}", start, end)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut args = vec![
+            self.kind.name().to_string(),
+            self.id.clone(),
+            self.ip.clone(),
+            self.port.to_string(),
+            self.bus_port.to_string(),
+            self.current_epoch.to_string(),
+            self.config_epoch.to_string(),
+            slots,
+        ];
+        for entry in &self.gossip {
+            args.extend([
+                entry.id.clone(),
+                entry.ip.clone(),
+                entry.port.to_string(),
+                entry.bus_port.to_string(),
+            ]);
+        }
+        args
+    }
+
+    pub fn parse(args: &[&str]) -> Option<Message> {
+        let [kind, id, ip, port, bus_port, current_epoch, config_epoch, slots, gossip @ ..] = args
+        else {
+            return None;
+        };
+        let kind = match kind.to_uppercase().as_str() {
+            "PING" => MessageKind::Ping,
+            "PONG" => MessageKind::Pong,
+            "MEET" => MessageKind::Meet,
+            _ => return None,
+        };
+        let slots = slots
+            .split(',')
+            .filter(|range| !range.is_empty())
+            .map(|range| {
+                let (start, end) = range.split_once('-').unwrap_or((range, range));
+                Some((parse_slot(start).ok()?, parse_slot(end).ok()?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if gossip.len() % 4 != 0 {
+            return None;
+        }
+        let gossip = gossip
+            .chunks(4)
+            .map(|entry| {
+                Some(GossipEntry {
+                    id: entry[0].to_string(),
+                    ip: entry[1].to_string(),
+                    port: entry[2].parse().ok()?,
+                    bus_port: entry[3].parse().ok()?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Message {
+            kind,
+            id: id.to_string(),
+            ip: ip.to_string(),
+            port: port.parse().ok()?,
+            bus_port: bus_port.parse().ok()?,
+            current_epoch: current_epoch.parse().ok()?,
+            config_epoch: config_epoch.parse().ok()?,
+            slots,
+            gossip,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct State {
+    pub current_epoch: u64,
+    pub myself: String,
+    /// Kendimiz dahil bilinen tüm node'lar
+    pub nodes: HashMap<String, Node>,
+    /// Slot → sahibi olan node
+    pub slots: Vec<Option<String>>,
+    /// Bizden başka node'a taşınmakta olan slot'lar (hedef node)
+    pub migrating: HashMap<u16, String>,
+    /// Başka node'dan bize taşınmakta olan slot'lar (kaynak node)
+    pub importing: HashMap<u16, String>,
+}
+
+impl State {
+    pub fn new(id: String, ip: String, port: u16, now: Instant) -> Self {
+        let myself = Node::new(id.clone(), ip, port, port + BUS_PORT_OFFSET, now);
+        State {
+            current_epoch: 0,
+            myself: id.clone(),
+            nodes: HashMap::from([(id, myself)]),
+            slots: vec![None; CLUSTER_SLOTS],
+            migrating: HashMap::new(),
+            importing: HashMap::new(),
+        }
+    }
+
+    pub fn myself(&self) -> &Node {
+        &self.nodes[&self.myself]
+    }
+
+    fn myself_mut(&mut self) -> &mut Node {
+        self.nodes
+            .get_mut(&self.myself)
+            .expect("myself is always known")
+    }
+
+    pub fn owned_ranges(&self, id: &str) -> Vec<(u16, u16)> {
+        to_ranges(
+            (0..CLUSTER_SLOTS as u16)
+                .filter(|&slot| self.slots[slot as usize].as_deref() == Some(id)),
+        )
+    }
+
+    /// Kendi durumumuzu anlatan mesaj.
+    pub fn message(&self, kind: MessageKind) -> Message {
+        let myself = self.myself();
+        Message {
+            kind,
+            id: myself.id.clone(),
+            ip: myself.ip.clone(),
+            port: myself.port,
+            bus_port: myself.bus_port,
+            current_epoch: self.current_epoch,
+            config_epoch: myself.config_epoch,
+            slots: self.owned_ranges(&myself.id),
+            gossip: self
+                .nodes
+                .values()
+                .filter(|node| node.id != self.myself && !node.handshake)
+                .map(|node| GossipEntry {
+                    id: node.id.clone(),
+                    ip: node.ip.clone(),
+                    port: node.port,
+                    bus_port: node.bus_port,
+                })
+                .collect(),
+        }
+    }
+
+    /// Gelen mesajı işler. `peer_ip` bağlantının karşı ucudur; gönderen kendi adresini
+    /// bilmiyorsa onu kullanırız. `handshake_id`, MEET ile eklenmiş ve bu mesajla gerçek
+    /// kimliğini bildiren node'un geçici kimliğidir.
+    pub fn process(
+        &mut self,
+        message: &Message,
+        peer_ip: &str,
+        handshake_id: Option<&str>,
+        now: Instant,
+    ) {
+        if let Some(temp_id) = handshake_id.filter(|temp_id| *temp_id != message.id) {
+            self.nodes.remove(temp_id);
+        }
+        if message.id == self.myself {
+            return;
+        }
+        let trusted = message.kind == MessageKind::Meet || handshake_id.is_some();
+        if !self.nodes.contains_key(&message.id) && !trusted {
+            return;
+        }
+        self.current_epoch = self.current_epoch.max(message.current_epoch);
+
+        let ip = if message.ip.is_empty() {
+            peer_ip.to_string()
+        } else {
+            message.ip.clone()
+        };
+        let node = self
+            .nodes
+            .entry(message.id.clone())
+            .or_insert_with(|| Node::new(message.id.clone(), String::new(), 0, 0, now));
+        node.ip = ip;
+        node.port = message.port;
+        node.bus_port = message.bus_port;
+        node.config_epoch = message.config_epoch;
+        node.last_pong = Some(now);
+        node.handshake = false;
+
+        // Aynı config epoch'a sahip iki node olmamalı: kimliği küçük olan yeni bir dönem alır
+        if message.config_epoch == self.myself().config_epoch && self.myself < message.id {
+            self.current_epoch += 1;
+            let epoch = self.current_epoch;
+            self.myself_mut().config_epoch = epoch;
+        }
+
+        for &(start, end) in &message.slots {
+            for slot in start..=end {
+                let owner_epoch = match &self.slots[slot as usize] {
+                    Some(owner) if *owner == message.id => continue,
+                    Some(owner) => self.nodes.get(owner).map_or(0, |node| node.config_epoch),
+                    None => 0,
+                };
+                if self.slots[slot as usize].is_none() || message.config_epoch > owner_epoch {
+                    if self.slots[slot as usize].as_deref() == Some(self.myself.as_str()) {
+                        self.migrating.remove(&slot);
+                    }
+                    self.importing.remove(&slot);
+                    self.slots[slot as usize] = Some(message.id.clone());
+                }
+            }
+        }
+
+        for entry in &message.gossip {
+            if entry.id != self.myself && !self.nodes.contains_key(&entry.id) {
+                self.nodes.insert(
+                    entry.id.clone(),
+                    Node::new(
+                        entry.id.clone(),
+                        entry.ip.clone(),
+                        entry.port,
+                        entry.bus_port,
+                        now,
+                    ),
+                );
+            }
+        }
+    }
+
+    /// MEET gönderilecek node'u geçici bir kimlikle ekler.
+    pub fn meet(&mut self, ip: String, port: u16, bus_port: u16, now: Instant) {
+        let id = random_id();
+        let mut node = Node::new(id.clone(), ip, port, bus_port, now);
+        node.handshake = true;
+        self.nodes.insert(id, node);
+    }
+
+    /// Kimliğini bildirmeyen MEET adaylarını ve geçersiz kalan kayıtları temizler.
+    pub fn remove_stale_handshakes(&mut self, now: Instant) {
+        self.nodes
+            .retain(|_, node| !node.handshake || !node.failing(now));
+    }
+
+    pub fn add_slots(&mut self, slots: &[u16]) -> Result<(), String> {
+        for &slot in slots {
+            if self.slots[slot as usize].is_some() {
+                return Err(format!("Slot {} is already busy", slot));
+            }
+        }
+        for &slot in slots {
+            self.slots[slot as usize] = Some(self.myself.clone());
+            self.importing.remove(&slot);
+        }
+        Ok(())
+    }
+
+    pub fn del_slots(&mut self, slots: &[u16]) -> Result<(), String> {
+        for &slot in slots {
+            if self.slots[slot as usize].is_none() {
+                return Err(format!("Slot {} is already unassigned", slot));
+            }
+        }
+        for &slot in slots {
+            self.slots[slot as usize] = None;
+            self.migrating.remove(&slot);
+        }
+        Ok(())
+    }
+
+    fn known_node(&self, id: &str) -> Result<(), String> {
+        match self.nodes.get(id) {
+            Some(node) if !node.handshake => Ok(()),
+            _ => Err(format!("I don't know about node {}", id)),
+        }
+    }
+
+    /// CLUSTER SETSLOT slot IMPORTING|MIGRATING|NODE|STABLE [node].
+    pub fn set_slot(&mut self, slot: u16, action: &str, node: Option<&str>) -> Result<(), String> {
+        let owner = self.slots[slot as usize].clone();
+        let mine = owner.as_deref() == Some(self.myself.as_str());
+        match (action.to_uppercase().as_str(), node) {
+            ("MIGRATING", Some(target)) => {
+                if !mine {
+                    return Err(format!("I'm not the owner of hash slot {}", slot));
+                }
+                self.known_node(target)?;
+                self.migrating.insert(slot, target.to_string());
+            }
+            ("IMPORTING", Some(source)) => {
+                if mine {
+                    return Err(format!("I'm already the owner of hash slot {}", slot));
+                }
+                self.known_node(source)?;
+                self.importing.insert(slot, source.to_string());
+            }
+            ("STABLE", None) => {
+                self.migrating.remove(&slot);
+                self.importing.remove(&slot);
+            }
+            ("NODE", Some(id)) => {
+                self.known_node(id)?;
+                if id == self.myself {
+                    // Taşıma bitti: yeni sahipliğin diğer node'lardaki eski bilgiyi
+                    // ezmesi için onay beklemeden yeni bir config epoch alınır
+                    if self.importing.remove(&slot).is_some() {
+                        self.current_epoch += 1;
+                        let epoch = self.current_epoch;
+                        self.myself_mut().config_epoch = epoch;
+                    }
+                } else if mine {
+                    self.migrating.remove(&slot);
+                }
+                self.slots[slot as usize] = Some(id.to_string());
+            }
+            _ => return Err("Invalid CLUSTER SETSLOT action or number of arguments".to_string()),
+        }
+        Ok(())
+    }
+
+    /// Slot'taki isteğin bu node'da çalışıp çalışamayacağına karar verir. `missing`,
+    /// istekteki `total` anahtardan bu node'da bulunmayanların sayısıdır ve yalnızca
+    /// taşınmakta olan slot'lar için önemlidir.
+    pub fn route(
+        &self,
+        slot: u16,
+        asking: bool,
+        missing: usize,
+        total: usize,
+    ) -> Result<(), String> {
+        match self.slots[slot as usize].as_deref() {
+            Some(owner) if owner == self.myself => match self.migrating.get(&slot) {
+                Some(target) if missing > 0 && missing == total => {
+                    Err(format!("ASK {} {}", slot, self.nodes[target].addr()))
+                }
+                Some(_) if missing > 0 => {
+                    Err("TRYAGAIN Multiple keys request during rehashing of slot".to_string())
+                }
+                _ => Ok(()),
+            },
+            _ if asking && self.importing.contains_key(&slot) => Ok(()),
+            Some(owner) => Err(format!("MOVED {} {}", slot, self.nodes[owner].addr())),
+            None => Err("CLUSTERDOWN Hash slot not served".to_string()),
+        }
+    }
+
+    /// Tüm slot'lar erişilebilir node'lara atanmış mı?
+    pub fn is_ok(&self, now: Instant) -> bool {
+        self.slots.iter().all(|owner| {
+            owner
+                .as_ref()
+                .and_then(|owner| self.nodes.get(owner))
+                .is_some_and(|node| node.id == self.myself || !node.failing(now))
+        })
+    }
+
+    fn sorted_nodes(&self) -> Vec<&Node> {
+        let mut nodes: Vec<&Node> = self.nodes.values().collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        nodes
+    }
+
+    pub fn info(&self, now: Instant) -> String {
+        let assigned = self.slots.iter().filter(|owner| owner.is_some()).count();
+        let pfail = self
+            .slots
+            .iter()
+            .flatten()
+            .filter(|owner| {
+                **owner != self.myself && self.nodes.get(*owner).is_some_and(|n| n.failing(now))
+            })
+            .count();
+        let size = self
+            .nodes
+            .keys()
+            .filter(|id| self.slots.iter().any(|owner| owner.as_ref() == Some(*id)))
+            .count();
+        [
+            format!(
+                "cluster_state:{}",
+                if self.is_ok(now) { "ok" } else { "fail" }
+            ),
+            format!("cluster_slots_assigned:{}", assigned),
+            format!("cluster_slots_ok:{}", assigned - pfail),
+            format!("cluster_slots_pfail:{}", pfail),
+            "cluster_slots_fail:0".to_string(),
+            format!("cluster_known_nodes:{}", self.nodes.len()),
+            format!("cluster_size:{}", size),
+            format!("cluster_current_epoch:{}", self.current_epoch),
+            format!("cluster_my_epoch:{}", self.myself().config_epoch),
+        ]
+        .iter()
+        .map(|line| format!("{}\r\n", line))
+        .collect()
+    }
+
+    /// CLUSTER NODES çıktısı: her node için bir satır.
+    pub fn nodes_reply(&self, now: Instant) -> String {
+        let mut out = String::new();
+        for node in self.sorted_nodes() {
+            let myself = node.id == self.myself;
+            let mut flags = Vec::new();
+            if myself {
+                flags.push("myself");
+            }
+            flags.push("master");
+            if node.handshake {
+                flags.push("handshake");
+            }
+            if !myself && node.failing(now) {
+                flags.push("fail?");
+            }
+            let pong = if myself {
+                0
+            } else {
+                node.last_pong.map_or(0, unix_ms)
+            };
+            let link = if myself || !node.failing(now) {
+                "connected"
+            } else {
+                "disconnected"
+            };
+            out.push_str(&format!(
+                "{} {}:{}@{} {} - 0 {} {} {}",
+                node.id,
+                node.ip,
+                node.port,
+                node.bus_port,
+                flags.join(","),
+                pong,
+                node.config_epoch,
+                link
+            ));
+            for (start, end) in self.owned_ranges(&node.id) {
+                if start == end {
+                    out.push_str(&format!(" {}", start));
+                } else {
+                    out.push_str(&format!(" {}-{}", start, end));
+                }
+            }
+            if myself {
+                let mut migrating: Vec<_> = self.migrating.iter().collect();
+                migrating.sort();
+                for (slot, target) in migrating {
+                    out.push_str(&format!(" [{}->-{}]", slot, target));
+                }
+                let mut importing: Vec<_> = self.importing.iter().collect();
+                importing.sort();
+                for (slot, source) in importing {
+                    out.push_str(&format!(" [{}-<-{}]", slot, source));
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// CLUSTER SLOTS: `[başlangıç, bitiş, [ip, port, id]]` listesi.
+    pub fn slots_reply(&self) -> RespValue {
+        let mut ranges: Vec<(u16, u16, &Node)> = self
+            .nodes
+            .values()
+            .flat_map(|node| {
+                self.owned_ranges(&node.id)
+                    .into_iter()
+                    .map(move |(start, end)| (start, end, node))
+            })
+            .collect();
+        ranges.sort_by_key(|(start, _, _)| *start);
+        RespValue::Array(Some(
+            ranges
+                .into_iter()
+                .map(|(start, end, node)| {
+                    RespValue::Array(Some(vec![
+                        RespValue::Integer(start as i64),
+                        RespValue::Integer(end as i64),
+                        RespValue::Array(Some(vec![
+                            RespValue::BulkString(Some(node.ip.clone())),
+                            RespValue::Integer(node.port as i64),
+                            RespValue::BulkString(Some(node.id.clone())),
+                        ])),
+                    ]))
+                })
+                .collect(),
+        ))
+    }
+
+    /// CLUSTER SHARDS: her node bir shard'dır (replika desteği yok).
+    pub fn shards_reply(&self, now: Instant) -> RespValue {
+        let bulk = |s: &str| RespValue::BulkString(Some(s.to_string()));
+        RespValue::Array(Some(
+            self.sorted_nodes()
+                .into_iter()
+                .filter(|node| !node.handshake)
+                .map(|node| {
+                    let slots = self
+                        .owned_ranges(&node.id)
+                        .into_iter()
+                        .flat_map(|(start, end)| {
+                            [
+                                RespValue::Integer(start as i64),
+                                RespValue::Integer(end as i64),
+                            ]
+                        })
+                        .collect();
+                    let healthy = node.id == self.myself || !node.failing(now);
+                    let description = vec![
+                        bulk("id"),
+                        bulk(&node.id),
+                        bulk("port"),
+                        RespValue::Integer(node.port as i64),
+                        bulk("ip"),
+                        bulk(&node.ip),
+                        bulk("endpoint"),
+                        bulk(&node.ip),
+                        bulk("role"),
+                        bulk("master"),
+                        bulk("replication-offset"),
+                        RespValue::Integer(0),
+                        bulk("health"),
+                        bulk(if healthy { "online" } else { "fail" }),
+                    ];
+                    RespValue::Array(Some(vec![
+                        bulk("slots"),
+                        RespValue::Array(Some(slots)),
+                        bulk("nodes"),
+                        RespValue::Array(Some(vec![RespValue::Array(Some(description))])),
+                    ]))
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// Cluster modunda çalışan sunucunun paylaşılan cluster durumu.
+pub struct Cluster {
+    pub state: Mutex<State>,
+}
+
+impl Cluster {
+    /// `ip` boşsa kendi adresimizi diğer node'lardan gelen ilk bağlantıdan öğreniriz.
+    pub fn new(ip: String, port: u16) -> Self {
+        Cluster {
+            state: Mutex::new(State::new(random_id(), ip, port, Instant::now())),
+        }
+    }
+
+    pub fn bus_port(&self) -> u16 {
+        self.state.lock().myself().bus_port
+    }
+
+    /// Anahtarların slot'u taşınmaktaysa `true`; bu durumda yönlendirme kararı için
+    /// anahtarların bu node'da olup olmadığı gerekir.
+    pub fn is_migrating(&self, slot: u16) -> bool {
+        self.state.lock().migrating.contains_key(&slot)
+    }
+
+    pub fn route(
+        &self,
+        slot: u16,
+        asking: bool,
+        missing: usize,
+        total: usize,
+    ) -> Result<(), String> {
+        self.state.lock().route(slot, asking, missing, total)
+    }
+
+    /// Veritabanına dokunmayan CLUSTER alt komutlarını çalıştırır.
+    pub fn command(&self, args: &[String]) -> RespValue {
+        match self.try_command(args) {
+            Ok(reply) => reply,
+            Err(e) => RespValue::Error(e),
+        }
+    }
+
+    fn try_command(&self, args: &[String]) -> Result<RespValue, String> {
+        let ok = || RespValue::SimpleString("OK".to_string());
+        let Some(subcommand) = args.first().map(|s| s.to_uppercase()) else {
+            return Err("wrong number of arguments for 'CLUSTER' command".to_string());
+        };
+        let wrong_args = || {
+            format!(
+                "wrong number of arguments for 'CLUSTER {}' command",
+                subcommand
+            )
+        };
+        let now = Instant::now();
+        let mut state = self.state.lock();
+
+        match subcommand.as_str() {
+            "INFO" => Ok(RespValue::BulkString(Some(state.info(now)))),
+            "MYID" => Ok(RespValue::BulkString(Some(state.myself.clone()))),
+            "NODES" => Ok(RespValue::BulkString(Some(state.nodes_reply(now)))),
+            "SLOTS" => Ok(state.slots_reply()),
+            "SHARDS" => Ok(state.shards_reply(now)),
+            "KEYSLOT" => {
+                let key = args.get(1).ok_or_else(wrong_args)?;
+                Ok(RespValue::Integer(key_slot(key) as i64))
+            }
+            "MEET" => {
+                let (Some(ip), Some(port)) = (args.get(1), args.get(2)) else {
+                    return Err(wrong_args());
+                };
+                let invalid = || format!("Invalid node address specified: {}:{}", ip, port);
+                let port: u16 = port.parse().map_err(|_| invalid())?;
+                let bus_port = match args.get(3) {
+                    Some(bus_port) => bus_port.parse().map_err(|_| invalid())?,
+                    None => port.checked_add(BUS_PORT_OFFSET).ok_or_else(invalid)?,
+                };
+                state.meet(ip.clone(), port, bus_port, now);
+                Ok(ok())
+            }
+            "ADDSLOTS" | "DELSLOTS" => {
+                if args.len() < 2 {
+                    return Err(wrong_args());
+                }
+                let slots = args[1..]
+                    .iter()
+                    .map(|slot| parse_slot(slot))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if subcommand == "ADDSLOTS" {
+                    state.add_slots(&slots)?;
+                } else {
+                    state.del_slots(&slots)?;
+                }
+                Ok(ok())
+            }
+            "ADDSLOTSRANGE" => {
+                if args.len() < 3 || args.len().is_multiple_of(2) {
+                    return Err(wrong_args());
+                }
+                let mut slots = Vec::new();
+                for pair in args[1..].chunks(2) {
+                    let (start, end) = (parse_slot(&pair[0])?, parse_slot(&pair[1])?);
+                    if start > end {
+                        return Err(format!(
+                            "start slot number {} is greater than end slot number {}",
+                            start, end
+                        ));
+                    }
+                    slots.extend(start..=end);
+                }
+                state.add_slots(&slots)?;
+                Ok(ok())
+            }
+            "SETSLOT" => {
+                let (Some(slot), Some(action)) = (args.get(1), args.get(2)) else {
+                    return Err(wrong_args());
+                };
+                let slot = parse_slot(slot)?;
+                state.set_slot(slot, action, args.get(3).map(String::as_str))?;
+                Ok(ok())
+            }
+            _ => Err(format!("unknown CLUSTER subcommand '{}'", args[0])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(id: &str, port: u16) -> State {
+        State::new(
+            id.to_string(),
+            "127.0.0.1".to_string(),
+            port,
+            Instant::now(),
+        )
+    }
+
+    #[test]
+    fn test_key_slot() {
+        // Redis Cluster ile aynı değerler
+        assert_eq!(crc16(b"123456789"), 0x31C3);
+        assert_eq!(key_slot("foo"), 12182);
+        assert_eq!(key_slot("{user1000}.following"), key_slot("user1000"));
+        // Boş tag ve kapanmayan parantez tüm anahtarı hash'ler
+        assert_eq!(key_slot("foo{}{bar}"), crc16(b"foo{}{bar}") % 16384);
+        assert_eq!(key_slot("foo{bar"), crc16(b"foo{bar") % 16384);
+        assert_eq!(keys_slot(&["{a}1", "{a}2"]).unwrap(), Some(key_slot("a")));
+        assert!(keys_slot(&["a", "b"]).unwrap_err().starts_with("CROSSSLOT"));
+    }
+
+    #[test]
+    fn test_message_round_trip() {
+        let mut a = state("a", 7000);
+        a.add_slots(&[0, 1, 2, 10]).unwrap();
+        let message = a.message(MessageKind::Ping);
+        assert_eq!(message.slots, vec![(0, 2), (10, 10)]);
+        let args = message.to_args();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        assert_eq!(Message::parse(&args), Some(message));
+        assert_eq!(Message::parse(&["PING", "a"]), None);
+    }
+
+    #[test]
+    fn test_meet_and_gossip() {
+        let now = Instant::now();
+        let mut a = state("a", 7000);
+        let mut b = state("b", 7001);
+        let mut c = state("c", 7002);
+        a.add_slots(&[1]).unwrap();
+
+        // B, A'yı MEET ile tanır; A cevaptaki PONG ile geçici kaydı gerçek kimliğe çevirir
+        a.meet("127.0.0.1".to_string(), 7001, 17001, now);
+        let temp_id = a.nodes.values().find(|n| n.handshake).unwrap().id.clone();
+        b.process(&a.message(MessageKind::Meet), "127.0.0.1", None, now);
+        a.process(
+            &b.message(MessageKind::Pong),
+            "127.0.0.1",
+            Some(&temp_id),
+            now,
+        );
+        assert!(a.nodes.contains_key("b") && !a.nodes.contains_key(&temp_id));
+        assert_eq!(b.slots[1].as_deref(), Some("a"));
+
+        // Tanımadığı node'dan gelen PING yok sayılır, gossip ile öğrenilen node eklenir
+        c.process(&a.message(MessageKind::Ping), "127.0.0.1", None, now);
+        assert_eq!(c.nodes.len(), 1);
+        c.process(&a.message(MessageKind::Meet), "127.0.0.1", None, now);
+        assert!(c.nodes.contains_key("a") && c.nodes.contains_key("b"));
+    }
+
+    #[test]
+    fn test_higher_config_epoch_wins_slot() {
+        let now = Instant::now();
+        let mut a = state("a", 7000);
+        let mut b = state("b", 7001);
+        a.add_slots(&[5]).unwrap();
+        b.process(&a.message(MessageKind::Meet), "127.0.0.1", None, now);
+        a.process(&b.message(MessageKind::Meet), "127.0.0.1", None, now);
+        b.process(&a.message(MessageKind::Ping), "127.0.0.1", None, now);
+
+        // Slot 5, B'ye taşınır
+        a.set_slot(5, "MIGRATING", Some("b")).unwrap();
+        b.set_slot(5, "IMPORTING", Some("a")).unwrap();
+        b.set_slot(5, "NODE", Some("b")).unwrap();
+        assert!(b.myself().config_epoch > a.myself().config_epoch);
+
+        a.process(&b.message(MessageKind::Ping), "127.0.0.1", None, now);
+        assert_eq!(a.slots[5].as_deref(), Some("b"));
+        assert!(a.migrating.is_empty());
+        assert_eq!(
+            a.route(5, false, 0, 1).unwrap_err(),
+            "MOVED 5 127.0.0.1:7001"
+        );
+    }
+
+    #[test]
+    fn test_route() {
+        let now = Instant::now();
+        let mut a = state("a", 7000);
+        let b = state("b", 7001);
+        a.process(&b.message(MessageKind::Meet), "127.0.0.1", None, now);
+        assert_eq!(
+            a.route(0, false, 0, 1).unwrap_err(),
+            "CLUSTERDOWN Hash slot not served"
+        );
+
+        a.add_slots(&[0]).unwrap();
+        assert!(a.route(0, false, 1, 1).is_ok());
+        a.set_slot(0, "MIGRATING", Some("b")).unwrap();
+        assert!(a.route(0, false, 0, 2).is_ok());
+        assert_eq!(a.route(0, false, 1, 1).unwrap_err(), "ASK 0 127.0.0.1:7001");
+        assert!(a.route(0, false, 1, 2).unwrap_err().starts_with("TRYAGAIN"));
+
+        // İçe aktarılan slot yalnızca ASKING sonrası açılır
+        a.set_slot(1, "IMPORTING", Some("b")).unwrap();
+        assert!(a.route(1, false, 0, 1).is_err());
+        assert!(a.route(1, true, 0, 1).is_ok());
+    }
+}
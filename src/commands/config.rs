@@ -0,0 +1,739 @@
+//! `CONFIG`: ayarların sunucu çalışırken okunması ve değiştirilmesi. Her ayar Redis'teki
+//! adıyla `Config`'teki bir alana bağlanır; `CONFIG SET` değeri doğrular ve sunucuya hemen
+//! uygular, `CONFIG REWRITE` yeni değerleri yorumlara dokunmadan TOML dosyasına yazar.
+
+use super::registry::Call;
+use super::{ok, strings_reply, CommandResult, Interpreter};
+use crate::acl::user::glob_match;
+use crate::clients::Clients;
+use crate::config::Config;
+use crate::network::limits::OutputLimit;
+use crate::network::tls::{Tls, TlsConfig};
+use crate::observability::{logging, stats::STATS};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use toml_edit::{DocumentMut, Item, TableLike, Value};
+
+/// Değeri doğrular ve config'e yazar
+type Setter = fn(&mut Config, &str) -> Result<(), String>;
+
+/// CONFIG REWRITE'ın dosyaya yazdığı anahtarlar; `None` anahtarın silineceğini gösterir
+type TomlFields = Vec<(&'static str, Option<Value>)>;
+
+struct Param {
+    name: &'static str,
+    get: fn(&Config) -> String,
+    /// Değiştirilemeyen ayarlarda `None`
+    set: Option<Setter>,
+    /// Ayarın TOML'daki karşılığı
+    toml: fn(&Config) -> TomlFields,
+}
+
+/// Yalnızca okunabilen ayar; dinleyiciler açıldıktan sonra değiştirilemez
+const fn fixed(name: &'static str, get: fn(&Config) -> String) -> Param {
+    Param {
+        name,
+        get,
+        set: None,
+        toml: |_| Vec::new(),
+    }
+}
+
+static PARAMS: &[Param] = &[
+    fixed("bind", |c| c.server.bind_address.clone()),
+    fixed("port", |c| c.server.port.to_string()),
+    fixed("tls-port", |c| {
+        c.server.tls.as_ref().map_or(0, |tls| tls.port).to_string()
+    }),
+    fixed("unixsocket", |c| {
+        c.server
+            .unix_socket
+            .as_ref()
+            .map_or_else(String::new, |unix| unix.path.clone())
+    }),
+    fixed("unixsocketperm", |c| {
+        c.server
+            .unix_socket
+            .as_ref()
+            .and_then(|unix| unix.permissions.clone())
+            .unwrap_or_else(|| "0".to_string())
+    }),
+    Param {
+        name: "maxclients",
+        get: |c| c.server.max_connections.to_string(),
+        set: Some(|c, value| {
+            c.server.max_connections = integer(value, 1, u32::MAX as u64)? as usize;
+            Ok(())
+        }),
+        toml: |c| {
+            vec![(
+                "server.max_connections",
+                Some(int(c.server.max_connections)),
+            )]
+        },
+    },
+    Param {
+        name: "tls-cert-file",
+        get: |c| tls_field(c, |tls| tls.cert_file.clone()),
+        set: Some(|c, value| {
+            tls_mut(c)?.cert_file = value.to_string();
+            Ok(())
+        }),
+        toml: |c| tls_toml(c, "server.tls.cert_file", |tls| Some(&tls.cert_file)),
+    },
+    Param {
+        name: "tls-key-file",
+        get: |c| tls_field(c, |tls| tls.key_file.clone()),
+        set: Some(|c, value| {
+            tls_mut(c)?.key_file = value.to_string();
+            Ok(())
+        }),
+        toml: |c| tls_toml(c, "server.tls.key_file", |tls| Some(&tls.key_file)),
+    },
+    Param {
+        name: "tls-ca-cert-file",
+        get: |c| tls_field(c, |tls| tls.ca_file.clone().unwrap_or_default()),
+        set: Some(|c, value| {
+            // Boş değer istemci sertifikası doğrulamasını kapatır
+            tls_mut(c)?.ca_file = Some(value.to_string()).filter(|path| !path.is_empty());
+            Ok(())
+        }),
+        toml: |c| tls_toml(c, "server.tls.ca_file", |tls| tls.ca_file.as_ref()),
+    },
+    Param {
+        name: "client-query-buffer-limit",
+        get: |c| c.server.limits.query_buffer.to_string(),
+        set: Some(|c, value| {
+            c.server.limits.query_buffer = memory(value)?;
+            Ok(())
+        }),
+        toml: |c| {
+            vec![(
+                "server.limits.query_buffer",
+                Some(int(c.server.limits.query_buffer)),
+            )]
+        },
+    },
+    Param {
+        name: "proto-max-bulk-len",
+        get: |c| c.server.limits.max_bulk_len.to_string(),
+        set: Some(|c, value| {
+            c.server.limits.max_bulk_len = memory(value)?;
+            Ok(())
+        }),
+        toml: |c| {
+            vec![(
+                "server.limits.max_bulk_len",
+                Some(int(c.server.limits.max_bulk_len)),
+            )]
+        },
+    },
+    Param {
+        name: "proto-max-multibulk-len",
+        get: |c| c.server.limits.max_multibulk_len.to_string(),
+        set: Some(|c, value| {
+            c.server.limits.max_multibulk_len = integer(value, 1, u32::MAX as u64)? as usize;
+            Ok(())
+        }),
+        toml: |c| {
+            vec![(
+                "server.limits.max_multibulk_len",
+                Some(int(c.server.limits.max_multibulk_len)),
+            )]
+        },
+    },
+    Param {
+        name: "client-output-buffer-limit",
+        get: |c| {
+            let output = &c.server.limits.output;
+            [
+                ("normal", &output.normal),
+                ("replica", &output.replica),
+                ("pubsub", &output.pubsub),
+            ]
+            .iter()
+            .map(|(class, limit)| {
+                format!(
+                    "{} {} {} {}",
+                    class, limit.hard, limit.soft, limit.soft_seconds
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+        },
+        set: Some(set_output_limits),
+        toml: |c| {
+            let output = &c.server.limits.output;
+            [
+                (&output.normal, OUTPUT_KEYS[0]),
+                (&output.replica, OUTPUT_KEYS[1]),
+                (&output.pubsub, OUTPUT_KEYS[2]),
+            ]
+            .into_iter()
+            .flat_map(|(limit, [hard, soft, seconds])| {
+                [
+                    (hard, Some(int(limit.hard))),
+                    (soft, Some(int(limit.soft))),
+                    (seconds, Some(int(limit.soft_seconds))),
+                ]
+            })
+            .collect()
+        },
+    },
+    Param {
+        name: "rdb-enabled",
+        get: |c| yes_no(c.persistence.rdb_enabled),
+        set: Some(|c, value| {
+            c.persistence.rdb_enabled = boolean(value)?;
+            Ok(())
+        }),
+        toml: |c| {
+            vec![(
+                "persistence.rdb_enabled",
+                Some(c.persistence.rdb_enabled.into()),
+            )]
+        },
+    },
+    Param {
+        name: "rdb-save-interval",
+        get: |c| c.persistence.rdb_save_interval.to_string(),
+        set: Some(|c, value| {
+            c.persistence.rdb_save_interval = integer(value, 1, i64::MAX as u64)?;
+            Ok(())
+        }),
+        toml: |c| {
+            vec![(
+                "persistence.rdb_save_interval",
+                Some(int(c.persistence.rdb_save_interval)),
+            )]
+        },
+    },
+    Param {
+        name: "appendonly",
+        get: |c| yes_no(c.persistence.aof_enabled),
+        set: Some(|c, value| {
+            c.persistence.aof_enabled = boolean(value)?;
+            Ok(())
+        }),
+        toml: |c| {
+            vec![(
+                "persistence.aof_enabled",
+                Some(c.persistence.aof_enabled.into()),
+            )]
+        },
+    },
+    Param {
+        name: "loglevel",
+        get: |c| c.logging.level.clone(),
+        set: Some(|c, value| {
+            logging::parse_level(value)?;
+            c.logging.level = value.to_lowercase();
+            Ok(())
+        }),
+        toml: |c| vec![("logging.level", Some(c.logging.level.as_str().into()))],
+    },
+    Param {
+        name: "maxmemory",
+        get: |c| c.memory.max_memory.to_string(),
+        set: Some(|c, value| {
+            c.memory.max_memory = memory(value)?;
+            Ok(())
+        }),
+        toml: |c| vec![("memory.max_memory", Some(int(c.memory.max_memory)))],
+    },
+];
+
+/// Normal, replika ve pubsub çıkış sınırlarının TOML anahtarları
+const OUTPUT_KEYS: [[&str; 3]; 3] = [
+    [
+        "server.limits.output.normal.hard",
+        "server.limits.output.normal.soft",
+        "server.limits.output.normal.soft_seconds",
+    ],
+    [
+        "server.limits.output.replica.hard",
+        "server.limits.output.replica.soft",
+        "server.limits.output.replica.soft_seconds",
+    ],
+    [
+        "server.limits.output.pubsub.hard",
+        "server.limits.output.pubsub.soft",
+        "server.limits.output.pubsub.soft_seconds",
+    ],
+];
+
+fn int<T: TryInto<i64>>(n: T) -> Value {
+    n.try_into().unwrap_or(i64::MAX).into()
+}
+
+fn yes_no(on: bool) -> String {
+    if on { "yes" } else { "no" }.to_string()
+}
+
+fn boolean(value: &str) -> Result<bool, String> {
+    match value.to_lowercase().as_str() {
+        "yes" => Ok(true),
+        "no" => Ok(false),
+        _ => Err("argument must be 'yes' or 'no'".to_string()),
+    }
+}
+
+fn integer(value: &str, min: u64, max: u64) -> Result<u64, String> {
+    let n: u64 = value
+        .parse()
+        .map_err(|_| "argument couldn't be parsed into an integer".to_string())?;
+    if n < min || n > max {
+        return Err(format!(
+            "argument must be between {} and {} inclusive",
+            min, max
+        ));
+    }
+    Ok(n)
+}
+
+/// `100`, `64mb`, `1gb` gibi bellek değerleri. Redis'teki gibi `k`, `m`, `g` 1000'in;
+/// `kb`, `mb`, `gb` 1024'ün katlarıdır.
+fn memory(value: &str) -> Result<usize, String> {
+    let value = value.to_lowercase();
+    let (digits, unit) = value.split_at(
+        value
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(value.len()),
+    );
+    let unit: usize = match unit {
+        "" | "b" => 1,
+        "k" => 1000,
+        "kb" => 1024,
+        "m" => 1000 * 1000,
+        "mb" => 1024 * 1024,
+        "g" => 1000 * 1000 * 1000,
+        "gb" => 1024 * 1024 * 1024,
+        _ => return Err("argument must be a memory value".to_string()),
+    };
+    digits
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_mul(unit))
+        .ok_or_else(|| "argument must be a memory value".to_string())
+}
+
+fn tls_field(config: &Config, field: fn(&TlsConfig) -> String) -> String {
+    config.server.tls.as_ref().map(field).unwrap_or_default()
+}
+
+fn tls_mut(config: &mut Config) -> Result<&mut TlsConfig, String> {
+    config
+        .server
+        .tls
+        .as_mut()
+        .ok_or_else(|| "TLS is not enabled".to_string())
+}
+
+fn tls_toml(
+    config: &Config,
+    key: &'static str,
+    field: fn(&TlsConfig) -> Option<&String>,
+) -> TomlFields {
+    match &config.server.tls {
+        Some(tls) => vec![(key, field(tls).map(|value| value.as_str().into()))],
+        None => Vec::new(),
+    }
+}
+
+/// `<sınıf> <sert> <yumuşak> <saniye>` dörtlüleri; verilmeyen sınıflar değişmez
+fn set_output_limits(config: &mut Config, value: &str) -> Result<(), String> {
+    let words: Vec<&str> = value.split_whitespace().collect();
+    if words.is_empty() || !words.len().is_multiple_of(4) {
+        return Err("wrong number of arguments in buffer limit configuration".to_string());
+    }
+    let mut output = config.server.limits.output.clone();
+    for group in words.chunks(4) {
+        let limit = match group[0].to_lowercase().as_str() {
+            "normal" => &mut output.normal,
+            "replica" | "slave" => &mut output.replica,
+            "pubsub" => &mut output.pubsub,
+            _ => {
+                return Err(
+                    "invalid client class specified in buffer limit configuration".to_string(),
+                )
+            }
+        };
+        *limit = OutputLimit {
+            hard: memory(group[1])?,
+            soft: memory(group[2])?,
+            soft_seconds: integer(group[3], 0, i64::MAX as u64)?,
+        };
+    }
+    config.server.limits.output = output;
+    Ok(())
+}
+
+/// `CONFIG GET`: desenlerden birine uyan ayarların ad/değer çiftleri. Her ayar bir kez ve
+/// tablodaki sırasıyla döner.
+fn get(config: &Config, patterns: &[String]) -> Vec<String> {
+    let patterns: Vec<String> = patterns.iter().map(|p| p.to_lowercase()).collect();
+    PARAMS
+        .iter()
+        .filter(|param| {
+            patterns
+                .iter()
+                .any(|pattern| glob_match(pattern.as_bytes(), param.name.as_bytes()))
+        })
+        .flat_map(|param| [param.name.to_string(), (param.get)(config)])
+        .collect()
+}
+
+/// `CONFIG SET`: ad/değer çiftlerini sırayla uygular ve değişen ayarların adlarını döner.
+/// Hata olursa config yarım kalır; çağıran kopya üzerinde çalışmalıdır.
+fn set(config: &mut Config, pairs: &[String]) -> Result<Vec<&'static str>, String> {
+    let mut changed = Vec::new();
+    for pair in pairs.chunks(2) {
+        let name = pair[0].to_lowercase();
+        let Some(param) = PARAMS.iter().find(|param| param.name == name) else {
+            return Err(format!(
+                "Unknown option or number of arguments for CONFIG SET - '{}'",
+                pair[0]
+            ));
+        };
+        let failed = |e: &str| {
+            format!(
+                "CONFIG SET failed (possibly related to argument '{}') - {}",
+                param.name, e
+            )
+        };
+        if changed.contains(&param.name) {
+            return Err(failed("duplicate parameter"));
+        }
+        let Some(setter) = param.set else {
+            return Err(failed("can't set immutable config"));
+        };
+        setter(config, &pair[1]).map_err(|e| failed(&e))?;
+        changed.push(param.name);
+    }
+    Ok(changed)
+}
+
+/// `CONFIG REWRITE`: ayarların güncel değerlerini TOML metnine yazar. Dosyada olan anahtarlar
+/// yerinde güncellenir, yorumlar ve sıra korunur; dosyada olmayan anahtarlar yalnızca
+/// varsayılandan farklıysa eklenir.
+fn rewrite(text: &str, config: &Config) -> Result<String, String> {
+    let mut doc: DocumentMut = text.parse().map_err(|e| format!("{}", e))?;
+    let defaults = Config::default();
+    for param in PARAMS {
+        let default_fields = (param.toml)(&defaults);
+        for (key, value) in (param.toml)(config) {
+            let path: Vec<&str> = key.split('.').collect();
+            let present = path
+                .iter()
+                .try_fold(doc.as_item(), |item, key| item.get(key))
+                .is_some();
+            let default = default_fields
+                .iter()
+                .find(|(default_key, _)| *default_key == key)
+                .map(|(_, value)| value.as_ref().map(Value::to_string));
+            if !present && default == Some(value.as_ref().map(Value::to_string)) {
+                continue;
+            }
+            write_value(doc.as_table_mut(), &path, value).map_err(|e| format!("{}: {}", key, e))?;
+        }
+    }
+    Ok(doc.to_string())
+}
+
+/// Anahtarı tabloya yazar; eksik ara tablolar başlıksız olarak oluşturulur. Mevcut değerin
+/// satır sonu yorumu korunur.
+fn write_value(
+    table: &mut dyn TableLike,
+    path: &[&str],
+    value: Option<Value>,
+) -> Result<(), String> {
+    let Some((key, rest)) = path.split_first() else {
+        return Ok(());
+    };
+    if rest.is_empty() {
+        match (table.get_mut(key).and_then(Item::as_value_mut), value) {
+            (Some(old), Some(mut value)) => {
+                *value.decor_mut() = old.decor().clone();
+                *old = value;
+            }
+            (None, Some(value)) => {
+                table.insert(key, Item::Value(value));
+            }
+            (_, None) => {
+                table.remove(key);
+            }
+        }
+        return Ok(());
+    }
+    if table.get(key).is_none() {
+        if value.is_none() {
+            return Ok(());
+        }
+        let mut child = toml_edit::Table::new();
+        child.set_implicit(true);
+        table.insert(key, Item::Table(child));
+    }
+    let child = table
+        .get_mut(key)
+        .and_then(Item::as_table_like_mut)
+        .ok_or_else(|| format!("'{}' is not a table", key))?;
+    write_value(child, rest, value)
+}
+
+/// CONFIG'in `Config` dışında dokunduğu sunucu parçaları. Sunucu bir tane oluşturur ve
+/// bütün bağlantılar paylaşır.
+pub struct Settings {
+    /// CONFIG REWRITE'ın güncellediği dosya
+    file: PathBuf,
+    /// TLS kapalıysa `None`
+    tls: Option<Arc<Tls>>,
+    /// Eş zamanlı bağlantı sınırı; izin sayısı `maxclients`'ı izler
+    connections: Arc<Semaphore>,
+    max_clients: parking_lot::Mutex<usize>,
+}
+
+impl Settings {
+    pub fn new(file: impl Into<PathBuf>, tls: Option<Arc<Tls>>, max_clients: usize) -> Self {
+        Settings {
+            file: file.into(),
+            tls,
+            connections: Arc::new(Semaphore::new(max_clients)),
+            max_clients: parking_lot::Mutex::new(max_clients),
+        }
+    }
+
+    /// CONFIG REWRITE'ın yazdığı dosya
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    /// Yeni bağlantıların alması gereken izinler
+    pub fn connections(&self) -> Arc<Semaphore> {
+        Arc::clone(&self.connections)
+    }
+
+    /// Config'teki değerleri çalışan sunucuya uygular: bağlantı sınırı, istemci buffer
+    /// sınırları ve log seviyesi. TLS sertifikaları `reload_tls` ile ayrıca okunur.
+    pub fn apply(&self, config: &Config, clients: &Clients) -> Result<(), String> {
+        self.set_max_clients(config.server.max_connections);
+        clients.set_limits(config.server.limits.clone());
+        logging::set_level(&config.logging.level)
+    }
+
+    /// TLS sertifikalarını config'teki yollardan yeniden okur. Hata olursa eski sertifikalar
+    /// kullanılmaya devam eder.
+    pub fn reload_tls(&self, config: &Config) -> Result<(), String> {
+        match (&self.tls, &config.server.tls) {
+            (Some(tls), Some(tls_config)) => tls.reload(tls_config),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sınır düşürüldüğünde açık bağlantılar kapatılmaz; kullanımdaki izinler bağlantılar
+    /// kapandıkça geri alınır.
+    fn set_max_clients(&self, max: usize) {
+        let mut current = self.max_clients.lock();
+        if max > *current {
+            self.connections.add_permits(max - *current);
+        } else if max < *current {
+            let excess = *current - max;
+            let missing = excess - self.connections.forget_permits(excess);
+            if missing > 0 {
+                let connections = Arc::clone(&self.connections);
+                let missing = u32::try_from(missing).unwrap_or(u32::MAX);
+                tokio::spawn(async move {
+                    if let Ok(permits) = connections.acquire_many_owned(missing).await {
+                        permits.forget();
+                    }
+                });
+            }
+        }
+        *current = max;
+    }
+
+    /// Config'i dosyaya yazar. Dosya önce geçici bir dosyaya yazılıp yerine taşınır;
+    /// yazma yarıda kalırsa eski dosya bozulmaz.
+    fn rewrite(&self, config: &Config) -> Result<(), String> {
+        let text = match std::fs::read_to_string(&self.file) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.to_string()),
+        };
+        let text = rewrite(&text, config)?;
+        let tmp = self.file.with_extension("toml.tmp");
+        std::fs::write(&tmp, text).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, &self.file).map_err(|e| e.to_string())
+    }
+}
+
+impl Interpreter {
+    /// `CONFIG GET pattern [pattern ...]`, `CONFIG SET parameter value [parameter value ...]`,
+    /// `CONFIG REWRITE` ve `CONFIG RESETSTAT`
+    pub(super) async fn config_command(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let subcommand = args[0].to_uppercase();
+        let rest = &args[1..];
+        let arity_error = || {
+            format!(
+                "wrong number of arguments for 'CONFIG {}' command",
+                subcommand
+            )
+        };
+        match subcommand.as_str() {
+            "GET" if rest.is_empty() => Err(arity_error()),
+            "GET" => Ok(strings_reply(get(&*self.config.read().await, rest)).into()),
+            "SET" if rest.is_empty() || !rest.len().is_multiple_of(2) => Err(arity_error()),
+            // Bütün değerler kopya üzerinde doğrulanır; biri hatalıysa hiçbiri uygulanmaz
+            "SET" => {
+                let mut config = self.config.write().await;
+                let mut updated = config.clone();
+                let changed = set(&mut updated, rest)?;
+                if changed.iter().any(|name| name.starts_with("tls-")) {
+                    self.settings
+                        .reload_tls(&updated)
+                        .map_err(|e| format!("CONFIG SET failed - {}", e))?;
+                }
+                *config = updated;
+                self.settings.apply(&config, &self.clients)?;
+                ok()
+            }
+            "REWRITE" if !rest.is_empty() => Err(arity_error()),
+            "REWRITE" => {
+                let config = self.config.read().await;
+                self.settings
+                    .rewrite(&config)
+                    .map_err(|e| format!("Rewriting config file: {}", e))?;
+                ok()
+            }
+            "RESETSTAT" if !rest.is_empty() => Err(arity_error()),
+            "RESETSTAT" => {
+                STATS.reset();
+                ok()
+            }
+            _ => Err(format!(
+                "unknown subcommand '{}'. Try CONFIG HELP.",
+                args[0]
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_memory() {
+        assert_eq!(memory("100"), Ok(100));
+        assert_eq!(memory("1k"), Ok(1000));
+        assert_eq!(memory("1KB"), Ok(1024));
+        assert_eq!(memory("64mb"), Ok(64 * 1024 * 1024));
+        assert_eq!(memory("2g"), Ok(2_000_000_000));
+        assert!(memory("").is_err());
+        assert!(memory("10tb").is_err());
+        assert!(memory("-1").is_err());
+        assert!(memory("99999999999999999999gb").is_err());
+    }
+
+    #[test]
+    fn test_get() {
+        let mut config = Config::default();
+        config.server.max_connections = 42;
+        assert_eq!(
+            get(&config, &args(&["MAXCLIENTS"])),
+            args(&["maxclients", "42"])
+        );
+        let names: Vec<String> = get(&config, &args(&["*cert*", "port"]))
+            .into_iter()
+            .step_by(2)
+            .collect();
+        assert_eq!(names, args(&["port", "tls-cert-file", "tls-ca-cert-file"]));
+        assert!(get(&config, &args(&["nosuch"])).is_empty());
+        assert_eq!(get(&config, &args(&["*"])).len(), PARAMS.len() * 2);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut config = Config::default();
+        assert_eq!(
+            set(
+                &mut config,
+                &args(&["MaxMemory", "1mb", "appendonly", "yes"])
+            ),
+            Ok(vec!["maxmemory", "appendonly"])
+        );
+        assert_eq!(config.memory.max_memory, 1024 * 1024);
+        assert!(config.persistence.aof_enabled);
+
+        assert_eq!(
+            set(&mut config, &args(&["port", "1"])),
+            Err("CONFIG SET failed (possibly related to argument 'port') - can't set immutable config".to_string())
+        );
+        assert_eq!(
+            set(&mut config, &args(&["maxclients", "0"])),
+            Err("CONFIG SET failed (possibly related to argument 'maxclients') - argument must be between 1 and 4294967295 inclusive".to_string())
+        );
+        assert!(set(&mut config, &args(&["loglevel", "loud"])).is_err());
+        assert!(set(&mut config, &args(&["tls-cert-file", "a.crt"])).is_err());
+        assert!(set(&mut config, &args(&["nosuch", "1"]))
+            .unwrap_err()
+            .starts_with("Unknown option"));
+        assert!(
+            set(&mut config, &args(&["maxmemory", "1", "maxmemory", "2"]))
+                .unwrap_err()
+                .ends_with("duplicate parameter")
+        );
+
+        set(
+            &mut config,
+            &args(&[
+                "client-output-buffer-limit",
+                "slave 1mb 512kb 10 pubsub 0 0 0",
+            ]),
+        )
+        .unwrap();
+        let output = &config.server.limits.output;
+        assert_eq!(output.replica.hard, 1024 * 1024);
+        assert_eq!(output.replica.soft_seconds, 10);
+        assert_eq!(output.pubsub, OutputLimit::default());
+        assert!(set(
+            &mut config,
+            &args(&["client-output-buffer-limit", "normal 1 2"])
+        )
+        .is_err());
+        assert!(set(
+            &mut config,
+            &args(&["client-output-buffer-limit", "other 1 2 3"])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_rewrite() {
+        let text = "# HexagonDB\n[server]\nbind_address = \"127.0.0.1\" # loopback only\n\
+                    # connection cap\nmax_connections = 100 # old\n\n[logging]\nlevel = \"info\"\n";
+        let mut config = Config::default();
+        config.server.max_connections = 500;
+        config.logging.level = "debug".to_string();
+        config.memory.max_memory = Config::default().memory.max_memory + 1024;
+
+        let text = rewrite(text, &config).unwrap();
+        assert!(text
+            .starts_with("# HexagonDB\n[server]\nbind_address = \"127.0.0.1\" # loopback only\n"));
+        assert!(text.contains("# connection cap\nmax_connections = 500 # old\n"));
+        assert!(text.contains("level = \"debug\""));
+        assert!(text.contains(&format!("max_memory = {}", config.memory.max_memory)));
+        // Varsayılan değerler dosyaya eklenmez
+        assert!(!text.contains("query_buffer"));
+        assert!(!text.contains("limits"));
+
+        // Yeniden yazmak metni değiştirmez
+        assert_eq!(rewrite(&text, &config).unwrap(), text);
+        assert!(rewrite("[server", &config).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use super::registry::{wrong_arity, Call};
+use super::{ok, CommandResult, ExecutionResult, Interpreter};
+use crate::acl;
+use crate::clients::resp3;
+use crate::network::resp::RespValue;
+
+impl Interpreter {
+    pub(super) async fn ping(&mut self, _call: Call) -> CommandResult {
+        Ok(RespValue::SimpleString("PONG".to_string()).into())
+    }
+
+    pub(super) async fn echo(&mut self, call: Call) -> CommandResult {
+        Ok(RespValue::BulkString(Some(call.args()[0].clone())).into())
+    }
+
+    /// `AUTH password` (default kullanıcısı) ya da `AUTH username password`
+    pub(super) async fn auth(&mut self, call: Call) -> CommandResult {
+        let (username, password) = match call.args() {
+            [password] => ("default", password),
+            [username, password] => (username.as_str(), password),
+            _ => return Err(wrong_arity(call.name)),
+        };
+        self.acl.authenticate(username, password)?;
+        self.set_user(username);
+        ok()
+    }
+
+    /// `HELLO [protover [AUTH username password] [SETNAME name]]`: protokol sürümünü seçer
+    /// ve sunucu bilgilerini döner. RESP3'te cevap map olarak gönderilir.
+    pub(super) async fn hello(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut resp = None;
+        if let Some(version) = args.first() {
+            let version = version
+                .parse::<u8>()
+                .map_err(|_| "Protocol version is not an integer or out of range")?;
+            if version != 2 && version != 3 {
+                return Err("NOPROTO unsupported protocol version".to_string());
+            }
+            resp = Some(version);
+        }
+        let mut auth = None;
+        let mut name = None;
+        let mut i = 1;
+        while i < args.len() {
+            match args[i].to_uppercase().as_str() {
+                "AUTH" if i + 2 < args.len() => {
+                    auth = Some((&args[i + 1], &args[i + 2]));
+                    i += 3;
+                }
+                "SETNAME" if i + 1 < args.len() => {
+                    name = Some(&args[i + 1]);
+                    i += 2;
+                }
+                _ => return Err(format!("Syntax error in HELLO option '{}'", args[i])),
+            }
+        }
+
+        match auth {
+            Some((username, password)) => {
+                self.acl.authenticate(username, password)?;
+                self.set_user(username);
+            }
+            None if self.user.is_none() => return Err(acl::NOAUTH.to_string()),
+            None => {}
+        }
+        if let Some(conn) = &self.conn {
+            if let Some(name) = name {
+                conn.set_name(name)?;
+            }
+            if let Some(resp) = resp {
+                conn.set_resp(resp);
+            }
+        }
+
+        let resp = self.conn.as_ref().map_or(2, |conn| conn.resp());
+        let bulk = |s: &str| RespValue::BulkString(Some(s.to_string()));
+        let fields = [
+            ("server", bulk("hexagondb")),
+            ("version", bulk(env!("CARGO_PKG_VERSION"))),
+            ("proto", RespValue::Integer(resp as i64)),
+            (
+                "id",
+                RespValue::Integer(self.conn.as_ref().map_or(0, |conn| conn.id as i64)),
+            ),
+            (
+                "mode",
+                bulk(if self.cluster.is_some() {
+                    "cluster"
+                } else {
+                    "standalone"
+                }),
+            ),
+            (
+                "role",
+                bulk(if self.replication.is_replica() {
+                    "replica"
+                } else {
+                    "master"
+                }),
+            ),
+            ("modules", RespValue::Array(Some(Vec::new()))),
+        ];
+        if resp == 3 {
+            return Ok(ExecutionResult::Raw(resp3::map(&fields)));
+        }
+        Ok(RespValue::Array(Some(
+            fields
+                .into_iter()
+                .flat_map(|(name, value)| [bulk(name), value])
+                .collect(),
+        ))
+        .into())
+    }
+
+    /// ASKING: sıradaki komut içe aktarılan slot'ta çalışabilir
+    pub(super) async fn asking_command(&mut self, _call: Call) -> CommandResult {
+        if self.cluster.is_none() {
+            return Err("This instance has cluster support disabled".to_string());
+        }
+        self.asking = true;
+        ok()
+    }
+
+    pub(super) async fn client(&mut self, call: Call) -> CommandResult {
+        let Some(conn) = &self.conn else {
+            return Err("CLIENT is only available to connected clients".to_string());
+        };
+        Ok(self.clients.command(conn, call.args()).into())
+    }
+}
@@ -0,0 +1,307 @@
+use super::registry::{wrong_arity, Call};
+use super::{CommandResult, Interpreter};
+use crate::db::geohash;
+use crate::db::ops::geo::{GeoMatch, GeoOps, GeoOrigin, GeoQuery, GeoShape, GeoSort};
+use crate::network::resp::RespValue;
+
+/// GEOADD argümanlarının ayrıştırılmış hali.
+pub(super) struct GeoAddArgs {
+    pub items: Vec<(f64, f64, String)>,
+    pub nx: bool,
+    pub xx: bool,
+    pub ch: bool,
+}
+
+/// GEOSEARCH / GEOSEARCHSTORE argümanlarının ayrıştırılmış hali.
+pub(super) struct GeoSearchArgs {
+    pub query: GeoQuery,
+    /// Sorgu biriminin metre karşılığı
+    pub unit: f64,
+    pub with_coord: bool,
+    pub with_dist: bool,
+    pub with_hash: bool,
+    pub store_dist: bool,
+}
+
+fn parse_float(value: &str) -> Result<f64, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| "value is not a valid float".to_string())
+}
+
+fn parse_unit(value: &str) -> Result<f64, String> {
+    geohash::unit_to_meters(value)
+        .ok_or_else(|| "unsupported unit provided. please use M, KM, FT, MI".to_string())
+}
+
+/// GEOADD seçeneklerini ve (boylam, enlem, üye) üçlülerini ayrıştırır.
+/// `args` anahtardan sonraki argümanlardır.
+pub(super) fn parse_geoadd(args: &[String]) -> Result<GeoAddArgs, String> {
+    let mut nx = false;
+    let mut xx = false;
+    let mut ch = false;
+
+    let mut pos = 0;
+    while pos < args.len() {
+        match args[pos].to_uppercase().as_str() {
+            "NX" => nx = true,
+            "XX" => xx = true,
+            "CH" => ch = true,
+            _ => break,
+        }
+        pos += 1;
+    }
+
+    if nx && xx {
+        return Err("XX and NX options at the same time are not compatible".to_string());
+    }
+
+    let rest = &args[pos..];
+    if rest.is_empty() || !rest.len().is_multiple_of(3) {
+        return Err("wrong number of arguments for 'GEOADD' command".to_string());
+    }
+
+    let mut items = Vec::with_capacity(rest.len() / 3);
+    for triple in rest.chunks(3) {
+        let lon = parse_float(&triple[0])?;
+        let lat = parse_float(&triple[1])?;
+        items.push((lon, lat, triple[2].clone()));
+    }
+
+    Ok(GeoAddArgs { items, nx, xx, ch })
+}
+
+/// GEOSEARCH seçeneklerini ayrıştırır. `args` kaynak anahtardan sonraki argümanlardır.
+/// `store` true ise GEOSEARCHSTORE kuralları uygulanır.
+pub(super) fn parse_geosearch(args: &[String], store: bool) -> Result<GeoSearchArgs, String> {
+    let syntax_error = || "syntax error".to_string();
+
+    let mut origin = None;
+    let mut shape = None;
+    let mut unit = 1.0;
+    let mut sort = None;
+    let mut count = None;
+    let mut any = false;
+    let mut with_coord = false;
+    let mut with_dist = false;
+    let mut with_hash = false;
+    let mut store_dist = false;
+
+    let mut pos = 0;
+    while pos < args.len() {
+        let remaining = args.len() - pos - 1;
+        match args[pos].to_uppercase().as_str() {
+            "FROMMEMBER" if remaining >= 1 => {
+                if origin.is_some() {
+                    return Err(
+                        "exactly one of FROMMEMBER or FROMLONLAT can be specified".to_string()
+                    );
+                }
+                origin = Some(GeoOrigin::Member(args[pos + 1].clone()));
+                pos += 1;
+            }
+            "FROMLONLAT" if remaining >= 2 => {
+                if origin.is_some() {
+                    return Err(
+                        "exactly one of FROMMEMBER or FROMLONLAT can be specified".to_string()
+                    );
+                }
+                let lon = parse_float(&args[pos + 1])?;
+                let lat = parse_float(&args[pos + 2])?;
+                if !geohash::is_valid_coord(lon, lat) {
+                    return Err(format!(
+                        "invalid longitude,latitude pair {:.6},{:.6}",
+                        lon, lat
+                    ));
+                }
+                origin = Some(GeoOrigin::LonLat(lon, lat));
+                pos += 2;
+            }
+            "BYRADIUS" if remaining >= 2 => {
+                if shape.is_some() {
+                    return Err("exactly one of BYRADIUS and BYBOX can be specified".to_string());
+                }
+                let radius = parse_float(&args[pos + 1])?;
+                if radius < 0.0 {
+                    return Err("radius cannot be negative".to_string());
+                }
+                unit = parse_unit(&args[pos + 2])?;
+                shape = Some(GeoShape::Radius(radius * unit));
+                pos += 2;
+            }
+            "BYBOX" if remaining >= 3 => {
+                if shape.is_some() {
+                    return Err("exactly one of BYRADIUS and BYBOX can be specified".to_string());
+                }
+                let width = parse_float(&args[pos + 1])?;
+                let height = parse_float(&args[pos + 2])?;
+                if width < 0.0 || height < 0.0 {
+                    return Err("height or width cannot be negative".to_string());
+                }
+                unit = parse_unit(&args[pos + 3])?;
+                shape = Some(GeoShape::Box {
+                    width: width * unit,
+                    height: height * unit,
+                });
+                pos += 3;
+            }
+            "ASC" => sort = Some(GeoSort::Asc),
+            "DESC" => sort = Some(GeoSort::Desc),
+            "COUNT" if remaining >= 1 => {
+                let n = args[pos + 1]
+                    .parse::<i64>()
+                    .map_err(|_| "value is not an integer or out of range".to_string())?;
+                if n <= 0 {
+                    return Err("COUNT must be > 0".to_string());
+                }
+                count = Some(n as usize);
+                pos += 1;
+            }
+            "ANY" => any = true,
+            "WITHCOORD" if !store => with_coord = true,
+            "WITHDIST" if !store => with_dist = true,
+            "WITHHASH" if !store => with_hash = true,
+            "STOREDIST" if store => store_dist = true,
+            _ => return Err(syntax_error()),
+        }
+        pos += 1;
+    }
+
+    let origin = origin
+        .ok_or_else(|| "exactly one of FROMMEMBER or FROMLONLAT can be specified".to_string())?;
+    let shape =
+        shape.ok_or_else(|| "exactly one of BYRADIUS and BYBOX can be specified".to_string())?;
+    if any && count.is_none() {
+        return Err("the ANY argument requires COUNT argument".to_string());
+    }
+
+    Ok(GeoSearchArgs {
+        query: GeoQuery {
+            origin,
+            shape,
+            sort,
+            count,
+            any,
+        },
+        unit,
+        with_coord,
+        with_dist,
+        with_hash,
+        store_dist,
+    })
+}
+
+/// Mesafeyi verilen birime çevirip 4 ondalık basamakla yazar.
+pub(super) fn format_distance(meters: f64, unit: f64) -> String {
+    format!("{:.4}", meters / unit)
+}
+
+/// Koordinatı [boylam, enlem] dizisi olarak döndürür.
+pub(super) fn coord_reply(lon: f64, lat: f64) -> RespValue {
+    RespValue::Array(Some(vec![
+        RespValue::BulkString(Some(lon.to_string())),
+        RespValue::BulkString(Some(lat.to_string())),
+    ]))
+}
+
+/// GEOSEARCH cevabını oluşturur.
+/// WITH* seçeneği yoksa sadece üye isimleri, varsa her üye için bir dizi döner.
+pub(super) fn search_reply(matches: Vec<GeoMatch>, args: &GeoSearchArgs) -> RespValue {
+    let plain = !(args.with_coord || args.with_dist || args.with_hash);
+
+    let items = matches
+        .into_iter()
+        .map(|m| {
+            if plain {
+                return RespValue::BulkString(Some(m.member));
+            }
+
+            let mut item = vec![RespValue::BulkString(Some(m.member))];
+            if args.with_dist {
+                item.push(RespValue::BulkString(Some(format_distance(
+                    m.dist, args.unit,
+                ))));
+            }
+            if args.with_hash {
+                item.push(RespValue::Integer(m.score as i64));
+            }
+            if args.with_coord {
+                item.push(coord_reply(m.lon, m.lat));
+            }
+            RespValue::Array(Some(item))
+        })
+        .collect();
+
+    RespValue::Array(Some(items))
+}
+
+impl Interpreter {
+    pub(super) async fn geoadd(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let add = parse_geoadd(&args[1..])?;
+        let mut db = self.db.write().await;
+        let count = db.geoadd(args[0].clone(), add.items, add.nx, add.xx, add.ch)?;
+        self.propagate(call.argv).await;
+        Ok(RespValue::Integer(count as i64).into())
+    }
+
+    pub(super) async fn geopos(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut db = self.db.write().await;
+        let positions = db.geopos(args[0].clone(), args[1..].to_vec())?;
+        let positions = positions
+            .into_iter()
+            .map(|pos| match pos {
+                Some((lon, lat)) => coord_reply(lon, lat),
+                None => RespValue::Array(None),
+            })
+            .collect();
+        Ok(RespValue::Array(Some(positions)).into())
+    }
+
+    pub(super) async fn geodist(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        if args.len() > 4 {
+            return Err(wrong_arity(call.name));
+        }
+        let unit = match args.get(3) {
+            Some(unit) => geohash::unit_to_meters(unit)
+                .ok_or("unsupported unit provided. please use M, KM, FT, MI")?,
+            None => 1.0,
+        };
+
+        let mut db = self.db.write().await;
+        let dist = db.geodist(args[0].clone(), args[1].clone(), args[2].clone())?;
+        Ok(RespValue::BulkString(dist.map(|dist| format_distance(dist, unit))).into())
+    }
+
+    pub(super) async fn geohash(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut db = self.db.write().await;
+        let hashes = db.geohash(args[0].clone(), args[1..].to_vec())?;
+        let hashes = hashes.into_iter().map(RespValue::BulkString).collect();
+        Ok(RespValue::Array(Some(hashes)).into())
+    }
+
+    pub(super) async fn geosearch(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let search = parse_geosearch(&args[1..], false)?;
+        let mut db = self.db.write().await;
+        let matches = db.geosearch(args[0].clone(), &search.query)?;
+        Ok(search_reply(matches, &search).into())
+    }
+
+    pub(super) async fn geosearchstore(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let search = parse_geosearch(&args[2..], true)?;
+        let store_dist = search.store_dist.then_some(search.unit);
+
+        let mut db = self.db.write().await;
+        let count =
+            db.geosearchstore(args[0].clone(), args[1].clone(), &search.query, store_dist)?;
+        self.propagate(call.argv).await;
+        Ok(RespValue::Integer(count as i64).into())
+    }
+}
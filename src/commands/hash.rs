@@ -0,0 +1,42 @@
+use super::registry::Call;
+use super::{strings_reply, CommandResult, Interpreter};
+use crate::db::HashOps;
+use crate::network::resp::RespValue;
+
+impl Interpreter {
+    pub(super) async fn hset(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let key = args[0].clone();
+        let mut db = self.db.write().await;
+        let added = db.hset(key.clone(), args[1].clone(), args[2].clone())?;
+        db.sync_indexes(&key);
+        self.propagate(call.argv).await;
+        Ok(RespValue::Integer(added as i64).into())
+    }
+
+    pub(super) async fn hget(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut db = self.db.write().await;
+        db.lookup(&args[0], true);
+        let value = db.hget(args[0].clone(), args[1].clone())?;
+        Ok(RespValue::BulkString(value).into())
+    }
+
+    pub(super) async fn hgetall(&mut self, call: Call) -> CommandResult {
+        let key = call.args()[0].clone();
+        let mut db = self.db.write().await;
+        db.lookup(&key, true);
+        let values = db.hgetall(key)?;
+        Ok(strings_reply(values).into())
+    }
+
+    pub(super) async fn hdel(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let key = args[0].clone();
+        let mut db = self.db.write().await;
+        let removed = db.hdel(key.clone(), args[1].clone())?;
+        db.sync_indexes(&key);
+        self.propagate(call.argv).await;
+        Ok(RespValue::Integer(removed as i64).into())
+    }
+}
@@ -0,0 +1,304 @@
+//! `INFO`: sunucu bilgileri Redis'teki bölümler ve alan adlarıyla. Redis'i izleyen araçlar
+//! cevabı değiştirmeden okuyabilir. Bölümler `INFO server clients` gibi adlarıyla, `default`,
+//! `all` ya da `everything` ile seçilir.
+
+use super::registry::{self, Call};
+use super::{CommandResult, Interpreter, AOF_FILE};
+use crate::network::resp::RespValue;
+use crate::observability::stats::STATS;
+use crate::replication;
+use std::sync::atomic::Ordering;
+use std::sync::LazyLock;
+use std::time::Instant;
+
+/// Redis sürümüne göre davranan istemciler için bildirilen sürüm
+const REDIS_VERSION: &str = "7.2.0";
+
+/// Bölümler gösterildikleri sırayla. İkinci alan, bölümün `INFO` ve `INFO default`
+/// cevabında olup olmadığıdır.
+const SECTIONS: &[(&str, bool)] = &[
+    ("server", true),
+    ("clients", true),
+    ("memory", true),
+    ("persistence", true),
+    ("stats", true),
+    ("replication", true),
+    ("cpu", true),
+    ("commandstats", false),
+    ("errorstats", true),
+    ("cluster", true),
+    ("raft", true),
+    ("cdc", true),
+    ("keyspace", true),
+];
+
+static RUN_ID: LazyLock<String> = LazyLock::new(replication::random_id);
+
+/// İstenen bölümler, tablodaki sırayla. Bilinmeyen adlar atlanır.
+fn selected(args: &[String]) -> Vec<&'static str> {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_lowercase()).collect();
+    let args = if args.is_empty() {
+        vec!["default".to_string()]
+    } else {
+        args
+    };
+    SECTIONS
+        .iter()
+        .filter(|(name, default)| {
+            args.iter().any(|arg| match arg.as_str() {
+                "default" => *default,
+                "all" | "everything" => true,
+                arg => arg == *name,
+            })
+        })
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+/// Hata cevabının `errorstats`'ta sayıldığı kod. Büyük harfli ilk kelime (`WRONGTYPE`,
+/// `NOAUTH`) koddur; komut adıyla başlayan ve kodsuz hatalar `ERR` sayılır.
+pub(super) fn error_code(error: &str) -> &str {
+    let word = error.split(' ').next().unwrap_or_default();
+    let is_code = word.len() > 1
+        && word.bytes().all(|b| b.is_ascii_uppercase())
+        && registry::lookup(word).is_none();
+    if is_code {
+        word
+    } else {
+        "ERR"
+    }
+}
+
+/// Redis'in `used_memory_human` biçimi
+fn human(bytes: u64) -> String {
+    const UNITS: [(u64, &str); 4] = [
+        (1 << 40, "T"),
+        (1 << 30, "G"),
+        (1 << 20, "M"),
+        (1 << 10, "K"),
+    ];
+    UNITS.iter().find(|(size, _)| bytes >= *size).map_or_else(
+        || format!("{}B", bytes),
+        |(size, unit)| format!("{:.2}{}", bytes as f64 / *size as f64, unit),
+    )
+}
+
+/// Sürecin bellekte tuttuğu bayt; `/proc` olmayan sistemlerde 0. Ayırıcı istatistiği
+/// olmadığı için `used_memory` da bu değerdir.
+pub(super) fn resident_memory() -> u64 {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            status.lines().find_map(|line| {
+                line.strip_prefix("VmRSS:")?
+                    .trim()
+                    .trim_end_matches("kB")
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+            })
+        })
+        .map_or(0, |kb| kb * 1024)
+}
+
+/// Sistem ve kullanıcı CPU süresi (saniye); `/proc` olmayan sistemlerde 0
+fn cpu_times() -> (f64, f64) {
+    // Linux'ta saat tıkı saniyede 100'dür
+    const TICKS: f64 = 100.0;
+    let stat = std::fs::read_to_string("/proc/self/stat").unwrap_or_default();
+    // Süreç adı boşluk içerebilir; alanlar son parantezden sonra sayılır
+    let fields: Vec<&str> = stat
+        .rsplit_once(')')
+        .map_or(Vec::new(), |(_, rest)| rest.split_whitespace().collect());
+    let field = |i: usize| {
+        fields
+            .get(i)
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(0.0)
+            / TICKS
+    };
+    // utime ve stime, stat'taki 14. ve 15. alanlar
+    (field(12), field(11))
+}
+
+/// `db0:keys=...,expires=...,avg_ttl=...` satırı; veritabanı boşsa `None`
+fn keyspace_line(keys: usize, ttls: &[u128]) -> Option<String> {
+    if keys == 0 {
+        return None;
+    }
+    let avg_ttl = if ttls.is_empty() {
+        0
+    } else {
+        ttls.iter().sum::<u128>() / ttls.len() as u128
+    };
+    Some(format!(
+        "db0:keys={},expires={},avg_ttl={}\r\n",
+        keys,
+        ttls.len(),
+        avg_ttl
+    ))
+}
+
+impl Interpreter {
+    /// `INFO [section ...]`
+    pub(super) async fn info(&mut self, call: Call) -> CommandResult {
+        let mut sections = Vec::new();
+        for name in selected(call.args()) {
+            if let Some(section) = self.info_section(name).await {
+                sections.push(section);
+            }
+        }
+        Ok(RespValue::BulkString(Some(sections.join("\r\n"))).into())
+    }
+
+    /// Bölümün metni; kapalı özelliklerin (raft, CDC) bölümü yoktur.
+    async fn info_section(&self, name: &str) -> Option<String> {
+        let section = match name {
+            "server" => {
+                let config = self.config.read().await;
+                let uptime = STATS.started.elapsed().as_secs();
+                format!(
+                    "# Server\r\nredis_version:{}\r\nhexagondb_version:{}\r\nredis_mode:{}\r\nos:{} {}\r\narch_bits:{}\r\nprocess_id:{}\r\nrun_id:{}\r\ntcp_port:{}\r\nuptime_in_seconds:{}\r\nuptime_in_days:{}\r\nconfig_file:{}\r\n",
+                    REDIS_VERSION,
+                    env!("CARGO_PKG_VERSION"),
+                    if self.cluster.is_some() { "cluster" } else { "standalone" },
+                    std::env::consts::OS,
+                    std::env::consts::ARCH,
+                    usize::BITS,
+                    std::process::id(),
+                    *RUN_ID,
+                    config.server.port,
+                    uptime,
+                    uptime / 86400,
+                    self.settings.file().display()
+                )
+            }
+            "clients" => {
+                let max_clients = self.config.read().await.server.max_connections;
+                self.clients.info(max_clients)
+            }
+            "memory" => {
+                let used = resident_memory();
+                let max_memory = self.config.read().await.memory.max_memory as u64;
+                format!(
+                    "# Memory\r\nused_memory:{}\r\nused_memory_human:{}\r\nused_memory_rss:{}\r\nused_memory_rss_human:{}\r\nmaxmemory:{}\r\nmaxmemory_human:{}\r\nmaxmemory_policy:noeviction\r\n",
+                    used,
+                    human(used),
+                    used,
+                    human(used),
+                    max_memory,
+                    human(max_memory)
+                )
+            }
+            "persistence" => {
+                let changes = self.db.read().await.get_changes();
+                let aof_enabled = self.config.read().await.persistence.aof_enabled;
+                let status = |ok: bool| if ok { "ok" } else { "err" };
+                // Yeniden yazma komutu yok; dosya yalnızca büyür
+                format!(
+                    "# Persistence\r\nloading:0\r\nrdb_changes_since_last_save:{}\r\nrdb_bgsave_in_progress:{}\r\nrdb_last_save_time:{}\r\nrdb_last_bgsave_status:{}\r\naof_enabled:{}\r\naof_rewrite_in_progress:0\r\naof_rewrite_scheduled:0\r\naof_current_size:{}\r\naof_last_write_status:{}\r\n",
+                    changes,
+                    STATS.bgsave_in_progress.load(Ordering::Relaxed) as u8,
+                    STATS.last_save_time.load(Ordering::Relaxed),
+                    status(STATS.last_save_ok.load(Ordering::Relaxed)),
+                    aof_enabled as u8,
+                    std::fs::metadata(AOF_FILE).map_or(0, |meta| meta.len()),
+                    status(STATS.aof_last_write_ok.load(Ordering::Relaxed))
+                )
+            }
+            "stats" => STATS.info(),
+            "replication" => self.replication.info(),
+            "cpu" => {
+                let (sys, user) = cpu_times();
+                format!(
+                    "# CPU\r\nused_cpu_sys:{:.6}\r\nused_cpu_user:{:.6}\r\n",
+                    sys, user
+                )
+            }
+            "commandstats" => STATS.commandstats_info(),
+            "errorstats" => STATS.errorstats_info(),
+            "cluster" => format!(
+                "# Cluster\r\ncluster_enabled:{}\r\n",
+                self.cluster.is_some() as u8
+            ),
+            "raft" => self.raft.as_ref()?.info(),
+            "cdc" => self.cdc.as_ref()?.info(),
+            "keyspace" => {
+                let db = self.db.read().await;
+                let now = Instant::now();
+                let ttls: Vec<u128> = db
+                    .items
+                    .values()
+                    .filter_map(|entry| entry.expires_at)
+                    .map(|at| at.saturating_duration_since(now).as_millis())
+                    .collect();
+                let mut section = "# Keyspace\r\n".to_string();
+                section.extend(keyspace_line(db.items.len(), &ttls));
+                section
+            }
+            _ => return None,
+        };
+        Some(section)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_selected() {
+        let default = selected(&[]);
+        assert_eq!(default[0], "server");
+        assert!(!default.contains(&"commandstats"));
+        assert_eq!(selected(&args(&["default"])), default);
+        assert_eq!(selected(&args(&["all"])).len(), SECTIONS.len());
+        assert_eq!(
+            selected(&args(&["Keyspace", "CPU", "nosuch"])),
+            vec!["cpu", "keyspace"]
+        );
+        assert_eq!(
+            selected(&args(&["commandstats", "default"])).len(),
+            SECTIONS.len()
+        );
+        assert!(selected(&args(&["nosuch"])).is_empty());
+    }
+
+    #[test]
+    fn test_error_code() {
+        assert_eq!(error_code("WRONGTYPE Operation against a key"), "WRONGTYPE");
+        assert_eq!(error_code("NOAUTH Authentication required."), "NOAUTH");
+        assert_eq!(
+            error_code("wrong number of arguments for 'get' command"),
+            "ERR"
+        );
+        assert_eq!(error_code("CONFIG SET failed - x"), "ERR");
+        assert_eq!(error_code("I/O error"), "ERR");
+        assert_eq!(error_code(""), "ERR");
+    }
+
+    #[test]
+    fn test_human() {
+        assert_eq!(human(0), "0B");
+        assert_eq!(human(1023), "1023B");
+        assert_eq!(human(1536), "1.50K");
+        assert_eq!(human(3 << 30), "3.00G");
+    }
+
+    #[test]
+    fn test_keyspace_line() {
+        assert_eq!(keyspace_line(0, &[]), None);
+        assert_eq!(
+            keyspace_line(3, &[1000, 3000]),
+            Some("db0:keys=3,expires=2,avg_ttl=2000\r\n".to_string())
+        );
+        assert_eq!(
+            keyspace_line(1, &[]),
+            Some("db0:keys=1,expires=0,avg_ttl=0\r\n".to_string())
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! `COMMAND`: komut tablosunun istemcilere açılması. Cluster istemcileri anahtar
+//! konumlarını, istemci kütüphaneleri de komut listesini bağlanırken buradan alır. Cevaplar
+//! doğrudan komut tablosundan üretilir; tabloya eklenen her komut burada da görünür.
+
+use super::registry::{self, Call, Command, Keys};
+use super::{strings_reply, CommandResult, Interpreter};
+use crate::acl::category;
+use crate::network::resp::RespValue;
+
+fn bulk(s: &str) -> RespValue {
+    RespValue::BulkString(Some(s.to_string()))
+}
+
+fn status(s: &str) -> RespValue {
+    RespValue::SimpleString(s.to_string())
+}
+
+/// İlk anahtar, son anahtar ve adım. Anahtarların yeri argümanlara bağlıysa Redis gibi
+/// üçü de 0'dır; istemci `COMMAND GETKEYS` ile sormalıdır.
+fn key_range(command: &Command) -> (i32, i32, i32) {
+    match command.keys {
+        Keys::Range { first, last, step } => (first, last, step),
+        Keys::None | Keys::Movable(_) => (0, 0, 0),
+    }
+}
+
+/// Redis'in komut grupları; veri tipi grupları kategoriyle aynı adı taşır
+fn group(command: &Command) -> &'static str {
+    match command.categories.first().copied() {
+        Some("keyspace") => "generic",
+        Some("admin" | "dangerous") | None => "server",
+        Some(category) => category,
+    }
+}
+
+/// `COMMAND INFO` girdisi: ad, arity, bayraklar, ilk/son anahtar ve adım, ACL
+/// kategorileri, ipuçları, key spec'ler ve alt komutlar. Son ikisi boş gönderilir.
+fn info_reply(command: &Command) -> RespValue {
+    let mut flags: Vec<RespValue> = command
+        .flags
+        .iter()
+        .map(|flag| status(flag.name()))
+        .collect();
+    if matches!(command.keys, Keys::Movable(_)) {
+        flags.push(status("movablekeys"));
+    }
+    let (first, last, step) = key_range(command);
+    let categories = category::command_categories(command.name)
+        .into_iter()
+        .map(|category| status(&format!("@{}", category)))
+        .collect();
+    RespValue::Array(Some(vec![
+        bulk(&command.name.to_lowercase()),
+        RespValue::Integer(command.arity as i64),
+        RespValue::Array(Some(flags)),
+        RespValue::Integer(first as i64),
+        RespValue::Integer(last as i64),
+        RespValue::Integer(step as i64),
+        RespValue::Array(Some(categories)),
+        RespValue::Array(Some(command.tips.iter().map(|tip| bulk(tip)).collect())),
+        RespValue::Array(Some(Vec::new())),
+        RespValue::Array(Some(Vec::new())),
+    ]))
+}
+
+/// `COMMAND DOCS` girdisi: komut adı ve ardından alan/değer çiftleri
+fn docs_reply(command: &Command) -> [RespValue; 2] {
+    [
+        bulk(&command.name.to_lowercase()),
+        RespValue::Array(Some(vec![
+            bulk("summary"),
+            bulk(command.summary),
+            bulk("group"),
+            bulk(group(command)),
+        ])),
+    ]
+}
+
+/// `COMMAND GETKEYS command [arg ...]`: komutun verilen argümanlardaki anahtarları
+fn getkeys(argv: &[String]) -> Result<RespValue, String> {
+    let Some(command) = registry::lookup(&argv[0].to_uppercase()) else {
+        return Err("Invalid command specified".to_string());
+    };
+    if !command.accepts(argv.len()) {
+        return Err("Invalid number of arguments specified for command".to_string());
+    }
+    let keys = command.keys(&argv[1..]);
+    if keys.is_empty() {
+        return Err("The command has no key arguments".to_string());
+    }
+    Ok(strings_reply(keys.into_iter().map(str::to_string)))
+}
+
+/// İsim verilmediyse bütün komutlar, verildiyse büyük/küçük harf gözetmeden o komutlar
+fn selected<'a>(names: &'a [String]) -> Box<dyn Iterator<Item = Option<&'static Command>> + 'a> {
+    if names.is_empty() {
+        return Box::new(registry::COMMANDS.iter().map(Some));
+    }
+    Box::new(
+        names
+            .iter()
+            .map(|name| registry::lookup(&name.to_uppercase())),
+    )
+}
+
+impl Interpreter {
+    /// `COMMAND`, `COMMAND COUNT`, `COMMAND INFO [command ...]`,
+    /// `COMMAND DOCS [command ...]` ve `COMMAND GETKEYS command [arg ...]`
+    pub(super) async fn command_command(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let Some(subcommand) = args.first().map(|s| s.to_uppercase()) else {
+            return Ok(
+                RespValue::Array(Some(registry::COMMANDS.iter().map(info_reply).collect())).into(),
+            );
+        };
+        let rest = &args[1..];
+        let arity_error = || {
+            format!(
+                "wrong number of arguments for 'COMMAND|{}' command",
+                subcommand
+            )
+        };
+        let reply = match subcommand.as_str() {
+            "COUNT" if rest.is_empty() => RespValue::Integer(registry::COMMANDS.len() as i64),
+            "COUNT" => return Err(arity_error()),
+            // Bilinmeyen komutlar için nil
+            "INFO" => RespValue::Array(Some(
+                selected(rest)
+                    .map(|command| command.map_or(RespValue::Array(None), info_reply))
+                    .collect(),
+            )),
+            // Bilinmeyen komutlar atlanır
+            "DOCS" => RespValue::Array(Some(
+                selected(rest).flatten().flat_map(docs_reply).collect(),
+            )),
+            "GETKEYS" if rest.is_empty() => return Err(arity_error()),
+            "GETKEYS" => getkeys(rest)?,
+            _ => {
+                return Err(format!(
+                    "unknown subcommand '{}'. Try COMMAND HELP.",
+                    args[0]
+                ))
+            }
+        };
+        Ok(reply.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn info(name: &str) -> Vec<RespValue> {
+        match info_reply(registry::lookup(name).unwrap()) {
+            RespValue::Array(Some(fields)) => fields,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_info_reply() {
+        let get = info("GET");
+        assert_eq!(get.len(), 10);
+        assert_eq!(get[0], bulk("get"));
+        assert_eq!(get[1], RespValue::Integer(2));
+        assert_eq!(get[2], RespValue::Array(Some(vec![status("readonly")])));
+        assert_eq!(
+            get[3..6],
+            [
+                RespValue::Integer(1),
+                RespValue::Integer(1),
+                RespValue::Integer(1)
+            ]
+        );
+        assert_eq!(
+            get[6],
+            RespValue::Array(Some(vec![status("@string"), status("@read")]))
+        );
+
+        // Anahtarların yeri argümanlara bağlı
+        let xread = info("XREAD");
+        let RespValue::Array(Some(flags)) = &xread[2] else {
+            unreachable!()
+        };
+        assert!(flags.contains(&status("movablekeys")));
+        assert_eq!(xread[3], RespValue::Integer(0));
+
+        let madd = info("TS.MADD");
+        assert_eq!(madd[4], RespValue::Integer(-1));
+        assert_eq!(madd[5], RespValue::Integer(3));
+
+        let keys = info("KEYS");
+        assert_eq!(
+            keys[7],
+            RespValue::Array(Some(vec![
+                bulk("request_policy:all_shards"),
+                bulk("nondeterministic_output_order")
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_docs_reply() {
+        let [name, docs] = docs_reply(registry::lookup("DEL").unwrap());
+        assert_eq!(name, bulk("del"));
+        let RespValue::Array(Some(fields)) = docs else {
+            unreachable!()
+        };
+        assert_eq!(fields[0], bulk("summary"));
+        assert_eq!(fields[3], bulk("generic"));
+        assert_eq!(group(registry::lookup("SAVE").unwrap()), "server");
+        assert_eq!(group(registry::lookup("XADD").unwrap()), "stream");
+        assert!(registry::COMMANDS.iter().all(|c| !c.summary.is_empty()));
+    }
+
+    #[test]
+    fn test_getkeys() {
+        assert_eq!(
+            getkeys(&args(&["set", "k", "v"])),
+            Ok(strings_reply(["k".to_string()]))
+        );
+        assert_eq!(
+            getkeys(&args(&["XREAD", "STREAMS", "a", "b", "0", "0"])),
+            Ok(strings_reply(["a".to_string(), "b".to_string()]))
+        );
+        assert!(getkeys(&args(&["NOSUCH", "k"])).is_err());
+        assert_eq!(
+            getkeys(&args(&["GET"])),
+            Err("Invalid number of arguments specified for command".to_string())
+        );
+        assert_eq!(
+            getkeys(&args(&["PING"])),
+            Err("The command has no key arguments".to_string())
+        );
+    }
+}
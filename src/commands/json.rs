@@ -0,0 +1,258 @@
+use super::registry::Call;
+use super::{ok, CommandResult, Interpreter};
+use crate::db::json::JsonPath;
+use crate::db::ops::json::JsonOps;
+use crate::network::resp::RespValue;
+use serde_json::{Map, Number, Value};
+
+/// Argümandaki JSON metnini ayrıştırır.
+pub(super) fn parse_value(text: &str) -> Result<Value, String> {
+    serde_json::from_str(text).map_err(|e| e.to_string())
+}
+
+/// NUMINCRBY artış değerini ayrıştırır.
+pub(super) fn parse_number(text: &str) -> Result<Number, String> {
+    match parse_value(text)? {
+        Value::Number(n) => Ok(n),
+        _ => Err("expected a number".to_string()),
+    }
+}
+
+/// Opsiyonel yol argümanını ayrıştırır; verilmezse `default` kullanılır.
+pub(super) fn parse_path(path: Option<&String>, default: &str) -> Result<JsonPath, String> {
+    JsonPath::parse(path.map_or(default, |p| p.as_str()))
+}
+
+/// JSON.SET'in `[NX|XX]` seçeneğini ayrıştırır.
+pub(super) fn parse_set_condition(args: &[String]) -> Result<(bool, bool), String> {
+    match args {
+        [] => Ok((false, false)),
+        [option] if option.eq_ignore_ascii_case("NX") => Ok((true, false)),
+        [option] if option.eq_ignore_ascii_case("XX") => Ok((false, true)),
+        _ => Err("syntax error".to_string()),
+    }
+}
+
+fn no_such_path(path: &str) -> String {
+    format!("Path '{}' does not exist", path)
+}
+
+/// JSON.GET cevabı. Tek yolda eşleşmeler, birden fazla yolda yol başına eşleşmeler döner.
+/// Eski söz dizimindeki yollar dizi yerine ilk eşleşmeyi döndürür.
+pub(super) fn get_reply(
+    raw_paths: &[&str],
+    paths: &[JsonPath],
+    matches: Vec<Vec<Value>>,
+) -> RespValue {
+    let legacy = paths.iter().all(|p| p.legacy);
+    let mut results = Vec::with_capacity(matches.len());
+    for (raw, mut values) in raw_paths.iter().zip(matches) {
+        let value = if legacy {
+            if values.is_empty() {
+                return RespValue::Error(no_such_path(raw));
+            }
+            values.swap_remove(0)
+        } else {
+            Value::Array(values)
+        };
+        results.push((raw.to_string(), value));
+    }
+
+    let value = if results.len() == 1 {
+        results.pop().map(|(_, v)| v).unwrap_or(Value::Null)
+    } else {
+        Value::Object(results.into_iter().collect::<Map<String, Value>>())
+    };
+    RespValue::BulkString(Some(value.to_string()))
+}
+
+/// JSON.TYPE cevabı.
+pub(super) fn type_reply(path: &JsonPath, types: Vec<&'static str>) -> RespValue {
+    if path.legacy {
+        return match types.first() {
+            Some(name) => RespValue::SimpleString(name.to_string()),
+            None => RespValue::BulkString(None),
+        };
+    }
+    RespValue::Array(Some(
+        types
+            .into_iter()
+            .map(|name| RespValue::BulkString(Some(name.to_string())))
+            .collect(),
+    ))
+}
+
+/// JSON.ARRAPPEND cevabı: yeni uzunluklar, dizi olmayanlar için nil.
+pub(super) fn arrappend_reply(
+    raw_path: &str,
+    path: &JsonPath,
+    lens: Vec<Option<usize>>,
+) -> RespValue {
+    if path.legacy {
+        return match lens.into_iter().flatten().last() {
+            Some(len) => RespValue::Integer(len as i64),
+            None => RespValue::Error(format!(
+                "Path '{}' does not exist or not an array",
+                raw_path
+            )),
+        };
+    }
+    RespValue::Array(Some(
+        lens.into_iter()
+            .map(|len| match len {
+                Some(len) => RespValue::Integer(len as i64),
+                None => RespValue::BulkString(None),
+            })
+            .collect(),
+    ))
+}
+
+/// JSON.NUMINCRBY cevabı: yeni değerlerin JSON metni, sayı olmayanlar için `null`.
+pub(super) fn numincrby_reply(
+    raw_path: &str,
+    path: &JsonPath,
+    values: Vec<Option<Value>>,
+) -> RespValue {
+    if path.legacy {
+        return match values.into_iter().flatten().last() {
+            Some(value) => RespValue::BulkString(Some(value.to_string())),
+            None => RespValue::Error(format!(
+                "Path '{}' does not exist or does not contain a number",
+                raw_path
+            )),
+        };
+    }
+    let values = values
+        .into_iter()
+        .map(|v| v.unwrap_or(Value::Null))
+        .collect();
+    RespValue::BulkString(Some(Value::Array(values).to_string()))
+}
+
+fn keys_reply(keys: Vec<String>) -> RespValue {
+    RespValue::Array(Some(
+        keys.into_iter()
+            .map(|k| RespValue::BulkString(Some(k)))
+            .collect(),
+    ))
+}
+
+/// JSON.OBJKEYS cevabı: nesnelerin alan isimleri, nesne olmayanlar için nil.
+pub(super) fn objkeys_reply(path: &JsonPath, keys: Vec<Option<Vec<String>>>) -> RespValue {
+    if path.legacy {
+        return match keys.into_iter().next() {
+            Some(Some(keys)) => keys_reply(keys),
+            _ => RespValue::Array(None),
+        };
+    }
+    RespValue::Array(Some(
+        keys.into_iter()
+            .map(|keys| match keys {
+                Some(keys) => keys_reply(keys),
+                None => RespValue::Array(None),
+            })
+            .collect(),
+    ))
+}
+
+impl Interpreter {
+    pub(super) async fn json_set(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let path = JsonPath::parse(&args[1])?;
+        let value = parse_value(&args[2])?;
+        let (nx, xx) = parse_set_condition(&args[3..])?;
+
+        let mut db = self.db.write().await;
+        if !db.json_set(args[0].clone(), &path, value, nx, xx)? {
+            return Ok(RespValue::BulkString(None).into());
+        }
+        self.propagate(call.argv).await;
+        ok()
+    }
+
+    pub(super) async fn json_get(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let raw_paths: Vec<&str> = if args.len() > 1 {
+            args[1..].iter().map(|p| p.as_str()).collect()
+        } else {
+            vec!["."]
+        };
+        let paths = raw_paths
+            .iter()
+            .map(|p| JsonPath::parse(p))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut db = self.db.write().await;
+        let reply = match db.json_get(args[0].clone(), &paths)? {
+            Some(matches) => get_reply(&raw_paths, &paths, matches),
+            None => RespValue::BulkString(None),
+        };
+        Ok(reply.into())
+    }
+
+    pub(super) async fn json_del(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let path = parse_path(args.get(1), "$")?;
+        let mut db = self.db.write().await;
+        let removed = db.json_del(args[0].clone(), &path)?;
+        if removed > 0 {
+            self.propagate(call.argv).await;
+        }
+        Ok(RespValue::Integer(removed as i64).into())
+    }
+
+    pub(super) async fn json_type(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let path = parse_path(args.get(1), ".")?;
+        let mut db = self.db.write().await;
+        let reply = match db.json_type(args[0].clone(), &path)? {
+            Some(types) => type_reply(&path, types),
+            None => RespValue::BulkString(None),
+        };
+        Ok(reply.into())
+    }
+
+    pub(super) async fn json_arrappend(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let path = JsonPath::parse(&args[1])?;
+        let values = args[2..]
+            .iter()
+            .map(|v| parse_value(v))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut db = self.db.write().await;
+        let lens = db.json_arrappend(args[0].clone(), &path, values)?;
+        let changed = lens.iter().any(Option::is_some);
+        let reply = arrappend_reply(&args[1], &path, lens);
+        if changed {
+            self.propagate(call.argv).await;
+        }
+        Ok(reply.into())
+    }
+
+    pub(super) async fn json_numincrby(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let path = JsonPath::parse(&args[1])?;
+        let by = parse_number(&args[2])?;
+
+        let mut db = self.db.write().await;
+        let values = db.json_numincrby(args[0].clone(), &path, by)?;
+        let changed = values.iter().any(Option::is_some);
+        let reply = numincrby_reply(&args[1], &path, values);
+        if changed {
+            self.propagate(call.argv).await;
+        }
+        Ok(reply.into())
+    }
+
+    pub(super) async fn json_objkeys(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let path = parse_path(args.get(1), ".")?;
+        let mut db = self.db.write().await;
+        let reply = match db.json_objkeys(args[0].clone(), &path)? {
+            Some(keys) => objkeys_reply(&path, keys),
+            None => RespValue::Array(None),
+        };
+        Ok(reply.into())
+    }
+}
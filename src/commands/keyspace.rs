@@ -0,0 +1,125 @@
+use super::registry::Call;
+use super::{ok, strings_reply, CommandResult, Interpreter};
+use crate::cluster::migrate::{self, DumpedKey, MigrateOptions};
+use crate::db::ops::cluster::ClusterOps;
+use crate::db::GenericOps;
+use crate::network::resp::RespValue;
+
+impl Interpreter {
+    pub(super) async fn del(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        let mut deleted = 0;
+        for key in call.args() {
+            if db.exists(key.clone()) {
+                db.del(key.clone());
+                db.sync_indexes(key);
+                deleted += 1;
+            }
+        }
+
+        if deleted > 0 {
+            self.propagate(call.argv).await;
+        }
+        Ok(RespValue::Integer(deleted).into())
+    }
+
+    /// Aynı anahtar birden çok kez verilirse her biri ayrı sayılır.
+    pub(super) async fn exists(&mut self, call: Call) -> CommandResult {
+        let db = self.db.read().await;
+        let count = call
+            .args()
+            .iter()
+            .filter(|key| db.exists((*key).clone()))
+            .count();
+        Ok(RespValue::Integer(count as i64).into())
+    }
+
+    pub(super) async fn keys(&mut self, call: Call) -> CommandResult {
+        let db = self.db.read().await;
+        Ok(strings_reply(db.keys(call.args()[0].clone())).into())
+    }
+
+    pub(super) async fn expire(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let seconds = args[1]
+            .parse::<u64>()
+            .map_err(|_| "value is not an integer or out of range")?;
+        let mut db = self.db.write().await;
+        let result = db.expire(args[0].clone(), seconds);
+        if result {
+            self.propagate(call.argv).await;
+        }
+        Ok(RespValue::Integer(result as i64).into())
+    }
+
+    pub(super) async fn ttl(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        Ok(RespValue::Integer(db.ttl(call.args()[0].clone())).into())
+    }
+
+    pub(super) async fn persist(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        let result = db.persist(call.args()[0].clone());
+        if result {
+            self.propagate(call.argv).await;
+        }
+        Ok(RespValue::Integer(result as i64).into())
+    }
+
+    pub(super) async fn dump(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        let payload = db.dump(&call.args()[0]).map(|(payload, _)| payload);
+        Ok(RespValue::BulkString(payload).into())
+    }
+
+    /// `RESTORE key ttl payload [REPLACE]`
+    pub(super) async fn restore(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let ttl = args[1]
+            .parse::<u64>()
+            .map_err(|_| "Invalid TTL value, must be >= 0")?;
+        let mut replace = false;
+        for option in &args[3..] {
+            if !option.eq_ignore_ascii_case("REPLACE") {
+                return Err("syntax error".to_string());
+            }
+            replace = true;
+        }
+        let mut db = self.db.write().await;
+        db.restore(args[0].clone(), ttl, &args[2], replace)?;
+        self.propagate(call.argv).await;
+        ok()
+    }
+
+    /// Anahtarları başka bir node'a taşır; `COPY` verilmediyse buradan silinir.
+    pub(super) async fn migrate(&mut self, call: Call) -> CommandResult {
+        let options = MigrateOptions::parse(call.args())?;
+        let dumped: Vec<DumpedKey> = {
+            let mut db = self.db.write().await;
+            options
+                .keys
+                .iter()
+                .filter_map(|key| {
+                    db.dump(key).map(|(payload, ttl_ms)| DumpedKey {
+                        key: key.clone(),
+                        payload,
+                        ttl_ms,
+                    })
+                })
+                .collect()
+        };
+        if dumped.is_empty() {
+            return Ok(RespValue::SimpleString("NOKEY".to_string()).into());
+        }
+        migrate::send(&options, &dumped).await?;
+        if !options.copy {
+            let mut db = self.db.write().await;
+            for dumped in dumped {
+                db.del(dumped.key.clone());
+                db.sync_indexes(&dumped.key);
+                self.propagate(vec!["DEL".to_string(), dumped.key]).await;
+            }
+        }
+        ok()
+    }
+}
@@ -0,0 +1,54 @@
+use super::registry::Call;
+use super::{strings_reply, CommandResult, Interpreter};
+use crate::db::ListOps;
+use crate::network::resp::RespValue;
+
+impl Interpreter {
+    /// LPUSH / RPUSH
+    pub(super) async fn push(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let (key, values) = (args[0].clone(), args[1..].to_vec());
+        let mut db = self.db.write().await;
+        let len = if call.name == "LPUSH" {
+            db.lpush_safe(key, values)
+        } else {
+            db.rpush(key, values)
+        }?;
+        self.propagate(call.argv).await;
+        Ok(RespValue::Integer(len as i64).into())
+    }
+
+    /// LPOP / RPOP
+    pub(super) async fn pop(&mut self, call: Call) -> CommandResult {
+        let key = call.args()[0].clone();
+        let mut db = self.db.write().await;
+        let value = if call.name == "LPOP" {
+            db.lpop(key)
+        } else {
+            db.rpop(key)
+        }?;
+        if value.is_some() {
+            self.propagate(call.argv).await;
+        }
+        Ok(RespValue::BulkString(value).into())
+    }
+
+    pub(super) async fn llen(&mut self, call: Call) -> CommandResult {
+        let key = call.args()[0].clone();
+        let mut db = self.db.write().await;
+        db.lookup(&key, true);
+        let len = db.llen(key)?;
+        Ok(RespValue::Integer(len as i64).into())
+    }
+
+    pub(super) async fn lrange(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let (Ok(start), Ok(stop)) = (args[1].parse::<i64>(), args[2].parse::<i64>()) else {
+            return Err("value is not an integer or out of range".to_string());
+        };
+        let mut db = self.db.write().await;
+        db.lookup(&args[0], true);
+        let values = db.lrange(args[0].clone(), start, stop)?;
+        Ok(strings_reply(values).into())
+    }
+}
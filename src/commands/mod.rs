@@ -1,32 +1,123 @@
+use crate::acl::{self, Acl};
+use crate::cdc::Cdc;
+use crate::clients::{Client, Clients, Peer, Registration};
+use crate::cluster::{self, keys::command_keys, Cluster};
 use crate::config::Config;
+use crate::db::ops::cluster::ClusterOps;
 use crate::db::pubsub::PubSub;
+use crate::db::GenericOps;
 use crate::db::DB;
-use crate::db::{GenericOps, HashOps, ListOps, SetOps, StringOps};
+use crate::network::inline;
 use crate::network::resp::RespValue;
 use crate::observability::metrics::{METRIC_COMMANDS_TOTAL, METRIC_COMMAND_LATENCY};
+use crate::observability::stats::STATS;
 use crate::persistence::aof::Aof;
-use crate::server_info::ServerInfo;
+use crate::raft::{self, Raft};
+use crate::replication::primary::SyncRequest;
+use crate::replication::{self, Replication};
+use config::Settings;
 use metrics::{counter, histogram};
+use registry::{Call, Command, Flag};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::error;
 
+pub mod config;
+mod connection;
+mod geo;
+mod hash;
+mod info;
+mod introspection;
+mod json;
+mod keyspace;
+mod list;
+mod probabilistic;
+mod pubsub;
+pub mod registry;
+mod search;
+mod server;
+mod set;
+mod stream;
+mod string;
+mod timeseries;
+
+/// AOF dosyası; sunucu onu çalışma dizininde açar
+pub const AOF_FILE: &str = "database.aof";
+
 /// İstemciden gelen komutları işleyen birim.
 /// Her bağlantı için bir Interpreter oluşturulur.
 pub struct Interpreter {
     db: Arc<RwLock<DB>>,
     aof: Arc<RwLock<Aof>>,
-    server_info: Arc<ServerInfo>,
-    #[allow(dead_code)]
     config: Arc<RwLock<Config>>,
+    settings: Arc<Settings>,
     pubsub: Arc<PubSub>,
+    replication: Arc<Replication>,
+    /// Replikanın primary'den gelen akışı uygulayan istemcisi mi?
+    /// Bu istemci READONLY kontrolüne takılmaz ve yazdıklarını yeniden yaymaz.
+    master_link: bool,
+    /// Bağlanan replikanın `REPLCONF listening-port` ile bildirdiği port
+    replica_listening_port: u16,
+    /// Cluster modunda değilse `None`
+    cluster: Option<Arc<Cluster>>,
+    /// ASKING gönderildi: sıradaki komut içe aktarılan slot'ta çalışabilir
+    asking: bool,
+    /// Raft modunda değilse `None`
+    raft: Option<Arc<Raft>>,
+    /// Commit edilmiş raft girdilerini uygulayan istemci
+    raft_apply: bool,
+    /// CDC kapalıysa `None`
+    cdc: Option<Arc<Cdc>>,
+    /// Komut çalışırken AOF'a giden yazmalar; komut bitince izleyen istemcilere bildirilir
+    /// ve CDC akışına eklenir
+    changes: parking_lot::Mutex<Vec<Vec<String>>>,
+    acl: Arc<Acl>,
+    /// Bağlantının giriş yaptığı kullanıcı; `AUTH` gerekiyorsa ve henüz yapılmadıysa `None`
+    user: Option<String>,
+    clients: Arc<Clients>,
+    /// İstemci kaydındaki bağlantı; iç istemcilerde (replikasyon, raft) `None`
+    conn: Option<Arc<Client>>,
 }
 
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 
 pub enum ExecutionResult {
     Response(RespValue),
     Subscribe(String, broadcast::Receiver<String>),
+    /// PSYNC: bağlantı bundan sonra replikasyon akışı taşır
+    Psync(SyncRequest),
+    /// Serileştirilmiş cevap; `RespValue`'da karşılığı olmayan RESP3 tipleri için
+    Raw(String),
+}
+
+impl From<RespValue> for ExecutionResult {
+    fn from(value: RespValue) -> Self {
+        ExecutionResult::Response(value)
+    }
+}
+
+/// Komut işleyicilerinin sonucu; hata istemciye hata cevabı olarak döner
+type CommandResult = Result<ExecutionResult, String>;
+
+fn ok() -> CommandResult {
+    Ok(RespValue::SimpleString("OK".to_string()).into())
+}
+
+/// Bulk string dizisi cevabı
+fn strings_reply(values: impl IntoIterator<Item = String>) -> RespValue {
+    RespValue::Array(Some(
+        values
+            .into_iter()
+            .map(|value| RespValue::BulkString(Some(value)))
+            .collect(),
+    ))
+}
+
+/// Komut çalışmadan reddedildi; `commandstats`'ta `rejected_calls` olarak sayılır
+fn rejected(command: &Command, error: String) -> String {
+    STATS.command_rejected(command.name);
+    error
 }
 
 struct LatencyGuard {
@@ -43,577 +134,414 @@ impl Drop for LatencyGuard {
 impl Interpreter {
     /// Yeni bir yorumlayıcı oluşturur.
     /// Veritabanı ve AOF (Persistence) modüllerine erişimi vardır.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         db: Arc<RwLock<DB>>,
         aof: Arc<RwLock<Aof>>,
-        server_info: Arc<ServerInfo>,
         config: Arc<RwLock<Config>>,
+        settings: Arc<Settings>,
         pubsub: Arc<PubSub>,
+        replication: Arc<Replication>,
+        cluster: Option<Arc<Cluster>>,
+        raft: Option<Arc<Raft>>,
+        cdc: Option<Arc<Cdc>>,
+        acl: Arc<Acl>,
+        clients: Arc<Clients>,
     ) -> Self {
+        let user = acl.default_login();
         Interpreter {
             db,
             aof,
-            server_info,
             config,
+            settings,
             pubsub,
+            replication,
+            master_link: false,
+            replica_listening_port: 0,
+            cluster,
+            asking: false,
+            raft,
+            raft_apply: false,
+            cdc,
+            changes: parking_lot::Mutex::new(Vec::new()),
+            acl,
+            user,
+            clients,
+            conn: None,
+        }
+    }
+
+    /// Bağlantıyı istemci kaydına ekler. Dönen değer bırakılınca bağlantı listeden çıkar.
+    /// Alıcı, bağlantıya yazılacak geçersiz kılma mesajlarını taşır.
+    pub fn connect(&mut self, peer: Peer) -> (Registration, mpsc::UnboundedReceiver<String>) {
+        let (registration, pushes) = self.clients.register(peer, self.user.clone());
+        self.conn = Some(Arc::clone(&registration));
+        (registration, pushes)
+    }
+
+    fn set_user(&mut self, name: &str) {
+        self.user = Some(name.to_string());
+        if let Some(conn) = &self.conn {
+            conn.set_user(self.user.clone());
+        }
+    }
+
+    /// TLS istemci sertifikasının CN'iyle giriş yapar. Bu adda açık bir ACL kullanıcısı
+    /// yoksa bağlantı şifreyle giriş yapan istemciler gibi devam eder.
+    pub fn login_with_certificate(&mut self, name: &str) -> bool {
+        let found = self.acl.certificate_login(name);
+        if found {
+            self.set_user(name);
         }
+        found
+    }
+
+    /// Primary'den gelen replikasyon akışını uygulayacak istemciyi oluşturur.
+    fn master_link_client(&self) -> Interpreter {
+        let mut client = Interpreter::new(
+            Arc::clone(&self.db),
+            Arc::clone(&self.aof),
+            Arc::clone(&self.config),
+            Arc::clone(&self.settings),
+            Arc::clone(&self.pubsub),
+            Arc::clone(&self.replication),
+            self.cluster.clone(),
+            self.raft.clone(),
+            self.cdc.clone(),
+            Arc::clone(&self.acl),
+            Arc::clone(&self.clients),
+        );
+        client.master_link = true;
+        client
+    }
+
+    /// Commit edilmiş raft girdilerini uygulayacak istemciyi oluşturur.
+    pub(crate) fn raft_applier(&self) -> Interpreter {
+        let mut client = Interpreter::new(
+            Arc::clone(&self.db),
+            Arc::clone(&self.aof),
+            Arc::clone(&self.config),
+            Arc::clone(&self.settings),
+            Arc::clone(&self.pubsub),
+            Arc::clone(&self.replication),
+            self.cluster.clone(),
+            self.raft.clone(),
+            self.cdc.clone(),
+            Arc::clone(&self.acl),
+            Arc::clone(&self.clients),
+        );
+        client.raft_apply = true;
+        client
+    }
+
+    /// Yazma komutunu AOF'a kaydeder ve replikalara yayar.
+    /// Primary'den gelen komutlar replika tarafında ham haliyle yayıldığı için burada atlanır.
+    async fn propagate(&self, command: Vec<String>) {
+        if self.cdc.is_some() || self.clients.tracking_active() {
+            self.changes.lock().push(command.clone());
+        }
+        // Raft modunda kalıcılığı raft log'u ve snapshot'ları sağlar
+        if self.raft.is_some() {
+            return;
+        }
+        if !self.master_link {
+            self.replication.feed(&command);
+        }
+        // `CONFIG SET appendonly no` ile AOF'a yazma durur
+        if !self.config.read().await.persistence.aof_enabled {
+            return;
+        }
+        let mut aof = self.aof.write().await;
+        let result = aof.append(command);
+        STATS
+            .aof_last_write_ok
+            .store(result.is_ok(), Ordering::Relaxed);
+        if let Err(e) = result {
+            error!("AOF write error: {}", e);
+        }
+    }
+
+    /// Cluster modunda komutun anahtarlarının bu node'da işlenip işlenemeyeceğine bakar;
+    /// işlenemiyorsa istemciye dönecek yönlendirme (MOVED/ASK) ya da hata döner.
+    async fn route(
+        &self,
+        cluster: &Cluster,
+        command: &Command,
+        args: &[String],
+        asking: bool,
+    ) -> Result<(), String> {
+        let keys = command.keys(args);
+        let Some(slot) = cluster::keys_slot(&keys)? else {
+            return Ok(());
+        };
+        // MIGRATE, taşınan slot'ta eksik anahtarlar için yönlendirilmez; Redis gibi NOKEY döner
+        let missing = if cluster.is_migrating(slot) && command.name != "MIGRATE" {
+            let db = self.db.read().await;
+            keys.iter()
+                .filter(|key| !db.exists(key.to_string()))
+                .count()
+        } else {
+            0
+        };
+        cluster.route(slot, asking, missing, keys.len())
+    }
+
+    /// Okuma komutunun anahtarlarını bağlantının izlediği anahtarlara ekler. Anahtarlar
+    /// komut çalışmadan önce kaydedilir; okuma ile kayıt arasına giren bir yazmanın mesajı
+    /// kaçırılmaz.
+    async fn remember_reads(&self, conn: &Client, cmd: &str, request: &RespValue) {
+        let args: Vec<String> = replication::command_args(request)
+            .iter()
+            .skip(1)
+            .map(|arg| arg.to_string())
+            .collect();
+        let keys = command_keys(cmd, &args);
+        if keys.is_empty() {
+            return;
+        }
+        let db = self.db.read().await;
+        let keys: Vec<_> = keys
+            .into_iter()
+            .map(|key| (key, db.items.get(key).and_then(|entry| entry.expires_at)))
+            .collect();
+        self.clients.remember(conn.id, &keys);
+    }
+
+    /// Komutun yazdığı anahtarları izleyen istemcilere geçersiz kılma mesajı gönderir.
+    /// Süresi olan anahtarlar, süre dolunca yeniden bildirilmek üzere zamanlayıcıya eklenir.
+    async fn invalidate_changes(&self, changes: &[Vec<String>]) {
+        let origin = self.conn.as_ref().map(|conn| conn.id);
+        let db = self.db.read().await;
+        for command in changes {
+            let Some((cmd, args)) = command.split_first() else {
+                continue;
+            };
+            let keys = command_keys(&cmd.to_uppercase(), args);
+            for key in &keys {
+                if let Some(at) = db.items.get(*key).and_then(|entry| entry.expires_at) {
+                    self.clients.expire_at(key, at);
+                }
+            }
+            let keys: Vec<String> = keys.iter().map(|key| key.to_string()).collect();
+            self.clients.invalidate(&keys, origin);
+        }
+    }
+
+    /// Veri setinin tamamı değişti (replikanın tam senkronizasyonu, raft snapshot'ı);
+    /// izleyen bütün istemcilere mesaj gider.
+    pub(crate) fn invalidate_all(&self) {
+        self.clients.invalidate_all(None);
+    }
+
+    /// Komutun yaptığı yazmaları CDC akışına ekler. Değerler komutun tuttuğu kilitler
+    /// bırakıldıktan sonra okunur; olaylar veritabanı kilidi altında eklendiği için bir
+    /// anahtarın son olayı her zaman son değerini taşır.
+    async fn publish_changes(&self, cdc: &Cdc, changes: Vec<Vec<String>>) {
+        let mut db = self.db.write().await;
+        for command in changes {
+            let Some((cmd, args)) = command.split_first() else {
+                continue;
+            };
+            let cmd = cmd.to_uppercase();
+            let op = cmd.to_lowercase();
+            let keys = command_keys(&cmd, args);
+            // FLUSHALL gibi anahtarsız yazmalar boş anahtarlı tek bir olay üretir
+            if keys.is_empty() {
+                cdc.append(&op, "", None, None);
+            }
+            for key in keys {
+                let (value, ttl) = db.dump(key).unzip();
+                cdc.append(&op, key, value, ttl.flatten());
+            }
+        }
+    }
+
+    /// Komutu bağlantının kullanıcısının ACL kurallarıyla kontrol eder. Replikasyon ve raft
+    /// akışını uygulayan iç istemciler kontrol edilmez; `AUTH` her zaman çalışabilir.
+    fn authorize(&self, request: &RespValue) -> Result<(), String> {
+        if self.master_link || self.raft_apply {
+            return Ok(());
+        }
+        let args = replication::command_args(request);
+        let Some((cmd, args)) = args.split_first() else {
+            return Ok(());
+        };
+        let cmd = cmd.to_uppercase();
+        if cmd == "AUTH" || cmd == "HELLO" {
+            return Ok(());
+        }
+        let Some(user) = &self.user else {
+            return Err(acl::NOAUTH.to_string());
+        };
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        self.acl.check(user, &cmd, &args)
     }
 
     /// İstemciden gelen komutu işler ve cevabı döndürür.
-    #[tracing::instrument(skip(self, request), fields(cmd, key))]
     pub async fn execute(&mut self, request: RespValue) -> ExecutionResult {
+        let cmd = replication::command_args(&request)
+            .first()
+            .map(|cmd| cmd.to_uppercase())
+            .unwrap_or_default();
+        if let Some(conn) = &self.conn {
+            conn.begin_command(&cmd);
+        }
+        if let Err(e) = self.authorize(&request) {
+            if let Some(command) = registry::lookup(&cmd) {
+                STATS.command_rejected(command.name);
+            }
+            STATS.error_replied(info::error_code(&e));
+            return ExecutionResult::Response(RespValue::Error(e));
+        }
+        // CLIENT PAUSE yalnızca bağlantıları bekletir; replikasyon ve raft akışı sürer.
+        // CLIENT komutları beklemez ki duraklatma UNPAUSE ile kaldırılabilsin.
+        if self.conn.is_some() && cmd != "CLIENT" {
+            self.clients
+                .wait_unpaused(registry::is_write_command(&cmd))
+                .await;
+        }
+        if let Some(conn) = &self.conn {
+            if conn.tracks_command(&cmd) && !registry::is_write_command(&cmd) {
+                self.remember_reads(conn, &cmd, &request).await;
+            }
+        }
+        let result = self.dispatch(request).await;
+        if let ExecutionResult::Response(RespValue::Error(e)) = &result {
+            STATS.error_replied(info::error_code(e));
+        }
+        let changes = std::mem::take(&mut *self.changes.lock());
+        if !changes.is_empty() {
+            if self.clients.tracking_active() {
+                self.invalidate_changes(&changes).await;
+            }
+            if let Some(cdc) = &self.cdc {
+                self.publish_changes(cdc, changes).await;
+            }
+        }
+        result
+    }
+
+    /// Komutu redis-cli'da yazıldığı gibi tek satırdan çalıştırır (`SET k "hello world"`).
+    /// Gömülü kullanım içindir; tırnak ve kaçış kuralları ağdan gelen inline isteklerle
+    /// aynıdır.
+    pub async fn execute_query(&mut self, query: &str) -> ExecutionResult {
+        match inline::split_args(query.as_bytes()) {
+            Ok(args) => {
+                let request = RespValue::Array(Some(
+                    args.iter()
+                        .map(|arg| {
+                            RespValue::BulkString(Some(String::from_utf8_lossy(arg).into_owned()))
+                        })
+                        .collect(),
+                ));
+                self.execute(request).await
+            }
+            Err(e) => ExecutionResult::Response(RespValue::Error(e)),
+        }
+    }
+
+    #[tracing::instrument(skip(self, request), fields(cmd, key))]
+    async fn dispatch(&mut self, request: RespValue) -> ExecutionResult {
         counter!(METRIC_COMMANDS_TOTAL).increment(1);
+        STATS.commands_processed.fetch_add(1, Ordering::Relaxed);
         let _guard = LatencyGuard {
             start: std::time::Instant::now(),
         };
 
-        match request {
-            RespValue::Array(Some(tokens)) => {
-                if tokens.is_empty() {
-                    return ExecutionResult::Response(RespValue::Error(
-                        "empty command".to_string(),
-                    ));
-                }
+        self.try_dispatch(request)
+            .await
+            .unwrap_or_else(|e| ExecutionResult::Response(RespValue::Error(e)))
+    }
 
-                // İlk eleman komut ismidir (SET, GET vs.)
-                let cmd_string = match &tokens[0] {
-                    RespValue::BulkString(Some(s)) => s.clone(),
-                    RespValue::SimpleString(s) => s.clone(),
-                    _ => {
-                        return ExecutionResult::Response(RespValue::Error(
-                            "invalid command format".to_string(),
-                        ));
-                    }
-                };
-
-                let cmd_upper = cmd_string.to_uppercase();
-                tracing::Span::current().record("cmd", &cmd_upper);
-                tracing::info!("Processing command");
-                let args: Vec<String> = tokens
-                    .iter()
-                    .skip(1)
-                    .filter_map(|t| match t {
-                        RespValue::BulkString(Some(s)) => Some(s.clone()),
-                        _ => None,
-                    })
-                    .collect();
-
-                // Komut argümanlarını string listesine çevir (AOF için lazım)
-                let mut full_cmd_args = vec![cmd_string.clone()];
-                full_cmd_args.extend(args.clone());
-
-                // --- Komutları İşle ---
-
-                if cmd_upper == "PING" {
-                    return ExecutionResult::Response(RespValue::SimpleString("PONG".to_string()));
-                }
+    /// Komutu tablodan bulur, argüman sayısını kontrol eder ve işleyicisini çağırır.
+    async fn try_dispatch(&mut self, request: RespValue) -> CommandResult {
+        let RespValue::Array(Some(tokens)) = request else {
+            return Err("invalid command format".to_string());
+        };
+        if tokens.is_empty() {
+            return Err("empty command".to_string());
+        }
 
-                if cmd_upper == "ECHO" {
-                    if let Some(arg) = args.get(0) {
-                        return ExecutionResult::Response(RespValue::BulkString(Some(arg.clone())));
-                    } else {
-                        return ExecutionResult::Response(RespValue::Error(
-                            "wrong number of arguments for 'ECHO' command".to_string(),
-                        ));
-                    }
-                }
+        // İlk eleman komut ismidir (SET, GET vs.)
+        let cmd_string = match &tokens[0] {
+            RespValue::BulkString(Some(s)) => s.clone(),
+            RespValue::SimpleString(s) => s.clone(),
+            _ => return Err("invalid command format".to_string()),
+        };
+        let Some(command) = registry::lookup(&cmd_string.to_uppercase()) else {
+            return Err(format!("unknown command '{}'", cmd_string));
+        };
+        tracing::Span::current().record("cmd", command.name);
+        tracing::info!("Processing command");
+
+        // Komut argümanlarını string listesine çevir (AOF için lazım)
+        let mut argv = vec![cmd_string];
+        argv.extend(tokens.into_iter().skip(1).filter_map(|t| match t {
+            RespValue::BulkString(Some(s)) => Some(s),
+            _ => None,
+        }));
+        if !command.accepts(argv.len()) {
+            return Err(rejected(command, registry::wrong_arity(command.name)));
+        }
+        let call = Call {
+            name: command.name,
+            argv,
+        };
 
-                if cmd_upper == "INFO" {
-                    let db_guard = self.db.read().await;
-                    let db_size = db_guard.items.len();
-                    drop(db_guard);
+        // Replikalar yazmaları yalnızca primary'den kabul eder
+        if !self.master_link && self.replication.is_replica() && command.is_write() {
+            return Err(rejected(
+                command,
+                "READONLY You can't write against a read only replica.".to_string(),
+            ));
+        }
 
-                    let info_str = self.server_info.generate_info(db_size);
-                    return ExecutionResult::Response(RespValue::BulkString(Some(info_str)));
-                }
+        // maxmemory aşıldıysa bellek ayırabilen komutlar reddedilir; anahtar silinmez
+        // (noeviction). Primary'den ve raft log'undan gelen yazmalar uygulanmaya devam eder.
+        if command.has_flag(Flag::DenyOom) && !self.master_link && !self.raft_apply {
+            let max_memory = self.config.read().await.memory.max_memory as u64;
+            if max_memory > 0 && info::resident_memory() > max_memory {
+                return Err(rejected(
+                    command,
+                    "OOM command not allowed when used memory > 'maxmemory'.".to_string(),
+                ));
+            }
+        }
 
-                // Anahtar gerektiren komutlar için kontrol
-                let key = if let Some(k) = args.get(0) {
-                    k.clone()
-                } else {
-                    // Bazı komutlar anahtar istemez (PING, ECHO, KEYS *)
-                    // Ama aşağıdakiler ister.
-                    if [
-                        "GET",
-                        "SET",
-                        "DEL",
-                        "EXISTS",
-                        "INCR",
-                        "DECR",
-                        "EXPIRE",
-                        "TTL",
-                        "PERSIST",
-                        "LPUSH",
-                        "RPUSH",
-                        "LPOP",
-                        "RPOP",
-                        "LLEN",
-                        "LRANGE",
-                        "HSET",
-                        "HGET",
-                        "HGETALL",
-                        "HDEL",
-                        "SADD",
-                        "SREM",
-                        "SMEMBERS",
-                        "SISMEMBER",
-                        "SCARD",
-                    ]
-                    .contains(&cmd_upper.as_str())
-                    {
-                        return ExecutionResult::Response(RespValue::Error(format!(
-                            "wrong number of arguments for '{}' command",
-                            cmd_upper
-                        )));
-                    }
-                    "".to_string()
-                };
-
-                if cmd_upper == "GET" {
-                    let mut db = self.db.write().await;
-                    return match db.get(key) {
-                        Ok(Some(value)) => {
-                            ExecutionResult::Response(RespValue::BulkString(Some(value)))
-                        }
-                        Ok(None) => ExecutionResult::Response(RespValue::BulkString(None)),
-                        Err(e) => ExecutionResult::Response(RespValue::Error(e)),
-                    };
-                } else if cmd_upper == "SET" {
-                    if let Some(value) = args.get(1) {
-                        let mut db = self.db.write().await;
-                        db.set(key, value.clone());
-
-                        // AOF'a kaydet (Kalıcılık)
-                        let mut aof = self.aof.write().await;
-                        if let Err(e) = aof.append(full_cmd_args) {
-                            error!("AOF write error: {}", e);
-                        }
-
-                        return ExecutionResult::Response(RespValue::SimpleString(
-                            "OK".to_string(),
-                        ));
-                    } else {
-                        return ExecutionResult::Response(RespValue::Error(
-                            "wrong number of arguments for 'SET' command".to_string(),
-                        ));
-                    }
-                } else if cmd_upper == "DEL" {
-                    let mut db = self.db.write().await;
-                    db.del(key);
-
-                    let mut aof = self.aof.write().await;
-                    if let Err(e) = aof.append(full_cmd_args) {
-                        error!("AOF write error: {}", e);
-                    }
-
-                    return ExecutionResult::Response(RespValue::Integer(1));
-                } else if cmd_upper == "EXISTS" {
-                    let db = self.db.read().await;
-                    let exists = db.exists(key);
-                    return ExecutionResult::Response(RespValue::Integer(if exists {
-                        1
-                    } else {
-                        0
-                    }));
-                } else if cmd_upper == "KEYS" {
-                    if let Some(pattern) = args.get(0) {
-                        let db = self.db.read().await;
-                        let keys = db.keys(pattern.clone());
-                        let resp_keys: Vec<RespValue> = keys
-                            .into_iter()
-                            .map(|k| RespValue::BulkString(Some(k)))
-                            .collect();
-                        return ExecutionResult::Response(RespValue::Array(Some(resp_keys)));
-                    } else {
-                        return ExecutionResult::Response(RespValue::Error(
-                            "wrong number of arguments for 'KEYS' command".to_string(),
-                        ));
-                    }
-                } else if cmd_upper == "INCR" {
-                    let mut db = self.db.write().await;
-                    match db.incr(key) {
-                        Ok(val) => {
-                            let mut aof = self.aof.write().await;
-                            if let Err(e) = aof.append(full_cmd_args) {
-                                error!("AOF write error: {}", e);
-                            }
-                            return ExecutionResult::Response(RespValue::Integer(val));
-                        }
-                        Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                    }
-                } else if cmd_upper == "DECR" {
-                    let mut db = self.db.write().await;
-                    match db.decr(key) {
-                        Ok(val) => {
-                            let mut aof = self.aof.write().await;
-                            if let Err(e) = aof.append(full_cmd_args) {
-                                error!("AOF write error: {}", e);
-                            }
-                            return ExecutionResult::Response(RespValue::Integer(val));
-                        }
-                        Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                    }
-                } else if cmd_upper == "LPUSH" || cmd_upper == "RPUSH" {
-                    if args.len() < 2 {
-                        return ExecutionResult::Response(RespValue::Error(format!(
-                            "wrong number of arguments for '{}' command",
-                            cmd_upper
-                        )));
-                    }
-                    let values = args[1..].to_vec();
-                    let mut db = self.db.write().await;
-
-                    let result = if cmd_upper == "LPUSH" {
-                        db.lpush_safe(key, values)
-                    } else {
-                        db.rpush(key, values)
-                    };
-
-                    match result {
-                        Ok(len) => {
-                            let mut aof = self.aof.write().await;
-                            if let Err(e) = aof.append(full_cmd_args) {
-                                error!("AOF write error: {}", e);
-                            }
-                            return ExecutionResult::Response(RespValue::Integer(len as i64));
-                        }
-                        Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                    }
-                } else if cmd_upper == "LPOP" || cmd_upper == "RPOP" {
-                    let mut db = self.db.write().await;
-                    let result = if cmd_upper == "LPOP" {
-                        db.lpop(key)
-                    } else {
-                        db.rpop(key)
-                    };
-
-                    match result {
-                        Ok(Some(val)) => {
-                            let mut aof = self.aof.write().await;
-                            if let Err(e) = aof.append(full_cmd_args) {
-                                error!("AOF write error: {}", e);
-                            }
-                            return ExecutionResult::Response(RespValue::BulkString(Some(val)));
-                        }
-                        Ok(None) => return ExecutionResult::Response(RespValue::BulkString(None)),
-                        Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                    }
-                } else if cmd_upper == "LLEN" {
-                    let mut db = self.db.write().await;
-                    match db.llen(key) {
-                        Ok(len) => {
-                            return ExecutionResult::Response(RespValue::Integer(len as i64))
-                        }
-                        Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                    }
-                } else if cmd_upper == "LRANGE" {
-                    if args.len() != 3 {
-                        return ExecutionResult::Response(RespValue::Error(
-                            "wrong number of arguments for 'LRANGE' command".to_string(),
-                        ));
-                    }
-                    let start_str = &args[1];
-                    let stop_str = &args[2];
-
-                    match (start_str.parse::<i64>(), stop_str.parse::<i64>()) {
-                        (Ok(start), Ok(stop)) => {
-                            let mut db = self.db.write().await;
-                            match db.lrange(key, start, stop) {
-                                Ok(values) => {
-                                    let resp_values: Vec<RespValue> = values
-                                        .into_iter()
-                                        .map(|s| RespValue::BulkString(Some(s)))
-                                        .collect();
-                                    return ExecutionResult::Response(RespValue::Array(Some(
-                                        resp_values,
-                                    )));
-                                }
-                                Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                            }
-                        }
-                        _ => {
-                            return ExecutionResult::Response(RespValue::Error(
-                                "value is not an integer or out of range".to_string(),
-                            ));
-                        }
-                    }
-                } else if cmd_upper == "HSET" {
-                    if args.len() != 3 {
-                        return ExecutionResult::Response(RespValue::Error(
-                            "wrong number of arguments for 'HSET' command".to_string(),
-                        ));
-                    }
-                    let field = args[1].clone();
-                    let value = args[2].clone();
-
-                    let mut db = self.db.write().await;
-                    match db.hset(key, field, value) {
-                        Ok(val) => {
-                            let mut aof = self.aof.write().await;
-                            if let Err(e) = aof.append(full_cmd_args) {
-                                error!("AOF write error: {}", e);
-                            }
-                            return ExecutionResult::Response(RespValue::Integer(val as i64));
-                        }
-                        Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                    }
-                } else if cmd_upper == "HGET" {
-                    if args.len() != 2 {
-                        return ExecutionResult::Response(RespValue::Error(
-                            "wrong number of arguments for 'HGET' command".to_string(),
-                        ));
-                    }
-                    let field = args[1].clone();
-
-                    let mut db = self.db.write().await;
-                    match db.hget(key, field) {
-                        Ok(Some(val)) => {
-                            return ExecutionResult::Response(RespValue::BulkString(Some(val)))
-                        }
-                        Ok(None) => return ExecutionResult::Response(RespValue::BulkString(None)),
-                        Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                    }
-                } else if cmd_upper == "HGETALL" {
-                    let mut db = self.db.write().await;
-                    match db.hgetall(key) {
-                        Ok(values) => {
-                            let resp_values: Vec<RespValue> = values
-                                .into_iter()
-                                .map(|s| RespValue::BulkString(Some(s)))
-                                .collect();
-                            return ExecutionResult::Response(RespValue::Array(Some(resp_values)));
-                        }
-                        Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                    }
-                } else if cmd_upper == "HDEL" {
-                    if args.len() != 2 {
-                        return ExecutionResult::Response(RespValue::Error(
-                            "wrong number of arguments for 'HDEL' command".to_string(),
-                        ));
-                    }
-                    let field = args[1].clone();
-
-                    let mut db = self.db.write().await;
-                    match db.hdel(key, field) {
-                        Ok(val) => {
-                            let mut aof = self.aof.write().await;
-                            if let Err(e) = aof.append(full_cmd_args) {
-                                error!("AOF write error: {}", e);
-                            }
-                            return ExecutionResult::Response(RespValue::Integer(val as i64));
-                        }
-                        Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                    }
-                } else if cmd_upper == "EXPIRE" {
-                    if let Some(seconds_str) = args.get(1) {
-                        if let Ok(seconds) = seconds_str.parse::<u64>() {
-                            let mut db = self.db.write().await;
-                            let result = db.expire(key, seconds);
-
-                            if result {
-                                let mut aof = self.aof.write().await;
-                                if let Err(e) = aof.append(full_cmd_args) {
-                                    error!("AOF write error: {}", e);
-                                }
-                            }
-
-                            return ExecutionResult::Response(RespValue::Integer(if result {
-                                1
-                            } else {
-                                0
-                            }));
-                        } else {
-                            return ExecutionResult::Response(RespValue::Error(
-                                "value is not an integer or out of range".to_string(),
-                            ));
-                        }
-                    } else {
-                        return ExecutionResult::Response(RespValue::Error(
-                            "wrong number of arguments for 'EXPIRE' command".to_string(),
-                        ));
-                    }
-                } else if cmd_upper == "TTL" {
-                    let mut db = self.db.write().await;
-                    let ttl = db.ttl(key);
-                    return ExecutionResult::Response(RespValue::Integer(ttl));
-                } else if cmd_upper == "PERSIST" {
-                    let mut db = self.db.write().await;
-                    let result = db.persist(key);
-
-                    if result {
-                        let mut aof = self.aof.write().await;
-                        if let Err(e) = aof.append(full_cmd_args) {
-                            error!("AOF write error: {}", e);
-                        }
-                    }
-
-                    return ExecutionResult::Response(RespValue::Integer(if result {
-                        1
-                    } else {
-                        0
-                    }));
-                } else if cmd_upper == "SADD" {
-                    if args.len() < 2 {
-                        return ExecutionResult::Response(RespValue::Error(
-                            "wrong number of arguments for 'SADD' command".to_string(),
-                        ));
-                    }
-                    let members = args[1..].to_vec();
-                    let mut db = self.db.write().await;
-                    match db.sadd(key, members) {
-                        Ok(added) => {
-                            let mut aof = self.aof.write().await;
-                            if let Err(e) = aof.append(full_cmd_args) {
-                                error!("AOF write error: {}", e);
-                            }
-                            return ExecutionResult::Response(RespValue::Integer(added as i64));
-                        }
-                        Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                    }
-                } else if cmd_upper == "SREM" {
-                    if args.len() != 2 {
-                        return ExecutionResult::Response(RespValue::Error(
-                            "wrong number of arguments for 'SREM' command".to_string(),
-                        ));
-                    }
-                    let member = args[1].clone();
-                    let mut db = self.db.write().await;
-                    match db.srem(key, member) {
-                        Ok(removed) => {
-                            let mut aof = self.aof.write().await;
-                            if let Err(e) = aof.append(full_cmd_args) {
-                                error!("AOF write error: {}", e);
-                            }
-                            return ExecutionResult::Response(RespValue::Integer(removed as i64));
-                        }
-                        Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                    }
-                } else if cmd_upper == "SMEMBERS" {
-                    let mut db = self.db.write().await;
-                    match db.smembers(key) {
-                        Ok(members) => {
-                            let resp_members: Vec<RespValue> = members
-                                .into_iter()
-                                .map(|m| RespValue::BulkString(Some(m)))
-                                .collect();
-                            return ExecutionResult::Response(RespValue::Array(Some(resp_members)));
-                        }
-                        Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                    }
-                } else if cmd_upper == "SISMEMBER" {
-                    if args.len() != 2 {
-                        return ExecutionResult::Response(RespValue::Error(
-                            "wrong number of arguments for 'SISMEMBER' command".to_string(),
-                        ));
-                    }
-                    let member = args[1].clone();
-                    let mut db = self.db.write().await;
-                    match db.sismember(key, member) {
-                        Ok(exists) => {
-                            return ExecutionResult::Response(RespValue::Integer(if exists {
-                                1
-                            } else {
-                                0
-                            }))
-                        }
-                        Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                    }
-                } else if cmd_upper == "SCARD" {
-                    let mut db = self.db.write().await;
-                    match db.scard(key) {
-                        Ok(count) => {
-                            return ExecutionResult::Response(RespValue::Integer(count as i64))
-                        }
-                        Err(e) => return ExecutionResult::Response(RespValue::Error(e)),
-                    }
-                } else if cmd_upper == "PUBLISH" {
-                    if tokens.len() != 3 {
-                        return ExecutionResult::Response(RespValue::Error(
-                            "ERR wrong number of arguments for 'publish' command".to_string(),
-                        ));
-                    }
-
-                    let channel = match &tokens[1] {
-                        RespValue::BulkString(Some(s)) => s.clone(),
-                        RespValue::SimpleString(s) => s.clone(),
-                        _ => {
-                            return ExecutionResult::Response(RespValue::Error(
-                                "ERR channel name must be a string".to_string(),
-                            ));
-                        }
-                    };
-
-                    let message = match &tokens[2] {
-                        RespValue::BulkString(Some(s)) => s.clone(),
-                        RespValue::SimpleString(s) => s.clone(),
-                        _ => {
-                            return ExecutionResult::Response(RespValue::Error(
-                                "ERR message must be a string".to_string(),
-                            ))
-                        }
-                    };
-
-                    let count = self.pubsub.publish(&channel, &message).await;
-                    return ExecutionResult::Response(RespValue::Integer(count as i64));
-                } else if cmd_upper == "SUBSCRIBE" {
-                    // SUBSCRIBE komutu özeldir: Bağlantıyı bloklar ve mesajları dinler.
-                    // İstemci "abone" moduna geçer ve sadece pub/sub komutlarını gönderebilir.
-                    // ExecutionResult::Subscribe döndürerek bağlantı yöneticisinin (connection handler)
-                    // yayın akışı (streaming) moduna geçmesini sağlarız.
-
-                    let channel_name = match &tokens[1] {
-                        RespValue::BulkString(Some(s)) => s.clone(),
-                        RespValue::SimpleString(s) => s.clone(),
-                        _ => {
-                            return ExecutionResult::Response(RespValue::Error(
-                                "ERR channel name must be a string".to_string(),
-                            ))
-                        }
-                    };
-
-                    let receiver = self.pubsub.subscribe(&channel_name).await;
-                    return ExecutionResult::Subscribe(channel_name, receiver);
-                } else if cmd_upper == "SAVE" {
-                    // Synchronous snapshot save
-                    use crate::persistence::snapshot;
-                    match snapshot::save("dump.rdb", &self.db).await {
-                        Ok(_) => {
-                            return ExecutionResult::Response(RespValue::SimpleString(
-                                "OK".to_string(),
-                            ))
-                        }
-                        Err(e) => {
-                            return ExecutionResult::Response(RespValue::Error(format!(
-                                "Failed to save snapshot: {}",
-                                e
-                            )))
-                        }
-                    }
-                } else if cmd_upper == "BGSAVE" {
-                    // Background snapshot save
-                    let db_clone = Arc::clone(&self.db);
-
-                    tokio::spawn(async move {
-                        use crate::persistence::snapshot;
-                        use tracing::{error, info};
-                        if let Err(e) = snapshot::save("dump.rdb", &db_clone).await {
-                            error!("Background save failed: {}", e);
-                        } else {
-                            info!("Background save completed successfully");
-                        }
-                    });
-
-                    return ExecutionResult::Response(RespValue::SimpleString(
-                        "Background saving started".to_string(),
+        // Cluster modunda başka node'a ait anahtarlar için istemci yönlendirilir
+        if let Some(cluster) = &self.cluster {
+            let asking = std::mem::take(&mut self.asking);
+            if !self.master_link {
+                self.route(cluster, command, call.args(), asking)
+                    .await
+                    .map_err(|e| rejected(command, e))?;
+            }
+        }
+
+        // Raft modunda yazmalar çoğunluğa commit edildikten sonra uygulanır;
+        // okumalar liderliğin sürdüğü doğrulanınca yapılır (read-index)
+        if let Some(raft) = &self.raft {
+            if !self.raft_apply && !raft::is_local_command(command.name) {
+                if command.name == "REPLICAOF" || command.name == "SLAVEOF" {
+                    return Err(rejected(
+                        command,
+                        "replication commands are not allowed in raft mode".to_string(),
                     ));
-                } else {
-                    return ExecutionResult::Response(RespValue::Error(format!(
-                        "unknown command '{}'",
-                        cmd_string
-                    )));
                 }
+                if command.is_write() {
+                    return Ok(raft.propose(call.argv).await?.into());
+                }
+                raft.read_index().await.map_err(|e| rejected(command, e))?;
             }
-            _ => ExecutionResult::Response(RespValue::Error("invalid command format".to_string())),
         }
+
+        let start = std::time::Instant::now();
+        let result = (command.handler)(self, call).await;
+        let failed = matches!(
+            result,
+            Err(_) | Ok(ExecutionResult::Response(RespValue::Error(_)))
+        );
+        STATS.command_called(command.name, start.elapsed(), failed);
+        result
     }
 }
@@ -0,0 +1,451 @@
+use super::registry::Call;
+use super::{ok, CommandResult, Interpreter};
+use crate::db::ops::probabilistic::{BloomOps, CmsOps, CuckooOps, TopKOps};
+use crate::db::probabilistic::{BloomFilter, CountMinSketch, CuckooFilter, TopK};
+use crate::network::resp::RespValue;
+
+fn parse_u64(value: &str) -> Result<u64, String> {
+    value
+        .parse::<u64>()
+        .map_err(|_| "value is not an integer or out of range".to_string())
+}
+
+/// (0, 1) aralığında olması gereken oranları ayrıştırır.
+fn parse_ratio(value: &str, name: &str) -> Result<f64, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|v| *v > 0.0 && *v < 1.0)
+        .ok_or_else(|| format!("(0 < {} range < 1)", name))
+}
+
+fn parse_positive(value: &str, name: &str) -> Result<u64, String> {
+    match value.parse::<u64>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("({} should be larger than 0)", name)),
+    }
+}
+
+/// BF.RESERVE: `error_rate capacity [EXPANSION n] [NONSCALING]`.
+pub(super) fn parse_bf_reserve(args: &[String]) -> Result<BloomFilter, String> {
+    if args.len() < 2 {
+        return Err("wrong number of arguments for 'BF.RESERVE' command".to_string());
+    }
+    let error_rate = parse_ratio(&args[0], "error rate")?;
+    let capacity = parse_positive(&args[1], "capacity")?;
+
+    let mut expansion = None;
+    let mut nonscaling = false;
+    let mut pos = 2;
+    while pos < args.len() {
+        match args[pos].to_uppercase().as_str() {
+            "EXPANSION" if pos + 1 < args.len() => {
+                expansion = Some(parse_positive(&args[pos + 1], "expansion")? as u32);
+                pos += 1;
+            }
+            "NONSCALING" => nonscaling = true,
+            _ => return Err("syntax error".to_string()),
+        }
+        pos += 1;
+    }
+
+    if nonscaling && expansion.is_some() {
+        return Err("Nonscaling filters cannot expand".to_string());
+    }
+    let expansion = if nonscaling {
+        0
+    } else {
+        expansion.unwrap_or(BloomFilter::DEFAULT_EXPANSION)
+    };
+    Ok(BloomFilter::new(capacity, error_rate, expansion))
+}
+
+/// CF.RESERVE: `capacity [BUCKETSIZE n] [MAXITERATIONS n] [EXPANSION n]`.
+/// EXPANSION 0 filtrenin büyümesini kapatır.
+pub(super) fn parse_cf_reserve(args: &[String]) -> Result<CuckooFilter, String> {
+    let capacity = match args.first() {
+        Some(capacity) => parse_positive(capacity, "capacity")?,
+        None => return Err("wrong number of arguments for 'CF.RESERVE' command".to_string()),
+    };
+
+    let mut bucket_size = CuckooFilter::DEFAULT_BUCKET_SIZE;
+    let mut max_iterations = CuckooFilter::DEFAULT_MAX_ITERATIONS;
+    let mut expansion = CuckooFilter::DEFAULT_EXPANSION;
+    let mut pos = 1;
+    while pos < args.len() {
+        let value = args
+            .get(pos + 1)
+            .ok_or_else(|| "syntax error".to_string())?;
+        match args[pos].to_uppercase().as_str() {
+            "BUCKETSIZE" => {
+                bucket_size = parse_positive(value, "bucket size")? as usize;
+                if bucket_size > 255 {
+                    return Err("Bucket size cannot be larger than 255".to_string());
+                }
+            }
+            "MAXITERATIONS" => {
+                max_iterations = parse_positive(value, "max iterations")? as u32;
+            }
+            "EXPANSION" => expansion = parse_u64(value)?.min(32768) as u32,
+            _ => return Err("syntax error".to_string()),
+        }
+        pos += 2;
+    }
+
+    Ok(CuckooFilter::new(
+        capacity,
+        bucket_size,
+        max_iterations,
+        expansion,
+    ))
+}
+
+/// CMS.INITBYDIM: `width depth`.
+pub(super) fn parse_cms_initbydim(args: &[String]) -> Result<CountMinSketch, String> {
+    match args {
+        [width, depth] => Ok(CountMinSketch::new(
+            parse_positive(width, "width")? as usize,
+            parse_positive(depth, "depth")? as usize,
+        )),
+        _ => Err("wrong number of arguments for 'CMS.INITBYDIM' command".to_string()),
+    }
+}
+
+/// CMS.INITBYPROB: `error probability`.
+pub(super) fn parse_cms_initbyprob(args: &[String]) -> Result<CountMinSketch, String> {
+    match args {
+        [error, probability] => Ok(CountMinSketch::with_error(
+            parse_ratio(error, "error")?,
+            parse_ratio(probability, "probability")?,
+        )),
+        _ => Err("wrong number of arguments for 'CMS.INITBYPROB' command".to_string()),
+    }
+}
+
+/// CMS.INCRBY: `item increment [item increment ...]`.
+pub(super) fn parse_cms_incrby(args: &[String]) -> Result<Vec<(String, u64)>, String> {
+    if args.is_empty() || !args.len().is_multiple_of(2) {
+        return Err("wrong number of arguments for 'CMS.INCRBY' command".to_string());
+    }
+    args.chunks(2)
+        .map(|pair| {
+            let by = parse_u64(&pair[1]).map_err(|_| "CMS: Cannot parse number".to_string())?;
+            Ok((pair[0].clone(), by))
+        })
+        .collect()
+}
+
+/// CMS.MERGE: `numKeys source [source ...] [WEIGHTS weight [weight ...]]`.
+/// Ağırlık verilmezse hepsi 1 kabul edilir.
+pub(super) fn parse_cms_merge(args: &[String]) -> Result<(Vec<String>, Vec<u64>), String> {
+    let num_keys = match args.first() {
+        Some(n) => parse_positive(n, "numKeys")? as usize,
+        None => return Err("wrong number of arguments for 'CMS.MERGE' command".to_string()),
+    };
+    let sources = args
+        .get(1..1 + num_keys)
+        .ok_or_else(|| "CMS: wrong number of keys".to_string())?
+        .to_vec();
+
+    let rest = &args[1 + num_keys..];
+    let weights = match rest.split_first() {
+        None => vec![1; num_keys],
+        Some((option, weights)) if option.eq_ignore_ascii_case("WEIGHTS") => {
+            if weights.len() != num_keys {
+                return Err("CMS: wrong number of keys/weights".to_string());
+            }
+            weights
+                .iter()
+                .map(|w| parse_u64(w))
+                .collect::<Result<_, _>>()?
+        }
+        Some(_) => return Err("syntax error".to_string()),
+    };
+    Ok((sources, weights))
+}
+
+/// TOPK.RESERVE: `topk [width depth decay]`.
+pub(super) fn parse_topk_reserve(args: &[String]) -> Result<TopK, String> {
+    match args {
+        [k] => Ok(TopK::new(
+            parse_positive(k, "topk")? as usize,
+            TopK::DEFAULT_WIDTH,
+            TopK::DEFAULT_DEPTH,
+            TopK::DEFAULT_DECAY,
+        )),
+        [k, width, depth, decay] => Ok(TopK::new(
+            parse_positive(k, "topk")? as usize,
+            parse_positive(width, "width")? as usize,
+            parse_positive(depth, "depth")? as usize,
+            decay
+                .parse::<f64>()
+                .ok()
+                .filter(|d| *d > 0.0 && *d <= 1.0)
+                .ok_or_else(|| "TopK: decay must be in range (0, 1]".to_string())?,
+        )),
+        _ => Err("wrong number of arguments for 'TOPK.RESERVE' command".to_string()),
+    }
+}
+
+pub(super) fn bools_reply(values: Vec<bool>) -> RespValue {
+    RespValue::Array(Some(
+        values
+            .into_iter()
+            .map(|v| RespValue::Integer(v as i64))
+            .collect(),
+    ))
+}
+
+pub(super) fn integers_reply(values: Vec<u64>) -> RespValue {
+    RespValue::Array(Some(
+        values
+            .into_iter()
+            .map(|v| RespValue::Integer(v as i64))
+            .collect(),
+    ))
+}
+
+/// `[alan, değer, ...]` biçimindeki INFO cevabı.
+fn info_reply(fields: Vec<(&str, RespValue)>) -> RespValue {
+    RespValue::Array(Some(
+        fields
+            .into_iter()
+            .flat_map(|(name, value)| [RespValue::SimpleString(name.to_string()), value])
+            .collect(),
+    ))
+}
+
+fn int(value: impl TryInto<i64>) -> RespValue {
+    RespValue::Integer(value.try_into().unwrap_or(i64::MAX))
+}
+
+pub(super) fn bf_info_reply(filter: &BloomFilter) -> RespValue {
+    info_reply(vec![
+        ("Capacity", int(filter.capacity())),
+        ("Size", int(filter.memory_usage())),
+        ("Number of filters", int(filter.filters())),
+        ("Number of items inserted", int(filter.len())),
+        (
+            "Expansion rate",
+            match filter.expansion() {
+                0 => RespValue::BulkString(None),
+                rate => int(rate),
+            },
+        ),
+    ])
+}
+
+pub(super) fn cf_info_reply(filter: &CuckooFilter) -> RespValue {
+    info_reply(vec![
+        ("Size", int(filter.memory_usage())),
+        ("Number of buckets", int(filter.buckets())),
+        ("Number of filters", int(filter.filters())),
+        ("Number of items inserted", int(filter.inserted())),
+        ("Number of items deleted", int(filter.deleted())),
+        ("Bucket size", int(filter.bucket_size())),
+        ("Expansion rate", int(filter.expansion())),
+        ("Max iterations", int(filter.max_iterations())),
+    ])
+}
+
+pub(super) fn cms_info_reply(sketch: &CountMinSketch) -> RespValue {
+    info_reply(vec![
+        ("width", int(sketch.width())),
+        ("depth", int(sketch.depth())),
+        ("count", int(sketch.count())),
+        ("size", int(sketch.memory_usage())),
+    ])
+}
+
+pub(super) fn topk_info_reply(topk: &TopK) -> RespValue {
+    info_reply(vec![
+        ("k", int(topk.k())),
+        ("width", int(topk.width())),
+        ("depth", int(topk.depth())),
+        (
+            "decay",
+            RespValue::BulkString(Some(topk.decay().to_string())),
+        ),
+        ("size", int(topk.memory_usage())),
+    ])
+}
+
+impl Interpreter {
+    pub(super) async fn bf_reserve(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let filter = parse_bf_reserve(&args[1..])?;
+        let mut db = self.db.write().await;
+        db.bf_reserve(args[0].clone(), filter)?;
+        self.propagate(call.argv).await;
+        ok()
+    }
+
+    /// BF.ADD / BF.MADD
+    pub(super) async fn bf_add(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut db = self.db.write().await;
+        let results = db.bf_add(args[0].clone(), args[1..].to_vec())?;
+        if results.iter().any(|r| r == &Ok(true)) {
+            self.propagate(call.argv).await;
+        }
+        let mut replies: Vec<RespValue> = results
+            .into_iter()
+            .map(|r| match r {
+                Ok(added) => RespValue::Integer(added as i64),
+                Err(e) => RespValue::Error(e),
+            })
+            .collect();
+        if call.name == "BF.ADD" {
+            return Ok(replies.remove(0).into());
+        }
+        Ok(RespValue::Array(Some(replies)).into())
+    }
+
+    pub(super) async fn bf_exists(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut db = self.db.write().await;
+        let found = db.bf_exists(args[0].clone(), vec![args[1].clone()])?;
+        Ok(RespValue::Integer(found[0] as i64).into())
+    }
+
+    pub(super) async fn bf_info(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        let filter = db.bf_info(&call.args()[0])?;
+        Ok(bf_info_reply(filter).into())
+    }
+
+    pub(super) async fn cf_reserve(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let filter = parse_cf_reserve(&args[1..])?;
+        let mut db = self.db.write().await;
+        db.cf_reserve(args[0].clone(), filter)?;
+        self.propagate(call.argv).await;
+        ok()
+    }
+
+    /// CF.ADD / CF.DEL
+    pub(super) async fn cf_add(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let (key, item) = (args[0].clone(), args[1].clone());
+        let mut db = self.db.write().await;
+        let changed = if call.name == "CF.ADD" {
+            db.cf_add(key, item).map(|_| true)
+        } else {
+            db.cf_del(key, item)
+        }?;
+        if changed {
+            self.propagate(call.argv).await;
+        }
+        Ok(RespValue::Integer(changed as i64).into())
+    }
+
+    pub(super) async fn cf_exists(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut db = self.db.write().await;
+        let found = db.cf_exists(args[0].clone(), vec![args[1].clone()])?;
+        Ok(RespValue::Integer(found[0] as i64).into())
+    }
+
+    pub(super) async fn cf_info(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        let filter = db.cf_info(&call.args()[0])?;
+        Ok(cf_info_reply(filter).into())
+    }
+
+    /// CMS.INITBYDIM / CMS.INITBYPROB
+    pub(super) async fn cms_init(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let sketch = if call.name == "CMS.INITBYDIM" {
+            parse_cms_initbydim(&args[1..])
+        } else {
+            parse_cms_initbyprob(&args[1..])
+        }?;
+        let mut db = self.db.write().await;
+        db.cms_init(args[0].clone(), sketch)?;
+        self.propagate(call.argv).await;
+        ok()
+    }
+
+    pub(super) async fn cms_incrby(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let items = parse_cms_incrby(&args[1..])?;
+        let mut db = self.db.write().await;
+        let counts = db.cms_incrby(args[0].clone(), items)?;
+        self.propagate(call.argv).await;
+        Ok(integers_reply(counts).into())
+    }
+
+    pub(super) async fn cms_query(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut db = self.db.write().await;
+        let counts = db.cms_query(args[0].clone(), args[1..].to_vec())?;
+        Ok(integers_reply(counts).into())
+    }
+
+    pub(super) async fn cms_merge(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let (sources, weights) = parse_cms_merge(&args[1..])?;
+        let mut db = self.db.write().await;
+        db.cms_merge(args[0].clone(), sources, weights)?;
+        self.propagate(call.argv).await;
+        ok()
+    }
+
+    pub(super) async fn cms_info(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        let sketch = db.cms_info(&call.args()[0])?;
+        Ok(cms_info_reply(sketch).into())
+    }
+
+    pub(super) async fn topk_reserve(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let topk = parse_topk_reserve(&args[1..])?;
+        let mut db = self.db.write().await;
+        db.topk_reserve(args[0].clone(), topk)?;
+        self.propagate(call.argv).await;
+        ok()
+    }
+
+    pub(super) async fn topk_add(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut db = self.db.write().await;
+        let expelled = db.topk_add(args[0].clone(), args[1..].to_vec())?;
+        self.propagate(call.argv).await;
+        Ok(RespValue::Array(Some(
+            expelled.into_iter().map(RespValue::BulkString).collect(),
+        ))
+        .into())
+    }
+
+    pub(super) async fn topk_query(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut db = self.db.write().await;
+        let found = db.topk_query(args[0].clone(), args[1..].to_vec())?;
+        Ok(bools_reply(found).into())
+    }
+
+    pub(super) async fn topk_list(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let with_count = match args.get(1) {
+            None => false,
+            Some(option) if option.eq_ignore_ascii_case("WITHCOUNT") => true,
+            Some(_) => return Err("syntax error".to_string()),
+        };
+
+        let mut db = self.db.write().await;
+        let mut items = Vec::new();
+        for (item, count) in db.topk_list(args[0].clone())? {
+            items.push(RespValue::BulkString(Some(item)));
+            if with_count {
+                items.push(RespValue::Integer(count as i64));
+            }
+        }
+        Ok(RespValue::Array(Some(items)).into())
+    }
+
+    pub(super) async fn topk_info(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        let topk = db.topk_info(&call.args()[0])?;
+        Ok(topk_info_reply(topk).into())
+    }
+}
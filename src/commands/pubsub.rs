@@ -0,0 +1,21 @@
+use super::registry::Call;
+use super::{CommandResult, ExecutionResult, Interpreter};
+use crate::network::resp::RespValue;
+
+impl Interpreter {
+    pub(super) async fn publish(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let count = self.pubsub.publish(&args[0], &args[1]).await;
+        Ok(RespValue::Integer(count as i64).into())
+    }
+
+    /// SUBSCRIBE komutu özeldir: Bağlantıyı bloklar ve mesajları dinler.
+    /// İstemci "abone" moduna geçer ve sadece pub/sub komutlarını gönderebilir.
+    /// ExecutionResult::Subscribe döndürerek bağlantı yöneticisinin (connection handler)
+    /// yayın akışı (streaming) moduna geçmesini sağlarız.
+    pub(super) async fn subscribe(&mut self, call: Call) -> CommandResult {
+        let channel = call.args()[0].clone();
+        let receiver = self.pubsub.subscribe(&channel).await;
+        Ok(ExecutionResult::Subscribe(channel, receiver))
+    }
+}
@@ -0,0 +1,545 @@
+//! Komut tablosu. Her komut adını, arity'sini, bayraklarını, anahtar konumlarını, ACL
+//! kategorilerini ve işleyicisini burada bildirir. Yorumlayıcı komutu bu tablodan bulur
+//! ve argüman sayısını işleyiciyi çağırmadan kontrol eder; replikasyon yazmaları
+//! bayraklardan, cluster yönlendirmesi ve ACL anahtar kuralları anahtar konumlarından,
+//! ACL kategorileri de buradaki listeden okunur.
+
+use super::{ExecutionResult, Interpreter};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::LazyLock;
+
+/// Komutun davranışını anlatan bayraklar; adları Redis'teki karşılıklarıdır.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flag {
+    /// Veriyi değiştirir; replikalar yalnızca primary'den kabul eder
+    Write,
+    /// Yalnızca veriyi okur
+    ReadOnly,
+    /// Bellek kullanımını artırabilir
+    DenyOom,
+    /// Sunucuyu yöneten komut
+    Admin,
+    /// Pub/sub komutu
+    PubSub,
+    /// Script içinden çağrılamaz
+    NoScript,
+}
+
+impl Flag {
+    pub fn name(self) -> &'static str {
+        match self {
+            Flag::Write => "write",
+            Flag::ReadOnly => "readonly",
+            Flag::DenyOom => "denyoom",
+            Flag::Admin => "admin",
+            Flag::PubSub => "pubsub",
+            Flag::NoScript => "noscript",
+        }
+    }
+}
+
+/// Komutun hangi argümanlarının anahtar olduğu.
+#[derive(Debug, Clone, Copy)]
+pub enum Keys {
+    None,
+    /// Redis'teki gibi ilk ve son anahtarın konumu ve adım. Konumlar komut adını da sayar
+    /// (ilk argüman 1'dir); negatif `last` sondan sayılır, -1 son argümandır.
+    Range {
+        first: i32,
+        last: i32,
+        step: i32,
+    },
+    /// Anahtarların yeri argümanların içeriğine bağlı (`args` komut adını içermez)
+    Movable(for<'a> fn(&'a [String]) -> Vec<&'a str>),
+}
+
+/// İlk argümanı anahtar olan komutlar
+const KEY: Keys = Keys::Range {
+    first: 1,
+    last: 1,
+    step: 1,
+};
+
+/// Bütün argümanları anahtar olan komutlar
+const ALL_KEYS: Keys = Keys::Range {
+    first: 1,
+    last: -1,
+    step: 1,
+};
+
+/// İlk iki argümanı anahtar olan komutlar
+const TWO_KEYS: Keys = Keys::Range {
+    first: 1,
+    last: 2,
+    step: 1,
+};
+
+/// Komutun çağrılışı.
+pub(crate) struct Call {
+    /// Tablodaki büyük harfli ad
+    pub name: &'static str,
+    /// İstemcinin gönderdiği haliyle komut adı ve argümanlar; AOF'a ve replikalara bu
+    /// yazılır
+    pub argv: Vec<String>,
+}
+
+impl Call {
+    /// Komut adından sonraki argümanlar
+    pub fn args(&self) -> &[String] {
+        &self.argv[1..]
+    }
+}
+
+pub(crate) type HandlerFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<ExecutionResult, String>> + Send + 'a>>;
+pub(crate) type Handler = for<'a> fn(&'a mut Interpreter, Call) -> HandlerFuture<'a>;
+
+pub struct Command {
+    /// Büyük harfli ad
+    pub name: &'static str,
+    /// Komut adı dahil argüman sayısı: pozitifse tam olarak bu kadar, negatifse en az
+    /// `-arity` kadar argüman gerekir
+    pub arity: i32,
+    pub flags: &'static [Flag],
+    pub keys: Keys,
+    /// ACL kategorileri; `read`/`write` ve `all` buraya yazılmaz
+    pub categories: &'static [&'static str],
+    /// `COMMAND DOCS` ile dönen tek cümlelik açıklama
+    pub summary: &'static str,
+    /// İstemcilere ve cluster proxy'lerine ipuçları (`nondeterministic_output`,
+    /// `request_policy:all_shards` ...)
+    pub tips: &'static [&'static str],
+    pub(crate) handler: Handler,
+}
+
+impl Command {
+    pub fn has_flag(&self, flag: Flag) -> bool {
+        self.flags.contains(&flag)
+    }
+
+    pub fn is_write(&self) -> bool {
+        self.has_flag(Flag::Write)
+    }
+
+    /// Argüman sayısı (komut adı dahil) komutun arity'sine uyuyor mu?
+    pub fn accepts(&self, argc: usize) -> bool {
+        let arity = self.arity.unsigned_abs() as usize;
+        if self.arity < 0 {
+            argc >= arity
+        } else {
+            argc == arity
+        }
+    }
+
+    /// Komutun anahtar argümanları (`args` komut adını içermez). Eksik argümanlar
+    /// atlanır; arity kontrolünden önce de çağrılabilir.
+    pub fn keys<'a>(&self, args: &'a [String]) -> Vec<&'a str> {
+        match self.keys {
+            Keys::None => Vec::new(),
+            Keys::Range { first, last, step } => {
+                let argc = args.len() as i32 + 1;
+                let last = if last < 0 {
+                    argc + last
+                } else {
+                    last.min(argc - 1)
+                };
+                (first..=last)
+                    .step_by(step as usize)
+                    .map(|pos| args[pos as usize - 1].as_str())
+                    .collect()
+            }
+            Keys::Movable(keys) => keys(args),
+        }
+    }
+}
+
+/// `wrong number of arguments` hatası
+pub(crate) fn wrong_arity(name: &str) -> String {
+    format!("wrong number of arguments for '{}' command", name)
+}
+
+/// CMS.MERGE dest numkeys src [src ...] [WEIGHTS ...]
+fn cms_merge_keys(args: &[String]) -> Vec<&str> {
+    let count = args
+        .get(1)
+        .and_then(|n| n.parse::<usize>().ok())
+        .unwrap_or(0);
+    args.iter()
+        .take(1)
+        .chain(args.iter().skip(2).take(count))
+        .map(String::as_str)
+        .collect()
+}
+
+/// XREAD/XREADGROUP ... STREAMS key [key ...] id [id ...]
+fn stream_keys(args: &[String]) -> Vec<&str> {
+    match args
+        .iter()
+        .position(|arg| arg.eq_ignore_ascii_case("STREAMS"))
+    {
+        Some(pos) => {
+            let rest = &args[pos + 1..];
+            rest[..rest.len() / 2].iter().map(String::as_str).collect()
+        }
+        None => Vec::new(),
+    }
+}
+
+/// MIGRATE host port key|"" db timeout [COPY] [REPLACE] [KEYS key [key ...]]
+fn migrate_keys(args: &[String]) -> Vec<&str> {
+    let key = args.get(2).filter(|key| !key.is_empty());
+    let listed = args
+        .iter()
+        .skip(5)
+        .position(|arg| arg.eq_ignore_ascii_case("KEYS"))
+        .map_or(&[][..], |pos| &args[5 + pos + 1..]);
+    key.into_iter().chain(listed).map(String::as_str).collect()
+}
+
+macro_rules! command {
+    (
+        $name:literal, $arity:literal, [$($flag:ident),*], $keys:expr, [$($category:literal),*],
+        $handler:ident, $summary:literal $(, [$($tip:literal),*])?
+    ) => {
+        Command {
+            name: $name,
+            arity: $arity,
+            flags: &[$(Flag::$flag),*],
+            keys: $keys,
+            categories: &[$($category),*],
+            summary: $summary,
+            tips: &[$($($tip),*)?],
+            handler: {
+                fn handler(client: &mut Interpreter, call: Call) -> HandlerFuture<'_> {
+                    Box::pin(client.$handler(call))
+                }
+                handler
+            },
+        }
+    };
+}
+
+/// Bütün komutlar
+#[rustfmt::skip]
+pub static COMMANDS: &[Command] = &[
+    command!("GET", 2, [ReadOnly], KEY, ["string"], get,
+        "Returns the string value of a key."),
+    command!("SET", -3, [Write, DenyOom], KEY, ["string"], set,
+        "Sets the string value of a key. The key is created if it doesn't exist."),
+    command!("INCR", 2, [Write, DenyOom], KEY, ["string"], incr,
+        "Increments the integer value of a key by one. Uses 0 as initial value if the key doesn't exist."),
+    command!("DECR", 2, [Write, DenyOom], KEY, ["string"], decr,
+        "Decrements the integer value of a key by one. Uses 0 as initial value if the key doesn't exist."),
+    command!("DEL", -2, [Write], ALL_KEYS, ["keyspace"], del,
+        "Deletes a key."),
+    command!("EXISTS", -2, [ReadOnly], ALL_KEYS, ["keyspace"], exists,
+        "Determines whether a key exists."),
+    command!("EXPIRE", -3, [Write], KEY, ["keyspace"], expire,
+        "Sets the expiration time of a key in seconds."),
+    command!("PERSIST", 2, [Write], KEY, ["keyspace"], persist,
+        "Removes the expiration time of a key."),
+    command!("TTL", 2, [ReadOnly], KEY, ["keyspace"], ttl,
+        "Returns the expiration time in seconds of a key.", ["nondeterministic_output"]),
+    command!("KEYS", 2, [ReadOnly], Keys::None, ["keyspace", "dangerous"], keys,
+        "Returns all key names that match a pattern.", ["request_policy:all_shards", "nondeterministic_output_order"]),
+    command!("DUMP", 2, [ReadOnly], KEY, ["keyspace"], dump,
+        "Returns a serialized representation of the value stored at a key."),
+    command!("RESTORE", -4, [Write, DenyOom], KEY, ["keyspace", "dangerous"], restore,
+        "Creates a key from the serialized representation of a value."),
+    command!("MIGRATE", -6, [Write], Keys::Movable(migrate_keys), ["keyspace", "dangerous"], migrate,
+        "Atomically transfers keys from one instance to another.", ["nondeterministic_output"]),
+    command!("LPUSH", -3, [Write, DenyOom], KEY, ["list"], push,
+        "Prepends one or more elements to a list. Creates the key if it doesn't exist."),
+    command!("RPUSH", -3, [Write, DenyOom], KEY, ["list"], push,
+        "Appends one or more elements to a list. Creates the key if it doesn't exist."),
+    command!("LPOP", -2, [Write], KEY, ["list"], pop,
+        "Returns the first element of a list after removing it. Deletes the list if the last element was popped."),
+    command!("RPOP", -2, [Write], KEY, ["list"], pop,
+        "Returns and removes the last element of a list. Deletes the list if the last element was popped."),
+    command!("LRANGE", 4, [ReadOnly], KEY, ["list"], lrange,
+        "Returns a range of elements from a list."),
+    command!("LLEN", 2, [ReadOnly], KEY, ["list"], llen,
+        "Returns the length of a list."),
+    command!("HSET", 4, [Write, DenyOom], KEY, ["hash"], hset,
+        "Creates or modifies the value of a field in a hash."),
+    command!("HGET", 3, [ReadOnly], KEY, ["hash"], hget,
+        "Returns the value of a field in a hash."),
+    command!("HDEL", 3, [Write], KEY, ["hash"], hdel,
+        "Deletes a field from a hash. Deletes the hash if no fields remain."),
+    command!("HGETALL", 2, [ReadOnly], KEY, ["hash"], hgetall,
+        "Returns all fields and values in a hash.", ["nondeterministic_output_order"]),
+    command!("SADD", -3, [Write, DenyOom], KEY, ["set"], sadd,
+        "Adds one or more members to a set. Creates the key if it doesn't exist."),
+    command!("SREM", 3, [Write], KEY, ["set"], srem,
+        "Removes a member from a set. Deletes the set if the last member was removed."),
+    command!("SMEMBERS", 2, [ReadOnly], KEY, ["set"], smembers,
+        "Returns all members of a set.", ["nondeterministic_output_order"]),
+    command!("SISMEMBER", 3, [ReadOnly], KEY, ["set"], sismember,
+        "Determines whether a member belongs to a set."),
+    command!("SCARD", 2, [ReadOnly], KEY, ["set"], scard,
+        "Returns the number of members in a set."),
+    command!("GEOADD", -5, [Write, DenyOom], KEY, ["geo"], geoadd,
+        "Adds one or more members to a geospatial index. The key is created if it doesn't exist."),
+    command!("GEODIST", -4, [ReadOnly], KEY, ["geo"], geodist,
+        "Returns the distance between two members of a geospatial index."),
+    command!("GEOHASH", -2, [ReadOnly], KEY, ["geo"], geohash,
+        "Returns members from a geospatial index as geohash strings."),
+    command!("GEOPOS", -2, [ReadOnly], KEY, ["geo"], geopos,
+        "Returns the longitude and latitude of members from a geospatial index."),
+    command!("GEOSEARCH", -7, [ReadOnly], KEY, ["geo"], geosearch,
+        "Queries a geospatial index for members inside an area of a box or a circle."),
+    command!("GEOSEARCHSTORE", -8, [Write, DenyOom], TWO_KEYS, ["geo"], geosearchstore,
+        "Queries a geospatial index for members inside an area of a box or a circle and stores the result."),
+    command!("XADD", -5, [Write, DenyOom], KEY, ["stream"], xadd,
+        "Appends a new message to a stream. Creates the key if it doesn't exist."),
+    command!("XLEN", 2, [ReadOnly], KEY, ["stream"], xlen,
+        "Returns the number of messages in a stream."),
+    command!("XRANGE", -4, [ReadOnly], KEY, ["stream"], xrange,
+        "Returns the messages from a stream within a range of IDs."),
+    command!("XREVRANGE", -4, [ReadOnly], KEY, ["stream"], xrange,
+        "Returns the messages from a stream within a range of IDs in reverse order."),
+    command!("XTRIM", -4, [Write], KEY, ["stream"], xtrim,
+        "Deletes messages from the beginning of a stream."),
+    command!("XREAD", -4, [ReadOnly], Keys::Movable(stream_keys), ["stream"], xread,
+        "Returns messages from multiple streams with IDs greater than the ones requested. Blocks until a message is available otherwise."),
+    command!("XGROUP", -2, [Write, DenyOom], Keys::Range { first: 2, last: 2, step: 1 }, ["stream"], xgroup,
+        "Creates, modifies and deletes consumer groups and their consumers."),
+    command!("XREADGROUP", -7, [Write], Keys::Movable(stream_keys), ["stream"], xread,
+        "Returns new or historical messages from a stream for a consumer in a group. Blocks until a message is available otherwise."),
+    command!("XACK", -4, [Write], KEY, ["stream"], xack,
+        "Returns the number of messages that were successfully acknowledged by the consumer group member of a stream."),
+    command!("XPENDING", -3, [ReadOnly], KEY, ["stream"], xpending,
+        "Returns the information and entries from a stream consumer group's pending entries list.", ["nondeterministic_output"]),
+    command!("XCLAIM", -6, [Write], KEY, ["stream"], xclaim,
+        "Changes, or acquires, ownership of a message in a consumer group, as if the message was delivered to a consumer group member.", ["nondeterministic_output"]),
+    command!("XAUTOCLAIM", -6, [Write], KEY, ["stream"], xautoclaim,
+        "Changes, or acquires, ownership of messages in a consumer group, as if the messages were delivered to a consumer group member.", ["nondeterministic_output"]),
+    command!("XINFO", -2, [ReadOnly], Keys::Range { first: 2, last: 2, step: 1 }, ["stream"], xinfo,
+        "Returns information about streams, consumer groups and their consumers.", ["nondeterministic_output"]),
+    command!("JSON.SET", -4, [Write, DenyOom], KEY, ["json"], json_set,
+        "Sets or updates the JSON value at a path."),
+    command!("JSON.GET", -2, [ReadOnly], KEY, ["json"], json_get,
+        "Gets the value at one or more paths in JSON serialized form."),
+    command!("JSON.DEL", -2, [Write], KEY, ["json"], json_del,
+        "Deletes the values at a path."),
+    command!("JSON.TYPE", -2, [ReadOnly], KEY, ["json"], json_type,
+        "Returns the type of the JSON value at a path."),
+    command!("JSON.ARRAPPEND", -4, [Write, DenyOom], KEY, ["json"], json_arrappend,
+        "Appends one or more JSON values to the arrays at a path."),
+    command!("JSON.NUMINCRBY", 4, [Write, DenyOom], KEY, ["json"], json_numincrby,
+        "Increments the numeric values at a path."),
+    command!("JSON.OBJKEYS", -2, [ReadOnly], KEY, ["json"], json_objkeys,
+        "Returns the keys of the objects at a path."),
+    command!("BF.RESERVE", -4, [Write, DenyOom], KEY, ["probabilistic"], bf_reserve,
+        "Creates a new Bloom filter."),
+    command!("BF.ADD", 3, [Write, DenyOom], KEY, ["probabilistic"], bf_add,
+        "Adds an item to a Bloom filter. The filter is created if it doesn't exist."),
+    command!("BF.MADD", -3, [Write, DenyOom], KEY, ["probabilistic"], bf_add,
+        "Adds one or more items to a Bloom filter. The filter is created if it doesn't exist."),
+    command!("BF.EXISTS", 3, [ReadOnly], KEY, ["probabilistic"], bf_exists,
+        "Checks whether an item exists in a Bloom filter."),
+    command!("BF.INFO", -2, [ReadOnly], KEY, ["probabilistic"], bf_info,
+        "Returns information about a Bloom filter."),
+    command!("CF.RESERVE", -3, [Write, DenyOom], KEY, ["probabilistic"], cf_reserve,
+        "Creates a new Cuckoo filter."),
+    command!("CF.ADD", 3, [Write, DenyOom], KEY, ["probabilistic"], cf_add,
+        "Adds an item to a Cuckoo filter. The filter is created if it doesn't exist."),
+    command!("CF.DEL", 3, [Write], KEY, ["probabilistic"], cf_add,
+        "Deletes an item from a Cuckoo filter."),
+    command!("CF.EXISTS", 3, [ReadOnly], KEY, ["probabilistic"], cf_exists,
+        "Checks whether an item exists in a Cuckoo filter."),
+    command!("CF.INFO", 2, [ReadOnly], KEY, ["probabilistic"], cf_info,
+        "Returns information about a Cuckoo filter."),
+    command!("CMS.INITBYDIM", 4, [Write, DenyOom], KEY, ["probabilistic"], cms_init,
+        "Initializes a Count-Min sketch with the given width and depth."),
+    command!("CMS.INITBYPROB", 4, [Write, DenyOom], KEY, ["probabilistic"], cms_init,
+        "Initializes a Count-Min sketch for the given error rate and probability."),
+    command!("CMS.INCRBY", -4, [Write, DenyOom], KEY, ["probabilistic"], cms_incrby,
+        "Increases the count of one or more items."),
+    command!("CMS.QUERY", -3, [ReadOnly], KEY, ["probabilistic"], cms_query,
+        "Returns the count of one or more items in a sketch."),
+    command!("CMS.MERGE", -4, [Write, DenyOom], Keys::Movable(cms_merge_keys), ["probabilistic"], cms_merge,
+        "Merges several sketches into one sketch."),
+    command!("CMS.INFO", 2, [ReadOnly], KEY, ["probabilistic"], cms_info,
+        "Returns information about a Count-Min sketch."),
+    command!("TOPK.RESERVE", -3, [Write, DenyOom], KEY, ["probabilistic"], topk_reserve,
+        "Initializes a Top-K sketch."),
+    command!("TOPK.ADD", -3, [Write, DenyOom], KEY, ["probabilistic"], topk_add,
+        "Adds one or more items to a Top-K sketch and returns the items that were expelled."),
+    command!("TOPK.QUERY", -3, [ReadOnly], KEY, ["probabilistic"], topk_query,
+        "Checks whether one or more items are in the Top-K list."),
+    command!("TOPK.LIST", -2, [ReadOnly], KEY, ["probabilistic"], topk_list,
+        "Returns the items in the Top-K list."),
+    command!("TOPK.INFO", 2, [ReadOnly], KEY, ["probabilistic"], topk_info,
+        "Returns information about a Top-K sketch."),
+    command!("TS.CREATE", -2, [Write, DenyOom], KEY, ["timeseries"], ts_create,
+        "Creates a new time series."),
+    command!("TS.ADD", -4, [Write, DenyOom], KEY, ["timeseries"], ts_add,
+        "Appends a sample to a time series. The series is created if it doesn't exist."),
+    command!("TS.MADD", -4, [Write, DenyOom], Keys::Range { first: 1, last: -1, step: 3 }, ["timeseries"], ts_madd,
+        "Appends samples to one or more time series."),
+    command!("TS.GET", -2, [ReadOnly], KEY, ["timeseries"], ts_get,
+        "Returns the sample with the highest timestamp from a time series."),
+    command!("TS.RANGE", -4, [ReadOnly], KEY, ["timeseries"], ts_range,
+        "Queries a range of samples from a time series."),
+    command!("TS.MRANGE", -3, [ReadOnly], Keys::None, ["timeseries"], ts_mrange,
+        "Queries a range of samples across the time series that match a filter."),
+    command!("TS.INFO", -2, [ReadOnly], KEY, ["timeseries"], ts_info,
+        "Returns information and statistics about a time series."),
+    command!("TS.CREATERULE", 6, [Write, DenyOom], TWO_KEYS, ["timeseries"], ts_createrule,
+        "Creates a compaction rule."),
+    command!("TS.DELETERULE", 3, [Write], TWO_KEYS, ["timeseries"], ts_deleterule,
+        "Deletes a compaction rule."),
+    command!("FT.CREATE", -2, [Write, DenyOom], Keys::None, ["search"], ft_create,
+        "Creates an index with the given schema."),
+    command!("FT.DROPINDEX", -2, [Write], Keys::None, ["search"], ft_dropindex,
+        "Deletes an index."),
+    command!("FT.SEARCH", -3, [ReadOnly], Keys::None, ["search"], ft_search,
+        "Searches an index with a query and returns the matching documents."),
+    command!("FT.AGGREGATE", -3, [ReadOnly], Keys::None, ["search"], ft_aggregate,
+        "Runs a query on an index and groups, reduces and sorts the results."),
+    command!("FT.INFO", 2, [ReadOnly], Keys::None, ["search"], ft_info,
+        "Returns information and statistics about an index.", ["nondeterministic_output"]),
+    command!("FT._LIST", 1, [ReadOnly], Keys::None, ["search"], ft_list,
+        "Returns the names of all indexes.", ["nondeterministic_output_order"]),
+    command!("PUBLISH", 3, [PubSub], Keys::None, ["pubsub"], publish,
+        "Posts a message to a channel."),
+    command!("SUBSCRIBE", -2, [PubSub, NoScript], Keys::None, ["pubsub"], subscribe,
+        "Listens for messages published to a channel."),
+    command!("PING", -1, [], Keys::None, ["connection"], ping,
+        "Returns the server's liveliness response.", ["request_policy:all_shards", "response_policy:all_succeeded"]),
+    command!("ECHO", 2, [], Keys::None, ["connection"], echo,
+        "Returns the given string."),
+    command!("AUTH", -2, [NoScript], Keys::None, ["connection"], auth,
+        "Authenticates the connection."),
+    command!("HELLO", -1, [NoScript], Keys::None, ["connection"], hello,
+        "Handshakes with the server."),
+    command!("ASKING", 1, [], Keys::None, ["connection"], asking_command,
+        "Signals that a cluster client is following an -ASK redirect."),
+    command!("INFO", -1, [], Keys::None, ["dangerous"], info,
+        "Returns information and statistics about the server.", ["nondeterministic_output", "request_policy:all_shards", "response_policy:special"]),
+    command!("ROLE", 1, [Admin, NoScript], Keys::None, ["admin", "dangerous"], role,
+        "Returns the replication role.", ["nondeterministic_output"]),
+    command!("WAIT", 3, [NoScript], Keys::None, ["connection"], wait,
+        "Blocks until the asynchronous replication of all preceding write commands sent by the connection is completed.", ["request_policy:all_shards", "response_policy:agg_min"]),
+    command!("CONFIG", -2, [Admin, NoScript], Keys::None, ["admin", "dangerous"], config_command,
+        "A container for server configuration commands."),
+    command!("SAVE", 1, [Admin, NoScript], Keys::None, ["admin", "dangerous"], save,
+        "Synchronously saves the database to disk."),
+    command!("BGSAVE", -1, [Admin, NoScript], Keys::None, ["admin", "dangerous"], bgsave,
+        "Asynchronously saves the database to disk."),
+    command!("REPLICAOF", 3, [Admin, NoScript], Keys::None, ["admin", "dangerous"], replicaof,
+        "Configures a server as replica of another, or promotes it to a primary."),
+    command!("SLAVEOF", 3, [Admin, NoScript], Keys::None, ["admin", "dangerous"], replicaof,
+        "Configures a server as replica of another, or promotes it to a primary. Alias of REPLICAOF."),
+    command!("REPLCONF", -1, [Admin, NoScript], Keys::None, ["admin", "dangerous"], replconf,
+        "An internal command for configuring the replication stream."),
+    command!("PSYNC", -3, [Admin, NoScript], Keys::None, ["admin", "dangerous"], psync,
+        "An internal command used in replication."),
+    command!("SYNC", 1, [Admin, NoScript], Keys::None, ["admin", "dangerous"], psync,
+        "An internal command used in replication."),
+    command!("CLUSTER", -2, [Admin], Keys::None, ["admin", "dangerous"], cluster_command,
+        "A container for cluster commands.", ["nondeterministic_output"]),
+    command!("RAFT", -2, [Admin, NoScript], Keys::None, ["admin", "dangerous"], raft_command,
+        "A container for raft commands."),
+    command!("CDC", -2, [Admin, NoScript], Keys::None, ["admin", "dangerous"], cdc_command,
+        "A container for change data capture commands."),
+    command!("ACL", -2, [Admin, NoScript], Keys::None, ["admin", "dangerous"], acl_command,
+        "A container for Access Control List commands."),
+    command!("COMMAND", -1, [], Keys::None, ["connection"], command_command,
+        "Returns detailed information about all commands."),
+    command!("CLIENT", -2, [Admin, NoScript], Keys::None, ["admin", "dangerous"], client,
+        "A container for client connection commands.", ["nondeterministic_output"]),
+];
+
+static INDEX: LazyLock<HashMap<&'static str, &'static Command>> = LazyLock::new(|| {
+    COMMANDS
+        .iter()
+        .map(|command| (command.name, command))
+        .collect()
+});
+
+/// Komutu büyük harfli adıyla bulur.
+pub fn lookup(name: &str) -> Option<&'static Command> {
+    INDEX.get(name).copied()
+}
+
+/// Komut veriyi değiştiriyor mu? Bilinmeyen komutlar için `false`.
+pub fn is_write_command(name: &str) -> bool {
+    lookup(name).is_some_and(Command::is_write)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_table() {
+        let mut names: Vec<_> = COMMANDS.iter().map(|command| command.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), COMMANDS.len());
+        for command in COMMANDS {
+            assert_eq!(command.name, command.name.to_uppercase());
+            assert_ne!(command.arity, 0, "{}", command.name);
+            assert!(
+                !(command.has_flag(Flag::Write) && command.has_flag(Flag::ReadOnly)),
+                "{}",
+                command.name
+            );
+        }
+        assert!(is_write_command("SET"));
+        assert!(!is_write_command("GET"));
+        assert!(!is_write_command("NOSUCHCOMMAND"));
+    }
+
+    #[test]
+    fn test_arity() {
+        let get = lookup("GET").unwrap();
+        assert!(get.accepts(2));
+        assert!(!get.accepts(1));
+        assert!(!get.accepts(3));
+        let set = lookup("SET").unwrap();
+        assert!(!set.accepts(2));
+        assert!(set.accepts(3));
+        assert!(set.accepts(5));
+    }
+
+    #[test]
+    fn test_keys() {
+        let keys = |cmd: &str, list: &[&str]| {
+            let args = args(list);
+            lookup(cmd)
+                .unwrap()
+                .keys(&args)
+                .into_iter()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(keys("GET", &["k"]), vec!["k"]);
+        assert!(keys("GET", &[]).is_empty());
+        assert_eq!(keys("DEL", &["a", "b", "c"]), vec!["a", "b", "c"]);
+        assert_eq!(keys("EXISTS", &["a", "a"]), vec!["a", "a"]);
+        assert_eq!(keys("GEOSEARCHSTORE", &["dst", "src"]), vec!["dst", "src"]);
+        assert_eq!(keys("XGROUP", &["CREATE", "s", "g", "$"]), vec!["s"]);
+        assert!(keys("XGROUP", &["HELP"]).is_empty());
+        assert_eq!(keys("TS.MADD", &["a", "1", "5", "b", "1"]), vec!["a", "b"]);
+        assert_eq!(
+            keys("MIGRATE", &["h", "6379", "k", "0", "5", "COPY"]),
+            vec!["k"]
+        );
+        assert_eq!(
+            keys(
+                "MIGRATE",
+                &["h", "6379", "", "0", "5", "REPLACE", "KEYS", "a", "b"]
+            ),
+            vec!["a", "b"]
+        );
+    }
+}
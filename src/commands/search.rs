@@ -0,0 +1,579 @@
+use super::registry::Call;
+use super::{ok, strings_reply, CommandResult, Interpreter};
+use crate::db::ops::search::{SearchHit, SearchOps};
+use crate::db::search::aggregate::{AggregatePlan, GroupBy, Reducer, Row};
+use crate::db::search::fields::{NumericField, TagField, TextField};
+use crate::db::search::query::{parse_query, KnnQuery};
+use crate::db::search::{FieldKind, FieldSpec, SearchIndex};
+use crate::db::vector::{DistanceMetric, Hnsw, VectorAlgorithm, VectorField};
+use crate::network::resp::RespValue;
+use std::collections::HashMap;
+
+fn parse_usize(value: &str) -> Result<usize, String> {
+    value
+        .parse::<usize>()
+        .map_err(|_| "value is not an integer or out of range".to_string())
+}
+
+/// `count arg [arg ...]` biçimindeki listeyi okur ve tükettiği argüman sayısıyla döndürür.
+fn parse_counted<'a>(args: &'a [String], name: &str) -> Result<(&'a [String], usize), String> {
+    let count = match args.first() {
+        Some(count) => parse_usize(count)?,
+        None => return Err(format!("Bad arguments for {}: missing count", name)),
+    };
+    let items = args
+        .get(1..1 + count)
+        .ok_or_else(|| format!("Bad arguments for {}: expected {} values", name, count))?;
+    Ok((items, 1 + count))
+}
+
+/// `VECTOR FLAT|HNSW nargs TYPE t DIM d DISTANCE_METRIC m [M n] [EF_CONSTRUCTION n] [EF_RUNTIME n]`.
+/// Dönen değer alan ve tüketilen argüman sayısıdır.
+fn parse_vector_field(args: &[String]) -> Result<(VectorField, usize), String> {
+    let algorithm = match args.first().map(|a| a.to_uppercase()).as_deref() {
+        Some("FLAT") => VectorAlgorithm::Flat,
+        Some("HNSW") => VectorAlgorithm::Hnsw,
+        _ => return Err("Bad arguments for vector similarity: unknown algorithm".to_string()),
+    };
+    let (attributes, consumed) = parse_counted(&args[1..], "vector similarity")?;
+    if !attributes.len().is_multiple_of(2) {
+        return Err("Bad arguments for vector similarity: odd number of attributes".to_string());
+    }
+
+    let mut dim = None;
+    let mut metric = None;
+    let mut m = Hnsw::DEFAULT_M;
+    let mut ef_construction = Hnsw::DEFAULT_EF_CONSTRUCTION;
+    let mut ef_runtime = VectorField::DEFAULT_EF_RUNTIME;
+    for pair in attributes.chunks(2) {
+        let value = &pair[1];
+        match pair[0].to_uppercase().as_str() {
+            "TYPE" => {
+                if !value.eq_ignore_ascii_case("FLOAT32") && !value.eq_ignore_ascii_case("FLOAT64")
+                {
+                    return Err(format!(
+                        "Bad arguments for vector similarity: unsupported type '{}'",
+                        value
+                    ));
+                }
+            }
+            "DIM" => dim = Some(parse_usize(value)?).filter(|d| *d > 0),
+            "DISTANCE_METRIC" => metric = Some(DistanceMetric::parse(value)?),
+            "M" => m = parse_usize(value)?,
+            "EF_CONSTRUCTION" => ef_construction = parse_usize(value)?,
+            "EF_RUNTIME" => ef_runtime = parse_usize(value)?,
+            // FLAT için bellek ayarları; vektörler zaten dinamik tutulur
+            "INITIAL_CAP" | "BLOCK_SIZE" => {
+                parse_usize(value)?;
+            }
+            other => {
+                return Err(format!(
+                    "Bad arguments for vector similarity: unknown argument '{}'",
+                    other
+                ))
+            }
+        }
+    }
+
+    let dim = dim.ok_or_else(|| "Bad arguments for vector similarity: missing DIM".to_string())?;
+    let metric = metric.ok_or_else(|| {
+        "Bad arguments for vector similarity: missing DISTANCE_METRIC".to_string()
+    })?;
+    let field = match algorithm {
+        VectorAlgorithm::Flat => VectorField::flat(dim, metric),
+        VectorAlgorithm::Hnsw => VectorField::hnsw(dim, metric, m, ef_construction, ef_runtime),
+    };
+    Ok((field, 1 + consumed))
+}
+
+/// TEXT/TAG/NUMERIC alanlarının seçenekleri. Dönen değer alan ve tüketilen argüman sayısıdır.
+/// SORTABLE kabul edilir ama bir şey değiştirmez; her alan SORTBY ile sıralanabilir.
+fn parse_scalar_field(field_type: &str, args: &[String]) -> Result<(FieldKind, usize), String> {
+    let mut weight = 1.0;
+    let mut separator = TagField::DEFAULT_SEPARATOR;
+    let mut case_sensitive = false;
+    let mut pos = 0;
+    while let Some(option) = args.get(pos).map(|a| a.to_uppercase()) {
+        match (field_type, option.as_str()) {
+            (_, "SORTABLE") => pos += 1,
+            ("TEXT", "WEIGHT") => {
+                weight = args
+                    .get(pos + 1)
+                    .and_then(|w| w.parse::<f64>().ok())
+                    .filter(|w| *w >= 0.0)
+                    .ok_or_else(|| "Bad argument for WEIGHT".to_string())?;
+                pos += 2;
+            }
+            ("TAG", "SEPARATOR") => {
+                let mut chars = args.get(pos + 1).map_or("", String::as_str).chars();
+                separator = match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => return Err("Tag separator must be a single character".to_string()),
+                };
+                pos += 2;
+            }
+            ("TAG", "CASESENSITIVE") => {
+                case_sensitive = true;
+                pos += 1;
+            }
+            // Sıradaki alan adı
+            _ => break,
+        }
+    }
+
+    let kind = match field_type {
+        "TEXT" => FieldKind::Text(TextField::new(weight)),
+        "TAG" => FieldKind::Tag(TagField::new(separator, case_sensitive)),
+        _ => FieldKind::Numeric(NumericField::default()),
+    };
+    Ok((kind, pos))
+}
+
+/// FT.CREATE: `index [ON HASH] [PREFIX n prefix ...] SCHEMA field type ... [field type ...]`.
+pub(super) fn parse_create(args: &[String]) -> Result<SearchIndex, String> {
+    let name = args[0].clone();
+    let mut prefixes = Vec::new();
+    let mut pos = 1;
+    loop {
+        match args.get(pos).map(|a| a.to_uppercase()).as_deref() {
+            Some("ON") => {
+                if !args
+                    .get(pos + 1)
+                    .is_some_and(|t| t.eq_ignore_ascii_case("HASH"))
+                {
+                    return Err("Only HASH indexes are supported".to_string());
+                }
+                pos += 2;
+            }
+            Some("PREFIX") => {
+                let (items, consumed) = parse_counted(&args[pos + 1..], "PREFIX")?;
+                prefixes = items.to_vec();
+                pos += 1 + consumed;
+            }
+            Some("SCHEMA") => {
+                pos += 1;
+                break;
+            }
+            Some(other) => return Err(format!("Unknown argument `{}`", other)),
+            None => return Err("No schema found".to_string()),
+        }
+    }
+
+    let mut fields: Vec<FieldSpec> = Vec::new();
+    while pos < args.len() {
+        let name = args[pos].clone();
+        if fields.iter().any(|f| f.name == name) {
+            return Err(format!("Duplicate field in schema - {}", name));
+        }
+        let kind = match args.get(pos + 1).map(|t| t.to_uppercase()).as_deref() {
+            Some(field_type @ ("TEXT" | "TAG" | "NUMERIC")) => {
+                let (kind, consumed) = parse_scalar_field(field_type, &args[pos + 2..])?;
+                pos += 2 + consumed;
+                kind
+            }
+            Some("VECTOR") => {
+                let (field, consumed) = parse_vector_field(&args[pos + 2..])?;
+                pos += 2 + consumed;
+                FieldKind::Vector(field)
+            }
+            Some(other) => {
+                return Err(format!(
+                    "Invalid field type for field `{}`: {}",
+                    name, other
+                ))
+            }
+            None => return Err(format!("Field `{}` does not have a type", name)),
+        };
+        fields.push(FieldSpec { name, kind });
+    }
+    if fields.is_empty() {
+        return Err("Fields arguments are missing".to_string());
+    }
+    Ok(SearchIndex::new(name, prefixes, fields))
+}
+
+/// FT.SEARCH seçenekleri.
+pub(super) struct SearchArgs {
+    pub query: String,
+    pub params: HashMap<String, String>,
+    pub nocontent: bool,
+    pub with_scores: bool,
+    pub return_fields: Option<Vec<String>>,
+    /// `(alan, artan mı)`
+    pub sort_by: Option<(String, bool)>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// FT.SEARCH: `index query [NOCONTENT] [WITHSCORES] [RETURN n field ...] [SORTBY field [ASC|DESC]]
+/// [LIMIT offset num] [PARAMS n name value ...] [DIALECT d]`.
+pub(super) fn parse_search(args: &[String]) -> Result<SearchArgs, String> {
+    let query = args
+        .get(1)
+        .cloned()
+        .ok_or_else(|| "wrong number of arguments for 'FT.SEARCH' command".to_string())?;
+    let mut search = SearchArgs {
+        query,
+        params: HashMap::new(),
+        nocontent: false,
+        with_scores: false,
+        return_fields: None,
+        sort_by: None,
+        offset: 0,
+        limit: 10,
+    };
+
+    let mut pos = 2;
+    while pos < args.len() {
+        match args[pos].to_uppercase().as_str() {
+            "NOCONTENT" => {
+                search.nocontent = true;
+                pos += 1;
+            }
+            "WITHSCORES" => {
+                search.with_scores = true;
+                pos += 1;
+            }
+            "SORTBY" => {
+                let field = args
+                    .get(pos + 1)
+                    .ok_or_else(|| "SORTBY requires a field".to_string())?;
+                let field = field.strip_prefix('@').unwrap_or(field).to_string();
+                let ascending = match args.get(pos + 2).map(|d| d.to_uppercase()).as_deref() {
+                    Some("ASC") => Some(true),
+                    Some("DESC") => Some(false),
+                    _ => None,
+                };
+                pos += if ascending.is_some() { 3 } else { 2 };
+                search.sort_by = Some((field, ascending.unwrap_or(true)));
+            }
+            "RETURN" => {
+                let (fields, consumed) = parse_counted(&args[pos + 1..], "RETURN")?;
+                search.return_fields = Some(fields.to_vec());
+                pos += 1 + consumed;
+            }
+            "LIMIT" => {
+                let (offset, limit) = match (args.get(pos + 1), args.get(pos + 2)) {
+                    (Some(offset), Some(limit)) => (parse_usize(offset)?, parse_usize(limit)?),
+                    _ => return Err("LIMIT requires two arguments".to_string()),
+                };
+                search.offset = offset;
+                search.limit = limit;
+                pos += 3;
+            }
+            "PARAMS" => {
+                let (pairs, consumed) = parse_counted(&args[pos + 1..], "PARAMS")?;
+                if !pairs.len().is_multiple_of(2) {
+                    return Err("Bad arguments for PARAMS: odd number of arguments".to_string());
+                }
+                for pair in pairs.chunks(2) {
+                    search.params.insert(pair[0].clone(), pair[1].clone());
+                }
+                pos += 1 + consumed;
+            }
+            "DIALECT" if pos + 1 < args.len() => pos += 2,
+            _ => return Err("syntax error".to_string()),
+        }
+    }
+    Ok(search)
+}
+
+fn pairs_reply(pairs: Vec<(String, String)>) -> RespValue {
+    RespValue::Array(Some(
+        pairs
+            .into_iter()
+            .flat_map(|(f, v)| {
+                [
+                    RespValue::BulkString(Some(f)),
+                    RespValue::BulkString(Some(v)),
+                ]
+            })
+            .collect(),
+    ))
+}
+
+/// FT.SEARCH cevabı: `[toplam, anahtar, [puan], [alan, değer, ...], ...]`.
+/// Toplam, LIMIT uygulanmadan önceki eşleşme sayısıdır.
+pub(super) fn search_reply(hits: Vec<SearchHit>, search: &SearchArgs) -> RespValue {
+    let mut reply = vec![RespValue::Integer(hits.len() as i64)];
+    for hit in hits.into_iter().skip(search.offset).take(search.limit) {
+        reply.push(RespValue::BulkString(Some(hit.key)));
+        if search.with_scores {
+            reply.push(RespValue::BulkString(Some(hit.score.to_string())));
+        }
+        if search.nocontent {
+            continue;
+        }
+
+        let selected = match &search.return_fields {
+            Some(names) => names
+                .iter()
+                .filter_map(|name| hit.fields.iter().find(|(f, _)| f == name).cloned())
+                .collect(),
+            None => hit.fields,
+        };
+        reply.push(pairs_reply(selected));
+    }
+    RespValue::Array(Some(reply))
+}
+
+/// `@alan` biçimindeki özellik adını okur.
+fn parse_property(value: Option<&String>) -> Result<String, String> {
+    value
+        .and_then(|v| v.strip_prefix('@'))
+        .map(str::to_string)
+        .ok_or_else(|| "Bad arguments: property names must start with '@'".to_string())
+}
+
+/// `REDUCE fonksiyon nargs arg ... [AS ad]`. Dönen değer tüketilen argüman sayısıdır.
+fn parse_reducer(args: &[String]) -> Result<((Reducer, String), usize), String> {
+    let function = args
+        .first()
+        .map(|f| f.to_uppercase())
+        .ok_or_else(|| "Bad arguments for REDUCE".to_string())?;
+    let (reducer_args, consumed) = parse_counted(&args[1..], "REDUCE")?;
+    let reducer = match (function.as_str(), reducer_args) {
+        ("COUNT", []) => Reducer::Count,
+        ("COUNT_DISTINCT", [field]) => Reducer::CountDistinct(parse_property(Some(field))?),
+        ("SUM", [field]) => Reducer::Sum(parse_property(Some(field))?),
+        ("AVG", [field]) => Reducer::Avg(parse_property(Some(field))?),
+        ("MIN", [field]) => Reducer::Min(parse_property(Some(field))?),
+        ("MAX", [field]) => Reducer::Max(parse_property(Some(field))?),
+        _ => return Err(format!("Bad arguments for REDUCE {}", function)),
+    };
+
+    let mut pos = 1 + consumed;
+    let alias = match args.get(pos) {
+        Some(keyword) if keyword.eq_ignore_ascii_case("AS") => {
+            let alias = args
+                .get(pos + 1)
+                .cloned()
+                .ok_or_else(|| "AS requires a name".to_string())?;
+            pos += 2;
+            alias
+        }
+        _ => reducer.default_alias(),
+    };
+    Ok(((reducer, alias), pos))
+}
+
+/// FT.AGGREGATE: `index query [LOAD n @field ...] [GROUPBY n @field ... [REDUCE ...]...]
+/// [SORTBY n @field [ASC|DESC] ...] [LIMIT offset num] [PARAMS n name value ...] [DIALECT d]`.
+/// Yalnızca tek bir GROUPBY adımı desteklenir.
+pub(super) fn parse_aggregate(
+    args: &[String],
+) -> Result<(String, HashMap<String, String>, AggregatePlan), String> {
+    let query = args
+        .get(1)
+        .cloned()
+        .ok_or_else(|| "wrong number of arguments for 'FT.AGGREGATE' command".to_string())?;
+    let mut params = HashMap::new();
+    let mut plan = AggregatePlan::default();
+
+    let mut pos = 2;
+    while pos < args.len() {
+        match args[pos].to_uppercase().as_str() {
+            "LOAD" => {
+                let (fields, consumed) = parse_counted(&args[pos + 1..], "LOAD")?;
+                plan.load = fields
+                    .iter()
+                    .map(|f| parse_property(Some(f)))
+                    .collect::<Result<_, _>>()?;
+                pos += 1 + consumed;
+            }
+            "GROUPBY" => {
+                if plan.group_by.is_some() {
+                    return Err("Only one GROUPBY step is supported".to_string());
+                }
+                let (fields, consumed) = parse_counted(&args[pos + 1..], "GROUPBY")?;
+                let fields = fields
+                    .iter()
+                    .map(|f| parse_property(Some(f)))
+                    .collect::<Result<_, _>>()?;
+                pos += 1 + consumed;
+
+                let mut reducers = Vec::new();
+                while args
+                    .get(pos)
+                    .is_some_and(|a| a.eq_ignore_ascii_case("REDUCE"))
+                {
+                    let (reducer, consumed) = parse_reducer(&args[pos + 1..])?;
+                    reducers.push(reducer);
+                    pos += 1 + consumed;
+                }
+                plan.group_by = Some(GroupBy { fields, reducers });
+            }
+            "SORTBY" => {
+                let (items, consumed) = parse_counted(&args[pos + 1..], "SORTBY")?;
+                let mut sort_by: Vec<(String, bool)> = Vec::new();
+                for item in items {
+                    match item.to_uppercase().as_str() {
+                        "ASC" | "DESC" => match sort_by.last_mut() {
+                            Some((_, ascending)) => *ascending = item.eq_ignore_ascii_case("ASC"),
+                            None => return Err("Bad arguments for SORTBY".to_string()),
+                        },
+                        _ => sort_by.push((parse_property(Some(item))?, true)),
+                    }
+                }
+                plan.sort_by = sort_by;
+                pos += 1 + consumed;
+            }
+            "LIMIT" => {
+                let (offset, limit) = match (args.get(pos + 1), args.get(pos + 2)) {
+                    (Some(offset), Some(limit)) => (parse_usize(offset)?, parse_usize(limit)?),
+                    _ => return Err("LIMIT requires two arguments".to_string()),
+                };
+                plan.limit = Some((offset, limit));
+                pos += 3;
+            }
+            "PARAMS" => {
+                let (pairs, consumed) = parse_counted(&args[pos + 1..], "PARAMS")?;
+                if !pairs.len().is_multiple_of(2) {
+                    return Err("Bad arguments for PARAMS: odd number of arguments".to_string());
+                }
+                for pair in pairs.chunks(2) {
+                    params.insert(pair[0].clone(), pair[1].clone());
+                }
+                pos += 1 + consumed;
+            }
+            "DIALECT" if pos + 1 < args.len() => pos += 2,
+            _ => return Err("syntax error".to_string()),
+        }
+    }
+    Ok((query, params, plan))
+}
+
+/// FT.AGGREGATE cevabı: `[satır sayısı, [alan, değer, ...], ...]`.
+pub(super) fn aggregate_reply(rows: Vec<Row>) -> RespValue {
+    let mut reply = vec![RespValue::Integer(rows.len() as i64)];
+    reply.extend(rows.into_iter().map(pairs_reply));
+    RespValue::Array(Some(reply))
+}
+
+pub(super) fn info_reply(index: &SearchIndex) -> RespValue {
+    let bulk = |s: &str| RespValue::BulkString(Some(s.to_string()));
+    let attributes = index
+        .fields
+        .iter()
+        .map(|field| {
+            let mut attribute = vec![
+                bulk("identifier"),
+                bulk(&field.name),
+                bulk("type"),
+                bulk(field.kind.type_name()),
+            ];
+            match &field.kind {
+                FieldKind::Text(text) => {
+                    attribute.extend([bulk("WEIGHT"), bulk(&text.weight.to_string())]);
+                }
+                FieldKind::Tag(tags) => {
+                    attribute.extend([bulk("SEPARATOR"), bulk(&tags.separator.to_string())]);
+                    if tags.case_sensitive {
+                        attribute.push(bulk("CASESENSITIVE"));
+                    }
+                }
+                FieldKind::Numeric(_) => {}
+                FieldKind::Vector(vectors) => {
+                    attribute.extend([
+                        bulk("algorithm"),
+                        bulk(match vectors.algorithm {
+                            VectorAlgorithm::Flat => "FLAT",
+                            VectorAlgorithm::Hnsw => "HNSW",
+                        }),
+                        bulk("dim"),
+                        RespValue::Integer(vectors.dim as i64),
+                        bulk("distance_metric"),
+                        bulk(vectors.metric.name()),
+                    ]);
+                    if let Some((m, ef_construction)) = vectors.hnsw_params() {
+                        attribute.extend([
+                            bulk("M"),
+                            RespValue::Integer(m as i64),
+                            bulk("ef_construction"),
+                            RespValue::Integer(ef_construction as i64),
+                            bulk("ef_runtime"),
+                            RespValue::Integer(vectors.ef_runtime as i64),
+                        ]);
+                    }
+                }
+            }
+            RespValue::Array(Some(attribute))
+        })
+        .collect();
+
+    RespValue::Array(Some(vec![
+        bulk("index_name"),
+        bulk(&index.name),
+        bulk("index_definition"),
+        RespValue::Array(Some(vec![
+            bulk("key_type"),
+            bulk("HASH"),
+            bulk("prefixes"),
+            RespValue::Array(Some(index.prefixes.iter().map(|p| bulk(p)).collect())),
+        ])),
+        bulk("attributes"),
+        RespValue::Array(Some(attributes)),
+        bulk("num_docs"),
+        RespValue::Integer(index.num_docs() as i64),
+        bulk("hash_indexing_failures"),
+        RespValue::Integer(index.indexing_failures as i64),
+    ]))
+}
+
+impl Interpreter {
+    pub(super) async fn ft_create(&mut self, call: Call) -> CommandResult {
+        let index = parse_create(call.args())?;
+        let mut db = self.db.write().await;
+        db.ft_create(index)?;
+        self.propagate(call.argv).await;
+        ok()
+    }
+
+    pub(super) async fn ft_dropindex(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        db.ft_dropindex(&call.args()[0])?;
+        self.propagate(call.argv).await;
+        ok()
+    }
+
+    pub(super) async fn ft_list(&mut self, _call: Call) -> CommandResult {
+        let db = self.db.read().await;
+        Ok(strings_reply(db.ft_list()).into())
+    }
+
+    pub(super) async fn ft_info(&mut self, call: Call) -> CommandResult {
+        let db = self.db.read().await;
+        let index = db.ft_info(&call.args()[0])?;
+        Ok(info_reply(index).into())
+    }
+
+    pub(super) async fn ft_search(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let options = parse_search(args)?;
+        let knn = KnnQuery::parse(&options.query, &options.params)?;
+
+        let db = self.db.read().await;
+        let hits = match knn {
+            Some(knn) => db.ft_knn(&args[0], &knn)?,
+            None => {
+                let query = parse_query(&options.query, &options.params)?;
+                let sort_by = options
+                    .sort_by
+                    .as_ref()
+                    .map(|(field, ascending)| (field.as_str(), *ascending));
+                db.ft_search(&args[0], &query, sort_by)?
+            }
+        };
+        Ok(search_reply(hits, &options).into())
+    }
+
+    pub(super) async fn ft_aggregate(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let (query, params, plan) = parse_aggregate(args)?;
+        let query = parse_query(&query, &params)?;
+
+        let db = self.db.read().await;
+        let rows = db.ft_aggregate(&args[0], &query, &plan)?;
+        Ok(aggregate_reply(rows).into())
+    }
+}
@@ -0,0 +1,172 @@
+use super::registry::{wrong_arity, Call};
+use super::{ok, strings_reply, CommandResult, ExecutionResult, Interpreter};
+use crate::cluster;
+use crate::db::ops::cluster::ClusterOps;
+use crate::network::resp::RespValue;
+use crate::observability::stats::STATS;
+use crate::persistence::snapshot;
+use crate::replication::primary::SyncRequest;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tracing::{error, info};
+
+impl Interpreter {
+    pub(super) async fn save(&mut self, _call: Call) -> CommandResult {
+        let result = snapshot::save("dump.rdb", &self.db).await;
+        STATS.saved(result.is_ok());
+        result.map_err(|e| format!("Failed to save snapshot: {}", e))?;
+        ok()
+    }
+
+    pub(super) async fn bgsave(&mut self, _call: Call) -> CommandResult {
+        if STATS.bgsave_in_progress.swap(true, Ordering::Relaxed) {
+            return Err("Background save already in progress".to_string());
+        }
+        let db = Arc::clone(&self.db);
+        tokio::spawn(async move {
+            let result = snapshot::save("dump.rdb", &db).await;
+            STATS.saved(result.is_ok());
+            STATS.bgsave_in_progress.store(false, Ordering::Relaxed);
+            if let Err(e) = result {
+                error!("Background save failed: {}", e);
+            } else {
+                info!("Background save completed successfully");
+            }
+        });
+        Ok(RespValue::SimpleString("Background saving started".to_string()).into())
+    }
+
+    /// REPLICAOF / SLAVEOF
+    pub(super) async fn replicaof(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        if args[0].eq_ignore_ascii_case("NO") && args[1].eq_ignore_ascii_case("ONE") {
+            self.replication.become_primary();
+            return ok();
+        }
+        let port = args[1]
+            .parse::<u16>()
+            .map_err(|_| "value is not an integer or out of range")?;
+        let changed = self.replication.replicate_from(
+            args[0].clone(),
+            port,
+            Arc::clone(&self.db),
+            self.master_link_client(),
+        );
+        if !changed {
+            return Ok(RespValue::SimpleString(
+                "OK Already connected to specified master".to_string(),
+            )
+            .into());
+        }
+        ok()
+    }
+
+    pub(super) async fn role(&mut self, _call: Call) -> CommandResult {
+        Ok(self.replication.role_reply().into())
+    }
+
+    /// Replikanın PSYNC öncesi el sıkışması
+    pub(super) async fn replconf(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let option = args.first().map(|o| o.to_lowercase()).unwrap_or_default();
+        match (option.as_str(), args.get(1)) {
+            ("listening-port", Some(port)) => {
+                self.replica_listening_port = port
+                    .parse::<u16>()
+                    .map_err(|_| "value is not an integer or out of range")?;
+            }
+            ("capa" | "ip-address", Some(_)) => {}
+            _ => return Err(format!("Unrecognized REPLCONF option: {}", option)),
+        }
+        ok()
+    }
+
+    /// PSYNC / SYNC
+    pub(super) async fn psync(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        // SYNC, eski istemciler için koşulsuz tam senkronizasyondur
+        let (replid, offset) = if call.name == "SYNC" {
+            ("?".to_string(), -1)
+        } else {
+            let offset = args[1].parse::<i64>().map_err(|_| wrong_arity(call.name))?;
+            (args[0].clone(), offset)
+        };
+        Ok(ExecutionResult::Psync(SyncRequest {
+            replid,
+            offset,
+            listening_port: self.replica_listening_port,
+            db: Arc::clone(&self.db),
+            replication: Arc::clone(&self.replication),
+        }))
+    }
+
+    pub(super) async fn wait(&mut self, call: Call) -> CommandResult {
+        if self.replication.is_replica() {
+            return Err("WAIT cannot be used with replica instances".to_string());
+        }
+        let args = call.args();
+        let (Ok(numreplicas), Ok(timeout)) = (args[0].parse::<usize>(), args[1].parse::<u64>())
+        else {
+            return Err("value is not an integer or out of range".to_string());
+        };
+        // 0, süresiz beklemek demektir
+        let timeout = (timeout > 0).then(|| std::time::Duration::from_millis(timeout));
+        let acked = self.replication.wait(numreplicas, timeout).await;
+        Ok(RespValue::Integer(acked as i64).into())
+    }
+
+    pub(super) async fn cluster_command(&mut self, call: Call) -> CommandResult {
+        let Some(cluster) = &self.cluster else {
+            return Err("This instance has cluster support disabled".to_string());
+        };
+        let args = call.args();
+        let subcommand = args[0].to_uppercase();
+        if subcommand != "COUNTKEYSINSLOT" && subcommand != "GETKEYSINSLOT" {
+            return Ok(cluster.command(args).into());
+        }
+
+        let wanted = if subcommand == "COUNTKEYSINSLOT" {
+            2
+        } else {
+            3
+        };
+        if args.len() != wanted {
+            return Err(format!(
+                "wrong number of arguments for 'CLUSTER {}' command",
+                subcommand
+            ));
+        }
+        let slot = match args[1].parse::<u16>() {
+            Ok(slot) if (slot as usize) < cluster::CLUSTER_SLOTS => slot,
+            _ => return Err("Invalid slot".to_string()),
+        };
+        let mut db = self.db.write().await;
+        if subcommand == "COUNTKEYSINSLOT" {
+            let count = db.count_keys_in_slot(slot);
+            return Ok(RespValue::Integer(count as i64).into());
+        }
+        let count = args[2]
+            .parse::<usize>()
+            .map_err(|_| "Invalid number of keys")?;
+        Ok(strings_reply(db.keys_in_slot(slot, count)).into())
+    }
+
+    pub(super) async fn raft_command(&mut self, call: Call) -> CommandResult {
+        let Some(raft) = &self.raft else {
+            return Err("this instance has raft mode disabled".to_string());
+        };
+        Ok(raft.command(call.args()).await.into())
+    }
+
+    pub(super) async fn cdc_command(&mut self, call: Call) -> CommandResult {
+        let Some(cdc) = &self.cdc else {
+            return Err("this instance has cdc disabled".to_string());
+        };
+        Ok(cdc.command(call.args()).await.into())
+    }
+
+    pub(super) async fn acl_command(&mut self, call: Call) -> CommandResult {
+        let user = self.user.as_deref().unwrap_or("default");
+        Ok(self.acl.command(user, call.args()).into())
+    }
+}
@@ -0,0 +1,46 @@
+use super::registry::Call;
+use super::{strings_reply, CommandResult, Interpreter};
+use crate::db::SetOps;
+use crate::network::resp::RespValue;
+
+impl Interpreter {
+    pub(super) async fn sadd(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut db = self.db.write().await;
+        let added = db.sadd(args[0].clone(), args[1..].to_vec())?;
+        self.propagate(call.argv).await;
+        Ok(RespValue::Integer(added as i64).into())
+    }
+
+    pub(super) async fn srem(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut db = self.db.write().await;
+        let removed = db.srem(args[0].clone(), args[1].clone())?;
+        self.propagate(call.argv).await;
+        Ok(RespValue::Integer(removed as i64).into())
+    }
+
+    pub(super) async fn smembers(&mut self, call: Call) -> CommandResult {
+        let key = call.args()[0].clone();
+        let mut db = self.db.write().await;
+        db.lookup(&key, true);
+        let members = db.smembers(key)?;
+        Ok(strings_reply(members).into())
+    }
+
+    pub(super) async fn sismember(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut db = self.db.write().await;
+        db.lookup(&args[0], true);
+        let exists = db.sismember(args[0].clone(), args[1].clone())?;
+        Ok(RespValue::Integer(exists as i64).into())
+    }
+
+    pub(super) async fn scard(&mut self, call: Call) -> CommandResult {
+        let key = call.args()[0].clone();
+        let mut db = self.db.write().await;
+        db.lookup(&key, true);
+        let count = db.scard(key)?;
+        Ok(RespValue::Integer(count as i64).into())
+    }
+}
@@ -0,0 +1,965 @@
+use super::registry::Call;
+use super::{CommandResult, Interpreter};
+use crate::db::ops::stream::{
+    now_ms, GroupReadResult, PendingInfo, PendingQuery, PendingSummary, StreamOps, StreamReadResult,
+};
+use crate::db::stream::{
+    AutoClaim, ClaimOptions, GroupReadStart, IdSpec, Stream, StreamFields, StreamId, TrimStrategy,
+};
+use crate::network::resp::RespValue;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+/// XADD argümanlarının ayrıştırılmış hali.
+pub(super) struct XAddArgs {
+    pub nomkstream: bool,
+    pub trim: Option<(TrimStrategy, Option<usize>)>,
+    pub id: IdSpec,
+    /// Kimlik argümanının `args` içindeki yeri (AOF'a gerçek kimliği yazmak için)
+    pub id_index: usize,
+    pub fields: StreamFields,
+}
+
+/// XREAD / XREADGROUP argümanlarının ayrıştırılmış hali.
+pub(super) struct XReadArgs {
+    /// XREADGROUP için (grup, tüketici)
+    pub group: Option<(String, String)>,
+    pub count: Option<usize>,
+    pub block: Option<u64>,
+    pub noack: bool,
+    pub keys: Vec<String>,
+    pub ids: Vec<String>,
+}
+
+fn parse_u64(value: &str) -> Result<u64, String> {
+    value
+        .parse::<u64>()
+        .map_err(|_| "value is not an integer or out of range".to_string())
+}
+
+fn parse_usize(value: &str) -> Result<usize, String> {
+    value
+        .parse::<usize>()
+        .map_err(|_| "value is not an integer or out of range".to_string())
+}
+
+/// `MAXLEN|MINID [=|~] eşik [LIMIT n]` kısmını `pos`tan itibaren ayrıştırır.
+fn parse_trim(args: &[String], pos: &mut usize) -> Result<(TrimStrategy, Option<usize>), String> {
+    let syntax_error = || "syntax error".to_string();
+    let kind = args[*pos].to_uppercase();
+    *pos += 1;
+
+    let mut approx = false;
+    match args.get(*pos).map(|s| s.as_str()) {
+        Some("~") => {
+            approx = true;
+            *pos += 1;
+        }
+        Some("=") => *pos += 1,
+        _ => {}
+    }
+
+    let threshold = args.get(*pos).ok_or_else(syntax_error)?;
+    *pos += 1;
+    let strategy = match kind.as_str() {
+        "MAXLEN" => TrimStrategy::MaxLen(parse_usize(threshold)?),
+        _ => TrimStrategy::MinId(StreamId::parse(threshold)?),
+    };
+
+    let mut limit = None;
+    if args.get(*pos).map(|s| s.to_uppercase()) == Some("LIMIT".to_string()) {
+        if !approx {
+            return Err(
+                "syntax error, LIMIT cannot be used without the special ~ option".to_string(),
+            );
+        }
+        let value = args.get(*pos + 1).ok_or_else(syntax_error)?;
+        limit = Some(parse_usize(value)?).filter(|&l| l > 0);
+        *pos += 2;
+    }
+
+    Ok((strategy, limit))
+}
+
+/// XADD argümanlarını ayrıştırır. `args` anahtardan sonraki argümanlardır.
+pub(super) fn parse_xadd(args: &[String]) -> Result<XAddArgs, String> {
+    let mut nomkstream = false;
+    let mut trim = None;
+
+    let mut pos = 0;
+    while pos < args.len() {
+        match args[pos].to_uppercase().as_str() {
+            "NOMKSTREAM" => {
+                nomkstream = true;
+                pos += 1;
+            }
+            "MAXLEN" | "MINID" => trim = Some(parse_trim(args, &mut pos)?),
+            _ => break,
+        }
+    }
+
+    let id_index = pos;
+    let id = match args.get(pos) {
+        Some(id) => IdSpec::parse(id)?,
+        None => return Err("wrong number of arguments for 'XADD' command".to_string()),
+    };
+
+    let rest = &args[pos + 1..];
+    if rest.is_empty() || !rest.len().is_multiple_of(2) {
+        return Err("wrong number of arguments for 'XADD' command".to_string());
+    }
+    let fields = rest
+        .chunks(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect();
+
+    Ok(XAddArgs {
+        nomkstream,
+        trim,
+        id,
+        id_index,
+        fields,
+    })
+}
+
+/// XTRIM argümanlarını ayrıştırır. `args` anahtardan sonraki argümanlardır.
+pub(super) fn parse_xtrim(args: &[String]) -> Result<(TrimStrategy, Option<usize>), String> {
+    match args.first().map(|s| s.to_uppercase()) {
+        Some(kind) if kind == "MAXLEN" || kind == "MINID" => {
+            let mut pos = 0;
+            let trim = parse_trim(args, &mut pos)?;
+            if pos != args.len() {
+                return Err("syntax error".to_string());
+            }
+            Ok(trim)
+        }
+        _ => Err("syntax error".to_string()),
+    }
+}
+
+/// XRANGE / XREVRANGE için opsiyonel `COUNT n` kısmını ayrıştırır.
+pub(super) fn parse_range_count(args: &[String]) -> Result<Option<usize>, String> {
+    match args {
+        [] => Ok(None),
+        [option, count] if option.eq_ignore_ascii_case("COUNT") => Ok(Some(parse_usize(count)?)),
+        _ => Err("syntax error".to_string()),
+    }
+}
+
+/// XREAD veya XREADGROUP argümanlarını ayrıştırır.
+pub(super) fn parse_xread(args: &[String], with_group: bool) -> Result<XReadArgs, String> {
+    let syntax_error = || "syntax error".to_string();
+    let cmd = if with_group { "xreadgroup" } else { "xread" };
+
+    let mut group = None;
+    let mut count = None;
+    let mut block = None;
+    let mut noack = false;
+
+    let mut pos = 0;
+    loop {
+        let option = args.get(pos).ok_or_else(syntax_error)?.to_uppercase();
+        match option.as_str() {
+            "GROUP" if with_group && pos + 2 < args.len() => {
+                group = Some((args[pos + 1].clone(), args[pos + 2].clone()));
+                pos += 3;
+            }
+            "COUNT" if pos + 1 < args.len() => {
+                count = Some(parse_usize(&args[pos + 1])?).filter(|&c| c > 0);
+                pos += 2;
+            }
+            "BLOCK" if pos + 1 < args.len() => {
+                block = Some(
+                    parse_u64(&args[pos + 1])
+                        .map_err(|_| "timeout is not an integer or out of range".to_string())?,
+                );
+                pos += 2;
+            }
+            "NOACK" if with_group => {
+                noack = true;
+                pos += 1;
+            }
+            "STREAMS" => {
+                pos += 1;
+                break;
+            }
+            _ => return Err(syntax_error()),
+        }
+    }
+
+    if with_group && group.is_none() {
+        return Err("Missing GROUP option for XREADGROUP".to_string());
+    }
+
+    let rest = &args[pos..];
+    if rest.is_empty() || !rest.len().is_multiple_of(2) {
+        let expected = if with_group { ">" } else { "$" };
+        return Err(format!(
+            "Unbalanced '{}' list of streams: for each stream key an ID or '{}' must be specified.",
+            cmd, expected
+        ));
+    }
+    let (keys, ids) = rest.split_at(rest.len() / 2);
+
+    Ok(XReadArgs {
+        group,
+        count,
+        block,
+        noack,
+        keys: keys.to_vec(),
+        ids: ids.to_vec(),
+    })
+}
+
+/// XCLAIM'in kimlik listesini ve seçeneklerini ayrıştırır.
+/// `args` min-idle-time'dan sonraki argümanlardır.
+pub(super) fn parse_xclaim(args: &[String]) -> Result<(Vec<StreamId>, ClaimOptions), String> {
+    let mut ids = Vec::new();
+    let mut pos = 0;
+    while pos < args.len() {
+        match StreamId::parse(&args[pos]) {
+            Ok(id) => ids.push(id),
+            Err(_) => break,
+        }
+        pos += 1;
+    }
+
+    let mut options = ClaimOptions::default();
+    while pos < args.len() {
+        let remaining = args.len() - pos - 1;
+        match args[pos].to_uppercase().as_str() {
+            "IDLE" if remaining >= 1 => {
+                options.idle = Some(parse_u64(&args[pos + 1])?);
+                pos += 1;
+            }
+            "TIME" if remaining >= 1 => {
+                options.time = Some(parse_u64(&args[pos + 1])?);
+                pos += 1;
+            }
+            "RETRYCOUNT" if remaining >= 1 => {
+                options.retry_count = Some(parse_u64(&args[pos + 1])?);
+                pos += 1;
+            }
+            "LASTID" if remaining >= 1 => {
+                options.last_id = Some(StreamId::parse(&args[pos + 1])?);
+                pos += 1;
+            }
+            "FORCE" => options.force = true,
+            "JUSTID" => options.just_id = true,
+            _ => return Err(format!("Unrecognized XCLAIM option '{}'", args[pos])),
+        }
+        pos += 1;
+    }
+
+    if ids.is_empty() {
+        return Err("wrong number of arguments for 'XCLAIM' command".to_string());
+    }
+    Ok((ids, options))
+}
+
+/// XAUTOCLAIM'in `[COUNT n] [JUSTID]` seçeneklerini ayrıştırır.
+pub(super) fn parse_xautoclaim(args: &[String]) -> Result<(usize, bool), String> {
+    let mut count = 100;
+    let mut just_id = false;
+
+    let mut pos = 0;
+    while pos < args.len() {
+        match args[pos].to_uppercase().as_str() {
+            "COUNT" if pos + 1 < args.len() => {
+                count = parse_usize(&args[pos + 1])?;
+                if count == 0 {
+                    return Err("COUNT must be > 0".to_string());
+                }
+                pos += 1;
+            }
+            "JUSTID" => just_id = true,
+            _ => return Err("syntax error".to_string()),
+        }
+        pos += 1;
+    }
+    Ok((count, just_id))
+}
+
+/// XPENDING'in genişletilmiş biçimini ayrıştırır: `[IDLE ms] start end count [consumer]`.
+pub(super) fn parse_xpending(args: &[String]) -> Result<PendingQuery, String> {
+    let (min_idle, rest) = match args.first() {
+        Some(option) if option.eq_ignore_ascii_case("IDLE") => {
+            let idle = args.get(1).ok_or_else(|| "syntax error".to_string())?;
+            (Some(parse_u64(idle)?), &args[2..])
+        }
+        _ => (None, args),
+    };
+
+    match rest {
+        [start, end, count] | [start, end, count, _] => Ok(PendingQuery {
+            start: StreamId::parse_range_start(start)?,
+            end: StreamId::parse_range_end(end)?,
+            count: count
+                .parse::<i64>()
+                .map_err(|_| "value is not an integer or out of range".to_string())?
+                .max(0) as usize,
+            consumer: rest.get(3).cloned(),
+            min_idle,
+        }),
+        _ => Err("syntax error".to_string()),
+    }
+}
+
+/// `$` için `None`, diğer durumlarda ayrıştırılmış kimlik döner (XGROUP).
+pub(super) fn parse_group_id(value: &str) -> Result<Option<StreamId>, String> {
+    if value == "$" {
+        Ok(None)
+    } else {
+        StreamId::parse(value).map(Some)
+    }
+}
+
+/// XGROUP'un `ENTRIESREAD n` seçeneğini ayrıştırır.
+pub(super) fn parse_entries_read(args: &[String]) -> Result<Option<u64>, String> {
+    match args {
+        [] => Ok(None),
+        [option, value] if option.eq_ignore_ascii_case("ENTRIESREAD") => {
+            Ok(Some(parse_u64(value)?))
+        }
+        _ => Err("syntax error".to_string()),
+    }
+}
+
+fn bulk(s: impl Into<String>) -> RespValue {
+    RespValue::BulkString(Some(s.into()))
+}
+
+fn fields_reply(fields: &StreamFields) -> RespValue {
+    RespValue::Array(Some(
+        fields
+            .iter()
+            .flat_map(|(f, v)| [bulk(f.clone()), bulk(v.clone())])
+            .collect(),
+    ))
+}
+
+/// Tek bir kayıt: [kimlik, [alan, değer, ...]].
+pub(super) fn entry_reply(id: StreamId, fields: Option<&StreamFields>) -> RespValue {
+    RespValue::Array(Some(vec![
+        bulk(id.to_string()),
+        match fields {
+            Some(fields) => fields_reply(fields),
+            None => RespValue::Array(None),
+        },
+    ]))
+}
+
+pub(super) fn entries_reply(entries: &[(StreamId, StreamFields)]) -> RespValue {
+    RespValue::Array(Some(
+        entries
+            .iter()
+            .map(|(id, fields)| entry_reply(*id, Some(fields)))
+            .collect(),
+    ))
+}
+
+pub(super) fn ids_reply(ids: impl IntoIterator<Item = StreamId>) -> RespValue {
+    RespValue::Array(Some(
+        ids.into_iter().map(|id| bulk(id.to_string())).collect(),
+    ))
+}
+
+/// XREAD cevabı: [[anahtar, kayıtlar], ...]. Sonuç yoksa nil döner.
+pub(super) fn read_reply(result: StreamReadResult) -> RespValue {
+    if result.is_empty() {
+        return RespValue::Array(None);
+    }
+    RespValue::Array(Some(
+        result
+            .into_iter()
+            .map(|(key, entries)| RespValue::Array(Some(vec![bulk(key), entries_reply(&entries)])))
+            .collect(),
+    ))
+}
+
+/// XREADGROUP cevabı. Silinmiş kayıtlar için alanlar nil döner.
+pub(super) fn group_read_reply(result: GroupReadResult) -> RespValue {
+    if result.is_empty() {
+        return RespValue::Array(None);
+    }
+    RespValue::Array(Some(
+        result
+            .into_iter()
+            .map(|(key, entries)| {
+                let entries = entries
+                    .iter()
+                    .map(|(id, fields)| entry_reply(*id, fields.as_ref()))
+                    .collect();
+                RespValue::Array(Some(vec![bulk(key), RespValue::Array(Some(entries))]))
+            })
+            .collect(),
+    ))
+}
+
+/// XPENDING özet cevabı: [sayı, en küçük, en büyük, [[tüketici, sayı], ...]].
+pub(super) fn pending_summary_reply(summary: PendingSummary) -> RespValue {
+    if summary.count == 0 {
+        return RespValue::Array(Some(vec![
+            RespValue::Integer(0),
+            RespValue::BulkString(None),
+            RespValue::BulkString(None),
+            RespValue::Array(None),
+        ]));
+    }
+
+    let consumers = summary
+        .consumers
+        .into_iter()
+        .map(|(name, count)| RespValue::Array(Some(vec![bulk(name), bulk(count.to_string())])))
+        .collect();
+    RespValue::Array(Some(vec![
+        RespValue::Integer(summary.count as i64),
+        RespValue::BulkString(summary.min.map(|id| id.to_string())),
+        RespValue::BulkString(summary.max.map(|id| id.to_string())),
+        RespValue::Array(Some(consumers)),
+    ]))
+}
+
+/// XPENDING genişletilmiş cevabı: [[kimlik, tüketici, boşta geçen ms, teslim sayısı], ...].
+pub(super) fn pending_range_reply(pending: Vec<PendingInfo>) -> RespValue {
+    RespValue::Array(Some(
+        pending
+            .into_iter()
+            .map(|p| {
+                RespValue::Array(Some(vec![
+                    bulk(p.id.to_string()),
+                    bulk(p.consumer),
+                    RespValue::Integer(p.idle as i64),
+                    RespValue::Integer(p.delivery_count as i64),
+                ]))
+            })
+            .collect(),
+    ))
+}
+
+/// XINFO STREAM cevabı.
+pub(super) fn info_stream_reply(stream: &Stream) -> RespValue {
+    let entry_or_nil = |entry: Option<(StreamId, &StreamFields)>| match entry {
+        Some((id, fields)) => entry_reply(id, Some(fields)),
+        None => RespValue::BulkString(None),
+    };
+    let first_id = stream
+        .first_entry()
+        .map(|(id, _)| id)
+        .unwrap_or(StreamId::MIN);
+
+    RespValue::Array(Some(vec![
+        bulk("length"),
+        RespValue::Integer(stream.len() as i64),
+        bulk("last-generated-id"),
+        bulk(stream.last_id.to_string()),
+        bulk("entries-added"),
+        RespValue::Integer(stream.entries_added as i64),
+        bulk("recorded-first-entry-id"),
+        bulk(first_id.to_string()),
+        bulk("groups"),
+        RespValue::Integer(stream.groups.len() as i64),
+        bulk("first-entry"),
+        entry_or_nil(stream.first_entry()),
+        bulk("last-entry"),
+        entry_or_nil(stream.last_entry()),
+    ]))
+}
+
+/// XINFO GROUPS cevabı.
+pub(super) fn info_groups_reply(stream: &Stream) -> RespValue {
+    RespValue::Array(Some(
+        stream
+            .groups
+            .iter()
+            .map(|(name, group)| {
+                RespValue::Array(Some(vec![
+                    bulk("name"),
+                    bulk(name.clone()),
+                    bulk("consumers"),
+                    RespValue::Integer(group.consumers.len() as i64),
+                    bulk("pending"),
+                    RespValue::Integer(group.pel.len() as i64),
+                    bulk("last-delivered-id"),
+                    bulk(group.last_id.to_string()),
+                    bulk("entries-read"),
+                    RespValue::Integer(group.entries_read as i64),
+                    bulk("lag"),
+                    RespValue::Integer(stream.lag(group) as i64),
+                ]))
+            })
+            .collect(),
+    ))
+}
+
+/// XINFO CONSUMERS cevabı. `None` grup yok demektir.
+pub(super) fn info_consumers_reply(stream: &Stream, group: &str, now_ms: u64) -> Option<RespValue> {
+    let group = stream.group(group)?;
+    Some(RespValue::Array(Some(
+        group
+            .consumers
+            .iter()
+            .map(|(name, consumer)| {
+                let inactive = match consumer.active_time {
+                    Some(active) => now_ms.saturating_sub(active) as i64,
+                    None => -1,
+                };
+                RespValue::Array(Some(vec![
+                    bulk("name"),
+                    bulk(name.clone()),
+                    bulk("pending"),
+                    RespValue::Integer(consumer.pending.len() as i64),
+                    bulk("idle"),
+                    RespValue::Integer(now_ms.saturating_sub(consumer.seen_time) as i64),
+                    bulk("inactive"),
+                    RespValue::Integer(inactive),
+                ]))
+            })
+            .collect(),
+    )))
+}
+
+/// XAUTOCLAIM cevabı: [sonraki başlangıç, sahiplenilenler, silinmiş kimlikler].
+pub(super) fn autoclaim_reply(result: AutoClaim, just_id: bool) -> RespValue {
+    let claimed = if just_id {
+        ids_reply(result.claimed.iter().map(|(id, _)| *id))
+    } else {
+        entries_reply(&result.claimed)
+    };
+    RespValue::Array(Some(vec![
+        bulk(result.next.to_string()),
+        claimed,
+        ids_reply(result.deleted),
+    ]))
+}
+
+/// Stream'lere yeni kayıt eklenmesini bekler.
+/// `deadline` yoksa süresiz bekler; süre dolarsa `false` döner.
+pub(super) async fn wait_for_event(
+    events: &mut watch::Receiver<u64>,
+    deadline: Option<Instant>,
+) -> bool {
+    match deadline {
+        Some(deadline) => matches!(
+            tokio::time::timeout_at(deadline, events.changed()).await,
+            Ok(Ok(()))
+        ),
+        None => events.changed().await.is_ok(),
+    }
+}
+
+/// XREADGROUP'un AOF kaydı. BLOCK yeniden yüklemede anlamsız olduğu için atlanır.
+pub(super) fn xreadgroup_propagation(read: &XReadArgs) -> Vec<String> {
+    let mut cmd = vec!["XREADGROUP".to_string()];
+    if let Some((group, consumer)) = &read.group {
+        cmd.extend(["GROUP".to_string(), group.clone(), consumer.clone()]);
+    }
+    if let Some(count) = read.count {
+        cmd.extend(["COUNT".to_string(), count.to_string()]);
+    }
+    if read.noack {
+        cmd.push("NOACK".to_string());
+    }
+    cmd.push("STREAMS".to_string());
+    cmd.extend(read.keys.iter().cloned());
+    cmd.extend(read.ids.iter().cloned());
+    cmd
+}
+
+/// XCLAIM / XAUTOCLAIM'in AOF kaydı.
+/// Boşta kalma süreleri yeniden yüklemede anlamsız olduğu için, sahiplenilen ve
+/// silinen kimlikler min-idle-time 0 ile açıkça yazılır.
+pub(super) fn claim_propagation(
+    key: &str,
+    group: &str,
+    consumer: &str,
+    ids: impl IntoIterator<Item = StreamId>,
+    options: &ClaimOptions,
+) -> Vec<String> {
+    let mut cmd = vec![
+        "XCLAIM".to_string(),
+        key.to_string(),
+        group.to_string(),
+        consumer.to_string(),
+        "0".to_string(),
+    ];
+    cmd.extend(ids.into_iter().map(|id| id.to_string()));
+    if options.force {
+        cmd.push("FORCE".to_string());
+    }
+    if options.just_id {
+        cmd.push("JUSTID".to_string());
+    }
+    if let Some(retry_count) = options.retry_count {
+        cmd.push("RETRYCOUNT".to_string());
+        cmd.push(retry_count.to_string());
+    }
+    if let Some(last_id) = options.last_id {
+        cmd.push("LASTID".to_string());
+        cmd.push(last_id.to_string());
+    }
+    cmd
+}
+
+impl Interpreter {
+    pub(super) async fn xadd(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let add = parse_xadd(&args[1..])?;
+        let mut db = self.db.write().await;
+        let Some(id) = db.xadd(
+            args[0].clone(),
+            add.id,
+            add.fields,
+            add.nomkstream,
+            add.trim,
+        )?
+        else {
+            return Ok(RespValue::BulkString(None).into());
+        };
+
+        // Otomatik kimlik yeniden yüklemede farklı üretilmesin diye AOF'a üretilen
+        // kimlik yazılır
+        let mut argv = call.argv;
+        argv[2 + add.id_index] = id.to_string();
+        self.propagate(argv).await;
+        Ok(RespValue::BulkString(Some(id.to_string())).into())
+    }
+
+    pub(super) async fn xlen(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        let len = db.xlen(call.args()[0].clone())?;
+        Ok(RespValue::Integer(len as i64).into())
+    }
+
+    /// XRANGE / XREVRANGE
+    pub(super) async fn xrange(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let rev = call.name == "XREVRANGE";
+        // XREVRANGE önce bitişi, sonra başlangıcı alır
+        let (start, end) = if rev {
+            (&args[2], &args[1])
+        } else {
+            (&args[1], &args[2])
+        };
+        let start = StreamId::parse_range_start(start)?;
+        let end = StreamId::parse_range_end(end)?;
+        let count = parse_range_count(&args[3..])?;
+
+        let mut db = self.db.write().await;
+        let entries = db.xrange(args[0].clone(), start, end, count, rev)?;
+        Ok(entries_reply(&entries).into())
+    }
+
+    pub(super) async fn xtrim(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let (strategy, limit) = parse_xtrim(&args[1..])?;
+        let mut db = self.db.write().await;
+        let removed = db.xtrim(args[0].clone(), strategy, limit)?;
+        if removed > 0 {
+            self.propagate(call.argv).await;
+        }
+        Ok(RespValue::Integer(removed as i64).into())
+    }
+
+    /// XREAD / XREADGROUP
+    pub(super) async fn xread(&mut self, call: Call) -> CommandResult {
+        let read = parse_xread(call.args(), call.name == "XREADGROUP")?;
+
+        // Bildirimleri ilk okumadan önce dinlemeye başla ki arada gelen XADD kaçırılmasın
+        let mut events = self.db.read().await.subscribe_stream_events();
+        let deadline = match read.block {
+            Some(0) | None => None,
+            Some(ms) => Some(Instant::now() + std::time::Duration::from_millis(ms)),
+        };
+
+        let reply = if let Some((group, consumer)) = &read.group {
+            let mut streams = Vec::with_capacity(read.keys.len());
+            for (key, id) in read.keys.iter().zip(&read.ids) {
+                let start = match id.as_str() {
+                    ">" => GroupReadStart::New,
+                    "$" => return Err("The $ ID is meaningless in the context of XREADGROUP: you want to read the history of this consumer by specifying a proper ID, or use the > ID to get new messages. The $ ID would just return an empty result set.".to_string()),
+                    id => GroupReadStart::History(StreamId::parse(id)?),
+                };
+                streams.push((key.clone(), start));
+            }
+
+            // Okuma kayıt teslim etmese de tüketiciyi oluşturur. O durumda XREADGROUP
+            // AOF'a yazılmadığından yeni tüketiciler XGROUP CREATECONSUMER olarak yazılır.
+            let new_consumers: Vec<String> = {
+                let mut db = self.db.write().await;
+                let mut keys = Vec::new();
+                for key in &read.keys {
+                    let known = db
+                        .xstream(key)
+                        .ok()
+                        .flatten()
+                        .and_then(|stream| stream.group(group))
+                        .map(|g| g.consumers.contains_key(consumer));
+                    if known == Some(false) {
+                        keys.push(key.clone());
+                    }
+                }
+                keys
+            };
+
+            let mut delivered = false;
+            let reply = loop {
+                let result = {
+                    let mut db = self.db.write().await;
+                    db.xreadgroup(group, consumer, &streams, read.count, read.noack)
+                };
+                match result {
+                    Ok(result) if !result.is_empty() || read.block.is_none() => {
+                        if result.iter().any(|(_, entries)| !entries.is_empty()) {
+                            delivered = true;
+                            self.propagate(xreadgroup_propagation(&read)).await;
+                        }
+                        break group_read_reply(result);
+                    }
+                    Ok(_) => {}
+                    Err(e) => break RespValue::Error(e),
+                }
+                if !wait_for_event(&mut events, deadline).await {
+                    break RespValue::Array(None);
+                }
+            };
+
+            if !delivered && !matches!(reply, RespValue::Error(_)) {
+                for key in new_consumers {
+                    self.propagate(vec![
+                        "XGROUP".to_string(),
+                        "CREATECONSUMER".to_string(),
+                        key,
+                        group.clone(),
+                        consumer.clone(),
+                    ])
+                    .await;
+                }
+            }
+            reply
+        } else {
+            // `$` bekleme başladığı andaki son kimlik olarak bir kez çözülür
+            let mut streams = Vec::with_capacity(read.keys.len());
+            {
+                let mut db = self.db.write().await;
+                for (key, id) in read.keys.iter().zip(&read.ids) {
+                    let id = if id == "$" {
+                        db.xstream(key)?.map_or(StreamId::MIN, |s| s.last_id)
+                    } else {
+                        StreamId::parse(id)?
+                    };
+                    streams.push((key.clone(), id));
+                }
+            }
+
+            loop {
+                let result = {
+                    let mut db = self.db.write().await;
+                    db.xread(&streams, read.count)
+                };
+                match result {
+                    Ok(result) if !result.is_empty() || read.block.is_none() => {
+                        break read_reply(result)
+                    }
+                    Ok(_) => {}
+                    Err(e) => break RespValue::Error(e),
+                }
+                if !wait_for_event(&mut events, deadline).await {
+                    break RespValue::Array(None);
+                }
+            }
+        };
+        Ok(reply.into())
+    }
+
+    pub(super) async fn xgroup(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let subcommand = args[0].to_uppercase();
+        let arity = match subcommand.as_str() {
+            "CREATE" => args.len() >= 4,
+            "SETID" => args.len() >= 4,
+            "DESTROY" => args.len() == 3,
+            "CREATECONSUMER" | "DELCONSUMER" => args.len() == 4,
+            _ => {
+                return Err(format!(
+                    "unknown subcommand '{}'. Try XGROUP HELP.",
+                    args[0]
+                ))
+            }
+        };
+        if !arity {
+            return Err(format!(
+                "wrong number of arguments for 'XGROUP|{}' command",
+                subcommand
+            ));
+        }
+        let stream_key = args[1].clone();
+        let group = args[2].clone();
+
+        let mut db = self.db.write().await;
+        let reply = match subcommand.as_str() {
+            "CREATE" | "SETID" => {
+                let mut options = &args[4..];
+                let mut mkstream = false;
+                if subcommand == "CREATE"
+                    && options
+                        .first()
+                        .is_some_and(|o| o.eq_ignore_ascii_case("MKSTREAM"))
+                {
+                    mkstream = true;
+                    options = &options[1..];
+                }
+                let id = parse_group_id(&args[3])?;
+                let entries_read = parse_entries_read(options)?;
+                if subcommand == "CREATE" {
+                    db.xgroup_create(stream_key, group, id, mkstream, entries_read)?;
+                } else {
+                    db.xgroup_setid(stream_key, group, id, entries_read)?;
+                }
+                RespValue::SimpleString("OK".to_string())
+            }
+            "DESTROY" => RespValue::Integer(db.xgroup_destroy(stream_key, group)? as i64),
+            "CREATECONSUMER" => {
+                RespValue::Integer(
+                    db.xgroup_createconsumer(stream_key, group, args[3].clone())? as i64,
+                )
+            }
+            _ => {
+                RespValue::Integer(db.xgroup_delconsumer(stream_key, group, args[3].clone())? as i64)
+            }
+        };
+
+        self.propagate(call.argv).await;
+        Ok(reply.into())
+    }
+
+    pub(super) async fn xack(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let ids = args[2..]
+            .iter()
+            .map(|id| StreamId::parse(id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut db = self.db.write().await;
+        let acked = db.xack(args[0].clone(), args[1].clone(), ids)?;
+        if acked > 0 {
+            self.propagate(call.argv).await;
+        }
+        Ok(RespValue::Integer(acked as i64).into())
+    }
+
+    pub(super) async fn xpending(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let (key, group) = (args[0].clone(), args[1].clone());
+
+        let mut db = self.db.write().await;
+        if args.len() == 2 {
+            let summary = db.xpending(key, group)?;
+            return Ok(pending_summary_reply(summary).into());
+        }
+        let query = parse_xpending(&args[2..])?;
+        let pending = db.xpending_range(key, group, query)?;
+        Ok(pending_range_reply(pending).into())
+    }
+
+    pub(super) async fn xclaim(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let min_idle = args[3]
+            .parse::<u64>()
+            .map_err(|_| "Invalid min-idle-time argument for XCLAIM")?;
+        let (ids, options) = parse_xclaim(&args[4..])?;
+        let (key, group, consumer) = (&args[0], &args[1], &args[2]);
+
+        let mut db = self.db.write().await;
+        let result = db.xclaim(
+            key.clone(),
+            group.clone(),
+            consumer.clone(),
+            min_idle,
+            ids,
+            options.clone(),
+        )?;
+        let touched = result
+            .claimed
+            .iter()
+            .map(|(id, _)| *id)
+            .chain(result.deleted.iter().copied());
+        let propagated = claim_propagation(key, group, consumer, touched, &options);
+        if propagated.len() > 5 {
+            self.propagate(propagated).await;
+        }
+
+        let reply = if options.just_id {
+            ids_reply(result.claimed.iter().map(|(id, _)| *id))
+        } else {
+            entries_reply(&result.claimed)
+        };
+        Ok(reply.into())
+    }
+
+    pub(super) async fn xautoclaim(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let min_idle = args[3]
+            .parse::<u64>()
+            .map_err(|_| "Invalid min-idle-time argument for XAUTOCLAIM")?;
+        let start = StreamId::parse_range_start(&args[4])?;
+        let (count, just_id) = parse_xautoclaim(&args[5..])?;
+        let (key, group, consumer) = (&args[0], &args[1], &args[2]);
+
+        let mut db = self.db.write().await;
+        let result = db.xautoclaim(
+            key.clone(),
+            group.clone(),
+            consumer.clone(),
+            min_idle,
+            start,
+            count,
+            just_id,
+        )?;
+        let touched = result
+            .claimed
+            .iter()
+            .map(|(id, _)| *id)
+            .chain(result.deleted.iter().copied());
+        let options = ClaimOptions {
+            just_id,
+            ..ClaimOptions::default()
+        };
+        let propagated = claim_propagation(key, group, consumer, touched, &options);
+        if propagated.len() > 5 {
+            self.propagate(propagated).await;
+        }
+        Ok(autoclaim_reply(result, just_id).into())
+    }
+
+    pub(super) async fn xinfo(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let subcommand = args[0].to_uppercase();
+        let arity = match subcommand.as_str() {
+            "STREAM" => args.len() == 2,
+            "GROUPS" => args.len() == 2,
+            "CONSUMERS" => args.len() == 3,
+            _ => return Err(format!("unknown subcommand '{}'. Try XINFO HELP.", args[0])),
+        };
+        if !arity {
+            return Err(format!(
+                "wrong number of arguments for 'XINFO|{}' command",
+                subcommand
+            ));
+        }
+
+        let mut db = self.db.write().await;
+        let stream_data = db.xstream(&args[1])?.ok_or("no such key")?;
+        let reply = match subcommand.as_str() {
+            "STREAM" => info_stream_reply(stream_data),
+            "GROUPS" => info_groups_reply(stream_data),
+            _ => info_consumers_reply(stream_data, &args[2], now_ms()).ok_or_else(|| {
+                format!(
+                    "NOGROUP No such consumer group '{}' for key name '{}'",
+                    args[2], args[1]
+                )
+            })?,
+        };
+        Ok(reply.into())
+    }
+}
@@ -0,0 +1,36 @@
+use super::registry::Call;
+use super::{ok, CommandResult, Interpreter};
+use crate::db::StringOps;
+use crate::network::resp::RespValue;
+
+impl Interpreter {
+    pub(super) async fn get(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        let value = db.get(call.args()[0].clone())?;
+        Ok(RespValue::BulkString(value).into())
+    }
+
+    pub(super) async fn set(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut db = self.db.write().await;
+        db.set(args[0].clone(), args[1].clone());
+
+        // AOF'a kaydet ve replikalara ilet
+        self.propagate(call.argv).await;
+        ok()
+    }
+
+    pub(super) async fn incr(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        let value = db.incr(call.args()[0].clone())?;
+        self.propagate(call.argv).await;
+        Ok(RespValue::Integer(value).into())
+    }
+
+    pub(super) async fn decr(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        let value = db.decr(call.args()[0].clone())?;
+        self.propagate(call.argv).await;
+        Ok(RespValue::Integer(value).into())
+    }
+}
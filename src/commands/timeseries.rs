@@ -0,0 +1,368 @@
+use super::registry::{wrong_arity, Call};
+use super::{ok, CommandResult, Interpreter};
+use crate::db::ops::stream::now_ms;
+use crate::db::ops::timeseries::{SeriesRange, TimeSeriesOps};
+use crate::db::timeseries::{Aggregation, DuplicatePolicy, LabelFilter, RangeQuery, TimeSeries};
+use crate::network::resp::RespValue;
+
+fn parse_u64(value: &str, error: &str) -> Result<u64, String> {
+    value.parse::<u64>().map_err(|_| error.to_string())
+}
+
+/// Zaman damgası; `*` sunucu saatini kullanır.
+pub(super) fn parse_timestamp(value: &str, now: u64) -> Result<u64, String> {
+    if value == "*" {
+        return Ok(now);
+    }
+    parse_u64(value, "TSDB: invalid timestamp")
+}
+
+pub(super) fn parse_value(value: &str) -> Result<f64, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|v| !v.is_nan())
+        .ok_or_else(|| "TSDB: invalid value".to_string())
+}
+
+/// Aralık sınırı; `-` en küçük, `+` en büyük zaman damgasıdır.
+pub(super) fn parse_range_bound(value: &str) -> Result<u64, String> {
+    match value {
+        "-" => Ok(0),
+        "+" => Ok(u64::MAX),
+        _ => parse_u64(value, "TSDB: invalid timestamp"),
+    }
+}
+
+/// `AGGREGATION tip kova` çiftini ayrıştırır.
+fn parse_aggregation(args: &[String]) -> Result<(Aggregation, u64), String> {
+    match args {
+        [aggregation, bucket, ..] => {
+            let aggregation = Aggregation::parse(aggregation)?;
+            match bucket.parse::<u64>() {
+                Ok(bucket) if bucket > 0 => Ok((aggregation, bucket)),
+                _ => Err("TSDB: bucketDuration must be greater than zero".to_string()),
+            }
+        }
+        _ => Err("TSDB: missing AGGREGATION arguments".to_string()),
+    }
+}
+
+/// `LABELS etiket değer [etiket değer ...]`; argümanların geri kalanını tüketir.
+fn parse_labels(args: &[String]) -> Result<Vec<(String, String)>, String> {
+    if args.is_empty() || !args.len().is_multiple_of(2) {
+        return Err("TSDB: wrong number of LABELS arguments".to_string());
+    }
+    Ok(args
+        .chunks(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect())
+}
+
+/// TS.CREATE / TS.ADD seçenekleri: `[RETENTION ms] [DUPLICATE_POLICY p] [ON_DUPLICATE p] [LABELS ...]`.
+/// ON_DUPLICATE yalnızca `allow_on_duplicate` açıksa kabul edilir.
+fn parse_series_options(
+    args: &[String],
+    allow_on_duplicate: bool,
+) -> Result<(TimeSeries, Option<DuplicatePolicy>), String> {
+    let mut series = TimeSeries::default();
+    let mut on_duplicate = None;
+    let mut pos = 0;
+    while pos < args.len() {
+        let option = args[pos].to_uppercase();
+        if option == "LABELS" {
+            series.labels = parse_labels(&args[pos + 1..])?;
+            break;
+        }
+
+        let value = args
+            .get(pos + 1)
+            .ok_or_else(|| "syntax error".to_string())?;
+        match option.as_str() {
+            "RETENTION" => series.retention = parse_u64(value, "TSDB: invalid RETENTION")?,
+            "DUPLICATE_POLICY" => series.duplicate_policy = DuplicatePolicy::parse(value)?,
+            "ON_DUPLICATE" if allow_on_duplicate => {
+                on_duplicate = Some(DuplicatePolicy::parse(value)?)
+            }
+            _ => return Err("syntax error".to_string()),
+        }
+        pos += 2;
+    }
+    Ok((series, on_duplicate))
+}
+
+/// TS.CREATE: `key` sonrasındaki seçenekler.
+pub(super) fn parse_create(args: &[String]) -> Result<TimeSeries, String> {
+    parse_series_options(args, false).map(|(series, _)| series)
+}
+
+/// TS.ADD: `timestamp value` sonrasındaki seçenekler.
+/// Seri yoksa kullanılacak ayarlarla birlikte tekrar politikası döner.
+pub(super) fn parse_add(args: &[String]) -> Result<(TimeSeries, Option<DuplicatePolicy>), String> {
+    parse_series_options(args, true)
+}
+
+/// TS.RANGE: `[COUNT n] [AGGREGATION tip kova]`.
+pub(super) fn parse_range_query(args: &[String]) -> Result<RangeQuery, String> {
+    let mut query = RangeQuery::default();
+    let mut pos = 0;
+    while pos < args.len() {
+        match args[pos].to_uppercase().as_str() {
+            "COUNT" => {
+                let count = args
+                    .get(pos + 1)
+                    .ok_or_else(|| "syntax error".to_string())?;
+                query.count = Some(parse_u64(count, "TSDB: Couldn't parse COUNT")? as usize);
+                pos += 2;
+            }
+            "AGGREGATION" => {
+                query.aggregation = Some(parse_aggregation(&args[pos + 1..])?);
+                pos += 3;
+            }
+            _ => return Err("syntax error".to_string()),
+        }
+    }
+    Ok(query)
+}
+
+/// TS.MRANGE: `[COUNT n] [AGGREGATION tip kova] [WITHLABELS] FILTER filtre ...`.
+/// FILTER argümanların geri kalanını tüketir.
+pub(super) fn parse_mrange(
+    args: &[String],
+) -> Result<(RangeQuery, bool, Vec<LabelFilter>), String> {
+    let filter_pos = args
+        .iter()
+        .position(|arg| arg.eq_ignore_ascii_case("FILTER"))
+        .ok_or_else(|| "TSDB: missing FILTER argument".to_string())?;
+    let filters = args[filter_pos + 1..]
+        .iter()
+        .map(|f| LabelFilter::parse(f))
+        .collect::<Result<Vec<_>, _>>()?;
+    if filters.is_empty() {
+        return Err("TSDB: missing FILTER argument".to_string());
+    }
+
+    let mut options = args[..filter_pos].to_vec();
+    let with_labels = match options
+        .iter()
+        .position(|arg| arg.eq_ignore_ascii_case("WITHLABELS"))
+    {
+        Some(pos) => {
+            options.remove(pos);
+            true
+        }
+        None => false,
+    };
+    let query = parse_range_query(&options)?;
+    Ok((query, with_labels, filters))
+}
+
+/// TS.CREATERULE: `dest` sonrasındaki `AGGREGATION tip kova`.
+pub(super) fn parse_createrule(args: &[String]) -> Result<(Aggregation, u64), String> {
+    match args.split_first() {
+        Some((option, rest)) if option.eq_ignore_ascii_case("AGGREGATION") && rest.len() == 2 => {
+            parse_aggregation(rest)
+        }
+        _ => Err("syntax error".to_string()),
+    }
+}
+
+fn value_reply(value: f64) -> RespValue {
+    RespValue::BulkString(Some(value.to_string()))
+}
+
+/// `[zaman, değer]` ikilisi.
+pub(super) fn sample_reply(sample: (u64, f64)) -> RespValue {
+    RespValue::Array(Some(vec![
+        RespValue::Integer(sample.0 as i64),
+        value_reply(sample.1),
+    ]))
+}
+
+pub(super) fn samples_reply(samples: Vec<(u64, f64)>) -> RespValue {
+    RespValue::Array(Some(samples.into_iter().map(sample_reply).collect()))
+}
+
+fn labels_reply(labels: &[(String, String)]) -> RespValue {
+    RespValue::Array(Some(
+        labels
+            .iter()
+            .map(|(label, value)| {
+                RespValue::Array(Some(vec![
+                    RespValue::BulkString(Some(label.clone())),
+                    RespValue::BulkString(Some(value.clone())),
+                ]))
+            })
+            .collect(),
+    ))
+}
+
+/// TS.MRANGE cevabı: her seri için `[anahtar, etiketler, örnekler]`.
+/// WITHLABELS verilmezse etiket listesi boş döner.
+pub(super) fn mrange_reply(series: Vec<SeriesRange>, with_labels: bool) -> RespValue {
+    RespValue::Array(Some(
+        series
+            .into_iter()
+            .map(|(key, labels, samples)| {
+                let labels = if with_labels { labels } else { Vec::new() };
+                RespValue::Array(Some(vec![
+                    RespValue::BulkString(Some(key)),
+                    labels_reply(&labels),
+                    samples_reply(samples),
+                ]))
+            })
+            .collect(),
+    ))
+}
+
+pub(super) fn info_reply(series: &TimeSeries) -> RespValue {
+    let int = |value: u64| RespValue::Integer(value.try_into().unwrap_or(i64::MAX));
+    let timestamp = |sample: Option<(u64, f64)>| int(sample.map_or(0, |(ts, _)| ts));
+    let rules = series
+        .rules
+        .iter()
+        .map(|rule| {
+            RespValue::Array(Some(vec![
+                RespValue::BulkString(Some(rule.dest.clone())),
+                int(rule.bucket),
+                RespValue::SimpleString(rule.aggregation.name().to_string()),
+            ]))
+        })
+        .collect();
+
+    let fields = vec![
+        ("totalSamples", int(series.samples.len() as u64)),
+        ("memoryUsage", int(series.memory_usage() as u64)),
+        ("firstTimestamp", timestamp(series.first())),
+        ("lastTimestamp", timestamp(series.last())),
+        ("retentionTime", int(series.retention)),
+        (
+            "duplicatePolicy",
+            RespValue::SimpleString(series.duplicate_policy.name().to_string()),
+        ),
+        ("labels", labels_reply(&series.labels)),
+        ("sourceKey", RespValue::BulkString(series.source.clone())),
+        ("rules", RespValue::Array(Some(rules))),
+    ];
+    RespValue::Array(Some(
+        fields
+            .into_iter()
+            .flat_map(|(name, value)| [RespValue::SimpleString(name.to_string()), value])
+            .collect(),
+    ))
+}
+
+impl Interpreter {
+    pub(super) async fn ts_create(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let series = parse_create(&args[1..])?;
+        let mut db = self.db.write().await;
+        db.ts_create(args[0].clone(), series)?;
+        self.propagate(call.argv).await;
+        ok()
+    }
+
+    pub(super) async fn ts_add(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let timestamp = parse_timestamp(&args[1], now_ms())?;
+        let value = parse_value(&args[2])?;
+        let (template, on_duplicate) = parse_add(&args[3..])?;
+
+        let mut db = self.db.write().await;
+        let timestamp = db.ts_add(args[0].clone(), timestamp, value, on_duplicate, template)?;
+        // `*` yeniden yüklemede farklı zamana düşmesin diye AOF'a sunucunun seçtiği
+        // zaman damgası yazılır
+        let mut argv = call.argv;
+        argv[2] = timestamp.to_string();
+        self.propagate(argv).await;
+        Ok(RespValue::Integer(timestamp as i64).into())
+    }
+
+    pub(super) async fn ts_madd(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        if !args.len().is_multiple_of(3) {
+            return Err(wrong_arity(call.name));
+        }
+
+        let now = now_ms();
+        let mut db = self.db.write().await;
+        let mut results = Vec::with_capacity(args.len() / 3);
+        // AOF'a yalnızca başarılı örnekler, somut zaman damgalarıyla yazılır
+        let mut propagated = vec![call.argv[0].clone()];
+        for triple in args.chunks(3) {
+            let added = parse_timestamp(&triple[1], now)
+                .and_then(|ts| parse_value(&triple[2]).map(|value| (ts, value)))
+                .and_then(|(ts, value)| {
+                    db.ts_get(&triple[0])?;
+                    db.ts_add(triple[0].clone(), ts, value, None, TimeSeries::default())
+                });
+            match added {
+                Ok(ts) => {
+                    propagated.extend([triple[0].clone(), ts.to_string(), triple[2].clone()]);
+                    results.push(RespValue::Integer(ts as i64));
+                }
+                Err(e) => results.push(RespValue::Error(e)),
+            }
+        }
+
+        if propagated.len() > 1 {
+            self.propagate(propagated).await;
+        }
+        Ok(RespValue::Array(Some(results)).into())
+    }
+
+    pub(super) async fn ts_get(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        let reply = match db.ts_get(&call.args()[0])? {
+            Some(sample) => sample_reply(sample),
+            None => RespValue::Array(Some(vec![])),
+        };
+        Ok(reply.into())
+    }
+
+    pub(super) async fn ts_range(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let from = parse_range_bound(&args[1])?;
+        let to = parse_range_bound(&args[2])?;
+        let query = parse_range_query(&args[3..])?;
+
+        let mut db = self.db.write().await;
+        let samples = db.ts_range(&args[0], from, to, &query)?;
+        Ok(samples_reply(samples).into())
+    }
+
+    pub(super) async fn ts_mrange(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        // TS.MRANGE anahtar almaz; ilk iki argüman aralığın sınırlarıdır
+        let from = parse_range_bound(&args[0])?;
+        let to = parse_range_bound(&args[1])?;
+        let (query, with_labels, filters) = parse_mrange(&args[2..])?;
+
+        let mut db = self.db.write().await;
+        let series = db.ts_mrange(from, to, &query, &filters)?;
+        Ok(mrange_reply(series, with_labels).into())
+    }
+
+    pub(super) async fn ts_createrule(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let (aggregation, bucket) = parse_createrule(&args[2..])?;
+        let mut db = self.db.write().await;
+        db.ts_createrule(args[0].clone(), args[1].clone(), aggregation, bucket)?;
+        self.propagate(call.argv).await;
+        ok()
+    }
+
+    pub(super) async fn ts_deleterule(&mut self, call: Call) -> CommandResult {
+        let args = call.args();
+        let mut db = self.db.write().await;
+        db.ts_deleterule(args[0].clone(), args[1].clone())?;
+        self.propagate(call.argv).await;
+        ok()
+    }
+
+    pub(super) async fn ts_info(&mut self, call: Call) -> CommandResult {
+        let mut db = self.db.write().await;
+        let series = db.ts_info(&call.args()[0])?;
+        Ok(info_reply(series).into())
+    }
+}
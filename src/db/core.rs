@@ -1,7 +1,11 @@
-use crate::db::types::Entry;
-use std::collections::HashMap;
+use crate::db::search::SearchIndex;
+use crate::db::types::{DataType, Entry};
+use crate::observability::stats::STATS;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::watch;
 
 /// Veritabanının kalbi.
 /// Tüm veriler bellekte (RAM) bu HashMap içinde duruyor.
@@ -9,14 +13,22 @@ pub struct DB {
     pub(crate) items: HashMap<String, Entry>,
     /// Son kayıttan bu yana yapılan değişiklik sayısı
     pub(crate) changes_since_save: Arc<AtomicUsize>,
+    /// Stream'lere kayıt eklendikçe artan sayaç.
+    /// XREAD BLOCK ile bekleyen istemciler bu kanalı dinler.
+    pub(crate) stream_events: watch::Sender<u64>,
+    /// FT.CREATE ile tanımlanan arama indeksleri (ada göre)
+    pub(crate) search_indexes: BTreeMap<String, SearchIndex>,
 }
 
 impl DB {
     /// Yeni, tertemiz bir veritabanı oluşturur.
     pub fn new() -> Self {
+        let (stream_events, _) = watch::channel(0);
         DB {
             items: HashMap::new(),
             changes_since_save: Arc::new(AtomicUsize::new(0)),
+            stream_events,
+            search_indexes: BTreeMap::new(),
         }
     }
 
@@ -39,4 +51,75 @@ impl DB {
     pub fn get_changes_counter(&self) -> Arc<AtomicUsize> {
         Arc::clone(&self.changes_since_save)
     }
+
+    /// Stream olaylarını dinlemek için yeni bir alıcı döndürür.
+    pub fn subscribe_stream_events(&self) -> watch::Receiver<u64> {
+        self.stream_events.subscribe()
+    }
+
+    /// Bir stream'e yeni kayıt eklendiğini bekleyenlere bildirir.
+    pub(crate) fn notify_stream_event(&self) {
+        self.stream_events.send_modify(|v| *v = v.wrapping_add(1));
+    }
+
+    /// Tüm anahtarları siler (replikanın tam senkronizasyonu öncesi).
+    /// Arama indekslerinin tanımları korunur, yalnızca içerikleri boşalır.
+    pub fn clear(&mut self) {
+        let keys: Vec<String> = self.items.drain().map(|(key, _)| key).collect();
+        for key in &keys {
+            self.sync_indexes(key);
+        }
+        self.increment_changes();
+    }
+
+    /// Süresi `now` itibarıyla dolmuşsa anahtarı siler ve `expired_keys`'e sayar. Komutların
+    /// ve arka plandaki temizliğin sildiği süresi dolmuş anahtarlar buradan geçer.
+    pub(crate) fn remove_expired(&mut self, key: &str, now: Instant) -> bool {
+        let expired = self
+            .items
+            .get(key)
+            .and_then(|entry| entry.expires_at)
+            .is_some_and(|at| at <= now);
+        if expired {
+            self.items.remove(key);
+            self.sync_indexes(key);
+            STATS.expired_keys.fetch_add(1, Ordering::Relaxed);
+        }
+        expired
+    }
+
+    /// Anahtarı arar; süresi dolmuşsa önce siler. Okuma komutlarının aramaları (`read`)
+    /// `keyspace_hits`/`keyspace_misses`'e sayılır. Anahtar varsa `true` döner.
+    pub(crate) fn lookup(&mut self, key: &str, read: bool) -> bool {
+        self.remove_expired(key, Instant::now());
+        let found = self.items.contains_key(key);
+        if read {
+            let counter = if found {
+                &STATS.keyspace_hits
+            } else {
+                &STATS.keyspace_misses
+            };
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+        found
+    }
+
+    /// Anahtar değiştiğinde onu kapsayan arama indekslerini günceller.
+    /// Hash yazan ve anahtar silen her işlem bunu çağırmalıdır; güncel değere göre
+    /// yeniden indekslediği için aynı anahtar için birden çok çağrı zararsızdır.
+    pub(crate) fn sync_indexes(&mut self, key: &str) {
+        if self.search_indexes.is_empty() {
+            return;
+        }
+        let hash = match self.items.get(key) {
+            Some(Entry {
+                value: DataType::Hash(hash),
+                ..
+            }) => Some(hash),
+            _ => None,
+        };
+        for index in self.search_indexes.values_mut() {
+            index.update(key, hash);
+        }
+    }
 }
@@ -0,0 +1,255 @@
+//! Geohash kodlama yardımcıları.
+//! Koordinatlar 52 bitlik bir tamsayıya (26 adım enlem + 26 adım boylam) kodlanır
+//! ve sıralı kümede skor olarak saklanır. Yakın noktalar yakın skorlara düşer.
+
+use std::f64::consts::PI;
+
+/// Maksimum hassasiyet (adım sayısı). 26 adım = 52 bit.
+pub const GEO_STEP_MAX: u8 = 26;
+
+/// Web Mercator'ın desteklediği enlem aralığı.
+pub const GEO_LAT_MIN: f64 = -85.05112878;
+pub const GEO_LAT_MAX: f64 = 85.05112878;
+pub const GEO_LONG_MIN: f64 = -180.0;
+pub const GEO_LONG_MAX: f64 = 180.0;
+
+/// Mesafe hesaplarında kullanılan dünya yarıçapı (metre).
+const EARTH_RADIUS_IN_METERS: f64 = 6372797.560856;
+const MERCATOR_MAX: f64 = 20037726.37;
+
+/// Standart geohash karakter seti.
+const GEOALPHABET: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Bir geohash hücresinin kapsadığı alan.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoArea {
+    pub lon_min: f64,
+    pub lon_max: f64,
+    pub lat_min: f64,
+    pub lat_max: f64,
+}
+
+/// Koordinat çiftinin kodlanabilir aralıkta olup olmadığını kontrol eder.
+pub fn is_valid_coord(lon: f64, lat: f64) -> bool {
+    (GEO_LONG_MIN..=GEO_LONG_MAX).contains(&lon) && (GEO_LAT_MIN..=GEO_LAT_MAX).contains(&lat)
+}
+
+/// Mesafe birimini metre çarpanına çevirir (m, km, ft, mi).
+pub fn unit_to_meters(unit: &str) -> Option<f64> {
+    match unit.to_lowercase().as_str() {
+        "m" => Some(1.0),
+        "km" => Some(1000.0),
+        "ft" => Some(0.3048),
+        "mi" => Some(1609.34),
+        _ => None,
+    }
+}
+
+/// Enlem ve boylam ofsetlerinin bitlerini iç içe geçirir.
+/// Enlem bitleri çift, boylam bitleri tek pozisyonlara yerleşir.
+fn interleave(lat_offset: u32, lon_offset: u32) -> u64 {
+    let mut bits = 0u64;
+    for i in 0..32 {
+        bits |= ((lat_offset as u64 >> i) & 1) << (2 * i);
+        bits |= ((lon_offset as u64 >> i) & 1) << (2 * i + 1);
+    }
+    bits
+}
+
+/// `interleave` işleminin tersi: (enlem ofseti, boylam ofseti) döndürür.
+fn deinterleave(bits: u64) -> (u32, u32) {
+    let mut lat_offset = 0u32;
+    let mut lon_offset = 0u32;
+    for i in 0..32 {
+        lat_offset |= (((bits >> (2 * i)) & 1) as u32) << i;
+        lon_offset |= (((bits >> (2 * i + 1)) & 1) as u32) << i;
+    }
+    (lat_offset, lon_offset)
+}
+
+fn encode_in_range(lon: f64, lat: f64, lat_min: f64, lat_max: f64, step: u8) -> u64 {
+    let scale = (1u64 << step) as f64;
+    let max_offset = (1u64 << step) - 1;
+    let lat_offset = ((lat - lat_min) / (lat_max - lat_min) * scale) as u64;
+    let lon_offset = ((lon - GEO_LONG_MIN) / (GEO_LONG_MAX - GEO_LONG_MIN) * scale) as u64;
+    interleave(
+        lat_offset.min(max_offset) as u32,
+        lon_offset.min(max_offset) as u32,
+    )
+}
+
+/// Koordinatı verilen adım sayısında kodlar (sonuç `2 * step` bit).
+pub fn encode(lon: f64, lat: f64, step: u8) -> u64 {
+    encode_in_range(lon, lat, GEO_LAT_MIN, GEO_LAT_MAX, step)
+}
+
+/// Hücrenin sınırlarını hesaplar.
+pub fn decode(bits: u64, step: u8) -> GeoArea {
+    let (lat_offset, lon_offset) = deinterleave(bits);
+    let scale = (1u64 << step) as f64;
+    let lat_span = GEO_LAT_MAX - GEO_LAT_MIN;
+    let lon_span = GEO_LONG_MAX - GEO_LONG_MIN;
+
+    GeoArea {
+        lon_min: GEO_LONG_MIN + (lon_offset as f64 / scale) * lon_span,
+        lon_max: GEO_LONG_MIN + ((lon_offset as f64 + 1.0) / scale) * lon_span,
+        lat_min: GEO_LAT_MIN + (lat_offset as f64 / scale) * lat_span,
+        lat_max: GEO_LAT_MIN + ((lat_offset as f64 + 1.0) / scale) * lat_span,
+    }
+}
+
+/// 52 bitlik skoru hücrenin merkez koordinatına çevirir: (boylam, enlem).
+pub fn decode_to_lonlat(bits: u64) -> (f64, f64) {
+    let area = decode(bits, GEO_STEP_MAX);
+    let lon = ((area.lon_min + area.lon_max) / 2.0).clamp(GEO_LONG_MIN, GEO_LONG_MAX);
+    let lat = ((area.lat_min + area.lat_max) / 2.0).clamp(GEO_LAT_MIN, GEO_LAT_MAX);
+    (lon, lat)
+}
+
+/// Skoru 11 karakterlik standart geohash metnine çevirir.
+/// Dahili kodlama -85/85 enlem aralığını kullandığı için,
+/// standart -90/90 aralığıyla yeniden kodlamak gerekir.
+pub fn to_geohash_string(bits: u64) -> String {
+    let (lon, lat) = decode_to_lonlat(bits);
+    let hash = encode_in_range(lon, lat, -90.0, 90.0, GEO_STEP_MAX);
+
+    (0..11)
+        .map(|i| {
+            let idx = if i == 10 {
+                0
+            } else {
+                (hash >> (52 - (i + 1) * 5)) & 0x1f
+            };
+            GEOALPHABET[idx as usize] as char
+        })
+        .collect()
+}
+
+/// İki nokta arasındaki mesafeyi metre cinsinden hesaplar (haversine).
+pub fn distance(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let lat1r = lat1 * PI / 180.0;
+    let lat2r = lat2 * PI / 180.0;
+    let u = ((lat2r - lat1r) / 2.0).sin();
+    let v = ((lon2 - lon1) * PI / 180.0 / 2.0).sin();
+    let a = u * u + lat1r.cos() * lat2r.cos() * v * v;
+    2.0 * EARTH_RADIUS_IN_METERS * a.sqrt().asin()
+}
+
+/// Verilen yarıçapı 3x3 hücre komşuluğuyla kapsayacak adım sayısını bulur.
+pub fn estimate_steps(radius_meters: f64, lat: f64) -> u8 {
+    if radius_meters <= 0.0 {
+        return GEO_STEP_MAX;
+    }
+
+    let mut range = radius_meters;
+    let mut step: i32 = 1;
+    while range < MERCATOR_MAX {
+        range *= 2.0;
+        step += 1;
+    }
+    step -= 2;
+
+    // Kutuplara yakın bölgelerde hücreler daralır
+    if !(-66.0..=66.0).contains(&lat) {
+        step -= 1;
+        if !(-80.0..=80.0).contains(&lat) {
+            step -= 1;
+        }
+    }
+
+    let mut step = step.clamp(1, GEO_STEP_MAX as i32) as u8;
+
+    // Komşuluğun gerçekten yarıçapı kapsadığından emin ol
+    let lat_deg = radius_meters / EARTH_RADIUS_IN_METERS * 180.0 / PI;
+    let worst_lat = (lat.abs() + lat_deg).min(GEO_LAT_MAX) * PI / 180.0;
+    while step > 1 {
+        let scale = (1u64 << step) as f64;
+        let height = (GEO_LAT_MAX - GEO_LAT_MIN) / scale * PI / 180.0 * EARTH_RADIUS_IN_METERS;
+        let width = 360.0 / scale * PI / 180.0 * EARTH_RADIUS_IN_METERS * worst_lat.cos();
+        if height >= radius_meters && width >= radius_meters {
+            break;
+        }
+        step -= 1;
+    }
+
+    step
+}
+
+/// Hücreyi ve sekiz komşusunu döndürür. Tekrarlar ve geçersiz hücreler atılır.
+pub fn neighbors(bits: u64, step: u8) -> Vec<u64> {
+    let area = decode(bits, step);
+    let width = area.lon_max - area.lon_min;
+    let height = area.lat_max - area.lat_min;
+    let center_lon = (area.lon_min + area.lon_max) / 2.0;
+    let center_lat = (area.lat_min + area.lat_max) / 2.0;
+
+    let mut cells = Vec::with_capacity(9);
+    for dy in [-1.0, 0.0, 1.0] {
+        let lat = center_lat + dy * height;
+        if !(GEO_LAT_MIN..=GEO_LAT_MAX).contains(&lat) {
+            continue;
+        }
+        for dx in [-1.0, 0.0, 1.0] {
+            let mut lon = center_lon + dx * width;
+            if lon > GEO_LONG_MAX {
+                lon -= 360.0;
+            } else if lon < GEO_LONG_MIN {
+                lon += 360.0;
+            }
+            let cell = encode(lon, lat, step);
+            if !cells.contains(&cell) {
+                cells.push(cell);
+            }
+        }
+    }
+    cells
+}
+
+/// Hücreye düşen 52 bitlik skorların aralığını döndürür: [min, max).
+pub fn score_range(bits: u64, step: u8) -> (u64, u64) {
+    let shift = 2 * (GEO_STEP_MAX - step) as u32;
+    (bits << shift, (bits + 1) << shift)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let score = encode(13.361389, 38.115556, GEO_STEP_MAX);
+        assert_eq!(score, 3479099956230698);
+
+        let (lon, lat) = decode_to_lonlat(score);
+        assert!((lon - 13.361389).abs() < 0.00001);
+        assert!((lat - 38.115556).abs() < 0.00001);
+    }
+
+    #[test]
+    fn test_geohash_string() {
+        let palermo = encode(13.361389, 38.115556, GEO_STEP_MAX);
+        let catania = encode(15.087269, 37.502669, GEO_STEP_MAX);
+        assert_eq!(to_geohash_string(palermo), "sqc8b49rny0");
+        assert_eq!(to_geohash_string(catania), "sqdtr74hyu0");
+    }
+
+    #[test]
+    fn test_distance() {
+        let d = distance(13.361389, 38.115556, 15.087269, 37.502669);
+        assert!((d - 166274.15).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_neighbors_cover_point() {
+        let step = estimate_steps(200_000.0, 38.0);
+        let center = encode(15.0, 37.0, step);
+        let cells = neighbors(center, step);
+        assert!(cells.len() <= 9);
+
+        let palermo = encode(13.361389, 38.115556, GEO_STEP_MAX);
+        assert!(cells.iter().any(|&cell| {
+            let (min, max) = score_range(cell, step);
+            palermo >= min && palermo < max
+        }));
+    }
+}
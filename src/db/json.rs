@@ -0,0 +1,378 @@
+//! JSON belgeleri için JSONPath alt kümesi.
+//! Desteklenenler: `$` kök, `.alan`, `['alan']`, `[n]` (negatif dahil), `*`, `[*]` ve `..` özyinelemeli iniş.
+//! `$` ile başlamayan yollar (`.a.b`, `a[0]`) eski söz dizimi olarak kabul edilir ve tek bir değer döndürür.
+
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// Yolun tek bir seçicisi.
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Child(String),
+    Index(i64),
+    Wildcard,
+    /// `..` ile başlayan seçici: düğümün kendisine ve tüm altındakilere uygulanır
+    Descendant(Box<Selector>),
+}
+
+/// Belgedeki somut bir konumun tek adımı.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    Key(String),
+    Index(usize),
+}
+
+impl PartialOrd for Step {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Step {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Step::Index(a), Step::Index(b)) => a.cmp(b),
+            (Step::Key(a), Step::Key(b)) => a.cmp(b),
+            (Step::Index(_), Step::Key(_)) => Ordering::Less,
+            (Step::Key(_), Step::Index(_)) => Ordering::Greater,
+        }
+    }
+}
+
+/// Belgedeki somut konum (kökten itibaren adımlar).
+pub type Location = Vec<Step>;
+
+/// Ayrıştırılmış yol ifadesi.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    selectors: Vec<Selector>,
+    /// Eski söz dizimi: sonuç dizi yerine tek değer olarak döner
+    pub legacy: bool,
+}
+
+impl JsonPath {
+    /// Yol ifadesini ayrıştırır.
+    pub fn parse(path: &str) -> Result<JsonPath, String> {
+        let (body, legacy) = match path.strip_prefix('$') {
+            Some(rest) => (rest.to_string(), false),
+            None if path == "." => (String::new(), true),
+            None if path.starts_with('.') || path.starts_with('[') => (path.to_string(), true),
+            None => (format!(".{}", path), true),
+        };
+
+        let invalid = || format!("invalid JSONPath '{}'", path);
+        let chars: Vec<char> = body.chars().collect();
+        let mut selectors = Vec::new();
+        let mut pos = 0;
+
+        while pos < chars.len() {
+            let descendant = chars[pos] == '.' && chars.get(pos + 1) == Some(&'.');
+            let selector = match chars[pos] {
+                '.' => {
+                    pos += if descendant { 2 } else { 1 };
+                    if chars.get(pos) == Some(&'[') {
+                        if !descendant {
+                            return Err(invalid());
+                        }
+                        parse_bracket(&chars, &mut pos).ok_or_else(invalid)?
+                    } else if chars.get(pos) == Some(&'*') {
+                        pos += 1;
+                        Selector::Wildcard
+                    } else {
+                        let start = pos;
+                        while pos < chars.len() && chars[pos] != '.' && chars[pos] != '[' {
+                            pos += 1;
+                        }
+                        if start == pos {
+                            return Err(invalid());
+                        }
+                        Selector::Child(chars[start..pos].iter().collect())
+                    }
+                }
+                '[' => parse_bracket(&chars, &mut pos).ok_or_else(invalid)?,
+                _ => return Err(invalid()),
+            };
+
+            selectors.push(if descendant {
+                Selector::Descendant(Box::new(selector))
+            } else {
+                selector
+            });
+        }
+
+        Ok(JsonPath { selectors, legacy })
+    }
+
+    /// Yol belgenin kökünü mü gösteriyor?
+    pub fn is_root(&self) -> bool {
+        self.selectors.is_empty()
+    }
+
+    /// Yolun eşleştiği tüm somut konumları döndürür.
+    pub fn find(&self, root: &Value) -> Vec<Location> {
+        find_from(root, &self.selectors)
+    }
+
+    /// Henüz var olmayan tek bir alan için (ebeveyn konumları, alan adı) döndürür.
+    /// Yalnızca son seçici bir alan adıysa anlamlıdır (JSON.SET ile yeni alan ekleme).
+    pub fn find_new_parents(&self, root: &Value) -> Option<(Vec<Location>, String)> {
+        let (last, parents) = self.selectors.split_last()?;
+        match last {
+            Selector::Child(name) => {
+                let parents = find_from(root, parents)
+                    .into_iter()
+                    .filter(|loc| {
+                        get(root, loc)
+                            .and_then(Value::as_object)
+                            .is_some_and(|obj| !obj.contains_key(name))
+                    })
+                    .collect();
+                Some((parents, name.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// `[` ile başlayan seçiciyi ayrıştırır: `[n]`, `[*]`, `['alan']` veya `["alan"]`.
+fn parse_bracket(chars: &[char], pos: &mut usize) -> Option<Selector> {
+    *pos += 1;
+    let selector = match chars.get(*pos)? {
+        '*' => {
+            *pos += 1;
+            Selector::Wildcard
+        }
+        &quote @ ('\'' | '"') => {
+            *pos += 1;
+            let mut name = String::new();
+            loop {
+                match chars.get(*pos)? {
+                    '\\' => {
+                        name.push(*chars.get(*pos + 1)?);
+                        *pos += 2;
+                    }
+                    &c if c == quote => {
+                        *pos += 1;
+                        break;
+                    }
+                    &c => {
+                        name.push(c);
+                        *pos += 1;
+                    }
+                }
+            }
+            Selector::Child(name)
+        }
+        _ => {
+            let start = *pos;
+            while *pos < chars.len() && chars[*pos] != ']' {
+                *pos += 1;
+            }
+            let index: String = chars[start..*pos].iter().collect();
+            Selector::Index(index.trim().parse().ok()?)
+        }
+    };
+
+    if chars.get(*pos) != Some(&']') {
+        return None;
+    }
+    *pos += 1;
+    Some(selector)
+}
+
+fn find_from(root: &Value, selectors: &[Selector]) -> Vec<Location> {
+    let mut current = vec![Vec::new()];
+    for selector in selectors {
+        let mut next = Vec::new();
+        for loc in &current {
+            if let Some(value) = get(root, loc) {
+                apply(selector, value, loc, &mut next);
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+/// Seçiciyi bir düğüme uygular ve eşleşen konumları `out`a ekler.
+fn apply(selector: &Selector, value: &Value, loc: &Location, out: &mut Vec<Location>) {
+    let child = |step: Step| {
+        let mut loc = loc.clone();
+        loc.push(step);
+        loc
+    };
+
+    match selector {
+        Selector::Child(name) => {
+            if value.as_object().is_some_and(|obj| obj.contains_key(name)) {
+                out.push(child(Step::Key(name.clone())));
+            }
+        }
+        Selector::Index(index) => {
+            if let Some(arr) = value.as_array() {
+                let len = arr.len() as i64;
+                let index = if *index < 0 { len + index } else { *index };
+                if (0..len).contains(&index) {
+                    out.push(child(Step::Index(index as usize)));
+                }
+            }
+        }
+        Selector::Wildcard => match value {
+            Value::Object(obj) => out.extend(obj.keys().map(|k| child(Step::Key(k.clone())))),
+            Value::Array(arr) => out.extend((0..arr.len()).map(|i| child(Step::Index(i)))),
+            _ => {}
+        },
+        Selector::Descendant(inner) => {
+            apply(inner, value, loc, out);
+            match value {
+                Value::Object(obj) => {
+                    for (k, v) in obj {
+                        apply(selector, v, &child(Step::Key(k.clone())), out);
+                    }
+                }
+                Value::Array(arr) => {
+                    for (i, v) in arr.iter().enumerate() {
+                        apply(selector, v, &child(Step::Index(i)), out);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Konumdaki değeri döndürür.
+pub fn get<'a>(root: &'a Value, loc: &[Step]) -> Option<&'a Value> {
+    loc.iter().try_fold(root, |value, step| match step {
+        Step::Key(key) => value.as_object()?.get(key),
+        Step::Index(i) => value.as_array()?.get(*i),
+    })
+}
+
+/// Konumdaki değeri değiştirilebilir olarak döndürür.
+pub fn get_mut<'a>(root: &'a mut Value, loc: &[Step]) -> Option<&'a mut Value> {
+    loc.iter().try_fold(root, |value, step| match step {
+        Step::Key(key) => value.as_object_mut()?.get_mut(key),
+        Step::Index(i) => value.as_array_mut()?.get_mut(*i),
+    })
+}
+
+/// Konumdaki değeri ebeveyninden siler. Kök silinemez.
+pub fn remove(root: &mut Value, loc: &[Step]) -> bool {
+    let (last, parent) = match loc.split_last() {
+        Some(split) => split,
+        None => return false,
+    };
+    match (get_mut(root, parent), last) {
+        (Some(Value::Object(obj)), Step::Key(key)) => obj.shift_remove(key).is_some(),
+        (Some(Value::Array(arr)), Step::Index(i)) if *i < arr.len() => {
+            arr.remove(*i);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// JSON.TYPE isimleri.
+pub fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Sayıyı artırır. İki taraf da tamsayıysa ve taşma yoksa sonuç tamsayı kalır.
+pub fn add_numbers(a: &serde_json::Number, b: &serde_json::Number) -> Result<Value, String> {
+    if let (Some(x), Some(y)) = (a.as_i64(), b.as_i64()) {
+        if let Some(sum) = x.checked_add(y) {
+            return Ok(Value::from(sum));
+        }
+    }
+    let sum = a.as_f64().unwrap_or(0.0) + b.as_f64().unwrap_or(0.0);
+    serde_json::Number::from_f64(sum)
+        .map(Value::Number)
+        .ok_or_else(|| "result is not a valid number".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn doc() -> Value {
+        json!({"a": 1, "b": {"a": [1, 2, {"a": "x"}]}, "c": [true, null]})
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(JsonPath::parse("$").unwrap().is_root());
+        assert!(JsonPath::parse(".").unwrap().legacy);
+        assert_eq!(
+            JsonPath::parse("$.b['a'][2]").unwrap(),
+            JsonPath::parse(".b.a[2]")
+                .map(|p| JsonPath { legacy: false, ..p })
+                .unwrap()
+        );
+        assert_eq!(
+            JsonPath::parse("b.a").unwrap(),
+            JsonPath::parse(".b.a").unwrap()
+        );
+        assert!(JsonPath::parse("$.").is_err());
+        assert!(JsonPath::parse("$[1").is_err());
+        assert!(JsonPath::parse("$x").is_err());
+    }
+
+    #[test]
+    fn test_find() {
+        let doc = doc();
+        let found = |p: &str| -> Vec<Value> {
+            let path = JsonPath::parse(p).unwrap();
+            path.find(&doc)
+                .iter()
+                .map(|loc| get(&doc, loc).unwrap().clone())
+                .collect()
+        };
+
+        assert_eq!(found("$.a"), vec![json!(1)]);
+        assert_eq!(found("$.b.a[-1].a"), vec![json!("x")]);
+        assert_eq!(found("$.c[*]"), vec![json!(true), json!(null)]);
+        assert_eq!(found("$..a").len(), 3);
+        assert!(found("$.missing").is_empty());
+        assert!(found("$.a[0]").is_empty());
+    }
+
+    #[test]
+    fn test_remove_and_new_parents() {
+        let mut doc = doc();
+        let path = JsonPath::parse("$.b.a[0]").unwrap();
+        let loc = path.find(&doc).pop().unwrap();
+        assert!(remove(&mut doc, &loc));
+        assert_eq!(doc["b"]["a"], json!([2, {"a": "x"}]));
+
+        let path = JsonPath::parse("$..new").unwrap();
+        assert!(path.find_new_parents(&doc).is_none());
+        let path = JsonPath::parse("$.b.new").unwrap();
+        let (parents, name) = path.find_new_parents(&doc).unwrap();
+        assert_eq!(parents, vec![vec![Step::Key("b".to_string())]]);
+        assert_eq!(name, "new");
+    }
+
+    #[test]
+    fn test_add_numbers() {
+        let n = |v: Value| v.as_number().unwrap().clone();
+        assert_eq!(add_numbers(&n(json!(1)), &n(json!(2))).unwrap(), json!(3));
+        assert_eq!(
+            add_numbers(&n(json!(1)), &n(json!(0.5))).unwrap(),
+            json!(1.5)
+        );
+        assert_eq!(
+            add_numbers(&n(json!(i64::MAX)), &n(json!(1))).unwrap(),
+            json!(i64::MAX as f64 + 1.0)
+        );
+    }
+}
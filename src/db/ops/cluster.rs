@@ -0,0 +1,167 @@
+use crate::cluster::key_slot;
+use crate::db::core::DB;
+use crate::db::probabilistic::{BloomFilter, CountMinSketch, CuckooFilter, TopK};
+use crate::db::sorted_set::SortedSet;
+use crate::db::stream::Stream;
+use crate::db::timeseries::TimeSeries;
+use crate::db::types::{DataType, Entry};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// DUMP biçiminin sürümü; farklı sürümdeki veri RESTORE edilmez.
+const DUMP_VERSION: u32 = 1;
+
+/// DUMP ile taşınan değer. Sorted set dışındaki tipler olduğu gibi serileştirilir.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+enum Payload {
+    String(String),
+    List(Vec<String>),
+    Hash(HashMap<String, String>),
+    Set(HashSet<String>),
+    Zset(Vec<(String, f64)>),
+    Stream(Stream),
+    Json(serde_json::Value),
+    Bloom(BloomFilter),
+    Cuckoo(CuckooFilter),
+    Cms(CountMinSketch),
+    Topk(TopK),
+    Timeseries(TimeSeries),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Dump {
+    version: u32,
+    #[serde(flatten)]
+    payload: Payload,
+}
+
+impl From<&DataType> for Payload {
+    fn from(value: &DataType) -> Self {
+        match value {
+            DataType::String(s) => Payload::String(s.clone()),
+            DataType::List(list) => Payload::List(list.clone()),
+            DataType::Hash(hash) => Payload::Hash(hash.clone()),
+            DataType::Set(set) => Payload::Set(set.clone()),
+            DataType::SortedSet(zset) => Payload::Zset(
+                zset.iter()
+                    .map(|(member, score)| (member.to_string(), score))
+                    .collect(),
+            ),
+            DataType::Stream(stream) => Payload::Stream(stream.clone()),
+            DataType::Json(doc) => Payload::Json(doc.clone()),
+            DataType::Bloom(filter) => Payload::Bloom(filter.clone()),
+            DataType::Cuckoo(filter) => Payload::Cuckoo(filter.clone()),
+            DataType::CountMin(sketch) => Payload::Cms(sketch.clone()),
+            DataType::TopK(topk) => Payload::Topk(topk.clone()),
+            DataType::TimeSeries(series) => Payload::Timeseries(series.clone()),
+        }
+    }
+}
+
+impl From<Payload> for DataType {
+    fn from(payload: Payload) -> Self {
+        match payload {
+            Payload::String(s) => DataType::String(s),
+            Payload::List(list) => DataType::List(list),
+            Payload::Hash(hash) => DataType::Hash(hash),
+            Payload::Set(set) => DataType::Set(set),
+            Payload::Zset(members) => {
+                let mut zset = SortedSet::new();
+                for (member, score) in members {
+                    zset.insert(member, score);
+                }
+                DataType::SortedSet(zset)
+            }
+            Payload::Stream(stream) => DataType::Stream(stream),
+            Payload::Json(doc) => DataType::Json(doc),
+            Payload::Bloom(filter) => DataType::Bloom(filter),
+            Payload::Cuckoo(filter) => DataType::Cuckoo(filter),
+            Payload::Cms(sketch) => DataType::CountMin(sketch),
+            Payload::Topk(topk) => DataType::TopK(topk),
+            Payload::Timeseries(series) => DataType::TimeSeries(series),
+        }
+    }
+}
+
+/// Anahtarları node'lar arasında taşımak için gereken operasyonlar
+/// (DUMP, RESTORE ve slot bazında anahtar listeleme).
+pub trait ClusterOps {
+    /// Değeri serileştirir; anahtar yoksa `None`. İkinci değer kalan TTL'dir (ms).
+    fn dump(&mut self, key: &str) -> Option<(String, Option<u64>)>;
+    fn restore(
+        &mut self,
+        key: String,
+        ttl_ms: u64,
+        payload: &str,
+        replace: bool,
+    ) -> Result<(), String>;
+    fn keys_in_slot(&mut self, slot: u16, count: usize) -> Vec<String>;
+    fn count_keys_in_slot(&mut self, slot: u16) -> usize;
+}
+
+impl ClusterOps for DB {
+    fn dump(&mut self, key: &str) -> Option<(String, Option<u64>)> {
+        if !self.lookup(key, false) {
+            return None;
+        }
+        let entry = self.items.get(key)?;
+        let dump = Dump {
+            version: DUMP_VERSION,
+            payload: Payload::from(&entry.value),
+        };
+        let ttl = entry
+            .expires_at
+            .map(|at| at.saturating_duration_since(Instant::now()).as_millis() as u64);
+        serde_json::to_string(&dump)
+            .ok()
+            .map(|payload| (payload, ttl))
+    }
+
+    /// Serileştirilmiş değeri anahtara yazar. `ttl_ms` 0 ise anahtarın süresi dolmaz.
+    fn restore(
+        &mut self,
+        key: String,
+        ttl_ms: u64,
+        payload: &str,
+        replace: bool,
+    ) -> Result<(), String> {
+        let dump: Dump = serde_json::from_str(payload)
+            .ok()
+            .filter(|dump: &Dump| dump.version == DUMP_VERSION)
+            .ok_or_else(|| "DUMP payload version or checksum are wrong".to_string())?;
+        if !replace && self.lookup(&key, false) {
+            return Err("BUSYKEY Target key name already exists.".to_string());
+        }
+
+        let expires_at = (ttl_ms > 0).then(|| Instant::now() + Duration::from_millis(ttl_ms));
+        self.items.insert(
+            key.clone(),
+            Entry {
+                value: dump.payload.into(),
+                expires_at,
+            },
+        );
+        self.sync_indexes(&key);
+        self.increment_changes();
+        Ok(())
+    }
+
+    fn keys_in_slot(&mut self, slot: u16, count: usize) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .items
+            .keys()
+            .filter(|key| key_slot(key) == slot)
+            .cloned()
+            .collect();
+        keys.retain(|key| self.lookup(key, false));
+        keys.sort();
+        keys.truncate(count);
+        keys
+    }
+
+    fn count_keys_in_slot(&mut self, slot: u16) -> usize {
+        self.keys_in_slot(slot, usize::MAX).len()
+    }
+}
@@ -0,0 +1,498 @@
+use crate::db::core::DB;
+use crate::db::geohash::{self, GEO_STEP_MAX};
+use crate::db::sorted_set::SortedSet;
+use crate::db::types::{DataType, Entry};
+
+/// Aramanın merkez noktası.
+#[derive(Debug, Clone)]
+pub enum GeoOrigin {
+    /// Kümedeki bir üyenin konumu (FROMMEMBER)
+    Member(String),
+    /// Verilen koordinat (FROMLONLAT)
+    LonLat(f64, f64),
+}
+
+/// Arama alanı. Tüm ölçüler metre cinsindendir.
+#[derive(Debug, Clone, Copy)]
+pub enum GeoShape {
+    Radius(f64),
+    Box { width: f64, height: f64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeoSort {
+    Asc,
+    Desc,
+}
+
+/// GEOSEARCH / GEOSEARCHSTORE sorgusu.
+#[derive(Debug, Clone)]
+pub struct GeoQuery {
+    pub origin: GeoOrigin,
+    pub shape: GeoShape,
+    pub sort: Option<GeoSort>,
+    pub count: Option<usize>,
+    /// COUNT dolduğunda taramayı bırak (sonuçlar en yakınlar olmayabilir)
+    pub any: bool,
+}
+
+/// Arama sonucundaki tek bir üye.
+#[derive(Debug, Clone)]
+pub struct GeoMatch {
+    pub member: String,
+    /// Merkeze uzaklık (metre)
+    pub dist: f64,
+    pub score: u64,
+    pub lon: f64,
+    pub lat: f64,
+}
+
+/// Coğrafi indeks operasyonları.
+/// Konumlar sıralı kümede 52 bitlik geohash skoru olarak saklanır.
+pub trait GeoOps {
+    fn geoadd(
+        &mut self,
+        key: String,
+        items: Vec<(f64, f64, String)>,
+        nx: bool,
+        xx: bool,
+        ch: bool,
+    ) -> Result<usize, String>;
+    fn geopos(
+        &mut self,
+        key: String,
+        members: Vec<String>,
+    ) -> Result<Vec<Option<(f64, f64)>>, String>;
+    fn geodist(
+        &mut self,
+        key: String,
+        member1: String,
+        member2: String,
+    ) -> Result<Option<f64>, String>;
+    fn geohash(&mut self, key: String, members: Vec<String>)
+        -> Result<Vec<Option<String>>, String>;
+    fn geosearch(&mut self, key: String, query: &GeoQuery) -> Result<Vec<GeoMatch>, String>;
+    fn geosearchstore(
+        &mut self,
+        destination: String,
+        source: String,
+        query: &GeoQuery,
+        store_dist: Option<f64>,
+    ) -> Result<usize, String>;
+}
+
+/// Anahtardaki sıralı kümeyi döndürür. Anahtar yoksa `None`, tip yanlışsa hata döner.
+fn get_zset<'a>(db: &'a mut DB, key: &str, read: bool) -> Result<Option<&'a SortedSet>, String> {
+    if !db.lookup(key, read) {
+        return Ok(None);
+    }
+
+    match db.items.get(key) {
+        Some(entry) => match &entry.value {
+            DataType::SortedSet(zset) => Ok(Some(zset)),
+            _ => {
+                Err("WRONGTYPE Operation against a key holding the wrong kind of value".to_string())
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+/// Noktanın arama alanında olup olmadığını kontrol eder.
+/// Alandaysa merkeze olan mesafeyi döndürür.
+fn distance_if_within(shape: GeoShape, center: (f64, f64), lon: f64, lat: f64) -> Option<f64> {
+    let (center_lon, center_lat) = center;
+    match shape {
+        GeoShape::Radius(radius) => {
+            let dist = geohash::distance(center_lon, center_lat, lon, lat);
+            if dist <= radius {
+                Some(dist)
+            } else {
+                None
+            }
+        }
+        GeoShape::Box { width, height } => {
+            let lat_distance = geohash::distance(lon, lat, lon, center_lat);
+            if lat_distance > height / 2.0 {
+                return None;
+            }
+            let lon_distance = geohash::distance(lon, lat, center_lon, lat);
+            if lon_distance > width / 2.0 {
+                return None;
+            }
+            Some(geohash::distance(center_lon, center_lat, lon, lat))
+        }
+    }
+}
+
+impl GeoOps for DB {
+    /// Üyeleri konumlarıyla birlikte ekler.
+    /// Yeni eklenen üye sayısını (CH ile değişenler dahil) döner.
+    fn geoadd(
+        &mut self,
+        key: String,
+        items: Vec<(f64, f64, String)>,
+        nx: bool,
+        xx: bool,
+        ch: bool,
+    ) -> Result<usize, String> {
+        for (lon, lat, _) in &items {
+            if !geohash::is_valid_coord(*lon, *lat) {
+                return Err(format!(
+                    "invalid longitude,latitude pair {:.6},{:.6}",
+                    lon, lat
+                ));
+            }
+        }
+
+        let exists = get_zset(self, &key, false)?.is_some();
+        if !exists && xx {
+            return Ok(0);
+        }
+
+        let entry = self.items.entry(key.clone()).or_insert_with(|| Entry {
+            value: DataType::SortedSet(SortedSet::new()),
+            expires_at: None,
+        });
+        let zset = match &mut entry.value {
+            DataType::SortedSet(zset) => zset,
+            _ => unreachable!("type checked above"),
+        };
+
+        let mut added = 0;
+        let mut changed = 0;
+        for (lon, lat, member) in items {
+            let score = geohash::encode(lon, lat, GEO_STEP_MAX) as f64;
+            match zset.score(&member) {
+                Some(old) => {
+                    if nx {
+                        continue;
+                    }
+                    if old != score {
+                        zset.insert(member, score);
+                        changed += 1;
+                    }
+                }
+                None => {
+                    if xx {
+                        continue;
+                    }
+                    zset.insert(member, score);
+                    added += 1;
+                }
+            }
+        }
+
+        if zset.is_empty() {
+            self.items.remove(&key);
+        }
+        if added + changed > 0 {
+            self.increment_changes();
+        }
+
+        Ok(if ch { added + changed } else { added })
+    }
+
+    /// Üyelerin (boylam, enlem) konumlarını döndürür.
+    fn geopos(
+        &mut self,
+        key: String,
+        members: Vec<String>,
+    ) -> Result<Vec<Option<(f64, f64)>>, String> {
+        let zset = get_zset(self, &key, true)?;
+        Ok(members
+            .iter()
+            .map(|member| {
+                zset.and_then(|z| z.score(member))
+                    .map(|score| geohash::decode_to_lonlat(score as u64))
+            })
+            .collect())
+    }
+
+    /// İki üye arasındaki mesafeyi metre cinsinden döndürür.
+    fn geodist(
+        &mut self,
+        key: String,
+        member1: String,
+        member2: String,
+    ) -> Result<Option<f64>, String> {
+        let zset = match get_zset(self, &key, true)? {
+            Some(zset) => zset,
+            None => return Ok(None),
+        };
+
+        match (zset.score(&member1), zset.score(&member2)) {
+            (Some(score1), Some(score2)) => {
+                let (lon1, lat1) = geohash::decode_to_lonlat(score1 as u64);
+                let (lon2, lat2) = geohash::decode_to_lonlat(score2 as u64);
+                Ok(Some(geohash::distance(lon1, lat1, lon2, lat2)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Üyelerin standart geohash metinlerini döndürür.
+    fn geohash(
+        &mut self,
+        key: String,
+        members: Vec<String>,
+    ) -> Result<Vec<Option<String>>, String> {
+        let zset = get_zset(self, &key, true)?;
+        Ok(members
+            .iter()
+            .map(|member| {
+                zset.and_then(|z| z.score(member))
+                    .map(|score| geohash::to_geohash_string(score as u64))
+            })
+            .collect())
+    }
+
+    /// Alan içindeki üyeleri bulur.
+    /// Merkezi kapsayan hücre ve komşuları skor aralığı olarak taranır,
+    /// ardından her aday gerçek mesafeyle filtrelenir.
+    fn geosearch(&mut self, key: String, query: &GeoQuery) -> Result<Vec<GeoMatch>, String> {
+        let zset = match get_zset(self, &key, true)? {
+            Some(zset) => zset,
+            None => return Ok(Vec::new()),
+        };
+
+        let center = match &query.origin {
+            GeoOrigin::LonLat(lon, lat) => (*lon, *lat),
+            GeoOrigin::Member(member) => match zset.score(member) {
+                Some(score) => geohash::decode_to_lonlat(score as u64),
+                None => return Err("could not decode requested zset member".to_string()),
+            },
+        };
+
+        let radius = match query.shape {
+            GeoShape::Radius(radius) => radius,
+            GeoShape::Box { width, height } => {
+                ((width / 2.0).powi(2) + (height / 2.0).powi(2)).sqrt()
+            }
+        };
+        let step = geohash::estimate_steps(radius, center.1);
+        let cell = geohash::encode(center.0, center.1, step);
+        let limit = if query.any { query.count } else { None };
+
+        let mut matches = Vec::new();
+        'cells: for neighbor in geohash::neighbors(cell, step) {
+            let (min, max) = geohash::score_range(neighbor, step);
+            for (member, score) in zset.range_by_score(min as f64, max as f64) {
+                let (lon, lat) = geohash::decode_to_lonlat(score as u64);
+                if let Some(dist) = distance_if_within(query.shape, center, lon, lat) {
+                    matches.push(GeoMatch {
+                        member: member.to_string(),
+                        dist,
+                        score: score as u64,
+                        lon,
+                        lat,
+                    });
+                    if limit.is_some_and(|n| matches.len() >= n) {
+                        break 'cells;
+                    }
+                }
+            }
+        }
+
+        // COUNT verilip sıralama belirtilmediyse en yakınlar döner
+        let sort = match query.sort {
+            Some(sort) => Some(sort),
+            None if query.count.is_some() && !query.any => Some(GeoSort::Asc),
+            None => None,
+        };
+        match sort {
+            Some(GeoSort::Asc) => matches.sort_by(|a, b| a.dist.total_cmp(&b.dist)),
+            Some(GeoSort::Desc) => matches.sort_by(|a, b| b.dist.total_cmp(&a.dist)),
+            None => {}
+        }
+        if let Some(count) = query.count {
+            matches.truncate(count);
+        }
+
+        Ok(matches)
+    }
+
+    /// Arama sonucunu hedef anahtara sıralı küme olarak yazar.
+    /// `store_dist` verilirse skor olarak o birime çevrilmiş mesafe saklanır.
+    fn geosearchstore(
+        &mut self,
+        destination: String,
+        source: String,
+        query: &GeoQuery,
+        store_dist: Option<f64>,
+    ) -> Result<usize, String> {
+        let matches = self.geosearch(source, query)?;
+
+        let mut zset = SortedSet::new();
+        for m in matches {
+            let score = match store_dist {
+                Some(unit) => m.dist / unit,
+                None => m.score as f64,
+            };
+            zset.insert(m.member, score);
+        }
+
+        let count = zset.len();
+        if count == 0 {
+            self.items.remove(&destination);
+        } else {
+            self.items.insert(
+                destination,
+                Entry {
+                    value: DataType::SortedSet(zset),
+                    expires_at: None,
+                },
+            );
+        }
+        self.increment_changes();
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PALERMO: (f64, f64) = (13.361389, 38.115556);
+    const CATANIA: (f64, f64) = (15.087269, 37.502669);
+    const AGRIGENTO: (f64, f64) = (13.583333, 37.316667);
+
+    fn item((lon, lat): (f64, f64), member: &str) -> (f64, f64, String) {
+        (lon, lat, member.to_string())
+    }
+
+    fn sicily() -> DB {
+        let mut db = DB::new();
+        let items = vec![
+            item(PALERMO, "Palermo"),
+            item(CATANIA, "Catania"),
+            item(AGRIGENTO, "Agrigento"),
+        ];
+        assert_eq!(
+            db.geoadd("Sicily".into(), items, false, false, false),
+            Ok(3)
+        );
+        db
+    }
+
+    fn query(shape: GeoShape, count: Option<usize>, any: bool) -> GeoQuery {
+        GeoQuery {
+            origin: GeoOrigin::LonLat(15.0, 37.0),
+            shape,
+            sort: None,
+            count,
+            any,
+        }
+    }
+
+    fn members(matches: &[GeoMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.member.as_str()).collect()
+    }
+
+    #[test]
+    fn test_geoadd_options() {
+        let mut db = sicily();
+        let key = || "Sicily".to_string();
+
+        // NX: var olan üye güncellenmez, yenisi eklenir
+        let items = vec![item(CATANIA, "Palermo"), item((13.0, 38.0), "Trapani")];
+        assert_eq!(db.geoadd(key(), items, true, false, false), Ok(1));
+        let pos = db.geopos(key(), vec!["Palermo".into()]).unwrap();
+        assert!((pos[0].unwrap().0 - PALERMO.0).abs() < 1e-5);
+
+        // XX: yalnızca var olanlar güncellenir; CH olmadan sayı 0'dır
+        let items = vec![item(CATANIA, "Palermo"), item((14.0, 37.0), "Gela")];
+        assert_eq!(db.geoadd(key(), items.clone(), false, true, false), Ok(0));
+        assert_eq!(db.geopos(key(), vec!["Gela".into()]), Ok(vec![None]));
+        let pos = db.geopos(key(), vec!["Palermo".into()]).unwrap();
+        assert!((pos[0].unwrap().0 - CATANIA.0).abs() < 1e-5);
+
+        // CH: değişen üyeler de sayılır, aynı konum değişiklik değildir
+        let items = vec![item(PALERMO, "Palermo"), item(CATANIA, "Catania")];
+        assert_eq!(db.geoadd(key(), items, false, false, true), Ok(1));
+
+        // XX ile olmayan anahtar oluşturulmaz
+        let items = vec![item(PALERMO, "Palermo")];
+        assert_eq!(db.geoadd("none".into(), items, false, true, false), Ok(0));
+        assert!(!db.items.contains_key("none"));
+
+        assert!(db
+            .geoadd(key(), vec![item((0.0, 86.0), "pole")], false, false, false)
+            .is_err());
+    }
+
+    #[test]
+    fn test_geodist_units() {
+        let mut db = sicily();
+        let dist = db
+            .geodist("Sicily".into(), "Palermo".into(), "Catania".into())
+            .unwrap()
+            .unwrap();
+        assert!((dist - 166274.1516).abs() < 0.01);
+        let km = geohash::unit_to_meters("KM").unwrap();
+        assert!((dist / km - 166.2742).abs() < 0.0001);
+        let mi = geohash::unit_to_meters("mi").unwrap();
+        assert!((dist / mi - 103.3182).abs() < 0.0001);
+        assert_eq!(geohash::unit_to_meters("yd"), None);
+
+        let missing = db.geodist("Sicily".into(), "Palermo".into(), "Rome".into());
+        assert_eq!(missing, Ok(None));
+    }
+
+    #[test]
+    fn test_geosearch_shapes() {
+        let mut db = sicily();
+        let km = 1000.0;
+
+        let mut by_radius = query(GeoShape::Radius(200.0 * km), None, false);
+        by_radius.sort = Some(GeoSort::Asc);
+        let matches = db.geosearch("Sicily".into(), &by_radius).unwrap();
+        assert_eq!(members(&matches), ["Catania", "Agrigento", "Palermo"]);
+        assert!((matches[0].dist - 56441.2660).abs() < 1.0);
+
+        // Agrigento 130 km uzakta ama 260 km'lik kutunun içinde; Palermo boylamca dışında
+        let by_radius = query(GeoShape::Radius(120.0 * km), None, false);
+        let matches = db.geosearch("Sicily".into(), &by_radius).unwrap();
+        assert_eq!(members(&matches), ["Catania"]);
+        let mut by_box = query(
+            GeoShape::Box {
+                width: 260.0 * km,
+                height: 260.0 * km,
+            },
+            None,
+            false,
+        );
+        by_box.sort = Some(GeoSort::Desc);
+        let matches = db.geosearch("Sicily".into(), &by_box).unwrap();
+        assert_eq!(members(&matches), ["Agrigento", "Catania"]);
+    }
+
+    #[test]
+    fn test_geosearch_count() {
+        let mut db = sicily();
+        let radius = GeoShape::Radius(300_000.0);
+
+        // ANY olmadan COUNT en yakınları döndürür
+        let matches = db
+            .geosearch("Sicily".into(), &query(radius, Some(2), false))
+            .unwrap();
+        assert_eq!(members(&matches), ["Catania", "Agrigento"]);
+
+        // ANY ile ilk bulunanlarda durulur
+        let matches = db
+            .geosearch("Sicily".into(), &query(radius, Some(1), true))
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+
+        let from_member = GeoQuery {
+            origin: GeoOrigin::Member("Rome".into()),
+            ..query(radius, None, false)
+        };
+        assert!(db.geosearch("Sicily".into(), &from_member).is_err());
+        assert!(db
+            .geosearch("none".into(), &from_member)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,300 @@
+use crate::db::core::DB;
+use crate::db::json::{self, JsonPath, Location};
+use crate::db::types::{DataType, Entry};
+use serde_json::{Number, Value};
+
+/// Yol başına eşleşen değerlere uygulanan işlemin sonucu.
+/// Eşleşen değer işleme uygun değilse `None` olur.
+pub type JsonResults<T> = Vec<Option<T>>;
+
+/// JSON belge operasyonları.
+/// Kısmi güncellemeler saklanan belge üzerinde yerinde yapılır.
+pub trait JsonOps {
+    fn json_set(
+        &mut self,
+        key: String,
+        path: &JsonPath,
+        value: Value,
+        nx: bool,
+        xx: bool,
+    ) -> Result<bool, String>;
+    fn json_get(
+        &mut self,
+        key: String,
+        paths: &[JsonPath],
+    ) -> Result<Option<Vec<Vec<Value>>>, String>;
+    fn json_del(&mut self, key: String, path: &JsonPath) -> Result<usize, String>;
+    fn json_type(
+        &mut self,
+        key: String,
+        path: &JsonPath,
+    ) -> Result<Option<Vec<&'static str>>, String>;
+    fn json_arrappend(
+        &mut self,
+        key: String,
+        path: &JsonPath,
+        values: Vec<Value>,
+    ) -> Result<JsonResults<usize>, String>;
+    fn json_numincrby(
+        &mut self,
+        key: String,
+        path: &JsonPath,
+        by: Number,
+    ) -> Result<JsonResults<Value>, String>;
+    fn json_objkeys(
+        &mut self,
+        key: String,
+        path: &JsonPath,
+    ) -> Result<Option<JsonResults<Vec<String>>>, String>;
+}
+
+/// Anahtardaki JSON belgesini döndürür. Anahtar yoksa `None`, tip yanlışsa hata döner.
+fn get_doc_mut<'a>(db: &'a mut DB, key: &str, read: bool) -> Result<Option<&'a mut Value>, String> {
+    if !db.lookup(key, read) {
+        return Ok(None);
+    }
+
+    match db.items.get_mut(key) {
+        Some(entry) => match &mut entry.value {
+            DataType::Json(doc) => Ok(Some(doc)),
+            _ => {
+                Err("WRONGTYPE Operation against a key holding the wrong kind of value".to_string())
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+/// Değiştiren işlemler için belgeyi döndürür; anahtar yoksa hata verir.
+fn existing_doc_mut<'a>(db: &'a mut DB, key: &str) -> Result<&'a mut Value, String> {
+    get_doc_mut(db, key, false)?
+        .ok_or_else(|| "could not perform this operation on a key that doesn't exist".to_string())
+}
+
+/// Eşleşen her konuma `f`i uygular ve sonuçları toplar.
+fn update_each<T>(
+    doc: &mut Value,
+    locations: Vec<Location>,
+    mut f: impl FnMut(&mut Value) -> Option<T>,
+) -> Vec<Option<T>> {
+    locations
+        .iter()
+        .map(|loc| json::get_mut(doc, loc).and_then(&mut f))
+        .collect()
+}
+
+impl JsonOps for DB {
+    /// Yoldaki değeri yazar. Kök dışındaki yollar için anahtarın var olması gerekir.
+    /// NX/XX koşulu sağlanmazsa `false` döner.
+    fn json_set(
+        &mut self,
+        key: String,
+        path: &JsonPath,
+        value: Value,
+        nx: bool,
+        xx: bool,
+    ) -> Result<bool, String> {
+        let doc = match get_doc_mut(self, &key, false)? {
+            Some(doc) => doc,
+            None => {
+                if !path.is_root() {
+                    return Err("new objects must be created at the root".to_string());
+                }
+                if xx {
+                    return Ok(false);
+                }
+                self.items.insert(
+                    key,
+                    Entry {
+                        value: DataType::Json(value),
+                        expires_at: None,
+                    },
+                );
+                self.increment_changes();
+                return Ok(true);
+            }
+        };
+
+        let existing = path.find(doc);
+        let updated = if !existing.is_empty() {
+            if nx {
+                return Ok(false);
+            }
+            for loc in &existing {
+                if let Some(target) = json::get_mut(doc, loc) {
+                    *target = value.clone();
+                }
+            }
+            true
+        } else {
+            if xx {
+                return Ok(false);
+            }
+            match path.find_new_parents(doc) {
+                Some((parents, name)) if !parents.is_empty() => {
+                    for loc in &parents {
+                        if let Some(Value::Object(obj)) = json::get_mut(doc, loc) {
+                            obj.insert(name.clone(), value.clone());
+                        }
+                    }
+                    true
+                }
+                _ => false,
+            }
+        };
+
+        if updated {
+            self.increment_changes();
+        }
+        Ok(updated)
+    }
+
+    /// Her yol için eşleşen değerleri döndürür. Anahtar yoksa `None` döner.
+    fn json_get(
+        &mut self,
+        key: String,
+        paths: &[JsonPath],
+    ) -> Result<Option<Vec<Vec<Value>>>, String> {
+        let doc = match get_doc_mut(self, &key, true)? {
+            Some(doc) => &*doc,
+            None => return Ok(None),
+        };
+
+        Ok(Some(
+            paths
+                .iter()
+                .map(|path| {
+                    path.find(doc)
+                        .iter()
+                        .filter_map(|loc| json::get(doc, loc).cloned())
+                        .collect()
+                })
+                .collect(),
+        ))
+    }
+
+    /// Yoldaki değerleri siler ve silinen sayısını döndürür.
+    /// Kök yolu anahtarın tamamını siler.
+    fn json_del(&mut self, key: String, path: &JsonPath) -> Result<usize, String> {
+        let doc = match get_doc_mut(self, &key, false)? {
+            Some(doc) => doc,
+            None => return Ok(0),
+        };
+
+        if path.is_root() {
+            self.items.remove(&key);
+            self.increment_changes();
+            return Ok(1);
+        }
+
+        // Dizi indeksleri kaymasın diye sondan başa doğru sil
+        let mut locations = path.find(doc);
+        locations.sort();
+        locations.dedup();
+        let removed = locations
+            .iter()
+            .rev()
+            .filter(|loc| json::remove(doc, loc))
+            .count();
+
+        if removed > 0 {
+            self.increment_changes();
+        }
+        Ok(removed)
+    }
+
+    /// Yoldaki değerlerin tip isimlerini döndürür.
+    fn json_type(
+        &mut self,
+        key: String,
+        path: &JsonPath,
+    ) -> Result<Option<Vec<&'static str>>, String> {
+        let doc = match get_doc_mut(self, &key, true)? {
+            Some(doc) => &*doc,
+            None => return Ok(None),
+        };
+
+        Ok(Some(
+            path.find(doc)
+                .iter()
+                .filter_map(|loc| json::get(doc, loc).map(json::type_name))
+                .collect(),
+        ))
+    }
+
+    /// Yoldaki dizilerin sonuna değerleri ekler ve yeni uzunlukları döndürür.
+    fn json_arrappend(
+        &mut self,
+        key: String,
+        path: &JsonPath,
+        values: Vec<Value>,
+    ) -> Result<JsonResults<usize>, String> {
+        let doc = existing_doc_mut(self, &key)?;
+        let locations = path.find(doc);
+        let results = update_each(doc, locations, |target| {
+            let arr = target.as_array_mut()?;
+            arr.extend(values.iter().cloned());
+            Some(arr.len())
+        });
+
+        if results.iter().any(Option::is_some) {
+            self.increment_changes();
+        }
+        Ok(results)
+    }
+
+    /// Yoldaki sayıları artırır ve yeni değerleri döndürür.
+    fn json_numincrby(
+        &mut self,
+        key: String,
+        path: &JsonPath,
+        by: Number,
+    ) -> Result<JsonResults<Value>, String> {
+        let doc = existing_doc_mut(self, &key)?;
+        let locations = path.find(doc);
+
+        // Tüm sonuçlar önce hesaplanır ki taşma hatası belgeyi yarım bırakmasın
+        let mut updates = Vec::with_capacity(locations.len());
+        for loc in &locations {
+            updates.push(match json::get(doc, loc) {
+                Some(Value::Number(n)) => Some(json::add_numbers(n, &by)?),
+                _ => None,
+            });
+        }
+
+        let mut updates = updates.into_iter();
+        let results = update_each(doc, locations, |target| {
+            let new_value = updates.next().flatten()?;
+            *target = new_value.clone();
+            Some(new_value)
+        });
+
+        if results.iter().any(Option::is_some) {
+            self.increment_changes();
+        }
+        Ok(results)
+    }
+
+    /// Yoldaki nesnelerin alan isimlerini döndürür.
+    fn json_objkeys(
+        &mut self,
+        key: String,
+        path: &JsonPath,
+    ) -> Result<Option<JsonResults<Vec<String>>>, String> {
+        let doc = match get_doc_mut(self, &key, true)? {
+            Some(doc) => &*doc,
+            None => return Ok(None),
+        };
+
+        Ok(Some(
+            path.find(doc)
+                .iter()
+                .map(|loc| {
+                    json::get(doc, loc)
+                        .and_then(Value::as_object)
+                        .map(|obj| obj.keys().cloned().collect())
+                })
+                .collect(),
+        ))
+    }
+}
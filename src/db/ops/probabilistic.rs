@@ -0,0 +1,310 @@
+use crate::db::core::DB;
+use crate::db::probabilistic::{BloomFilter, CountMinSketch, CuckooFilter, TopK};
+use crate::db::types::{DataType, Entry};
+
+/// Bloom filtresi operasyonları.
+pub trait BloomOps {
+    fn bf_reserve(&mut self, key: String, filter: BloomFilter) -> Result<(), String>;
+    fn bf_add(
+        &mut self,
+        key: String,
+        items: Vec<String>,
+    ) -> Result<Vec<Result<bool, String>>, String>;
+    fn bf_exists(&mut self, key: String, items: Vec<String>) -> Result<Vec<bool>, String>;
+    fn bf_info(&mut self, key: &str) -> Result<&BloomFilter, String>;
+}
+
+/// Cuckoo filtresi operasyonları.
+pub trait CuckooOps {
+    fn cf_reserve(&mut self, key: String, filter: CuckooFilter) -> Result<(), String>;
+    fn cf_add(&mut self, key: String, item: String) -> Result<(), String>;
+    fn cf_del(&mut self, key: String, item: String) -> Result<bool, String>;
+    fn cf_exists(&mut self, key: String, items: Vec<String>) -> Result<Vec<bool>, String>;
+    fn cf_info(&mut self, key: &str) -> Result<&CuckooFilter, String>;
+}
+
+/// Count-Min sketch operasyonları.
+pub trait CmsOps {
+    fn cms_init(&mut self, key: String, sketch: CountMinSketch) -> Result<(), String>;
+    fn cms_incrby(&mut self, key: String, items: Vec<(String, u64)>) -> Result<Vec<u64>, String>;
+    fn cms_query(&mut self, key: String, items: Vec<String>) -> Result<Vec<u64>, String>;
+    fn cms_merge(
+        &mut self,
+        destination: String,
+        sources: Vec<String>,
+        weights: Vec<u64>,
+    ) -> Result<(), String>;
+    fn cms_info(&mut self, key: &str) -> Result<&CountMinSketch, String>;
+}
+
+/// Top-K operasyonları.
+pub trait TopKOps {
+    fn topk_reserve(&mut self, key: String, topk: TopK) -> Result<(), String>;
+    fn topk_add(&mut self, key: String, items: Vec<String>) -> Result<Vec<Option<String>>, String>;
+    fn topk_query(&mut self, key: String, items: Vec<String>) -> Result<Vec<bool>, String>;
+    fn topk_list(&mut self, key: String) -> Result<Vec<(String, u64)>, String>;
+    fn topk_info(&mut self, key: &str) -> Result<&TopK, String>;
+}
+
+fn wrong_type() -> String {
+    "WRONGTYPE Operation against a key holding the wrong kind of value".to_string()
+}
+
+/// Anahtardaki yapıyı `extract` ile çıkarır. Anahtar yoksa `None`, tip yanlışsa hata döner.
+fn get_value<'a, T>(
+    db: &'a mut DB,
+    key: &str,
+    extract: fn(&mut DataType) -> Option<&mut T>,
+    read: bool,
+) -> Result<Option<&'a mut T>, String> {
+    if !db.lookup(key, read) {
+        return Ok(None);
+    }
+
+    match db.items.get_mut(key) {
+        Some(entry) => extract(&mut entry.value).map(Some).ok_or_else(wrong_type),
+        None => Ok(None),
+    }
+}
+
+/// Yeni bir yapıyı anahtara yazar. Anahtar zaten varsa `exists_error` döner.
+fn insert_new(db: &mut DB, key: String, value: DataType, exists_error: &str) -> Result<(), String> {
+    if db.lookup(&key, false) && db.items.contains_key(&key) {
+        return Err(exists_error.to_string());
+    }
+    db.items.insert(
+        key,
+        Entry {
+            value,
+            expires_at: None,
+        },
+    );
+    db.increment_changes();
+    Ok(())
+}
+
+fn bloom(value: &mut DataType) -> Option<&mut BloomFilter> {
+    match value {
+        DataType::Bloom(filter) => Some(filter),
+        _ => None,
+    }
+}
+
+fn cuckoo(value: &mut DataType) -> Option<&mut CuckooFilter> {
+    match value {
+        DataType::Cuckoo(filter) => Some(filter),
+        _ => None,
+    }
+}
+
+fn count_min(value: &mut DataType) -> Option<&mut CountMinSketch> {
+    match value {
+        DataType::CountMin(sketch) => Some(sketch),
+        _ => None,
+    }
+}
+
+fn top_k(value: &mut DataType) -> Option<&mut TopK> {
+    match value {
+        DataType::TopK(topk) => Some(topk),
+        _ => None,
+    }
+}
+
+impl BloomOps for DB {
+    /// Verilen ayarlarla boş bir Bloom filtresi oluşturur.
+    fn bf_reserve(&mut self, key: String, filter: BloomFilter) -> Result<(), String> {
+        insert_new(self, key, DataType::Bloom(filter), "item exists")
+    }
+
+    /// Öğeleri ekler; filtre yoksa varsayılan ayarlarla oluşturulur.
+    /// Her öğe için yeni eklenip eklenmediği ya da hatası döner.
+    fn bf_add(
+        &mut self,
+        key: String,
+        items: Vec<String>,
+    ) -> Result<Vec<Result<bool, String>>, String> {
+        if get_value(self, &key, bloom, false)?.is_none() {
+            let filter = BloomFilter::new(
+                BloomFilter::DEFAULT_CAPACITY,
+                BloomFilter::DEFAULT_ERROR_RATE,
+                BloomFilter::DEFAULT_EXPANSION,
+            );
+            insert_new(self, key.clone(), DataType::Bloom(filter), "item exists")?;
+        }
+
+        let filter = get_value(self, &key, bloom, false)?.expect("bloom filter exists");
+        let results: Vec<Result<bool, String>> = items
+            .iter()
+            .map(|item| filter.add(item.as_bytes()))
+            .collect();
+        if results.iter().any(|r| r == &Ok(true)) {
+            self.increment_changes();
+        }
+        Ok(results)
+    }
+
+    /// Öğelerin filtrede (muhtemelen) olup olmadığını döndürür.
+    fn bf_exists(&mut self, key: String, items: Vec<String>) -> Result<Vec<bool>, String> {
+        let filter = get_value(self, &key, bloom, true)?;
+        Ok(items
+            .iter()
+            .map(|item| filter.as_ref().is_some_and(|f| f.exists(item.as_bytes())))
+            .collect())
+    }
+
+    fn bf_info(&mut self, key: &str) -> Result<&BloomFilter, String> {
+        get_value(self, key, bloom, true)?
+            .map(|f| &*f)
+            .ok_or_else(|| "not found".to_string())
+    }
+}
+
+impl CuckooOps for DB {
+    /// Verilen ayarlarla boş bir Cuckoo filtresi oluşturur.
+    fn cf_reserve(&mut self, key: String, filter: CuckooFilter) -> Result<(), String> {
+        insert_new(self, key, DataType::Cuckoo(filter), "item exists")
+    }
+
+    /// Öğeyi ekler; filtre yoksa varsayılan ayarlarla oluşturulur.
+    fn cf_add(&mut self, key: String, item: String) -> Result<(), String> {
+        if get_value(self, &key, cuckoo, false)?.is_none() {
+            let filter = CuckooFilter::new(
+                CuckooFilter::DEFAULT_CAPACITY,
+                CuckooFilter::DEFAULT_BUCKET_SIZE,
+                CuckooFilter::DEFAULT_MAX_ITERATIONS,
+                CuckooFilter::DEFAULT_EXPANSION,
+            );
+            insert_new(self, key.clone(), DataType::Cuckoo(filter), "item exists")?;
+        }
+
+        let filter = get_value(self, &key, cuckoo, false)?.expect("cuckoo filter exists");
+        filter.add(item.as_bytes())?;
+        self.increment_changes();
+        Ok(())
+    }
+
+    /// Öğenin bir kopyasını siler.
+    fn cf_del(&mut self, key: String, item: String) -> Result<bool, String> {
+        let filter =
+            get_value(self, &key, cuckoo, false)?.ok_or_else(|| "Not found".to_string())?;
+        let deleted = filter.delete(item.as_bytes());
+        if deleted {
+            self.increment_changes();
+        }
+        Ok(deleted)
+    }
+
+    /// Öğelerin filtrede (muhtemelen) olup olmadığını döndürür.
+    fn cf_exists(&mut self, key: String, items: Vec<String>) -> Result<Vec<bool>, String> {
+        let filter = get_value(self, &key, cuckoo, true)?;
+        Ok(items
+            .iter()
+            .map(|item| filter.as_ref().is_some_and(|f| f.exists(item.as_bytes())))
+            .collect())
+    }
+
+    fn cf_info(&mut self, key: &str) -> Result<&CuckooFilter, String> {
+        get_value(self, key, cuckoo, true)?
+            .map(|f| &*f)
+            .ok_or_else(|| "not found".to_string())
+    }
+}
+
+impl CmsOps for DB {
+    /// Boş bir Count-Min sketch oluşturur.
+    fn cms_init(&mut self, key: String, sketch: CountMinSketch) -> Result<(), String> {
+        insert_new(
+            self,
+            key,
+            DataType::CountMin(sketch),
+            "CMS: key already exists",
+        )
+    }
+
+    /// Öğelerin sayaçlarını artırır ve yeni tahminleri döndürür.
+    fn cms_incrby(&mut self, key: String, items: Vec<(String, u64)>) -> Result<Vec<u64>, String> {
+        let sketch = get_value(self, &key, count_min, false)?
+            .ok_or_else(|| "CMS: key does not exist".to_string())?;
+        let counts = items
+            .iter()
+            .map(|(item, by)| sketch.incr_by(item.as_bytes(), *by))
+            .collect();
+        self.increment_changes();
+        Ok(counts)
+    }
+
+    /// Öğelerin tahmini sayılarını döndürür.
+    fn cms_query(&mut self, key: String, items: Vec<String>) -> Result<Vec<u64>, String> {
+        let sketch = get_value(self, &key, count_min, true)?
+            .ok_or_else(|| "CMS: key does not exist".to_string())?;
+        Ok(items
+            .iter()
+            .map(|item| sketch.query(item.as_bytes()))
+            .collect())
+    }
+
+    /// Kaynak sketch'leri ağırlıklarıyla toplayıp hedefe yazar. Hedef önceden oluşturulmuş olmalıdır.
+    fn cms_merge(
+        &mut self,
+        destination: String,
+        sources: Vec<String>,
+        weights: Vec<u64>,
+    ) -> Result<(), String> {
+        let mut snapshots = Vec::with_capacity(sources.len());
+        for source in &sources {
+            let sketch = get_value(self, source, count_min, false)?
+                .ok_or_else(|| "CMS: key does not exist".to_string())?;
+            snapshots.push(sketch.clone());
+        }
+
+        let dest = get_value(self, &destination, count_min, false)?
+            .ok_or_else(|| "CMS: key does not exist".to_string())?;
+        let weighted: Vec<(&CountMinSketch, u64)> = snapshots.iter().zip(weights).collect();
+        dest.merge(&weighted)?;
+        self.increment_changes();
+        Ok(())
+    }
+
+    fn cms_info(&mut self, key: &str) -> Result<&CountMinSketch, String> {
+        get_value(self, key, count_min, true)?
+            .map(|s| &*s)
+            .ok_or_else(|| "CMS: key does not exist".to_string())
+    }
+}
+
+impl TopKOps for DB {
+    /// Boş bir Top-K yapısı oluşturur.
+    fn topk_reserve(&mut self, key: String, topk: TopK) -> Result<(), String> {
+        insert_new(self, key, DataType::TopK(topk), "TopK: key already exists")
+    }
+
+    /// Öğeleri ekler ve her biri için listeden çıkarılan öğeyi döndürür.
+    fn topk_add(&mut self, key: String, items: Vec<String>) -> Result<Vec<Option<String>>, String> {
+        let topk = get_value(self, &key, top_k, false)?
+            .ok_or_else(|| "TopK: key does not exist".to_string())?;
+        let expelled = items.iter().map(|item| topk.add(item)).collect();
+        self.increment_changes();
+        Ok(expelled)
+    }
+
+    /// Öğelerin listede olup olmadığını döndürür.
+    fn topk_query(&mut self, key: String, items: Vec<String>) -> Result<Vec<bool>, String> {
+        let topk = get_value(self, &key, top_k, true)?
+            .ok_or_else(|| "TopK: key does not exist".to_string())?;
+        Ok(items.iter().map(|item| topk.contains(item)).collect())
+    }
+
+    /// Listedeki öğeleri sayılarıyla, azalan sırada döndürür.
+    fn topk_list(&mut self, key: String) -> Result<Vec<(String, u64)>, String> {
+        let topk = get_value(self, &key, top_k, true)?
+            .ok_or_else(|| "TopK: key does not exist".to_string())?;
+        Ok(topk.list())
+    }
+
+    fn topk_info(&mut self, key: &str) -> Result<&TopK, String> {
+        get_value(self, key, top_k, true)?
+            .map(|t| &*t)
+            .ok_or_else(|| "TopK: key does not exist".to_string())
+    }
+}
@@ -0,0 +1,199 @@
+use crate::db::core::DB;
+use crate::db::search::aggregate::{AggregatePlan, Row};
+use crate::db::search::query::{KnnQuery, Query};
+use crate::db::search::{FieldKind, SearchIndex};
+use crate::db::types::{DataType, Entry};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Arama sonucu.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub key: String,
+    /// Metin sorgularında TF-IDF puanı, KNN sorgularında uzaklık
+    pub score: f64,
+    pub fields: Vec<(String, String)>,
+}
+
+/// Arama indeksi operasyonları.
+pub trait SearchOps {
+    fn ft_create(&mut self, index: SearchIndex) -> Result<(), String>;
+    fn ft_dropindex(&mut self, name: &str) -> Result<(), String>;
+    fn ft_list(&self) -> Vec<String>;
+    fn ft_info(&self, name: &str) -> Result<&SearchIndex, String>;
+    fn ft_search(
+        &self,
+        name: &str,
+        query: &Query,
+        sort_by: Option<(&str, bool)>,
+    ) -> Result<Vec<SearchHit>, String>;
+    fn ft_knn(&self, name: &str, query: &KnnQuery) -> Result<Vec<SearchHit>, String>;
+    fn ft_aggregate(
+        &self,
+        name: &str,
+        query: &Query,
+        plan: &AggregatePlan,
+    ) -> Result<Vec<Row>, String>;
+}
+
+fn unknown_index() -> String {
+    "Unknown Index name".to_string()
+}
+
+/// Süresi dolmamış hash'i döndürür.
+fn live_hash(entry: Option<&Entry>) -> Option<&HashMap<String, String>> {
+    match entry {
+        Some(Entry {
+            value: DataType::Hash(hash),
+            expires_at,
+        }) if expires_at.is_none_or(|at| at > Instant::now()) => Some(hash),
+        _ => None,
+    }
+}
+
+/// Hash alanlarını ada göre sıralı döndürür.
+fn sorted_fields(hash: &HashMap<String, String>) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> =
+        hash.iter().map(|(f, v)| (f.clone(), v.clone())).collect();
+    fields.sort();
+    fields
+}
+
+impl SearchOps for DB {
+    /// İndeksi oluşturur ve kapsadığı mevcut hash'leri indeksler.
+    fn ft_create(&mut self, mut index: SearchIndex) -> Result<(), String> {
+        if self.search_indexes.contains_key(&index.name) {
+            return Err("Index already exists".to_string());
+        }
+        for (key, entry) in &self.items {
+            if let Some(hash) = live_hash(Some(entry)) {
+                index.update(key, Some(hash));
+            }
+        }
+        self.search_indexes.insert(index.name.clone(), index);
+        Ok(())
+    }
+
+    /// İndeksi siler; hash'lere dokunmaz.
+    fn ft_dropindex(&mut self, name: &str) -> Result<(), String> {
+        self.search_indexes
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(unknown_index)
+    }
+
+    fn ft_list(&self) -> Vec<String> {
+        self.search_indexes.keys().cloned().collect()
+    }
+
+    fn ft_info(&self, name: &str) -> Result<&SearchIndex, String> {
+        self.search_indexes.get(name).ok_or_else(unknown_index)
+    }
+
+    /// Sorguyla eşleşen tüm hash'leri döndürür. Varsayılan sıra azalan puandır;
+    /// `sort_by` verilirse o alanın değerine göre sıralanır.
+    fn ft_search(
+        &self,
+        name: &str,
+        query: &Query,
+        sort_by: Option<(&str, bool)>,
+    ) -> Result<Vec<SearchHit>, String> {
+        let index = self.search_indexes.get(name).ok_or_else(unknown_index)?;
+        let numeric_sort = match sort_by {
+            Some((field, _)) => match index.field(field).map(|f| &f.kind) {
+                Some(FieldKind::Numeric(_)) => true,
+                Some(_) => false,
+                None => return Err(format!("Property `{}` not loaded nor in schema", field)),
+            },
+            None => false,
+        };
+
+        let mut hits: Vec<SearchHit> = index
+            .search(query)?
+            .into_iter()
+            .filter_map(|(key, score)| {
+                let hash = live_hash(self.items.get(&key))?;
+                Some(SearchHit {
+                    fields: sorted_fields(hash),
+                    key,
+                    score,
+                })
+            })
+            .collect();
+
+        if let Some((field, ascending)) = sort_by {
+            let value = |hit: &SearchHit| {
+                hit.fields
+                    .iter()
+                    .find(|(f, _)| f == field)
+                    .map(|(_, v)| v.clone())
+            };
+            hits.sort_by(|a, b| {
+                let ordering = match (value(a), value(b)) {
+                    (Some(x), Some(y)) if numeric_sort => {
+                        let number = |v: &str| v.parse::<f64>().unwrap_or(f64::NAN);
+                        number(&x).total_cmp(&number(&y))
+                    }
+                    (Some(x), Some(y)) => x.cmp(&y),
+                    // Alanı olmayan belgeler her iki yönde de sona
+                    (Some(_), None) => return std::cmp::Ordering::Less,
+                    (None, Some(_)) => return std::cmp::Ordering::Greater,
+                    (None, None) => std::cmp::Ordering::Equal,
+                };
+                let ordering = if ascending {
+                    ordering
+                } else {
+                    ordering.reverse()
+                };
+                ordering.then_with(|| a.key.cmp(&b.key))
+            });
+        }
+        Ok(hits)
+    }
+
+    /// Vektör alanında en yakın komşuları bulur.
+    /// Hibrit filtre önce indekslerden hesaplanır, KNN yalnızca eşleşenler arasında yapılır.
+    fn ft_knn(&self, name: &str, query: &KnnQuery) -> Result<Vec<SearchHit>, String> {
+        let index = self.search_indexes.get(name).ok_or_else(unknown_index)?;
+        let vectors = match index.field(&query.field).map(|f| &f.kind) {
+            Some(FieldKind::Vector(vectors)) => vectors,
+            Some(_) => return Err(format!("Field `{}` is not a VECTOR field", query.field)),
+            None => return Err(format!("Unknown field `{}`", query.field)),
+        };
+
+        let allowed = index.evaluate(&query.filter)?;
+        let filter = |key: &str| allowed.contains(key) && live_hash(self.items.get(key)).is_some();
+        let nearest = vectors.knn(&query.vector, query.k, query.ef_runtime, &filter)?;
+
+        Ok(nearest
+            .into_iter()
+            .filter_map(|(key, distance)| {
+                let hash = live_hash(self.items.get(&key))?;
+                let mut fields = vec![(query.score_field.clone(), distance.to_string())];
+                fields.extend(sorted_fields(hash));
+                Some(SearchHit {
+                    key,
+                    score: distance as f64,
+                    fields,
+                })
+            })
+            .collect())
+    }
+
+    /// Eşleşen hash'leri AGGREGATE planından geçirir.
+    fn ft_aggregate(
+        &self,
+        name: &str,
+        query: &Query,
+        plan: &AggregatePlan,
+    ) -> Result<Vec<Row>, String> {
+        let index = self.search_indexes.get(name).ok_or_else(unknown_index)?;
+        let mut keys: Vec<String> = index.evaluate(query)?.into_iter().collect();
+        keys.sort();
+        let docs: Vec<HashMap<String, String>> = keys
+            .iter()
+            .filter_map(|key| live_hash(self.items.get(key)).cloned())
+            .collect();
+        Ok(plan.execute(&docs))
+    }
+}
@@ -0,0 +1,520 @@
+use crate::db::core::DB;
+use crate::db::stream::{
+    AutoClaim, ClaimOptions, ClaimResult, GroupReadStart, IdSpec, Stream, StreamFields, StreamId,
+    TrimStrategy,
+};
+use crate::db::types::{DataType, Entry};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Okuma sonucu: anahtar başına kayıt listesi.
+pub type StreamReadResult = Vec<(String, Vec<(StreamId, StreamFields)>)>;
+
+/// Grup okuma sonucu. Geçmişten okunan ama silinmiş kayıtların alanları `None` olur.
+pub type GroupReadResult = Vec<(String, Vec<(StreamId, Option<StreamFields>)>)>;
+
+/// XPENDING özet cevabı.
+#[derive(Debug, Clone)]
+pub struct PendingSummary {
+    pub count: usize,
+    pub min: Option<StreamId>,
+    pub max: Option<StreamId>,
+    /// Bekleyen kaydı olan tüketiciler ve kayıt sayıları
+    pub consumers: Vec<(String, usize)>,
+}
+
+/// XPENDING genişletilmiş sorgusu.
+#[derive(Debug, Clone)]
+pub struct PendingQuery {
+    pub start: StreamId,
+    pub end: StreamId,
+    pub count: usize,
+    pub consumer: Option<String>,
+    pub min_idle: Option<u64>,
+}
+
+/// XPENDING genişletilmiş cevabındaki tek bir kayıt.
+#[derive(Debug, Clone)]
+pub struct PendingInfo {
+    pub id: StreamId,
+    pub consumer: String,
+    pub idle: u64,
+    pub delivery_count: u64,
+}
+
+/// Stream veri tipi operasyonları.
+pub trait StreamOps {
+    fn xadd(
+        &mut self,
+        key: String,
+        id: IdSpec,
+        fields: StreamFields,
+        nomkstream: bool,
+        trim: Option<(TrimStrategy, Option<usize>)>,
+    ) -> Result<Option<StreamId>, String>;
+    fn xlen(&mut self, key: String) -> Result<usize, String>;
+    fn xrange(
+        &mut self,
+        key: String,
+        start: StreamId,
+        end: StreamId,
+        count: Option<usize>,
+        rev: bool,
+    ) -> Result<Vec<(StreamId, StreamFields)>, String>;
+    fn xtrim(
+        &mut self,
+        key: String,
+        strategy: TrimStrategy,
+        limit: Option<usize>,
+    ) -> Result<usize, String>;
+    fn xread(
+        &mut self,
+        streams: &[(String, StreamId)],
+        count: Option<usize>,
+    ) -> Result<StreamReadResult, String>;
+    fn xgroup_create(
+        &mut self,
+        key: String,
+        group: String,
+        id: Option<StreamId>,
+        mkstream: bool,
+        entries_read: Option<u64>,
+    ) -> Result<(), String>;
+    fn xgroup_destroy(&mut self, key: String, group: String) -> Result<bool, String>;
+    fn xgroup_createconsumer(
+        &mut self,
+        key: String,
+        group: String,
+        consumer: String,
+    ) -> Result<bool, String>;
+    fn xgroup_delconsumer(
+        &mut self,
+        key: String,
+        group: String,
+        consumer: String,
+    ) -> Result<usize, String>;
+    fn xgroup_setid(
+        &mut self,
+        key: String,
+        group: String,
+        id: Option<StreamId>,
+        entries_read: Option<u64>,
+    ) -> Result<(), String>;
+    fn xreadgroup(
+        &mut self,
+        group: &str,
+        consumer: &str,
+        streams: &[(String, GroupReadStart)],
+        count: Option<usize>,
+        noack: bool,
+    ) -> Result<GroupReadResult, String>;
+    fn xack(&mut self, key: String, group: String, ids: Vec<StreamId>) -> Result<usize, String>;
+    fn xpending(&mut self, key: String, group: String) -> Result<PendingSummary, String>;
+    fn xpending_range(
+        &mut self,
+        key: String,
+        group: String,
+        query: PendingQuery,
+    ) -> Result<Vec<PendingInfo>, String>;
+    fn xclaim(
+        &mut self,
+        key: String,
+        group: String,
+        consumer: String,
+        min_idle: u64,
+        ids: Vec<StreamId>,
+        options: ClaimOptions,
+    ) -> Result<ClaimResult, String>;
+    #[allow(clippy::too_many_arguments)]
+    fn xautoclaim(
+        &mut self,
+        key: String,
+        group: String,
+        consumer: String,
+        min_idle: u64,
+        start: StreamId,
+        count: usize,
+        just_id: bool,
+    ) -> Result<AutoClaim, String>;
+    fn xstream(&mut self, key: &str) -> Result<Option<&Stream>, String>;
+}
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn wrong_type() -> String {
+    "WRONGTYPE Operation against a key holding the wrong kind of value".to_string()
+}
+
+fn no_group(key: &str, group: &str) -> String {
+    format!(
+        "NOGROUP No such key '{}' or consumer group '{}'",
+        key, group
+    )
+}
+
+/// Anahtardaki stream'i değiştirilebilir olarak döndürür.
+fn get_stream_mut<'a>(
+    db: &'a mut DB,
+    key: &str,
+    read: bool,
+) -> Result<Option<&'a mut Stream>, String> {
+    if !db.lookup(key, read) {
+        return Ok(None);
+    }
+
+    match db.items.get_mut(key) {
+        Some(entry) => match &mut entry.value {
+            DataType::Stream(stream) => Ok(Some(stream)),
+            _ => Err(wrong_type()),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Grubu olan bir stream'i döndürür; yoksa NOGROUP hatası verir.
+fn get_group_stream<'a>(db: &'a mut DB, key: &str, group: &str) -> Result<&'a mut Stream, String> {
+    match get_stream_mut(db, key, false)? {
+        Some(stream) if stream.groups.contains_key(group) => Ok(stream),
+        _ => Err(no_group(key, group)),
+    }
+}
+
+impl StreamOps for DB {
+    /// Stream'e kayıt ekler. NOMKSTREAM verildiyse ve anahtar yoksa `None` döner.
+    fn xadd(
+        &mut self,
+        key: String,
+        id: IdSpec,
+        fields: StreamFields,
+        nomkstream: bool,
+        trim: Option<(TrimStrategy, Option<usize>)>,
+    ) -> Result<Option<StreamId>, String> {
+        let created = match get_stream_mut(self, &key, false)? {
+            Some(_) => false,
+            None if nomkstream => return Ok(None),
+            None => {
+                self.items.insert(
+                    key.clone(),
+                    Entry {
+                        value: DataType::Stream(Stream::new()),
+                        expires_at: None,
+                    },
+                );
+                true
+            }
+        };
+
+        let stream = get_stream_mut(self, &key, false)?.expect("stream exists");
+        let new_id = match stream.add(id, fields, now_ms()) {
+            Ok(new_id) => new_id,
+            Err(e) => {
+                if created {
+                    self.items.remove(&key);
+                }
+                return Err(e);
+            }
+        };
+        if let Some((strategy, limit)) = trim {
+            stream.trim(strategy, limit);
+        }
+
+        self.increment_changes();
+        self.notify_stream_event();
+        Ok(Some(new_id))
+    }
+
+    /// Stream'deki kayıt sayısını döndürür.
+    fn xlen(&mut self, key: String) -> Result<usize, String> {
+        Ok(get_stream_mut(self, &key, true)?.map_or(0, |s| s.len()))
+    }
+
+    /// Aralıktaki kayıtları döndürür. `rev` ise XREVRANGE sırası kullanılır.
+    fn xrange(
+        &mut self,
+        key: String,
+        start: StreamId,
+        end: StreamId,
+        count: Option<usize>,
+        rev: bool,
+    ) -> Result<Vec<(StreamId, StreamFields)>, String> {
+        Ok(get_stream_mut(self, &key, true)?
+            .map(|s| s.range(start, end, count, rev))
+            .unwrap_or_default())
+    }
+
+    /// Stream'i kırpar ve silinen kayıt sayısını döndürür.
+    fn xtrim(
+        &mut self,
+        key: String,
+        strategy: TrimStrategy,
+        limit: Option<usize>,
+    ) -> Result<usize, String> {
+        let removed = match get_stream_mut(self, &key, false)? {
+            Some(stream) => stream.trim(strategy, limit),
+            None => 0,
+        };
+        if removed > 0 {
+            self.increment_changes();
+        }
+        Ok(removed)
+    }
+
+    /// Her stream için verilen kimlikten sonraki kayıtları okur.
+    /// Yeni kaydı olmayan stream'ler sonuçta yer almaz.
+    fn xread(
+        &mut self,
+        streams: &[(String, StreamId)],
+        count: Option<usize>,
+    ) -> Result<StreamReadResult, String> {
+        let mut result = Vec::new();
+        for (key, id) in streams {
+            if let Some(stream) = get_stream_mut(self, key, true)? {
+                let entries = stream.read_after(*id, count);
+                if !entries.is_empty() {
+                    result.push((key.clone(), entries));
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Tüketici grubu oluşturur. `id` `None` ise stream'in son kimliği (`$`) kullanılır.
+    fn xgroup_create(
+        &mut self,
+        key: String,
+        group: String,
+        id: Option<StreamId>,
+        mkstream: bool,
+        entries_read: Option<u64>,
+    ) -> Result<(), String> {
+        if get_stream_mut(self, &key, false)?.is_none() {
+            if !mkstream {
+                return Err("The XGROUP subcommand requires the key to exist. Note that for CREATE you may want to use the MKSTREAM option to create an empty stream automatically.".to_string());
+            }
+            self.items.insert(
+                key.clone(),
+                Entry {
+                    value: DataType::Stream(Stream::new()),
+                    expires_at: None,
+                },
+            );
+        }
+
+        let stream = get_stream_mut(self, &key, false)?.expect("stream exists");
+        let last_id = id.unwrap_or(stream.last_id);
+        if !stream.create_group(group, last_id, entries_read) {
+            return Err("BUSYGROUP Consumer Group name already exists".to_string());
+        }
+
+        self.increment_changes();
+        Ok(())
+    }
+
+    /// Tüketici grubunu siler.
+    fn xgroup_destroy(&mut self, key: String, group: String) -> Result<bool, String> {
+        let stream = match get_stream_mut(self, &key, false)? {
+            Some(stream) => stream,
+            None => return Err(no_group(&key, &group)),
+        };
+        let removed = stream.groups.remove(&group).is_some();
+        if removed {
+            self.increment_changes();
+        }
+        Ok(removed)
+    }
+
+    /// Gruba boş bir tüketici ekler.
+    fn xgroup_createconsumer(
+        &mut self,
+        key: String,
+        group: String,
+        consumer: String,
+    ) -> Result<bool, String> {
+        let stream = get_group_stream(self, &key, &group)?;
+        let created = stream
+            .create_consumer(&group, &consumer, now_ms())
+            .unwrap_or(false);
+        if created {
+            self.increment_changes();
+        }
+        Ok(created)
+    }
+
+    /// Tüketiciyi siler ve bekleyen kayıt sayısını döndürür.
+    fn xgroup_delconsumer(
+        &mut self,
+        key: String,
+        group: String,
+        consumer: String,
+    ) -> Result<usize, String> {
+        let stream = get_group_stream(self, &key, &group)?;
+        let pending = stream.delete_consumer(&group, &consumer).unwrap_or(0);
+        self.increment_changes();
+        Ok(pending)
+    }
+
+    /// Grubun son teslim edilen kimliğini değiştirir.
+    fn xgroup_setid(
+        &mut self,
+        key: String,
+        group: String,
+        id: Option<StreamId>,
+        entries_read: Option<u64>,
+    ) -> Result<(), String> {
+        let stream = get_group_stream(self, &key, &group)?;
+        let last_id = id.unwrap_or(stream.last_id);
+        if let Some(g) = stream.groups.get_mut(&group) {
+            g.last_id = last_id;
+            if let Some(entries_read) = entries_read {
+                g.entries_read = entries_read;
+            }
+        }
+        self.increment_changes();
+        Ok(())
+    }
+
+    /// Grup adına okuma yapar. Tüm anahtarlar ve grup önceden doğrulanır.
+    fn xreadgroup(
+        &mut self,
+        group: &str,
+        consumer: &str,
+        streams: &[(String, GroupReadStart)],
+        count: Option<usize>,
+        noack: bool,
+    ) -> Result<GroupReadResult, String> {
+        for (key, _) in streams {
+            if get_group_stream(self, key, group).is_err() {
+                return Err(format!(
+                    "NOGROUP No such key '{}' or consumer group '{}' in XREADGROUP with GROUP option",
+                    key, group
+                ));
+            }
+        }
+
+        let now = now_ms();
+        let mut result = Vec::new();
+        let mut delivered = false;
+        for (key, start) in streams {
+            let stream = get_group_stream(self, key, group)?;
+            let entries = stream
+                .read_group(group, consumer, *start, count, noack, now)
+                .unwrap_or_default();
+
+            // Geçmiş okumaları boş olsa bile cevapta yer alır
+            if *start == GroupReadStart::New && entries.is_empty() {
+                continue;
+            }
+            delivered |= !entries.is_empty();
+            result.push((key.clone(), entries));
+        }
+
+        if delivered {
+            self.increment_changes();
+        }
+        Ok(result)
+    }
+
+    /// Kayıtları onaylar ve onaylanan sayıyı döndürür.
+    fn xack(&mut self, key: String, group: String, ids: Vec<StreamId>) -> Result<usize, String> {
+        let acked = match get_stream_mut(self, &key, false)? {
+            Some(stream) => stream.ack(&group, &ids),
+            None => 0,
+        };
+        if acked > 0 {
+            self.increment_changes();
+        }
+        Ok(acked)
+    }
+
+    /// Grubun bekleyen kayıt özetini döndürür.
+    fn xpending(&mut self, key: String, group: String) -> Result<PendingSummary, String> {
+        let stream = get_group_stream(self, &key, &group)?;
+        let g = stream.group(&group).expect("group exists");
+
+        Ok(PendingSummary {
+            count: g.pel.len(),
+            min: g.pel.keys().next().copied(),
+            max: g.pel.keys().next_back().copied(),
+            consumers: g
+                .consumers
+                .iter()
+                .filter(|(_, c)| !c.pending.is_empty())
+                .map(|(name, c)| (name.clone(), c.pending.len()))
+                .collect(),
+        })
+    }
+
+    /// Bekleyen kayıtları ayrıntılarıyla listeler.
+    fn xpending_range(
+        &mut self,
+        key: String,
+        group: String,
+        query: PendingQuery,
+    ) -> Result<Vec<PendingInfo>, String> {
+        let stream = get_group_stream(self, &key, &group)?;
+        let g = stream.group(&group).expect("group exists");
+        if query.start > query.end {
+            return Ok(Vec::new());
+        }
+
+        let now = now_ms();
+        Ok(g.pel
+            .range(query.start..=query.end)
+            .filter(|(_, p)| query.consumer.as_ref().is_none_or(|c| &p.consumer == c))
+            .map(|(id, p)| PendingInfo {
+                id: *id,
+                consumer: p.consumer.clone(),
+                idle: now.saturating_sub(p.delivery_time),
+                delivery_count: p.delivery_count,
+            })
+            .filter(|info| query.min_idle.is_none_or(|min| info.idle >= min))
+            .take(query.count)
+            .collect())
+    }
+
+    /// Bekleyen kayıtların sahipliğini alır.
+    fn xclaim(
+        &mut self,
+        key: String,
+        group: String,
+        consumer: String,
+        min_idle: u64,
+        ids: Vec<StreamId>,
+        options: ClaimOptions,
+    ) -> Result<ClaimResult, String> {
+        let stream = get_group_stream(self, &key, &group)?;
+        let claimed = stream
+            .claim(&group, &consumer, min_idle, &ids, &options, now_ms())
+            .unwrap_or_default();
+        self.increment_changes();
+        Ok(claimed)
+    }
+
+    /// PEL'i tarayarak boşta kalan kayıtları sahiplenir.
+    fn xautoclaim(
+        &mut self,
+        key: String,
+        group: String,
+        consumer: String,
+        min_idle: u64,
+        start: StreamId,
+        count: usize,
+        just_id: bool,
+    ) -> Result<AutoClaim, String> {
+        let stream = get_group_stream(self, &key, &group)?;
+        let result = stream
+            .auto_claim(&group, &consumer, min_idle, start, count, just_id, now_ms())
+            .ok_or_else(|| no_group(&key, &group))?;
+        self.increment_changes();
+        Ok(result)
+    }
+
+    /// XINFO gibi salt okunur komutlar için stream'in kendisini döndürür.
+    fn xstream(&mut self, key: &str) -> Result<Option<&Stream>, String> {
+        Ok(get_stream_mut(self, key, true)?.map(|s| &*s))
+    }
+}
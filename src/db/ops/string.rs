@@ -1,5 +1,4 @@
 use crate::db::core::DB;
-use crate::db::ops::generic::GenericOps;
 use crate::db::types::{DataType, Entry};
 
 /// String veri tipi operasyonları
@@ -14,7 +13,7 @@ impl StringOps for DB {
     /// String tipindeki bir değeri getirir.
     /// Eğer tip uyuşmazlığı varsa hata döner.
     fn get(&mut self, item: String) -> Result<Option<String>, String> {
-        if !self.check_expiration(&item) {
+        if !self.lookup(&item, true) {
             return Ok(None);
         }
 
@@ -45,9 +44,8 @@ impl StringOps for DB {
     /// Sayısal değeri 1 artırır.
     /// Eğer anahtar yoksa 0 kabul edip artırır.
     fn incr(&mut self, key: String) -> Result<i64, String> {
-        if !self.check_expiration(&key) {
-            // Süresi dolmuşsa silindi zaten
-        }
+        // Süresi dolmuşsa silinir; yazma olduğu için isabet sayılmaz
+        self.lookup(&key, false);
 
         let current_val = if let Some(entry) = self.items.get(&key) {
             match &entry.value {
@@ -83,9 +81,7 @@ impl StringOps for DB {
 
     /// Sayısal değeri 1 azaltır.
     fn decr(&mut self, key: String) -> Result<i64, String> {
-        if !self.check_expiration(&key) {
-            // Expired
-        }
+        self.lookup(&key, false);
 
         let current_val = if let Some(entry) = self.items.get(&key) {
             match &entry.value {
@@ -0,0 +1,230 @@
+use crate::db::core::DB;
+use crate::db::timeseries::{
+    Aggregation, CompactedSample, DuplicatePolicy, LabelFilter, RangeQuery, TimeSeries,
+};
+use crate::db::types::{DataType, Entry};
+
+/// TS.MRANGE sonucundaki tek seri: anahtar, etiketler ve örnekler.
+pub type SeriesRange = (String, Vec<(String, String)>, Vec<(u64, f64)>);
+
+/// Zaman serisi operasyonları.
+pub trait TimeSeriesOps {
+    fn ts_create(&mut self, key: String, series: TimeSeries) -> Result<(), String>;
+    fn ts_add(
+        &mut self,
+        key: String,
+        timestamp: u64,
+        value: f64,
+        on_duplicate: Option<DuplicatePolicy>,
+        template: TimeSeries,
+    ) -> Result<u64, String>;
+    fn ts_get(&mut self, key: &str) -> Result<Option<(u64, f64)>, String>;
+    fn ts_range(
+        &mut self,
+        key: &str,
+        from: u64,
+        to: u64,
+        query: &RangeQuery,
+    ) -> Result<Vec<(u64, f64)>, String>;
+    fn ts_mrange(
+        &mut self,
+        from: u64,
+        to: u64,
+        query: &RangeQuery,
+        filters: &[LabelFilter],
+    ) -> Result<Vec<SeriesRange>, String>;
+    fn ts_createrule(
+        &mut self,
+        source: String,
+        dest: String,
+        aggregation: Aggregation,
+        bucket: u64,
+    ) -> Result<(), String>;
+    fn ts_deleterule(&mut self, source: String, dest: String) -> Result<(), String>;
+    fn ts_info(&mut self, key: &str) -> Result<&TimeSeries, String>;
+}
+
+fn wrong_type() -> String {
+    "WRONGTYPE Operation against a key holding the wrong kind of value".to_string()
+}
+
+fn missing_key() -> String {
+    "TSDB: the key does not exist".to_string()
+}
+
+/// Anahtardaki seriyi döndürür. Anahtar yoksa `None`, tip yanlışsa hata döner.
+fn get_series<'a>(
+    db: &'a mut DB,
+    key: &str,
+    read: bool,
+) -> Result<Option<&'a mut TimeSeries>, String> {
+    if !db.lookup(key, read) {
+        return Ok(None);
+    }
+
+    match db.items.get_mut(key) {
+        Some(Entry {
+            value: DataType::TimeSeries(series),
+            ..
+        }) => Ok(Some(series)),
+        Some(_) => Err(wrong_type()),
+        None => Ok(None),
+    }
+}
+
+/// Sıkıştırma kurallarının ürettiği özetleri hedef serilere yazar.
+/// Hedefin kendi kuralları varsa zincir boyunca devam eder.
+fn apply_compactions(db: &mut DB, mut pending: Vec<CompactedSample>) {
+    while let Some(sample) = pending.pop() {
+        // Hedef silinmiş ya da tipi değişmişse özet sessizce atlanır
+        if let Ok(Some(dest)) = get_series(db, &sample.dest, false) {
+            // Yeniden hesaplanan kova eski özetin üzerine yazılır
+            if let Ok(more) = dest.add(sample.timestamp, sample.value, Some(DuplicatePolicy::Last))
+            {
+                pending.extend(more);
+            }
+        }
+    }
+}
+
+impl TimeSeriesOps for DB {
+    /// Boş bir seri oluşturur.
+    fn ts_create(&mut self, key: String, series: TimeSeries) -> Result<(), String> {
+        if self.lookup(&key, false) && self.items.contains_key(&key) {
+            return Err("TSDB: key already exists".to_string());
+        }
+        self.items.insert(
+            key,
+            Entry {
+                value: DataType::TimeSeries(series),
+                expires_at: None,
+            },
+        );
+        self.increment_changes();
+        Ok(())
+    }
+
+    /// Örnek ekler; seri yoksa `template` ayarlarıyla oluşturulur.
+    /// Sıkıştırma kuralları tetiklenirse hedef seriler de güncellenir.
+    fn ts_add(
+        &mut self,
+        key: String,
+        timestamp: u64,
+        value: f64,
+        on_duplicate: Option<DuplicatePolicy>,
+        template: TimeSeries,
+    ) -> Result<u64, String> {
+        if get_series(self, &key, false)?.is_none() {
+            self.ts_create(key.clone(), template)?;
+        }
+
+        let series = get_series(self, &key, false)?.expect("time series exists");
+        let compacted = series.add(timestamp, value, on_duplicate)?;
+        apply_compactions(self, compacted);
+        self.increment_changes();
+        Ok(timestamp)
+    }
+
+    /// Son örneği döndürür.
+    fn ts_get(&mut self, key: &str) -> Result<Option<(u64, f64)>, String> {
+        let series = get_series(self, key, true)?.ok_or_else(missing_key)?;
+        Ok(series.last())
+    }
+
+    fn ts_range(
+        &mut self,
+        key: &str,
+        from: u64,
+        to: u64,
+        query: &RangeQuery,
+    ) -> Result<Vec<(u64, f64)>, String> {
+        let series = get_series(self, key, true)?.ok_or_else(missing_key)?;
+        Ok(series.range(from, to, query))
+    }
+
+    /// Etiketleri filtrelerle eşleşen tüm serilerin aralığını anahtar sırasıyla döndürür.
+    fn ts_mrange(
+        &mut self,
+        from: u64,
+        to: u64,
+        query: &RangeQuery,
+        filters: &[LabelFilter],
+    ) -> Result<Vec<SeriesRange>, String> {
+        if !filters.iter().any(|f| f.is_matcher()) {
+            return Err("TSDB: please provide at least one matcher".to_string());
+        }
+
+        let mut keys: Vec<String> = self
+            .items
+            .iter()
+            .filter(|(_, entry)| {
+                matches!(&entry.value, DataType::TimeSeries(series) if series.matches(filters))
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.sort();
+
+        let mut result = Vec::with_capacity(keys.len());
+        for key in keys {
+            if let Some(series) = get_series(self, &key, true)? {
+                let samples = series.range(from, to, query);
+                result.push((key, series.labels.clone(), samples));
+            }
+        }
+        Ok(result)
+    }
+
+    /// `source` serisine gelen örneklerin `dest` serisine özetlenmesini sağlar.
+    fn ts_createrule(
+        &mut self,
+        source: String,
+        dest: String,
+        aggregation: Aggregation,
+        bucket: u64,
+    ) -> Result<(), String> {
+        if source == dest {
+            return Err("TSDB: the source key and destination key should be different".to_string());
+        }
+        if bucket == 0 {
+            return Err("TSDB: bucketDuration must be greater than zero".to_string());
+        }
+
+        let dest_series = get_series(self, &dest, false)?.ok_or_else(missing_key)?;
+        if dest_series.source.is_some() {
+            return Err("TSDB: the destination key already has a src rule".to_string());
+        }
+        if !dest_series.rules.is_empty() {
+            return Err("TSDB: the destination key already has a dst rule".to_string());
+        }
+
+        let source_series = get_series(self, &source, false)?.ok_or_else(missing_key)?;
+        if source_series.source.is_some() {
+            return Err("TSDB: the source key already has a src rule".to_string());
+        }
+        source_series.add_rule(dest.clone(), aggregation, bucket);
+
+        get_series(self, &dest, false)?
+            .expect("destination exists")
+            .source = Some(source);
+        self.increment_changes();
+        Ok(())
+    }
+
+    fn ts_deleterule(&mut self, source: String, dest: String) -> Result<(), String> {
+        let source_series = get_series(self, &source, false)?.ok_or_else(missing_key)?;
+        if !source_series.remove_rule(&dest) {
+            return Err("TSDB: compaction rule does not exist".to_string());
+        }
+        if let Some(dest_series) = get_series(self, &dest, false)? {
+            dest_series.source = None;
+        }
+        self.increment_changes();
+        Ok(())
+    }
+
+    fn ts_info(&mut self, key: &str) -> Result<&TimeSeries, String> {
+        get_series(self, key, true)?
+            .map(|s| &*s)
+            .ok_or_else(missing_key)
+    }
+}
@@ -0,0 +1,215 @@
+//! FT.AGGREGATE işlem hattı: eşleşen belgeler gruplanır, özetlenir, sıralanır ve sayfalanır.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Sonuç satırı: sıralı `(alan, değer)` çiftleri.
+pub type Row = Vec<(String, String)>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reducer {
+    Count,
+    CountDistinct(String),
+    Sum(String),
+    Avg(String),
+    Min(String),
+    Max(String),
+}
+
+impl Reducer {
+    /// Redis'in AS verilmediğinde ürettiği ad.
+    pub fn default_alias(&self) -> String {
+        match self {
+            Reducer::Count => "__generated_aliascount".to_string(),
+            Reducer::CountDistinct(field) => format!("__generated_aliascount_distinct{}", field),
+            Reducer::Sum(field) => format!("__generated_aliassum{}", field),
+            Reducer::Avg(field) => format!("__generated_aliasavg{}", field),
+            Reducer::Min(field) => format!("__generated_aliasmin{}", field),
+            Reducer::Max(field) => format!("__generated_aliasmax{}", field),
+        }
+    }
+
+    fn reduce(&self, group: &[&HashMap<String, String>]) -> String {
+        let numbers = |field: &str| -> Vec<f64> {
+            group
+                .iter()
+                .filter_map(|doc| doc.get(field)?.parse::<f64>().ok())
+                .collect()
+        };
+        let value = match self {
+            Reducer::Count => group.len() as f64,
+            Reducer::CountDistinct(field) => group
+                .iter()
+                .filter_map(|doc| doc.get(field))
+                .collect::<HashSet<_>>()
+                .len() as f64,
+            Reducer::Sum(field) => numbers(field).iter().sum(),
+            Reducer::Avg(field) => {
+                let values = numbers(field);
+                if values.is_empty() {
+                    0.0
+                } else {
+                    values.iter().sum::<f64>() / values.len() as f64
+                }
+            }
+            Reducer::Min(field) => numbers(field).into_iter().fold(f64::INFINITY, f64::min),
+            Reducer::Max(field) => numbers(field).into_iter().fold(f64::NEG_INFINITY, f64::max),
+        };
+        value.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupBy {
+    pub fields: Vec<String>,
+    pub reducers: Vec<(Reducer, String)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AggregatePlan {
+    /// Gruplama yoksa satırlara yüklenecek alanlar
+    pub load: Vec<String>,
+    pub group_by: Option<GroupBy>,
+    /// `(alan, artan mı)`
+    pub sort_by: Vec<(String, bool)>,
+    pub limit: Option<(usize, usize)>,
+}
+
+/// İki değer de sayıysa sayısal, değilse metin olarak karşılaştırır.
+fn compare_values(a: Option<&str>, b: Option<&str>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(x), Ok(y)) => x.total_cmp(&y),
+            _ => a.cmp(b),
+        },
+        // Değeri olmayan satırlar sona
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn value_of<'a>(row: &'a Row, field: &str) -> Option<&'a str> {
+    row.iter()
+        .find(|(name, _)| name == field)
+        .map(|(_, value)| value.as_str())
+}
+
+impl AggregatePlan {
+    pub fn execute(&self, docs: &[HashMap<String, String>]) -> Vec<Row> {
+        let mut rows: Vec<Row> = match &self.group_by {
+            None => docs
+                .iter()
+                .map(|doc| {
+                    self.load
+                        .iter()
+                        .filter_map(|field| Some((field.clone(), doc.get(field)?.clone())))
+                        .collect()
+                })
+                .collect(),
+            Some(group_by) => {
+                // Grup anahtarı alan değerlerinin listesidir; eksik alan boş sayılır
+                let mut groups: BTreeMap<Vec<String>, Vec<&HashMap<String, String>>> =
+                    BTreeMap::new();
+                for doc in docs {
+                    let key = group_by
+                        .fields
+                        .iter()
+                        .map(|field| doc.get(field).cloned().unwrap_or_default())
+                        .collect();
+                    groups.entry(key).or_default().push(doc);
+                }
+                groups
+                    .into_iter()
+                    .map(|(key, members)| {
+                        let mut row: Row = group_by.fields.iter().cloned().zip(key).collect();
+                        for (reducer, alias) in &group_by.reducers {
+                            row.push((alias.clone(), reducer.reduce(&members)));
+                        }
+                        row
+                    })
+                    .collect()
+            }
+        };
+
+        if !self.sort_by.is_empty() {
+            rows.sort_by(|a, b| {
+                self.sort_by
+                    .iter()
+                    .map(|(field, ascending)| {
+                        let ordering = compare_values(value_of(a, field), value_of(b, field));
+                        if *ascending {
+                            ordering
+                        } else {
+                            ordering.reverse()
+                        }
+                    })
+                    .find(|ordering| *ordering != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+
+        match self.limit {
+            Some((offset, count)) => rows.into_iter().skip(offset).take(count).collect(),
+            None => rows,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_group_by_with_reducers() {
+        let docs = vec![
+            doc(&[("genre", "drama"), ("rating", "8")]),
+            doc(&[("genre", "drama"), ("rating", "6")]),
+            doc(&[("genre", "comedy"), ("rating", "7")]),
+            doc(&[("rating", "1")]),
+        ];
+        let plan = AggregatePlan {
+            group_by: Some(GroupBy {
+                fields: vec!["genre".to_string()],
+                reducers: vec![
+                    (Reducer::Count, "count".to_string()),
+                    (Reducer::Avg("rating".to_string()), "avg".to_string()),
+                ],
+            }),
+            sort_by: vec![("count".to_string(), false), ("genre".to_string(), true)],
+            limit: Some((0, 2)),
+            ..Default::default()
+        };
+        let rows = plan.execute(&docs);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0],
+            vec![
+                ("genre".to_string(), "drama".to_string()),
+                ("count".to_string(), "2".to_string()),
+                ("avg".to_string(), "7".to_string()),
+            ]
+        );
+        assert_eq!(value_of(&rows[1], "genre"), Some(""));
+    }
+
+    #[test]
+    fn test_load_without_grouping() {
+        let docs = vec![doc(&[("a", "10"), ("b", "x")]), doc(&[("a", "9")])];
+        let plan = AggregatePlan {
+            load: vec!["a".to_string()],
+            sort_by: vec![("a".to_string(), true)],
+            ..Default::default()
+        };
+        let rows = plan.execute(&docs);
+        assert_eq!(rows[0], vec![("a".to_string(), "9".to_string())]);
+        assert_eq!(rows[1], vec![("a".to_string(), "10".to_string())]);
+    }
+}
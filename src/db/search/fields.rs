@@ -0,0 +1,301 @@
+//! Arama indeksinin alan tipleri: TAG, NUMERIC ve TEXT.
+//! Her alan hem ters indeksi hem de belge başına indekslenen değeri tutar;
+//! böylece belge güncellendiğinde eski kayıtlar tam olarak silinebilir.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Metni küçük harfli kelimelere böler.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+}
+
+/// Etiket alanı: ayraçla bölünmüş değerlerin tam eşleşmesi.
+#[derive(Debug, Clone)]
+pub struct TagField {
+    pub separator: char,
+    pub case_sensitive: bool,
+    postings: HashMap<String, HashSet<String>>,
+    docs: HashMap<String, Vec<String>>,
+}
+
+impl TagField {
+    pub const DEFAULT_SEPARATOR: char = ',';
+
+    pub fn new(separator: char, case_sensitive: bool) -> Self {
+        TagField {
+            separator,
+            case_sensitive,
+            postings: HashMap::new(),
+            docs: HashMap::new(),
+        }
+    }
+
+    pub fn normalize(&self, tag: &str) -> String {
+        if self.case_sensitive {
+            tag.trim().to_string()
+        } else {
+            tag.trim().to_lowercase()
+        }
+    }
+
+    pub fn index(&mut self, key: &str, value: Option<&str>) {
+        self.remove(key);
+        let Some(value) = value else {
+            return;
+        };
+        let mut tags: Vec<String> = value
+            .split(self.separator)
+            .map(|tag| self.normalize(tag))
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        for tag in &tags {
+            self.postings
+                .entry(tag.clone())
+                .or_default()
+                .insert(key.to_string());
+        }
+        self.docs.insert(key.to_string(), tags);
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        for tag in self.docs.remove(key).unwrap_or_default() {
+            if let Some(keys) = self.postings.get_mut(&tag) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.postings.remove(&tag);
+                }
+            }
+        }
+    }
+
+    /// Etiketlerden herhangi birine sahip belgeler.
+    pub fn matching(&self, tags: &[String]) -> HashSet<String> {
+        tags.iter()
+            .filter_map(|tag| self.postings.get(&self.normalize(tag)))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+}
+
+/// `f64` değerlerini sıralamayı koruyarak `u64` anahtara çevirir.
+fn sort_key(value: f64) -> u64 {
+    let bits = value.to_bits();
+    if bits >> 63 == 1 {
+        !bits
+    } else {
+        bits | (1 << 63)
+    }
+}
+
+/// Sayısal alan: aralık sorguları için sıralı indeks.
+#[derive(Debug, Clone, Default)]
+pub struct NumericField {
+    values: BTreeMap<u64, HashSet<String>>,
+    docs: HashMap<String, f64>,
+}
+
+impl NumericField {
+    /// Değer sayı değilse belge bu alanda indekslenmez ve hata döner.
+    pub fn index(&mut self, key: &str, value: Option<&str>) -> Result<(), String> {
+        self.remove(key);
+        let Some(value) = value else {
+            return Ok(());
+        };
+        let number = value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|n| !n.is_nan())
+            .ok_or_else(|| format!("'{}' is not a number", value))?;
+        self.values
+            .entry(sort_key(number))
+            .or_default()
+            .insert(key.to_string());
+        self.docs.insert(key.to_string(), number);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        if let Some(number) = self.docs.remove(key) {
+            let slot = sort_key(number);
+            if let Some(keys) = self.values.get_mut(&slot) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.values.remove(&slot);
+                }
+            }
+        }
+    }
+
+    pub fn value(&self, key: &str) -> Option<f64> {
+        self.docs.get(key).copied()
+    }
+
+    pub fn range(
+        &self,
+        min: f64,
+        min_exclusive: bool,
+        max: f64,
+        max_exclusive: bool,
+    ) -> HashSet<String> {
+        if min > max {
+            return HashSet::new();
+        }
+        self.values
+            .range(sort_key(min)..=sort_key(max))
+            .filter(|(slot, _)| {
+                let excluded = (min_exclusive && **slot == sort_key(min))
+                    || (max_exclusive && **slot == sort_key(max));
+                !excluded
+            })
+            .flat_map(|(_, keys)| keys.iter().cloned())
+            .collect()
+    }
+}
+
+/// Metin alanı: kelime -> belge -> geçiş sayısı ters indeksi.
+#[derive(Debug, Clone)]
+pub struct TextField {
+    pub weight: f64,
+    postings: HashMap<String, HashMap<String, u32>>,
+    docs: HashMap<String, Vec<String>>,
+}
+
+impl TextField {
+    pub fn new(weight: f64) -> Self {
+        TextField {
+            weight,
+            postings: HashMap::new(),
+            docs: HashMap::new(),
+        }
+    }
+
+    pub fn index(&mut self, key: &str, value: Option<&str>) {
+        self.remove(key);
+        let Some(value) = value else {
+            return;
+        };
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        for term in tokenize(value) {
+            *frequencies.entry(term).or_default() += 1;
+        }
+        let mut terms = Vec::with_capacity(frequencies.len());
+        for (term, count) in frequencies {
+            self.postings
+                .entry(term.clone())
+                .or_default()
+                .insert(key.to_string(), count);
+            terms.push(term);
+        }
+        self.docs.insert(key.to_string(), terms);
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        for term in self.docs.remove(key).unwrap_or_default() {
+            if let Some(docs) = self.postings.get_mut(&term) {
+                docs.remove(key);
+                if docs.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    /// Sorgu kelimesiyle eşleşen indeks kelimeleri; `prefix` ise önek eşleşmesi yapılır.
+    fn terms<'a>(&'a self, term: &'a str, prefix: bool) -> Vec<&'a str> {
+        if prefix {
+            self.postings
+                .keys()
+                .filter(|t| t.starts_with(term))
+                .map(String::as_str)
+                .collect()
+        } else if self.postings.contains_key(term) {
+            vec![term]
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn matching(&self, term: &str, prefix: bool) -> HashSet<String> {
+        self.terms(term, prefix)
+            .into_iter()
+            .flat_map(|t| self.postings[t].keys().cloned())
+            .collect()
+    }
+
+    /// Eşleşen belgelere TF-IDF puanı ekler.
+    pub fn score(&self, term: &str, prefix: bool, scores: &mut HashMap<String, f64>) {
+        let total = self.docs.len().max(1) as f64;
+        for t in self.terms(term, prefix) {
+            let docs = &self.postings[t];
+            let idf = (1.0 + total / docs.len() as f64).ln();
+            for (key, count) in docs {
+                *scores.entry(key.clone()).or_default() += *count as f64 * idf * self.weight;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_field() {
+        let mut field = TagField::new(',', false);
+        field.index("a", Some("Red, blue"));
+        field.index("b", Some("green"));
+        assert_eq!(field.matching(&["red".to_string()]).len(), 1);
+        assert_eq!(
+            field
+                .matching(&["BLUE".to_string(), "green".to_string()])
+                .len(),
+            2
+        );
+        field.index("a", Some("green"));
+        assert!(field.matching(&["red".to_string()]).is_empty());
+        assert_eq!(field.matching(&["green".to_string()]).len(), 2);
+    }
+
+    #[test]
+    fn test_numeric_field() {
+        let mut field = NumericField::default();
+        for (key, value) in [("a", "-5"), ("b", "0"), ("c", "10"), ("d", "10.5")] {
+            field.index(key, Some(value)).unwrap();
+        }
+        assert!(field.index("e", Some("abc")).is_err());
+        assert_eq!(field.range(-10.0, false, 10.0, false).len(), 3);
+        assert_eq!(field.range(0.0, true, 10.0, true).len(), 0);
+        assert_eq!(
+            field
+                .range(f64::NEG_INFINITY, false, f64::INFINITY, false)
+                .len(),
+            4
+        );
+        field.remove("a");
+        assert_eq!(field.range(-10.0, false, 0.0, false).len(), 1);
+    }
+
+    #[test]
+    fn test_text_field() {
+        let mut field = TextField::new(1.0);
+        field.index("a", Some("Hello world, hello!"));
+        field.index("b", Some("world peace"));
+        assert_eq!(field.matching("world", false).len(), 2);
+        assert_eq!(field.matching("hel", true).len(), 1);
+        assert!(field.matching("hel", false).is_empty());
+
+        let mut scores = HashMap::new();
+        field.score("hello", false, &mut scores);
+        field.score("world", false, &mut scores);
+        assert!(scores["a"] > scores["b"]);
+
+        field.index("a", None);
+        assert!(field.matching("hello", false).is_empty());
+    }
+}
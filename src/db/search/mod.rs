@@ -0,0 +1,360 @@
+//! Hash'ler üzerinde tanımlanan arama indeksleri (FT.*).
+//! İndeks, anahtar önekiyle eşleşen hash'leri izler; hash yazıldıkça güncellenir.
+
+pub mod aggregate;
+pub mod fields;
+pub mod query;
+
+use crate::db::vector::{parse_vector, VectorField};
+use fields::{NumericField, TagField, TextField};
+use query::Query;
+use std::collections::{HashMap, HashSet};
+
+/// İndekslenen alanın tipi.
+#[derive(Debug, Clone)]
+pub enum FieldKind {
+    Text(TextField),
+    Tag(TagField),
+    Numeric(NumericField),
+    Vector(VectorField),
+}
+
+impl FieldKind {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            FieldKind::Text(_) => "TEXT",
+            FieldKind::Tag(_) => "TAG",
+            FieldKind::Numeric(_) => "NUMERIC",
+            FieldKind::Vector(_) => "VECTOR",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldSpec {
+    pub name: String,
+    pub kind: FieldKind,
+}
+
+/// FT.CREATE ile tanımlanan indeks.
+#[derive(Debug, Clone)]
+pub struct SearchIndex {
+    pub name: String,
+    /// Boşsa tüm anahtarlar
+    pub prefixes: Vec<String>,
+    pub fields: Vec<FieldSpec>,
+    docs: HashSet<String>,
+    pub indexing_failures: u64,
+}
+
+impl SearchIndex {
+    pub fn new(name: String, prefixes: Vec<String>, fields: Vec<FieldSpec>) -> Self {
+        SearchIndex {
+            name,
+            prefixes,
+            fields,
+            docs: HashSet::new(),
+            indexing_failures: 0,
+        }
+    }
+
+    pub fn num_docs(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn covers(&self, key: &str) -> bool {
+        self.prefixes.is_empty() || self.prefixes.iter().any(|p| key.starts_with(p.as_str()))
+    }
+
+    pub fn field(&self, name: &str) -> Option<&FieldSpec> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    fn field_kind(&self, name: &str) -> Result<&FieldKind, String> {
+        self.field(name)
+            .map(|f| &f.kind)
+            .ok_or_else(|| format!("Unknown field `{}`", name))
+    }
+
+    /// Anahtarın indeks kayıtlarını güncel hash'e göre yeniler.
+    /// `hash` `None` ise anahtar silinmiş ya da artık hash değildir.
+    pub fn update(&mut self, key: &str, hash: Option<&HashMap<String, String>>) {
+        if !self.covers(key) {
+            return;
+        }
+        let Some(hash) = hash else {
+            if self.docs.remove(key) {
+                for field in &mut self.fields {
+                    match &mut field.kind {
+                        FieldKind::Text(text) => text.remove(key),
+                        FieldKind::Tag(tags) => tags.remove(key),
+                        FieldKind::Numeric(numbers) => numbers.remove(key),
+                        FieldKind::Vector(vectors) => vectors.remove(key),
+                    }
+                }
+            }
+            return;
+        };
+
+        self.docs.insert(key.to_string());
+        for field in &mut self.fields {
+            let value = hash.get(&field.name).map(String::as_str);
+            match &mut field.kind {
+                FieldKind::Text(text) => text.index(key, value),
+                FieldKind::Tag(tags) => tags.index(key, value),
+                FieldKind::Numeric(numbers) => {
+                    if numbers.index(key, value).is_err() {
+                        self.indexing_failures += 1;
+                    }
+                }
+                FieldKind::Vector(vectors) => match value.map(parse_vector) {
+                    Some(Ok(vector)) if vector.len() == vectors.dim => vectors.upsert(key, vector),
+                    Some(_) => {
+                        self.indexing_failures += 1;
+                        vectors.remove(key);
+                    }
+                    None => vectors.remove(key),
+                },
+            }
+        }
+    }
+
+    /// Sorguyla eşleşen belgeleri indekslerden hesaplar.
+    pub fn evaluate(&self, query: &Query) -> Result<HashSet<String>, String> {
+        match query {
+            Query::All => Ok(self.docs.clone()),
+            Query::Text {
+                field: Some(field),
+                term,
+                prefix,
+            } => match self.field_kind(field)? {
+                FieldKind::Text(text) => Ok(text.matching(term, *prefix)),
+                _ => Err(format!("Field `{}` is not a TEXT field", field)),
+            },
+            Query::Text {
+                field: None,
+                term,
+                prefix,
+            } => Ok(self
+                .fields
+                .iter()
+                .filter_map(|f| match &f.kind {
+                    FieldKind::Text(text) => Some(text.matching(term, *prefix)),
+                    _ => None,
+                })
+                .flatten()
+                .collect()),
+            Query::Tag { field, values } => match self.field_kind(field)? {
+                FieldKind::Tag(tags) => Ok(tags.matching(values)),
+                _ => Err(format!("Field `{}` is not a TAG field", field)),
+            },
+            Query::Numeric {
+                field,
+                min,
+                min_exclusive,
+                max,
+                max_exclusive,
+            } => match self.field_kind(field)? {
+                FieldKind::Numeric(numbers) => {
+                    Ok(numbers.range(*min, *min_exclusive, *max, *max_exclusive))
+                }
+                _ => Err(format!("Field `{}` is not a NUMERIC field", field)),
+            },
+            Query::And(queries) => {
+                let mut sets = queries
+                    .iter()
+                    .map(|q| self.evaluate(q))
+                    .collect::<Result<Vec<_>, _>>()?;
+                // En küçük kümeden başlayarak kesişim alınır
+                sets.sort_by_key(|s| s.len());
+                let mut sets = sets.into_iter();
+                let first = sets.next().unwrap_or_default();
+                Ok(sets.fold(first, |acc, set| {
+                    acc.into_iter().filter(|key| set.contains(key)).collect()
+                }))
+            }
+            Query::Or(queries) => {
+                let mut result = HashSet::new();
+                for q in queries {
+                    result.extend(self.evaluate(q)?);
+                }
+                Ok(result)
+            }
+            Query::Not(inner) => {
+                let excluded = self.evaluate(inner)?;
+                Ok(self
+                    .docs
+                    .iter()
+                    .filter(|key| !excluded.contains(*key))
+                    .cloned()
+                    .collect())
+            }
+        }
+    }
+
+    /// Olumsuzlanmamış metin kelimelerinden TF-IDF puanlarını toplar.
+    fn score_into(&self, query: &Query, scores: &mut HashMap<String, f64>) {
+        match query {
+            Query::Text {
+                field,
+                term,
+                prefix,
+            } => {
+                for spec in &self.fields {
+                    if let FieldKind::Text(text) = &spec.kind {
+                        if field.as_ref().is_none_or(|f| *f == spec.name) {
+                            text.score(term, *prefix, scores);
+                        }
+                    }
+                }
+            }
+            Query::And(queries) | Query::Or(queries) => {
+                for q in queries {
+                    self.score_into(q, scores);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Eşleşen belgeleri puanlarıyla, azalan puan sırasında döndürür.
+    pub fn search(&self, query: &Query) -> Result<Vec<(String, f64)>, String> {
+        let matches = self.evaluate(query)?;
+        let mut scores = HashMap::new();
+        self.score_into(query, &mut scores);
+
+        let mut results: Vec<(String, f64)> = matches
+            .into_iter()
+            .map(|key| {
+                let score = scores.get(&key).copied().unwrap_or(0.0);
+                (key, score)
+            })
+            .collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::query::parse_query;
+    use super::*;
+    use crate::db::vector::DistanceMetric;
+
+    fn hash(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn field(name: &str, kind: FieldKind) -> FieldSpec {
+        FieldSpec {
+            name: name.to_string(),
+            kind,
+        }
+    }
+
+    fn movies() -> SearchIndex {
+        let mut index = SearchIndex::new(
+            "movies".to_string(),
+            vec!["movie:".to_string()],
+            vec![
+                field("title", FieldKind::Text(TextField::new(1.0))),
+                field("genre", FieldKind::Tag(TagField::new(',', false))),
+                field("year", FieldKind::Numeric(NumericField::default())),
+            ],
+        );
+        let docs = [
+            ("movie:1", "The Dark Knight", "action,drama", "2008"),
+            ("movie:2", "Dark Waters", "drama", "2019"),
+            ("movie:3", "Knight and Day", "action,comedy", "2010"),
+            ("other:1", "Dark", "drama", "2000"),
+        ];
+        for (key, title, genre, year) in docs {
+            index.update(
+                key,
+                Some(&hash(&[("title", title), ("genre", genre), ("year", year)])),
+            );
+        }
+        index
+    }
+
+    fn keys(index: &SearchIndex, query: &str) -> Vec<String> {
+        let query = parse_query(query, &HashMap::new()).unwrap();
+        let mut keys: Vec<String> = index.evaluate(&query).unwrap().into_iter().collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_boolean_queries() {
+        let index = movies();
+        assert_eq!(keys(&index, "dark"), vec!["movie:1", "movie:2"]);
+        assert_eq!(keys(&index, "dark knight"), vec!["movie:1"]);
+        assert_eq!(
+            keys(&index, "dark | day"),
+            vec!["movie:1", "movie:2", "movie:3"]
+        );
+        assert_eq!(keys(&index, "knight -dark"), vec!["movie:3"]);
+        assert_eq!(keys(&index, "@genre:{Drama}"), vec!["movie:1", "movie:2"]);
+        assert_eq!(
+            keys(&index, "@year:[2009 +inf]"),
+            vec!["movie:2", "movie:3"]
+        );
+        assert_eq!(
+            keys(&index, "@title:kni* @year:[-inf (2010]"),
+            vec!["movie:1"]
+        );
+        assert_eq!(keys(&index, "*").len(), 3);
+
+        let unknown = parse_query("@rating:[1 2]", &HashMap::new()).unwrap();
+        assert!(index.evaluate(&unknown).is_err());
+        let wrong_type = parse_query("@year:{2008}", &HashMap::new()).unwrap();
+        assert!(index.evaluate(&wrong_type).is_err());
+    }
+
+    #[test]
+    fn test_incremental_updates_and_scores() {
+        let mut index = movies();
+        index.update(
+            "movie:2",
+            Some(&hash(&[
+                ("title", "Waters"),
+                ("genre", "drama"),
+                ("year", "x"),
+            ])),
+        );
+        assert_eq!(keys(&index, "dark"), vec!["movie:1"]);
+        assert_eq!(index.indexing_failures, 1);
+
+        index.update("movie:1", None);
+        assert!(keys(&index, "dark").is_empty());
+        assert_eq!(index.num_docs(), 2);
+
+        index.update("movie:4", Some(&hash(&[("title", "Knight Knight Knight")])));
+        let query = parse_query("knight", &HashMap::new()).unwrap();
+        let results = index.search(&query).unwrap();
+        assert_eq!(results[0].0, "movie:4");
+        assert!(results[0].1 > results[1].1);
+    }
+
+    #[test]
+    fn test_vector_field_update() {
+        let mut index = SearchIndex::new(
+            "idx".to_string(),
+            vec!["doc:".to_string()],
+            vec![field(
+                "v",
+                FieldKind::Vector(VectorField::flat(2, DistanceMetric::L2)),
+            )],
+        );
+        index.update("doc:1", Some(&hash(&[("v", "1,1")])));
+        index.update("doc:2", Some(&hash(&[("v", "1,1,1")])));
+        assert_eq!(index.indexing_failures, 1);
+        let FieldKind::Vector(vectors) = &index.fields[0].kind else {
+            unreachable!()
+        };
+        assert_eq!(vectors.len(), 1);
+    }
+}
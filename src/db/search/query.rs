@@ -0,0 +1,437 @@
+//! FT.SEARCH sorgu dili.
+//!
+//! ```text
+//! sorgu  := ve ('|' ve)*
+//! ve     := tekli+                  (boşlukla ayrılmış koşulların hepsi)
+//! tekli  := '-' tekli | öğe
+//! öğe    := '(' sorgu ')' | '*' | kelime | kelime* | "kelime kelime"
+//!         | '@alan:' ( '{a|b}' | '[min max]' | '(' sorgu ')' | kelime )
+//! ```
+//! Tırnak içindeki kelimeler konumlarına bakılmadan birlikte aranır.
+
+use crate::db::vector::parse_vector;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    /// `*`, indeksteki tüm belgeler
+    All,
+    /// Metin kelimesi; alan verilmezse tüm TEXT alanlarında aranır
+    Text {
+        field: Option<String>,
+        term: String,
+        prefix: bool,
+    },
+    /// `@alan:{a|b}`
+    Tag {
+        field: String,
+        values: Vec<String>,
+    },
+    /// `@alan:[min max]`; `(` sınırı dışarıda bırakır
+    Numeric {
+        field: String,
+        min: f64,
+        min_exclusive: bool,
+        max: f64,
+        max_exclusive: bool,
+    },
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    Not(Box<Query>),
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    params: &'a HashMap<String, String>,
+    /// `@alan:(...)` içindeki kelimelerin ait olduğu alan
+    field: Option<String>,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self) -> String {
+        format!(
+            "Syntax error at offset {} near '{}'",
+            self.pos,
+            &self.input[self.pos..]
+        )
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn take_until(&mut self, end: char) -> Result<&'a str, String> {
+        let rest = self.rest();
+        let len = rest.find(end).ok_or_else(|| self.error())?;
+        self.pos += len + end.len_utf8();
+        Ok(&rest[..len])
+    }
+
+    fn take_word(&mut self) -> &'a str {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$' || c == '.'))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// `$ad` biçimindeki parametreyi çözer.
+    fn resolve(&self, token: &str) -> Result<String, String> {
+        match token.strip_prefix('$') {
+            Some(name) => self
+                .params
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("No such parameter `{}`", name)),
+            None => Ok(token.to_string()),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Query, String> {
+        let mut branches = vec![self.parse_and()?];
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('|') {
+                break;
+            }
+            self.pos += 1;
+            branches.push(self.parse_and()?);
+        }
+        Ok(if branches.len() == 1 {
+            branches.remove(0)
+        } else {
+            Query::Or(branches)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Query, String> {
+        let mut terms = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None | Some(')') | Some('|') => break,
+                _ => terms.push(self.parse_unary()?),
+            }
+        }
+        match terms.len() {
+            0 => Err(self.error()),
+            1 => Ok(terms.remove(0)),
+            _ => Ok(Query::And(terms)),
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Query, String> {
+        if self.peek() == Some('-') {
+            self.pos += 1;
+            self.skip_whitespace();
+            return Ok(Query::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_atom()
+    }
+
+    fn parse_atom(&mut self) -> Result<Query, String> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let query = self.parse_or()?;
+                self.expect(')')?;
+                Ok(query)
+            }
+            Some('*') => {
+                self.pos += 1;
+                Ok(Query::All)
+            }
+            Some('@') => {
+                self.pos += 1;
+                let field = self.take_until(':')?.trim().to_string();
+                self.parse_field(field)
+            }
+            Some('"') => {
+                self.pos += 1;
+                let phrase = self.take_until('"')?;
+                let terms: Vec<Query> = crate::db::search::fields::tokenize(phrase)
+                    .map(|term| Query::Text {
+                        field: self.field.clone(),
+                        term,
+                        prefix: false,
+                    })
+                    .collect();
+                match terms.len() {
+                    0 => Err(self.error()),
+                    1 => Ok(terms.into_iter().next().expect("one term")),
+                    _ => Ok(Query::And(terms)),
+                }
+            }
+            _ => self.parse_term(),
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<Query, String> {
+        let word = self.take_word();
+        if word.is_empty() {
+            return Err(self.error());
+        }
+        let prefix = self.peek() == Some('*');
+        if prefix {
+            self.pos += 1;
+        }
+        Ok(Query::Text {
+            field: self.field.clone(),
+            term: self.resolve(word)?.to_lowercase(),
+            prefix,
+        })
+    }
+
+    fn parse_bound(&self, token: &str) -> Result<(f64, bool), String> {
+        let token = self.resolve(token)?;
+        let (value, exclusive) = match token.strip_prefix('(') {
+            Some(rest) => (rest.to_string(), true),
+            None => (token, false),
+        };
+        let number = match value.to_lowercase().as_str() {
+            "-inf" => f64::NEG_INFINITY,
+            "+inf" | "inf" => f64::INFINITY,
+            _ => value
+                .parse::<f64>()
+                .map_err(|_| format!("Expected a number but got '{}'", value))?,
+        };
+        Ok((number, exclusive))
+    }
+
+    fn parse_field(&mut self, field: String) -> Result<Query, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                let values = self
+                    .take_until('}')?
+                    .split('|')
+                    .map(|v| self.resolve(v.trim()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Query::Tag { field, values })
+            }
+            Some('[') => {
+                self.pos += 1;
+                let bounds: Vec<&str> = self.take_until(']')?.split_whitespace().collect();
+                let [min, max] = bounds[..] else {
+                    return Err(self.error());
+                };
+                let (min, min_exclusive) = self.parse_bound(min)?;
+                let (max, max_exclusive) = self.parse_bound(max)?;
+                Ok(Query::Numeric {
+                    field,
+                    min,
+                    min_exclusive,
+                    max,
+                    max_exclusive,
+                })
+            }
+            _ => {
+                let outer = self.field.replace(field);
+                let query = self.parse_unary();
+                self.field = outer;
+                query
+            }
+        }
+    }
+}
+
+/// Sorgu metnini ayrıştırır; `$param` referansları `params` içinden çözülür.
+pub fn parse_query(input: &str, params: &HashMap<String, String>) -> Result<Query, String> {
+    let mut parser = Parser {
+        input,
+        pos: 0,
+        params,
+        field: None,
+    };
+    parser.skip_whitespace();
+    if parser.pos == input.len() {
+        return Err("Syntax error: empty query".to_string());
+    }
+    let query = parser.parse_or()?;
+    parser.skip_whitespace();
+    if parser.pos != input.len() {
+        return Err(parser.error());
+    }
+    Ok(query)
+}
+
+/// `filtre=>[KNN k @alan $param [EF_RUNTIME n] [AS takma_ad]]` sorgusu.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnnQuery {
+    pub filter: Query,
+    pub k: usize,
+    pub field: String,
+    pub vector: Vec<f32>,
+    pub ef_runtime: Option<usize>,
+    /// Uzaklığın döndürüleceği alanın adı
+    pub score_field: String,
+}
+
+impl KnnQuery {
+    /// Sorguda `=>` yoksa `None` döner.
+    pub fn parse(
+        query: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<Option<KnnQuery>, String> {
+        let Some((filter, knn)) = query.split_once("=>") else {
+            return Ok(None);
+        };
+        let knn = knn
+            .trim()
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+            .ok_or_else(|| "Syntax error: expected '[KNN ...]'".to_string())?;
+
+        let resolve = |token: &str| -> Result<String, String> {
+            match token.strip_prefix('$') {
+                Some(name) => params
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| format!("No such parameter `{}`", name)),
+                None => Ok(token.to_string()),
+            }
+        };
+
+        let tokens: Vec<&str> = knn.split_whitespace().collect();
+        let [knn_keyword, k, field, vector, options @ ..] = &tokens[..] else {
+            return Err("Syntax error: expected 'KNN k @field $vector'".to_string());
+        };
+        if !knn_keyword.eq_ignore_ascii_case("KNN") {
+            return Err("Syntax error: expected 'KNN k @field $vector'".to_string());
+        }
+        let k = resolve(k)?
+            .parse::<usize>()
+            .map_err(|_| "Syntax error: KNN k must be a positive integer".to_string())?;
+        let field = field
+            .strip_prefix('@')
+            .ok_or_else(|| "Syntax error: expected '@field' after KNN k".to_string())?
+            .to_string();
+        let vector = parse_vector(&resolve(vector)?)?;
+
+        let mut ef_runtime = None;
+        let mut score_field = format!("__{}_score", field);
+        for pair in options.chunks(2) {
+            match pair {
+                [option, value] if option.eq_ignore_ascii_case("EF_RUNTIME") => {
+                    ef_runtime = Some(
+                        resolve(value)?
+                            .parse::<usize>()
+                            .map_err(|_| "Syntax error: invalid EF_RUNTIME".to_string())?,
+                    );
+                }
+                [option, value] if option.eq_ignore_ascii_case("AS") => {
+                    score_field = value.to_string();
+                }
+                _ => return Err("Syntax error in KNN clause".to_string()),
+            }
+        }
+
+        Ok(Some(KnnQuery {
+            filter: parse_query(filter, params)?,
+            k,
+            field,
+            vector,
+            ef_runtime,
+            score_field,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(term: &str) -> Query {
+        Query::Text {
+            field: None,
+            term: term.to_string(),
+            prefix: false,
+        }
+    }
+
+    #[test]
+    fn test_boolean_structure() {
+        let params = HashMap::new();
+        let query = parse_query("hello (world | -peace) @genre:{Drama|horror}", &params).unwrap();
+        assert_eq!(
+            query,
+            Query::And(vec![
+                text("hello"),
+                Query::Or(vec![text("world"), Query::Not(Box::new(text("peace")))]),
+                Query::Tag {
+                    field: "genre".to_string(),
+                    values: vec!["Drama".to_string(), "horror".to_string()]
+                },
+            ])
+        );
+        assert!(parse_query("(hello", &params).is_err());
+        assert!(parse_query("", &params).is_err());
+        assert!(parse_query("@year:[1]", &params).is_err());
+    }
+
+    #[test]
+    fn test_fields_prefixes_and_params() {
+        let params: HashMap<String, String> = [("lo".to_string(), "2000".to_string())].into();
+        let query = parse_query("@title:(hel* world) @year:[$lo (2010]", &params).unwrap();
+        assert_eq!(
+            query,
+            Query::And(vec![
+                Query::And(vec![
+                    Query::Text {
+                        field: Some("title".to_string()),
+                        term: "hel".to_string(),
+                        prefix: true
+                    },
+                    Query::Text {
+                        field: Some("title".to_string()),
+                        term: "world".to_string(),
+                        prefix: false
+                    },
+                ]),
+                Query::Numeric {
+                    field: "year".to_string(),
+                    min: 2000.0,
+                    min_exclusive: false,
+                    max: 2010.0,
+                    max_exclusive: true,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_knn_query() {
+        let params: HashMap<String, String> = [("vec".to_string(), "1,2,3".to_string())].into();
+        let query = KnnQuery::parse("@tag:{a}=>[KNN 5 @embedding $vec AS dist]", &params)
+            .unwrap()
+            .unwrap();
+        assert_eq!(query.k, 5);
+        assert_eq!(query.field, "embedding");
+        assert_eq!(query.vector, vec![1.0, 2.0, 3.0]);
+        assert_eq!(query.score_field, "dist");
+        assert!(KnnQuery::parse("*=>[KNN 5 @embedding $missing]", &params).is_err());
+        assert_eq!(KnnQuery::parse("@tag:{a}", &params), Ok(None));
+    }
+}
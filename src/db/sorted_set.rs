@@ -0,0 +1,97 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+
+/// Skor sırasına göre tutulan üye kümesi.
+/// Üye -> skor eşlemesi ile (skor, üye) sıralı indeksi birlikte tutulur,
+/// böylece hem üye sorgusu hem de skor aralığı taraması hızlıdır.
+#[derive(Clone, Debug, Default)]
+pub struct SortedSet {
+    scores: HashMap<String, f64>,
+    index: BTreeSet<(Score, String)>,
+}
+
+/// `f64` için toplam sıralama sağlayan sarmalayıcı.
+#[derive(Clone, Copy, Debug)]
+struct Score(f64);
+
+impl PartialEq for Score {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.total_cmp(&other.0) == Ordering::Equal
+    }
+}
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl SortedSet {
+    pub fn new() -> Self {
+        SortedSet::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Üyenin skorunu döndürür.
+    pub fn score(&self, member: &str) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    /// Üyeyi ekler veya skorunu günceller.
+    /// Üye yeni eklendiyse `true` döner.
+    pub fn insert(&mut self, member: String, score: f64) -> bool {
+        match self.scores.insert(member.clone(), score) {
+            Some(old) => {
+                self.index.remove(&(Score(old), member.clone()));
+                self.index.insert((Score(score), member));
+                false
+            }
+            None => {
+                self.index.insert((Score(score), member));
+                true
+            }
+        }
+    }
+
+    /// Üyeyi siler. Üye varsa `true` döner.
+    pub fn remove(&mut self, member: &str) -> bool {
+        match self.scores.remove(member) {
+            Some(score) => {
+                self.index.remove(&(Score(score), member.to_string()));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Skoru `[min, max)` aralığındaki üyeleri skor sırasıyla döndürür.
+    pub fn range_by_score(&self, min: f64, max: f64) -> impl Iterator<Item = (&str, f64)> {
+        // BTreeSet::range ters aralıkta panik yapar
+        let max = if max < min { min } else { max };
+        self.index
+            .range((Score(min), String::new())..(Score(max), String::new()))
+            .map(|(score, member)| (member.as_str(), score.0))
+    }
+
+    /// Tüm üyeleri skor sırasıyla döndürür.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.index
+            .iter()
+            .map(|(score, member)| (member.as_str(), score.0))
+    }
+}
@@ -0,0 +1,771 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{btree_map, BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Stream kayıt kimliği: `<milisaniye>-<sıra>`.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct StreamId {
+    pub ms: u64,
+    pub seq: u64,
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.ms, self.seq)
+    }
+}
+
+const INVALID_ID: &str = "Invalid stream ID specified as stream command argument";
+const ID_TOO_SMALL: &str =
+    "The ID specified in XADD is equal or smaller than the target stream top item";
+
+impl StreamId {
+    pub const MIN: StreamId = StreamId { ms: 0, seq: 0 };
+    pub const MAX: StreamId = StreamId {
+        ms: u64::MAX,
+        seq: u64::MAX,
+    };
+
+    pub fn new(ms: u64, seq: u64) -> Self {
+        StreamId { ms, seq }
+    }
+
+    /// `ms-seq` veya `ms` biçimindeki kimliği ayrıştırır.
+    /// Sıra verilmezse `default_seq` kullanılır.
+    pub fn parse_with_default(s: &str, default_seq: u64) -> Result<StreamId, String> {
+        let (ms, seq) = match s.split_once('-') {
+            Some((ms, seq)) => (ms, Some(seq)),
+            None => (s, None),
+        };
+        let ms = ms.parse::<u64>().map_err(|_| INVALID_ID.to_string())?;
+        let seq = match seq {
+            Some(seq) => seq.parse::<u64>().map_err(|_| INVALID_ID.to_string())?,
+            None => default_seq,
+        };
+        Ok(StreamId { ms, seq })
+    }
+
+    pub fn parse(s: &str) -> Result<StreamId, String> {
+        Self::parse_with_default(s, 0)
+    }
+
+    /// Aralık başlangıcı: `-`, `ms`, `ms-seq` veya hariç tutmak için `(ms-seq`.
+    pub fn parse_range_start(s: &str) -> Result<StreamId, String> {
+        if s == "-" {
+            return Ok(StreamId::MIN);
+        }
+        if let Some(id) = s.strip_prefix('(') {
+            return Self::parse_with_default(id, 0)?
+                .next()
+                .ok_or_else(|| "invalid start ID for the interval".to_string());
+        }
+        Self::parse_with_default(s, 0)
+    }
+
+    /// Aralık sonu: `+`, `ms`, `ms-seq` veya hariç tutmak için `(ms-seq`.
+    pub fn parse_range_end(s: &str) -> Result<StreamId, String> {
+        if s == "+" {
+            return Ok(StreamId::MAX);
+        }
+        if let Some(id) = s.strip_prefix('(') {
+            return Self::parse_with_default(id, u64::MAX)?
+                .prev()
+                .ok_or_else(|| "invalid end ID for the interval".to_string());
+        }
+        Self::parse_with_default(s, u64::MAX)
+    }
+
+    /// Bir sonraki olası kimlik.
+    pub fn next(&self) -> Option<StreamId> {
+        if self.seq < u64::MAX {
+            Some(StreamId::new(self.ms, self.seq + 1))
+        } else if self.ms < u64::MAX {
+            Some(StreamId::new(self.ms + 1, 0))
+        } else {
+            None
+        }
+    }
+
+    /// Bir önceki olası kimlik.
+    pub fn prev(&self) -> Option<StreamId> {
+        if self.seq > 0 {
+            Some(StreamId::new(self.ms, self.seq - 1))
+        } else if self.ms > 0 {
+            Some(StreamId::new(self.ms - 1, u64::MAX))
+        } else {
+            None
+        }
+    }
+}
+
+/// XADD'de kimliğin nasıl belirleneceği.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IdSpec {
+    /// `*`: zaman ve sıra otomatik
+    Auto,
+    /// `ms-*`: sıra otomatik
+    AutoSeq(u64),
+    /// Tam kimlik
+    Explicit(StreamId),
+}
+
+impl IdSpec {
+    pub fn parse(s: &str) -> Result<IdSpec, String> {
+        if s == "*" {
+            return Ok(IdSpec::Auto);
+        }
+        if let Some(ms) = s.strip_suffix("-*") {
+            let ms = ms.parse::<u64>().map_err(|_| INVALID_ID.to_string())?;
+            return Ok(IdSpec::AutoSeq(ms));
+        }
+        StreamId::parse(s).map(IdSpec::Explicit)
+    }
+}
+
+/// Kırpma stratejisi (MAXLEN veya MINID).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrimStrategy {
+    MaxLen(usize),
+    MinId(StreamId),
+}
+
+pub type StreamFields = Vec<(String, String)>;
+
+/// Henüz onaylanmamış (XACK) teslimat kaydı.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingEntry {
+    pub consumer: String,
+    /// Son teslim zamanı (unix ms)
+    pub delivery_time: u64,
+    pub delivery_count: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Consumer {
+    /// Son etkileşim zamanı (unix ms)
+    pub seen_time: u64,
+    /// Son başarılı okuma zamanı (unix ms)
+    pub active_time: Option<u64>,
+    pub pending: BTreeSet<StreamId>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConsumerGroup {
+    /// Gruba en son teslim edilen kimlik
+    pub last_id: StreamId,
+    pub entries_read: u64,
+    /// Grubun bekleyen kayıt listesi (PEL)
+    pub pel: BTreeMap<StreamId, PendingEntry>,
+    pub consumers: BTreeMap<String, Consumer>,
+}
+
+/// XREADGROUP'un nereden okuyacağı.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupReadStart {
+    /// `>`: hiç teslim edilmemiş yeni kayıtlar
+    New,
+    /// Tüketicinin bekleyen listesinden, verilen kimlikten sonrası
+    History(StreamId),
+}
+
+/// XCLAIM seçenekleri.
+#[derive(Debug, Clone, Default)]
+pub struct ClaimOptions {
+    pub idle: Option<u64>,
+    pub time: Option<u64>,
+    pub retry_count: Option<u64>,
+    pub force: bool,
+    pub just_id: bool,
+    pub last_id: Option<StreamId>,
+}
+
+/// XCLAIM sonucu. Stream'den silinmiş kayıtlar PEL'den çıkarılıp `deleted` içinde döner.
+#[derive(Debug, Clone, Default)]
+pub struct ClaimResult {
+    pub claimed: Vec<(StreamId, StreamFields)>,
+    pub deleted: Vec<StreamId>,
+}
+
+/// XAUTOCLAIM sonucu.
+#[derive(Debug, Clone)]
+pub struct AutoClaim {
+    pub next: StreamId,
+    pub claimed: Vec<(StreamId, StreamFields)>,
+    pub deleted: Vec<StreamId>,
+}
+
+/// Yalnızca eklemeye açık, kimlik sıralı kayıt günlüğü.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stream {
+    pub entries: BTreeMap<StreamId, StreamFields>,
+    pub last_id: StreamId,
+    /// Stream'e şimdiye kadar eklenen toplam kayıt
+    pub entries_added: u64,
+    pub groups: BTreeMap<String, ConsumerGroup>,
+}
+
+impl Stream {
+    pub fn new() -> Self {
+        Stream::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn first_entry(&self) -> Option<(StreamId, &StreamFields)> {
+        self.entries.iter().next().map(|(id, f)| (*id, f))
+    }
+
+    pub fn last_entry(&self) -> Option<(StreamId, &StreamFields)> {
+        self.entries.iter().next_back().map(|(id, f)| (*id, f))
+    }
+
+    /// Yeni kaydın kimliğini belirler. Kimlik son kimlikten büyük olmalıdır.
+    fn next_id(&self, spec: IdSpec, now_ms: u64) -> Result<StreamId, String> {
+        let last = self.last_id;
+        let id = match spec {
+            IdSpec::Auto => {
+                if now_ms > last.ms {
+                    StreamId::new(now_ms, 0)
+                } else {
+                    last.next().ok_or_else(|| {
+                        "The stream has exhausted the last possible ID, unable to add more items"
+                            .to_string()
+                    })?
+                }
+            }
+            IdSpec::AutoSeq(ms) => {
+                if ms < last.ms {
+                    return Err(ID_TOO_SMALL.to_string());
+                }
+                if ms == last.ms {
+                    if last.seq == u64::MAX {
+                        return Err(ID_TOO_SMALL.to_string());
+                    }
+                    StreamId::new(ms, last.seq + 1)
+                } else {
+                    StreamId::new(ms, 0)
+                }
+            }
+            IdSpec::Explicit(id) => {
+                if id == StreamId::MIN {
+                    return Err("The ID specified in XADD must be greater than 0-0".to_string());
+                }
+                if id <= last {
+                    return Err(ID_TOO_SMALL.to_string());
+                }
+                id
+            }
+        };
+        Ok(id)
+    }
+
+    /// Kayıt ekler ve atanan kimliği döner.
+    pub fn add(
+        &mut self,
+        spec: IdSpec,
+        fields: StreamFields,
+        now_ms: u64,
+    ) -> Result<StreamId, String> {
+        let id = self.next_id(spec, now_ms)?;
+        self.entries.insert(id, fields);
+        self.last_id = id;
+        self.entries_added += 1;
+        Ok(id)
+    }
+
+    /// Stream'i kırpar ve silinen kayıt sayısını döner.
+    /// `limit` verilirse en fazla o kadar kayıt silinir.
+    pub fn trim(&mut self, strategy: TrimStrategy, limit: Option<usize>) -> usize {
+        let mut removed = 0;
+        while let Some((&id, _)) = self.entries.iter().next() {
+            let remove = match strategy {
+                TrimStrategy::MaxLen(max) => self.entries.len() > max,
+                TrimStrategy::MinId(min) => id < min,
+            };
+            if !remove || limit.is_some_and(|l| removed >= l) {
+                break;
+            }
+            self.entries.remove(&id);
+            removed += 1;
+        }
+        removed
+    }
+
+    /// `[start, end]` aralığındaki kayıtları döner. `rev` ise sondan başa.
+    pub fn range(
+        &self,
+        start: StreamId,
+        end: StreamId,
+        count: Option<usize>,
+        rev: bool,
+    ) -> Vec<(StreamId, StreamFields)> {
+        if start > end {
+            return Vec::new();
+        }
+        let limit = count.unwrap_or(usize::MAX);
+        let range = self.entries.range(start..=end);
+        let items: Vec<_> = if rev {
+            range
+                .rev()
+                .take(limit)
+                .map(|(id, f)| (*id, f.clone()))
+                .collect()
+        } else {
+            range.take(limit).map(|(id, f)| (*id, f.clone())).collect()
+        };
+        items
+    }
+
+    /// Verilen kimlikten büyük kayıtları döner (XREAD).
+    pub fn read_after(&self, id: StreamId, count: Option<usize>) -> Vec<(StreamId, StreamFields)> {
+        match id.next() {
+            Some(start) => self.range(start, StreamId::MAX, count, false),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn group(&self, name: &str) -> Option<&ConsumerGroup> {
+        self.groups.get(name)
+    }
+
+    /// Tüketici grubu oluşturur. Grup zaten varsa `false` döner.
+    pub fn create_group(
+        &mut self,
+        name: String,
+        last_id: StreamId,
+        entries_read: Option<u64>,
+    ) -> bool {
+        if self.groups.contains_key(&name) {
+            return false;
+        }
+        let entries_read = entries_read.unwrap_or_else(|| self.count_up_to(last_id));
+        self.groups.insert(
+            name,
+            ConsumerGroup {
+                last_id,
+                entries_read,
+                ..Default::default()
+            },
+        );
+        true
+    }
+
+    /// Verilen kimliğe kadar (dahil) olan kayıt sayısı.
+    fn count_up_to(&self, id: StreamId) -> u64 {
+        self.entries.range(..=id).count() as u64
+    }
+
+    /// Grubun henüz teslim almadığı kayıt sayısı.
+    pub fn lag(&self, group: &ConsumerGroup) -> u64 {
+        match group.last_id.next() {
+            Some(start) => self.entries.range(start..).count() as u64,
+            None => 0,
+        }
+    }
+
+    /// Tüketiciyi oluşturur (yoksa) ve son görülme zamanını günceller.
+    fn touch_consumer<'a>(
+        group: &'a mut ConsumerGroup,
+        consumer: &str,
+        now_ms: u64,
+    ) -> &'a mut Consumer {
+        let c = group.consumers.entry(consumer.to_string()).or_default();
+        c.seen_time = now_ms;
+        c
+    }
+
+    /// Tüketiciyi gruba ekler. Zaten varsa `false` döner.
+    pub fn create_consumer(&mut self, group: &str, consumer: &str, now_ms: u64) -> Option<bool> {
+        let group = self.groups.get_mut(group)?;
+        if group.consumers.contains_key(consumer) {
+            return Some(false);
+        }
+        Self::touch_consumer(group, consumer, now_ms);
+        Some(true)
+    }
+
+    /// Tüketiciyi siler ve sahip olduğu bekleyen kayıt sayısını döner.
+    /// Tüketicinin bekleyen kayıtları grubun PEL'inden de silinir.
+    pub fn delete_consumer(&mut self, group: &str, consumer: &str) -> Option<usize> {
+        let group = self.groups.get_mut(group)?;
+        let removed = match group.consumers.remove(consumer) {
+            Some(c) => c,
+            None => return Some(0),
+        };
+        for id in &removed.pending {
+            group.pel.remove(id);
+        }
+        Some(removed.pending.len())
+    }
+
+    /// Grup için teslim edilmemiş kayıtları veya tüketicinin geçmişini okur.
+    /// Geçmiş okumada silinmiş kayıtlar `None` alanlarla döner.
+    pub fn read_group(
+        &mut self,
+        group_name: &str,
+        consumer: &str,
+        start: GroupReadStart,
+        count: Option<usize>,
+        noack: bool,
+        now_ms: u64,
+    ) -> Option<Vec<(StreamId, Option<StreamFields>)>> {
+        let entries = &self.entries;
+        let group = self.groups.get_mut(group_name)?;
+        Self::touch_consumer(group, consumer, now_ms);
+        let limit = count.unwrap_or(usize::MAX);
+
+        let mut result = Vec::new();
+        match start {
+            GroupReadStart::New => {
+                let start = match group.last_id.next() {
+                    Some(start) => start,
+                    None => return Some(result),
+                };
+                let new_entries: Vec<(StreamId, StreamFields)> = entries
+                    .range(start..)
+                    .take(limit)
+                    .map(|(id, f)| (*id, f.clone()))
+                    .collect();
+
+                for (id, fields) in new_entries {
+                    group.last_id = id;
+                    group.entries_read += 1;
+                    if !noack {
+                        // Kayıt başka bir tüketicide bekliyorsa sahipliği devral
+                        if let Some(old) = group.pel.get(&id) {
+                            if let Some(owner) = group.consumers.get_mut(&old.consumer) {
+                                owner.pending.remove(&id);
+                            }
+                        }
+                        group.pel.insert(
+                            id,
+                            PendingEntry {
+                                consumer: consumer.to_string(),
+                                delivery_time: now_ms,
+                                delivery_count: 1,
+                            },
+                        );
+                        if let Some(c) = group.consumers.get_mut(consumer) {
+                            c.pending.insert(id);
+                        }
+                    }
+                    result.push((id, Some(fields)));
+                }
+
+                if !result.is_empty() {
+                    if let Some(c) = group.consumers.get_mut(consumer) {
+                        c.active_time = Some(now_ms);
+                    }
+                }
+            }
+            GroupReadStart::History(after) => {
+                let ids: Vec<StreamId> = match (group.consumers.get(consumer), after.next()) {
+                    (Some(c), Some(from)) => c.pending.range(from..).take(limit).copied().collect(),
+                    _ => Vec::new(),
+                };
+                for id in ids {
+                    if let Some(pending) = group.pel.get_mut(&id) {
+                        pending.delivery_time = now_ms;
+                        pending.delivery_count += 1;
+                    }
+                    result.push((id, entries.get(&id).cloned()));
+                }
+            }
+        }
+
+        Some(result)
+    }
+
+    /// Kayıtları onaylar ve PEL'den çıkarır. Onaylanan sayıyı döner.
+    pub fn ack(&mut self, group: &str, ids: &[StreamId]) -> usize {
+        let group = match self.groups.get_mut(group) {
+            Some(group) => group,
+            None => return 0,
+        };
+        let mut acked = 0;
+        for id in ids {
+            if let Some(pending) = group.pel.remove(id) {
+                if let Some(c) = group.consumers.get_mut(&pending.consumer) {
+                    c.pending.remove(id);
+                }
+                acked += 1;
+            }
+        }
+        acked
+    }
+
+    /// Bekleyen kaydı yeni tüketiciye aktarır.
+    fn assign_pending(group: &mut ConsumerGroup, id: StreamId, consumer: &str) {
+        let previous = match group.pel.get_mut(&id) {
+            Some(pending) => std::mem::replace(&mut pending.consumer, consumer.to_string()),
+            None => return,
+        };
+        if previous != consumer {
+            if let Some(c) = group.consumers.get_mut(&previous) {
+                c.pending.remove(&id);
+            }
+        }
+        if let Some(c) = group.consumers.get_mut(consumer) {
+            c.pending.insert(id);
+        }
+    }
+
+    /// Bekleyen kaydı PEL'den tamamen siler.
+    fn drop_pending(group: &mut ConsumerGroup, id: StreamId) {
+        if let Some(pending) = group.pel.remove(&id) {
+            if let Some(c) = group.consumers.get_mut(&pending.consumer) {
+                c.pending.remove(&id);
+            }
+        }
+    }
+
+    /// En az `min_idle` ms boşta bekleyen kayıtların sahipliğini alır (XCLAIM).
+    /// Stream'den silinmiş kayıtlar PEL'den de çıkarılır.
+    pub fn claim(
+        &mut self,
+        group_name: &str,
+        consumer: &str,
+        min_idle: u64,
+        ids: &[StreamId],
+        options: &ClaimOptions,
+        now_ms: u64,
+    ) -> Option<ClaimResult> {
+        let entries = &self.entries;
+        let group = self.groups.get_mut(group_name)?;
+        Self::touch_consumer(group, consumer, now_ms);
+
+        if let Some(last_id) = options.last_id {
+            if last_id > group.last_id {
+                group.last_id = last_id;
+            }
+        }
+
+        let delivery_time = match (options.idle, options.time) {
+            (Some(idle), _) => now_ms.saturating_sub(idle),
+            (None, Some(time)) => time,
+            (None, None) => now_ms,
+        };
+
+        let mut result = ClaimResult::default();
+        for &id in ids {
+            let fields = match entries.get(&id) {
+                Some(fields) => fields,
+                None => {
+                    if group.pel.contains_key(&id) {
+                        Self::drop_pending(group, id);
+                        result.deleted.push(id);
+                    }
+                    continue;
+                }
+            };
+
+            let pending = match group.pel.entry(id) {
+                btree_map::Entry::Occupied(entry) => {
+                    let pending = entry.into_mut();
+                    if min_idle > 0 && now_ms.saturating_sub(pending.delivery_time) < min_idle {
+                        continue;
+                    }
+                    pending
+                }
+                // FORCE: PEL'de olmayan kayıt doğrudan bu tüketiciye yazılır
+                btree_map::Entry::Vacant(entry) => {
+                    if !options.force {
+                        continue;
+                    }
+                    entry.insert(PendingEntry {
+                        consumer: consumer.to_string(),
+                        delivery_time,
+                        delivery_count: 0,
+                    })
+                }
+            };
+            pending.delivery_time = delivery_time;
+            if let Some(retry_count) = options.retry_count {
+                pending.delivery_count = retry_count;
+            } else if !options.just_id {
+                pending.delivery_count += 1;
+            }
+
+            Self::assign_pending(group, id, consumer);
+            result.claimed.push((id, fields.clone()));
+        }
+
+        if !result.claimed.is_empty() {
+            if let Some(c) = group.consumers.get_mut(consumer) {
+                c.active_time = Some(now_ms);
+            }
+        }
+
+        Some(result)
+    }
+
+    /// PEL'i `start`tan itibaren tarayıp boşta kalan kayıtları sahiplenir (XAUTOCLAIM).
+    #[allow(clippy::too_many_arguments)]
+    pub fn auto_claim(
+        &mut self,
+        group_name: &str,
+        consumer: &str,
+        min_idle: u64,
+        start: StreamId,
+        count: usize,
+        just_id: bool,
+        now_ms: u64,
+    ) -> Option<AutoClaim> {
+        let entries = &self.entries;
+        let group = self.groups.get_mut(group_name)?;
+        Self::touch_consumer(group, consumer, now_ms);
+
+        // Redis gibi taranacak kayıt sayısını COUNT'un 10 katıyla sınırla
+        let mut attempts = count.saturating_mul(10);
+        let candidates: Vec<StreamId> = group.pel.range(start..).map(|(id, _)| *id).collect();
+
+        let mut next = StreamId::MIN;
+        let mut claimed = Vec::new();
+        let mut deleted = Vec::new();
+        let mut iter = candidates.into_iter();
+        for id in iter.by_ref() {
+            if attempts == 0 || claimed.len() >= count {
+                next = id;
+                break;
+            }
+            attempts -= 1;
+
+            let fields = match entries.get(&id) {
+                Some(fields) => fields,
+                None => {
+                    Self::drop_pending(group, id);
+                    deleted.push(id);
+                    continue;
+                }
+            };
+
+            let pending = group.pel.get_mut(&id).expect("pending entry exists");
+            if now_ms.saturating_sub(pending.delivery_time) < min_idle {
+                continue;
+            }
+            pending.delivery_time = now_ms;
+            if !just_id {
+                pending.delivery_count += 1;
+            }
+            Self::assign_pending(group, id, consumer);
+            claimed.push((id, fields.clone()));
+        }
+
+        if !claimed.is_empty() {
+            if let Some(c) = group.consumers.get_mut(consumer) {
+                c.active_time = Some(now_ms);
+            }
+        }
+
+        Some(AutoClaim {
+            next,
+            claimed,
+            deleted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(v: &str) -> StreamFields {
+        vec![("f".to_string(), v.to_string())]
+    }
+
+    #[test]
+    fn test_id_generation() {
+        let mut stream = Stream::new();
+        let a = stream.add(IdSpec::Auto, fields("a"), 1000).unwrap();
+        let b = stream.add(IdSpec::Auto, fields("b"), 1000).unwrap();
+        assert_eq!(a, StreamId::new(1000, 0));
+        assert_eq!(b, StreamId::new(1000, 1));
+
+        // Saat geri giderse sıra artmaya devam eder
+        let c = stream.add(IdSpec::Auto, fields("c"), 500).unwrap();
+        assert_eq!(c, StreamId::new(1000, 2));
+
+        assert!(stream
+            .add(IdSpec::Explicit(StreamId::new(999, 0)), fields("d"), 0)
+            .is_err());
+        assert_eq!(
+            stream.add(IdSpec::AutoSeq(2000), fields("e"), 0).unwrap(),
+            StreamId::new(2000, 0)
+        );
+    }
+
+    #[test]
+    fn test_trim() {
+        let mut stream = Stream::new();
+        for i in 1..=10 {
+            stream
+                .add(IdSpec::Explicit(StreamId::new(i, 0)), fields("x"), 0)
+                .unwrap();
+        }
+        assert_eq!(stream.trim(TrimStrategy::MaxLen(5), Some(2)), 2);
+        assert_eq!(stream.trim(TrimStrategy::MaxLen(5), None), 3);
+        assert_eq!(stream.len(), 5);
+        assert_eq!(
+            stream.trim(TrimStrategy::MinId(StreamId::new(8, 0)), None),
+            2
+        );
+        assert_eq!(stream.first_entry().unwrap().0, StreamId::new(8, 0));
+    }
+
+    #[test]
+    fn test_group_read_ack_claim() {
+        let mut stream = Stream::new();
+        for i in 1..=3 {
+            stream
+                .add(IdSpec::Explicit(StreamId::new(i, 0)), fields("x"), 0)
+                .unwrap();
+        }
+        assert!(stream.create_group("g".to_string(), StreamId::MIN, None));
+
+        let read = stream
+            .read_group("g", "alice", GroupReadStart::New, Some(2), false, 100)
+            .unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(stream.group("g").unwrap().pel.len(), 2);
+        assert_eq!(stream.lag(stream.group("g").unwrap()), 1);
+
+        assert_eq!(stream.ack("g", &[StreamId::new(1, 0)]), 1);
+
+        let claimed = stream
+            .claim(
+                "g",
+                "bob",
+                50,
+                &[StreamId::new(2, 0)],
+                &ClaimOptions::default(),
+                200,
+            )
+            .unwrap();
+        assert_eq!(claimed.claimed.len(), 1);
+
+        let group = stream.group("g").unwrap();
+        assert_eq!(group.pel[&StreamId::new(2, 0)].consumer, "bob");
+        assert_eq!(group.pel[&StreamId::new(2, 0)].delivery_count, 2);
+        assert!(group.consumers["alice"].pending.is_empty());
+
+        let history = stream
+            .read_group(
+                "g",
+                "bob",
+                GroupReadStart::History(StreamId::MIN),
+                None,
+                false,
+                300,
+            )
+            .unwrap();
+        assert_eq!(history.len(), 1);
+    }
+}
@@ -0,0 +1,475 @@
+//! Zaman serisi veri tipi.
+//! Örnekler zaman damgasına göre sıralı tutulur; saklama süresi, tekrar politikası,
+//! etiketler ve hedef serilere otomatik özet yazan sıkıştırma kuralları desteklenir.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Aynı zaman damgasına ikinci kez örnek yazıldığında ne yapılacağı.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum DuplicatePolicy {
+    /// Hata ver
+    #[default]
+    Block,
+    /// Eski değeri koru
+    First,
+    /// Yeni değeri yaz
+    Last,
+    Min,
+    Max,
+    /// Değerleri topla
+    Sum,
+}
+
+impl DuplicatePolicy {
+    pub fn parse(s: &str) -> Result<DuplicatePolicy, String> {
+        match s.to_uppercase().as_str() {
+            "BLOCK" => Ok(DuplicatePolicy::Block),
+            "FIRST" => Ok(DuplicatePolicy::First),
+            "LAST" => Ok(DuplicatePolicy::Last),
+            "MIN" => Ok(DuplicatePolicy::Min),
+            "MAX" => Ok(DuplicatePolicy::Max),
+            "SUM" => Ok(DuplicatePolicy::Sum),
+            _ => Err("TSDB: Unknown DUPLICATE_POLICY".to_string()),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DuplicatePolicy::Block => "block",
+            DuplicatePolicy::First => "first",
+            DuplicatePolicy::Last => "last",
+            DuplicatePolicy::Min => "min",
+            DuplicatePolicy::Max => "max",
+            DuplicatePolicy::Sum => "sum",
+        }
+    }
+
+    /// Mevcut değerle yeni değeri birleştirir.
+    fn resolve(&self, old: f64, new: f64) -> Result<f64, String> {
+        match self {
+            DuplicatePolicy::Block => Err(
+                "TSDB: Error at upsert, update is not supported when DUPLICATE_POLICY is set to BLOCK mode"
+                    .to_string(),
+            ),
+            DuplicatePolicy::First => Ok(old),
+            DuplicatePolicy::Last => Ok(new),
+            DuplicatePolicy::Min => Ok(old.min(new)),
+            DuplicatePolicy::Max => Ok(old.max(new)),
+            DuplicatePolicy::Sum => Ok(old + new),
+        }
+    }
+}
+
+/// Bir zaman aralığındaki örnekleri tek değere indiren fonksiyon.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Aggregation {
+    Avg,
+    Min,
+    Max,
+    Sum,
+    Count,
+}
+
+impl Aggregation {
+    pub fn parse(s: &str) -> Result<Aggregation, String> {
+        match s.to_uppercase().as_str() {
+            "AVG" => Ok(Aggregation::Avg),
+            "MIN" => Ok(Aggregation::Min),
+            "MAX" => Ok(Aggregation::Max),
+            "SUM" => Ok(Aggregation::Sum),
+            "COUNT" => Ok(Aggregation::Count),
+            _ => Err("TSDB: Unknown aggregation type".to_string()),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aggregation::Avg => "avg",
+            Aggregation::Min => "min",
+            Aggregation::Max => "max",
+            Aggregation::Sum => "sum",
+            Aggregation::Count => "count",
+        }
+    }
+
+    /// Değerleri özetler. Değer yoksa `None` döner.
+    pub fn apply(&self, values: impl Iterator<Item = f64>) -> Option<f64> {
+        let mut count = 0u64;
+        let mut sum = 0.0;
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        for value in values {
+            count += 1;
+            sum += value;
+            min = min.min(value);
+            max = max.max(value);
+        }
+        if count == 0 {
+            return None;
+        }
+
+        Some(match self {
+            Aggregation::Avg => sum / count as f64,
+            Aggregation::Min => min,
+            Aggregation::Max => max,
+            Aggregation::Sum => sum,
+            Aggregation::Count => count as f64,
+        })
+    }
+}
+
+/// Zaman damgasının ait olduğu kovanın başlangıcı.
+fn bucket_start(timestamp: u64, bucket: u64) -> u64 {
+    timestamp - timestamp % bucket
+}
+
+/// Örnekleri `bucket` genişliğindeki kovalara bölüp her kovayı özetler.
+pub fn aggregate<'a>(
+    samples: impl Iterator<Item = (&'a u64, &'a f64)>,
+    aggregation: Aggregation,
+    bucket: u64,
+) -> Vec<(u64, f64)> {
+    let mut result = Vec::new();
+    let mut current: Option<(u64, Vec<f64>)> = None;
+    for (ts, value) in samples {
+        let start = bucket_start(*ts, bucket);
+        match &mut current {
+            Some((bucket_ts, values)) if *bucket_ts == start => values.push(*value),
+            _ => {
+                if let Some((bucket_ts, values)) = current.take() {
+                    result.extend(
+                        aggregation
+                            .apply(values.into_iter())
+                            .map(|v| (bucket_ts, v)),
+                    );
+                }
+                current = Some((start, vec![*value]));
+            }
+        }
+    }
+    if let Some((bucket_ts, values)) = current {
+        result.extend(
+            aggregation
+                .apply(values.into_iter())
+                .map(|v| (bucket_ts, v)),
+        );
+    }
+    result
+}
+
+/// Kaynak seriye gelen örneklerden hedef seriye özet yazan kural.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactionRule {
+    pub dest: String,
+    pub aggregation: Aggregation,
+    pub bucket: u64,
+    /// Henüz kapanmamış (hedefe yazılmamış) kovanın başlangıcı
+    open_bucket: Option<u64>,
+}
+
+/// Sıkıştırma kuralının hedef seriye yazması gereken özet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactedSample {
+    pub dest: String,
+    pub timestamp: u64,
+    pub value: f64,
+}
+
+/// Etiket filtresi (TS.MRANGE FILTER).
+/// Boş değer etiketin olmaması anlamına gelir.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LabelFilter {
+    /// `etiket=değer` veya `etiket=(d1,d2)`
+    Eq(String, Vec<String>),
+    /// `etiket!=değer` veya `etiket!=(d1,d2)`
+    NotEq(String, Vec<String>),
+}
+
+impl LabelFilter {
+    pub fn parse(s: &str) -> Result<LabelFilter, String> {
+        let invalid = || "TSDB: failed parsing labels".to_string();
+        let (label, values, negate) = match s.split_once("!=") {
+            Some((label, values)) => (label, values, true),
+            None => match s.split_once('=') {
+                Some((label, values)) => (label, values, false),
+                None => return Err(invalid()),
+            },
+        };
+        if label.is_empty() {
+            return Err(invalid());
+        }
+
+        let values = match values.strip_prefix('(') {
+            Some(list) => list
+                .strip_suffix(')')
+                .ok_or_else(invalid)?
+                .split(',')
+                .map(|v| v.trim().to_string())
+                .collect(),
+            None => vec![values.to_string()],
+        };
+        Ok(if negate {
+            LabelFilter::NotEq(label.to_string(), values)
+        } else {
+            LabelFilter::Eq(label.to_string(), values)
+        })
+    }
+
+    /// Pozitif eşleşme mi? (`etiket=değer`, boş olmayan değerle)
+    pub fn is_matcher(&self) -> bool {
+        matches!(self, LabelFilter::Eq(_, values) if values.iter().any(|v| !v.is_empty()))
+    }
+
+    pub fn matches(&self, labels: &[(String, String)]) -> bool {
+        let value_of = |label: &str| {
+            labels
+                .iter()
+                .find(|(l, _)| l == label)
+                .map_or("", |(_, v)| v.as_str())
+        };
+        match self {
+            LabelFilter::Eq(label, values) => values.iter().any(|v| v == value_of(label)),
+            LabelFilter::NotEq(label, values) => values.iter().all(|v| v != value_of(label)),
+        }
+    }
+}
+
+/// Aralık sorgusu seçenekleri.
+#[derive(Debug, Clone, Default)]
+pub struct RangeQuery {
+    pub count: Option<usize>,
+    pub aggregation: Option<(Aggregation, u64)>,
+}
+
+/// Zaman serisi.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimeSeries {
+    pub samples: BTreeMap<u64, f64>,
+    /// Milisaniye; 0 ise örnekler hiç silinmez
+    pub retention: u64,
+    pub duplicate_policy: DuplicatePolicy,
+    pub labels: Vec<(String, String)>,
+    pub rules: Vec<CompactionRule>,
+    /// Bu seri bir sıkıştırma kuralının hedefiyse kaynağı
+    pub source: Option<String>,
+}
+
+impl TimeSeries {
+    pub fn new(
+        retention: u64,
+        duplicate_policy: DuplicatePolicy,
+        labels: Vec<(String, String)>,
+    ) -> Self {
+        TimeSeries {
+            retention,
+            duplicate_policy,
+            labels,
+            ..Default::default()
+        }
+    }
+
+    pub fn last(&self) -> Option<(u64, f64)> {
+        self.samples.iter().next_back().map(|(ts, v)| (*ts, *v))
+    }
+
+    pub fn first(&self) -> Option<(u64, f64)> {
+        self.samples.iter().next().map(|(ts, v)| (*ts, *v))
+    }
+
+    /// Örnek ekler. `on_duplicate` serinin politikasını bu ekleme için geçersiz kılar.
+    /// Sıkıştırma kurallarının hedef serilere yazması gereken özetleri döndürür.
+    pub fn add(
+        &mut self,
+        timestamp: u64,
+        value: f64,
+        on_duplicate: Option<DuplicatePolicy>,
+    ) -> Result<Vec<CompactedSample>, String> {
+        if let Some((last_ts, _)) = self.last() {
+            if self.retention > 0 && timestamp.saturating_add(self.retention) < last_ts {
+                return Err("TSDB: Timestamp is older than retention".to_string());
+            }
+        }
+
+        let value = match self.samples.get(&timestamp) {
+            Some(old) => on_duplicate
+                .unwrap_or(self.duplicate_policy)
+                .resolve(*old, value)?,
+            None => value,
+        };
+        self.samples.insert(timestamp, value);
+
+        let compacted = self.compact(timestamp);
+        self.trim();
+        Ok(compacted)
+    }
+
+    /// Saklama süresinin dışında kalan örnekleri siler.
+    fn trim(&mut self) {
+        if let (true, Some((last_ts, _))) = (self.retention > 0, self.last()) {
+            let cutoff = last_ts.saturating_sub(self.retention);
+            self.samples = self.samples.split_off(&cutoff);
+        }
+    }
+
+    /// Yeni örneğin kovası açık kovadan ilerideyse açık kova kapanır ve özeti yazılır.
+    /// Geç gelen örnekler zaten kapanmış kovalarını yeniden hesaplatır.
+    fn compact(&mut self, timestamp: u64) -> Vec<CompactedSample> {
+        let mut output = Vec::new();
+        for rule in &mut self.rules {
+            let start = bucket_start(timestamp, rule.bucket);
+            let closed = match rule.open_bucket {
+                None => None,
+                Some(open) if start > open => Some(open),
+                Some(open) if start < open => Some(start),
+                Some(_) => None,
+            };
+            if rule.open_bucket.is_none_or(|open| start > open) {
+                rule.open_bucket = Some(start);
+            }
+
+            if let Some(closed) = closed {
+                let values = self
+                    .samples
+                    .range(closed..closed + rule.bucket)
+                    .map(|(_, v)| *v);
+                if let Some(value) = rule.aggregation.apply(values) {
+                    output.push(CompactedSample {
+                        dest: rule.dest.clone(),
+                        timestamp: closed,
+                        value,
+                    });
+                }
+            }
+        }
+        output
+    }
+
+    /// Kaynağa yeni bir sıkıştırma kuralı ekler.
+    pub fn add_rule(&mut self, dest: String, aggregation: Aggregation, bucket: u64) {
+        self.rules.push(CompactionRule {
+            dest,
+            aggregation,
+            bucket,
+            open_bucket: self.last().map(|(ts, _)| bucket_start(ts, bucket)),
+        });
+    }
+
+    /// `dest` hedefli kuralı siler.
+    pub fn remove_rule(&mut self, dest: &str) -> bool {
+        let before = self.rules.len();
+        self.rules.retain(|rule| rule.dest != dest);
+        self.rules.len() != before
+    }
+
+    /// `[from, to]` aralığındaki örnekleri (isteğe bağlı özetleyerek) döndürür.
+    pub fn range(&self, from: u64, to: u64, query: &RangeQuery) -> Vec<(u64, f64)> {
+        if from > to {
+            return Vec::new();
+        }
+        let samples = self.samples.range(from..=to);
+        let mut result = match query.aggregation {
+            Some((aggregation, bucket)) => aggregate(samples, aggregation, bucket),
+            None => samples.map(|(ts, v)| (*ts, *v)).collect(),
+        };
+        if let Some(count) = query.count {
+            result.truncate(count);
+        }
+        result
+    }
+
+    /// Tüm filtreler etiketlerle eşleşiyor mu?
+    pub fn matches(&self, filters: &[LabelFilter]) -> bool {
+        filters.iter().all(|f| f.matches(&self.labels))
+    }
+
+    /// Yaklaşık bellek kullanımı (bayt).
+    pub fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.samples.len() * (std::mem::size_of::<(u64, f64)>() + 16)
+            + self
+                .labels
+                .iter()
+                .map(|(l, v)| l.len() + v.len())
+                .sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_policy_and_retention() {
+        let mut ts = TimeSeries::new(100, DuplicatePolicy::Block, Vec::new());
+        ts.add(1000, 1.0, None).unwrap();
+        assert!(ts.add(1000, 2.0, None).is_err());
+        ts.add(1000, 2.0, Some(DuplicatePolicy::Sum)).unwrap();
+        assert_eq!(ts.last(), Some((1000, 3.0)));
+
+        assert!(ts.add(800, 1.0, None).is_err());
+        ts.add(1150, 1.0, None).unwrap();
+        assert_eq!(ts.first(), Some((1150, 1.0)));
+    }
+
+    #[test]
+    fn test_range_aggregation() {
+        let mut ts = TimeSeries::default();
+        for (t, v) in [(0, 1.0), (5, 3.0), (10, 10.0), (25, 4.0)] {
+            ts.add(t, v, None).unwrap();
+        }
+        let query = RangeQuery {
+            count: None,
+            aggregation: Some((Aggregation::Avg, 10)),
+        };
+        assert_eq!(
+            ts.range(0, 100, &query),
+            vec![(0, 2.0), (10, 10.0), (20, 4.0)]
+        );
+
+        let query = RangeQuery {
+            count: Some(2),
+            aggregation: None,
+        };
+        assert_eq!(ts.range(5, 25, &query), vec![(5, 3.0), (10, 10.0)]);
+    }
+
+    #[test]
+    fn test_compaction_emits_closed_buckets() {
+        let mut ts = TimeSeries::default();
+        ts.add_rule("dest".to_string(), Aggregation::Sum, 10);
+        assert!(ts.add(1, 1.0, None).unwrap().is_empty());
+        assert!(ts.add(5, 2.0, None).unwrap().is_empty());
+
+        let out = ts.add(12, 5.0, None).unwrap();
+        assert_eq!(
+            out,
+            vec![CompactedSample {
+                dest: "dest".to_string(),
+                timestamp: 0,
+                value: 3.0
+            }]
+        );
+
+        // Geç gelen örnek kapanmış kovayı yeniden hesaplatır
+        let out = ts.add(7, 4.0, None).unwrap();
+        assert_eq!(out[0].value, 7.0);
+    }
+
+    #[test]
+    fn test_label_filters() {
+        let labels = vec![
+            ("area".to_string(), "eu".to_string()),
+            ("kind".to_string(), "cpu".to_string()),
+        ];
+        let check = |f: &str| LabelFilter::parse(f).unwrap().matches(&labels);
+        assert!(check("area=eu"));
+        assert!(check("area=(us,eu)"));
+        assert!(!check("area!=eu"));
+        assert!(check("host="));
+        assert!(check("kind!="));
+        assert!(!check("kind="));
+        assert!(LabelFilter::parse("noequals").is_err());
+        assert!(!LabelFilter::parse("host=").unwrap().is_matcher());
+    }
+}
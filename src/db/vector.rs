@@ -0,0 +1,528 @@
+//! Vektör benzerlik araması.
+//! Küçük kümelerde kaba kuvvet (flat) tarama, büyüklerde HNSW grafı kullanılır.
+
+use crate::db::probabilistic::hash64;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Bu sayıya kadar vektör varsa HNSW yerine kaba kuvvet kullanılır.
+pub const BRUTE_FORCE_LIMIT: usize = 1024;
+
+/// Uzaklık ölçütü. Her üçünde de küçük değer daha yakın demektir.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DistanceMetric {
+    /// Öklid uzaklığı
+    L2,
+    /// `1 - iç çarpım`
+    Ip,
+    /// `1 - kosinüs benzerliği`
+    Cosine,
+}
+
+impl DistanceMetric {
+    pub fn parse(s: &str) -> Result<DistanceMetric, String> {
+        match s.to_uppercase().as_str() {
+            "L2" => Ok(DistanceMetric::L2),
+            "IP" => Ok(DistanceMetric::Ip),
+            "COSINE" => Ok(DistanceMetric::Cosine),
+            _ => Err(format!(
+                "Bad arguments for vector similarity: unknown metric '{}'",
+                s
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DistanceMetric::L2 => "L2",
+            DistanceMetric::Ip => "IP",
+            DistanceMetric::Cosine => "COSINE",
+        }
+    }
+
+    pub fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        let dot = || a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+        match self {
+            DistanceMetric::L2 => a
+                .iter()
+                .zip(b)
+                .map(|(x, y)| (x - y) * (x - y))
+                .sum::<f32>()
+                .sqrt(),
+            DistanceMetric::Ip => 1.0 - dot(),
+            DistanceMetric::Cosine => {
+                let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+                let denominator = norm(a) * norm(b);
+                if denominator == 0.0 {
+                    1.0
+                } else {
+                    1.0 - dot() / denominator
+                }
+            }
+        }
+    }
+}
+
+/// Metin olarak verilen vektörü ayrıştırır: `1,2,3`, `1 2 3` veya `[1, 2, 3]`.
+/// Değerler UTF-8 string olarak saklandığından ikili (blob) biçim desteklenmez.
+pub fn parse_vector(s: &str) -> Result<Vec<f32>, String> {
+    let trimmed = s.trim();
+    let inner = trimmed
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .unwrap_or(trimmed);
+    let vector = inner
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<f32>().ok().filter(|v| v.is_finite()))
+        .collect::<Option<Vec<f32>>>()
+        .ok_or_else(|| "Error parsing vector".to_string())?;
+    if vector.is_empty() {
+        return Err("Error parsing vector".to_string());
+    }
+    Ok(vector)
+}
+
+/// Uzaklığı yığında sıralayabilmek için `f32` sarmalayıcı.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    distance: f32,
+    id: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.id.cmp(&other.id))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HnswNode {
+    key: String,
+    vector: Vec<f32>,
+    /// Katman başına komşular; `links[0]` en alt katmandır
+    links: Vec<Vec<usize>>,
+    deleted: bool,
+}
+
+/// Hierarchical Navigable Small World grafı.
+/// Silinen düğümler gezinme için grafta kalır; yarısından fazlası silinince graf yeniden kurulur.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hnsw {
+    pub m: usize,
+    pub ef_construction: usize,
+    metric: DistanceMetric,
+    nodes: Vec<HnswNode>,
+    ids: HashMap<String, usize>,
+    entry: Option<usize>,
+}
+
+impl Hnsw {
+    pub const DEFAULT_M: usize = 16;
+    pub const DEFAULT_EF_CONSTRUCTION: usize = 200;
+    const MAX_LEVEL: usize = 16;
+
+    pub fn new(metric: DistanceMetric, m: usize, ef_construction: usize) -> Self {
+        Hnsw {
+            m: m.max(2),
+            ef_construction: ef_construction.max(1),
+            metric,
+            nodes: Vec::new(),
+            ids: HashMap::new(),
+            entry: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Katman sayısı anahtardan türetilir; böylece AOF yeniden yüklemesinde aynı graf kurulur.
+    fn level_for(&self, key: &str) -> usize {
+        let uniform =
+            ((hash64(key.as_bytes(), 0x686e_7377) >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+        let ml = 1.0 / (self.m as f64).ln();
+        ((-uniform.ln() * ml) as usize).min(Self::MAX_LEVEL)
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.m * 2
+        } else {
+            self.m
+        }
+    }
+
+    fn distance_to(&self, query: &[f32], id: usize) -> f32 {
+        self.metric.distance(query, &self.nodes[id].vector)
+    }
+
+    /// Tek katmanda en yakın `ef` düğümü arar. Sonuç artan uzaklık sırasındadır.
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entry_points: &[usize],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<usize> = entry_points.iter().copied().collect();
+        let mut candidates = BinaryHeap::new();
+        let mut found = BinaryHeap::new();
+        for &id in entry_points {
+            let candidate = Candidate {
+                distance: self.distance_to(query, id),
+                id,
+            };
+            candidates.push(Reverse(candidate));
+            found.push(candidate);
+        }
+
+        while let Some(Reverse(current)) = candidates.pop() {
+            let farthest = found.peek().map_or(f32::INFINITY, |c| c.distance);
+            if current.distance > farthest && found.len() >= ef {
+                break;
+            }
+            let Some(neighbors) = self.nodes[current.id].links.get(layer) else {
+                continue;
+            };
+            for &neighbor in neighbors {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let candidate = Candidate {
+                    distance: self.distance_to(query, neighbor),
+                    id: neighbor,
+                };
+                let farthest = found.peek().map_or(f32::INFINITY, |c| c.distance);
+                if found.len() < ef || candidate.distance < farthest {
+                    candidates.push(Reverse(candidate));
+                    found.push(candidate);
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+        found.into_sorted_vec()
+    }
+
+    /// Üst katmanlardan `target` katmanına kadar açgözlü iner.
+    fn descend(&self, query: &[f32], target: usize) -> Option<usize> {
+        let mut current = self.entry?;
+        let top = self.nodes[current].links.len() - 1;
+        for layer in (target + 1..=top).rev() {
+            current = self.search_layer(query, &[current], 1, layer)[0].id;
+        }
+        Some(current)
+    }
+
+    pub fn insert(&mut self, key: String, vector: Vec<f32>) {
+        self.remove(&key);
+        let level = self.level_for(&key);
+        let id = self.nodes.len();
+        self.nodes.push(HnswNode {
+            key: key.clone(),
+            vector,
+            links: vec![Vec::new(); level + 1],
+            deleted: false,
+        });
+        self.ids.insert(key, id);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(id);
+            return;
+        };
+        let top = self.nodes[entry].links.len() - 1;
+        let query = self.nodes[id].vector.clone();
+        let mut entry_points = vec![self.descend(&query, level).unwrap_or(entry)];
+
+        for layer in (0..=level.min(top)).rev() {
+            let found = self.search_layer(&query, &entry_points, self.ef_construction, layer);
+            let neighbors: Vec<usize> = found.iter().take(self.m).map(|c| c.id).collect();
+            self.nodes[id].links[layer] = neighbors.clone();
+            for neighbor in neighbors {
+                self.nodes[neighbor].links[layer].push(id);
+                self.prune(neighbor, layer);
+            }
+            entry_points = found.into_iter().map(|c| c.id).collect();
+        }
+
+        if level > top {
+            self.entry = Some(id);
+        }
+    }
+
+    /// Komşu sayısı sınırı aşılırsa en uzak komşuları atar.
+    fn prune(&mut self, id: usize, layer: usize) {
+        let max_links = self.max_links(layer);
+        if self.nodes[id].links[layer].len() <= max_links {
+            return;
+        }
+        let vector = &self.nodes[id].vector;
+        let mut links: Vec<Candidate> = self.nodes[id].links[layer]
+            .iter()
+            .map(|&neighbor| Candidate {
+                distance: self.metric.distance(vector, &self.nodes[neighbor].vector),
+                id: neighbor,
+            })
+            .collect();
+        links.sort();
+        links.truncate(max_links);
+        self.nodes[id].links[layer] = links.into_iter().map(|c| c.id).collect();
+    }
+
+    pub fn remove(&mut self, key: &str) -> bool {
+        let Some(id) = self.ids.remove(key) else {
+            return false;
+        };
+        self.nodes[id].deleted = true;
+        if self.ids.is_empty() {
+            self.nodes.clear();
+            self.entry = None;
+        } else if self.ids.len() * 2 < self.nodes.len() {
+            self.rebuild();
+        }
+        true
+    }
+
+    /// Silinmiş düğümleri atarak grafı baştan kurar.
+    fn rebuild(&mut self) {
+        let mut live: Vec<(String, Vec<f32>)> = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .filter(|node| !node.deleted)
+            .map(|node| (node.key, node.vector))
+            .collect();
+        // Ekleme sırası grafı etkiler; yeniden yüklemede aynı sonuç için sıralanır
+        live.sort_by(|a, b| a.0.cmp(&b.0));
+        self.ids.clear();
+        self.entry = None;
+        for (key, vector) in live {
+            self.insert(key, vector);
+        }
+    }
+
+    /// `filter`'dan geçen en yakın `k` anahtarı döndürür.
+    /// Filtre sonuçları azaltırsa arama genişliği yeterli sonuç bulunana kadar artırılır.
+    pub fn search(
+        &self,
+        query: &[f32],
+        k: usize,
+        ef: usize,
+        filter: &dyn Fn(&str) -> bool,
+    ) -> Vec<(String, f32)> {
+        let Some(entry) = self.descend(query, 0) else {
+            return Vec::new();
+        };
+        let mut ef = ef.max(k);
+        loop {
+            let results: Vec<(String, f32)> = self
+                .search_layer(query, &[entry], ef, 0)
+                .into_iter()
+                .filter(|c| !self.nodes[c.id].deleted && filter(&self.nodes[c.id].key))
+                .take(k)
+                .map(|c| (self.nodes[c.id].key.clone(), c.distance))
+                .collect();
+            if results.len() >= k || ef >= self.nodes.len() {
+                return results;
+            }
+            ef *= 2;
+        }
+    }
+}
+
+/// İndeks algoritması.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum VectorAlgorithm {
+    Flat,
+    Hnsw,
+}
+
+/// Bir hash alanındaki vektörlerin indeksi.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorField {
+    pub algorithm: VectorAlgorithm,
+    pub dim: usize,
+    pub metric: DistanceMetric,
+    pub ef_runtime: usize,
+    vectors: HashMap<String, Vec<f32>>,
+    hnsw: Option<Hnsw>,
+}
+
+impl VectorField {
+    pub const DEFAULT_EF_RUNTIME: usize = 10;
+
+    pub fn flat(dim: usize, metric: DistanceMetric) -> Self {
+        VectorField {
+            algorithm: VectorAlgorithm::Flat,
+            dim,
+            metric,
+            ef_runtime: Self::DEFAULT_EF_RUNTIME,
+            vectors: HashMap::new(),
+            hnsw: None,
+        }
+    }
+
+    pub fn hnsw(
+        dim: usize,
+        metric: DistanceMetric,
+        m: usize,
+        ef_construction: usize,
+        ef_runtime: usize,
+    ) -> Self {
+        VectorField {
+            algorithm: VectorAlgorithm::Hnsw,
+            dim,
+            metric,
+            ef_runtime,
+            vectors: HashMap::new(),
+            hnsw: Some(Hnsw::new(metric, m, ef_construction)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+
+    pub fn hnsw_params(&self) -> Option<(usize, usize)> {
+        self.hnsw.as_ref().map(|h| (h.m, h.ef_construction))
+    }
+
+    /// Anahtarın vektörünü ekler ya da günceller. Vektör değişmemişse bir şey yapmaz.
+    pub fn upsert(&mut self, key: &str, vector: Vec<f32>) {
+        if self.vectors.get(key) == Some(&vector) {
+            return;
+        }
+        if let Some(hnsw) = &mut self.hnsw {
+            hnsw.insert(key.to_string(), vector.clone());
+        }
+        self.vectors.insert(key.to_string(), vector);
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        if self.vectors.remove(key).is_some() {
+            if let Some(hnsw) = &mut self.hnsw {
+                hnsw.remove(key);
+            }
+        }
+    }
+
+    /// `query`'ye en yakın, `filter`'dan geçen `k` anahtarı artan uzaklık sırasıyla döndürür.
+    pub fn knn(
+        &self,
+        query: &[f32],
+        k: usize,
+        ef_runtime: Option<usize>,
+        filter: &dyn Fn(&str) -> bool,
+    ) -> Result<Vec<(String, f32)>, String> {
+        if query.len() != self.dim {
+            return Err(format!(
+                "Error parsing vector similarity query: query vector dimension ({}) does not match index dimension ({})",
+                query.len(),
+                self.dim
+            ));
+        }
+
+        match &self.hnsw {
+            Some(hnsw) if self.vectors.len() > BRUTE_FORCE_LIMIT => {
+                Ok(hnsw.search(query, k, ef_runtime.unwrap_or(self.ef_runtime), filter))
+            }
+            _ => Ok(self.brute_force(query, k, filter)),
+        }
+    }
+
+    fn brute_force(
+        &self,
+        query: &[f32],
+        k: usize,
+        filter: &dyn Fn(&str) -> bool,
+    ) -> Vec<(String, f32)> {
+        let mut results: Vec<(String, f32)> = self
+            .vectors
+            .iter()
+            .filter(|(key, _)| filter(key))
+            .map(|(key, vector)| (key.clone(), self.metric.distance(query, vector)))
+            .collect();
+        results.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        results.truncate(k);
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics() {
+        let a = [1.0, 0.0];
+        let b = [0.0, 1.0];
+        assert_eq!(DistanceMetric::L2.distance(&a, &b), 2f32.sqrt());
+        assert_eq!(DistanceMetric::Cosine.distance(&a, &a), 0.0);
+        assert_eq!(DistanceMetric::Cosine.distance(&a, &b), 1.0);
+        assert_eq!(DistanceMetric::Ip.distance(&a, &[2.0, 0.0]), -1.0);
+    }
+
+    #[test]
+    fn test_parse_vector() {
+        assert_eq!(parse_vector("1,2.5,3").unwrap(), vec![1.0, 2.5, 3.0]);
+        assert_eq!(parse_vector("[1, 2]").unwrap(), vec![1.0, 2.0]);
+        assert_eq!(parse_vector("1 2").unwrap(), vec![1.0, 2.0]);
+        assert!(parse_vector("1,x").is_err());
+        assert!(parse_vector("").is_err());
+    }
+
+    #[test]
+    fn test_hnsw_matches_brute_force() {
+        let mut field = VectorField::hnsw(2, DistanceMetric::L2, 8, 64, 32);
+        for i in 0..2000u32 {
+            let x = (i % 50) as f32;
+            let y = (i / 50) as f32;
+            field.upsert(&format!("p:{}", i), vec![x, y]);
+        }
+        let query = [10.2, 20.1];
+        let everything = |_: &str| true;
+        let approx = field.knn(&query, 5, None, &everything).unwrap();
+        let exact = field.brute_force(&query, 5, &everything);
+        assert_eq!(approx[0], exact[0]);
+        let hits = approx.iter().filter(|r| exact.contains(r)).count();
+        assert!(hits >= 4);
+    }
+
+    #[test]
+    fn test_filter_and_remove() {
+        let mut field = VectorField::hnsw(1, DistanceMetric::L2, 4, 16, 4);
+        for i in 0..1500 {
+            field.upsert(&i.to_string(), vec![i as f32]);
+        }
+        let even = |key: &str| key.parse::<u32>().unwrap() % 2 == 0;
+        let results = field.knn(&[501.2], 3, None, &even).unwrap();
+        let keys: Vec<&str> = results.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, vec!["502", "500", "504"]);
+
+        for i in 0..1000 {
+            field.remove(&i.to_string());
+        }
+        let results = field.knn(&[0.0], 1, None, &|_| true).unwrap();
+        assert_eq!(results[0].0, "1000");
+        assert!(field.knn(&[0.0, 1.0], 1, None, &|_| true).is_err());
+    }
+}
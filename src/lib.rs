@@ -1,7 +1,13 @@
+pub mod acl;
+pub mod cdc;
+pub mod clients;
+pub mod cluster;
 pub mod commands;
 pub mod config;
 pub mod db;
 pub mod network;
 pub mod observability;
 pub mod persistence;
-pub mod server_info;
+pub mod raft;
+pub mod replication;
+pub mod sentinel;
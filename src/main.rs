@@ -1,13 +1,28 @@
 use clap::Parser;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use tokio::net::TcpListener;
-use tokio::sync::RwLock;
+use tokio::net::{TcpListener, UnixListener};
+use tokio::sync::{RwLock, Semaphore};
 use tracing::{error, info};
-use tracing_subscriber;
 
 use hexagondb::{
-    commands, config::Config, db::DB, network::connection, persistence::aof::Aof,
-    server_info::ServerInfo,
+    acl::Acl,
+    cdc::{Cdc, DEFAULT_CDC_BACKLOG},
+    clients::{Clients, Peer},
+    cluster::{self, Cluster},
+    commands::{self, config::Settings},
+    config::Config,
+    db::{pubsub::PubSub, DB},
+    network::client::Credentials,
+    network::connection,
+    network::resp::RespValue,
+    network::tls::{self, Tls},
+    network::unix,
+    observability::{logging, stats::STATS},
+    persistence::aof::Aof,
+    raft::{Raft, RaftConfig},
+    replication::Replication,
+    sentinel::{self, Addr, Sentinel, SentinelConfig},
 };
 
 /// HexagonDB - in-memory database written in Rust
@@ -25,6 +40,87 @@ struct Args {
     /// Override port
     #[arg(short, long)]
     port: Option<u16>,
+
+    /// Port for the Prometheus metrics endpoint
+    #[arg(long, default_value_t = hexagondb::observability::metrics::DEFAULT_METRICS_PORT)]
+    metrics_port: u16,
+
+    /// Start as a replica of the given primary
+    #[arg(long, num_args = 2, value_names = ["HOST", "PORT"])]
+    replicaof: Option<Vec<String>>,
+
+    /// User a replica logs in as on its primary; the default user if left out
+    #[arg(long, requires = "masterauth")]
+    masteruser: Option<String>,
+
+    /// Password a replica sends with AUTH to its primary
+    #[arg(long)]
+    masterauth: Option<String>,
+
+    /// Enable cluster mode; the cluster bus listens on port + 10000
+    #[arg(long, conflicts_with = "sentinel")]
+    cluster: bool,
+
+    /// Run in raft mode; ADDR (host:port) is the address other members use for this node
+    #[arg(long, value_name = "ADDR", conflicts_with_all = ["cluster", "sentinel", "replicaof"])]
+    raft: Option<String>,
+
+    /// Initial raft members, only used while the raft log is empty. Leave it out on a node
+    /// that will be added to an existing group with RAFT ADDNODE
+    #[arg(long, num_args = 1.., value_name = "ADDR", requires = "raft")]
+    raft_peers: Vec<String>,
+
+    /// Directory for the raft log and snapshots
+    #[arg(long, default_value = "raft")]
+    raft_dir: String,
+
+    /// Applied raft log entries kept before the log is compacted into a snapshot
+    #[arg(long, default_value_t = 1000)]
+    raft_snapshot_entries: u64,
+
+    /// User raft members log in as on each other; the default user if left out
+    #[arg(long, requires = "raft_auth")]
+    raft_user: Option<String>,
+
+    /// Password raft members send with AUTH to each other
+    #[arg(long, requires = "raft")]
+    raft_auth: Option<String>,
+
+    /// Record every write in a change data capture stream readable with CDC READ
+    #[arg(long)]
+    cdc: bool,
+
+    /// Change events kept in memory for consumers to resume from
+    #[arg(long, default_value_t = DEFAULT_CDC_BACKLOG, requires = "cdc")]
+    cdc_backlog: usize,
+
+    /// Load users from this ACL file; ACL LOAD and ACL SAVE use it too
+    #[arg(long)]
+    aclfile: Option<String>,
+
+    /// Run as a sentinel that monitors a primary instead of serving data
+    #[arg(long, requires = "monitor")]
+    sentinel: bool,
+
+    /// Primary watched in sentinel mode
+    #[arg(long, num_args = 4, value_names = ["NAME", "HOST", "PORT", "QUORUM"])]
+    monitor: Option<Vec<String>>,
+
+    /// Milliseconds without a valid reply before the primary is considered down
+    #[arg(long, default_value_t = 30000)]
+    down_after_ms: u64,
+
+    /// Milliseconds a failover may take before it is abandoned and retried
+    #[arg(long, default_value_t = 180000)]
+    failover_timeout_ms: u64,
+
+    /// User a sentinel logs in as on the primary and replicas; the default user if left out
+    #[arg(long, requires = "auth_pass")]
+    auth_user: Option<String>,
+
+    /// Password a sentinel sends with AUTH to the primary and replicas
+    #[arg(long, requires = "sentinel")]
+    auth_pass: Option<String>,
 }
 
 #[tokio::main]
@@ -46,22 +142,30 @@ async fn main() -> std::io::Result<()> {
         config.server.port = port;
     }
 
+    // Initialize logging with the configured level; CONFIG SET loglevel and SIGHUP change it later
+    logging::init(&config.logging.level);
+
     // Wrap config in Arc<RwLock> for hot reload
     let config = Arc::new(RwLock::new(config));
 
-    // Initialize logging with configured level.
-    // Note: Dynamic log level change requires tracing-subscriber reload layer.
-    // Currently, config reload does not update the log level.
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .with_thread_ids(true)
-        .with_file(true)
-        .with_line_number(true)
-        .compact()
-        .init();
+    if args.sentinel {
+        let down_after = std::time::Duration::from_millis(args.down_after_ms);
+        let failover_timeout = std::time::Duration::from_millis(args.failover_timeout_ms);
+        let auth = Credentials::new(args.auth_user, args.auth_pass);
+        let cfg = config.read().await;
+        return run_sentinel(
+            args.monitor.as_deref(),
+            down_after,
+            failover_timeout,
+            auth,
+            &cfg,
+        )
+        .await;
+    }
 
     // Initialize metrics
-    hexagondb::observability::metrics::init_metrics();
+    hexagondb::observability::metrics::init_metrics(args.metrics_port);
+    STATS.start_cron();
 
     {
         let cfg = config.read().await;
@@ -75,30 +179,210 @@ async fn main() -> std::io::Result<()> {
     let db: DB = DB::new();
     let db = Arc::new(RwLock::new(db));
 
-    // Initialize AOF
-    let aof = Aof::new("database.aof")?;
-    if let Err(e) = Aof::load("database.aof", &db).await {
-        error!("Error loading AOF: {}", e);
+    // Initialize AOF. In raft mode the data is rebuilt from the raft snapshot and log instead,
+    // and with appendonly off the file is neither loaded nor written
+    let aof = Aof::new(commands::AOF_FILE)?;
+    if args.raft.is_none() && config.read().await.persistence.aof_enabled {
+        if let Err(e) = Aof::load(commands::AOF_FILE, &db).await {
+            error!("Error loading AOF: {}", e);
+        }
     }
     let aof = Arc::new(RwLock::new(aof));
 
-    // Initialize server info
-    let server_info = Arc::new(ServerInfo::new());
-
     // Start TCP server
     let addr = config.read().await.server_address();
     let listener = TcpListener::bind(&addr).await?;
 
-    // Limit max concurrent connections
+    // Load the TLS certificates; the TLS listener starts once the shared state is ready
+    let tls_config = config.read().await.server.tls.clone();
+    let tls = match &tls_config {
+        Some(tls_config) => Some(Arc::new(
+            Tls::new(tls_config).map_err(std::io::Error::other)?,
+        )),
+        None => None,
+    };
+
+    // Limit max concurrent connections; CONFIG SET maxclients resizes the limit
     let max_conn = config.read().await.server.max_connections;
-    let connection_limit = Arc::new(tokio::sync::Semaphore::new(max_conn));
+    let settings = Arc::new(Settings::new(&args.config, tls.clone(), max_conn));
+    let connection_limit = settings.connections();
 
     // Initialize PubSub
-    let pubsub = Arc::new(hexagondb::db::pubsub::PubSub::new());
+    let pubsub = Arc::new(PubSub::new());
+
+    // Initialize replication
+    let port = config.read().await.server.port;
+    let replication = Arc::new(Replication::new(
+        port,
+        Credentials::new(args.masteruser, args.masterauth),
+    ));
+    replication.start_cron();
+
+    // Initialize cluster mode
+    let cluster = if args.cluster {
+        let bind = config.read().await.server.bind_address.clone();
+        // 0.0.0.0 is not reachable from peers; the bus learns our address on first contact
+        let ip = if bind == "0.0.0.0" {
+            String::new()
+        } else {
+            bind.clone()
+        };
+        let cluster = Arc::new(Cluster::new(ip, port));
+        let bus_listener = TcpListener::bind((bind.as_str(), cluster.bus_port())).await?;
+        let id = cluster.state.lock().myself.clone();
+        info!(
+            "Cluster mode enabled. Node id {}, bus port {}",
+            id,
+            cluster.bus_port()
+        );
+        tokio::spawn(cluster::bus::serve(bus_listener, Arc::clone(&cluster)));
+        cluster::bus::start(Arc::clone(&cluster));
+        Some(cluster)
+    } else {
+        None
+    };
+
+    let cdc = if args.cdc {
+        info!(
+            "Change data capture enabled, keeping {} events",
+            args.cdc_backlog
+        );
+        Some(Arc::new(Cdc::new(args.cdc_backlog)))
+    } else {
+        None
+    };
+
+    let acl = match args.aclfile {
+        Some(path) => {
+            let acl = Acl::open(path.clone().into()).map_err(std::io::Error::other)?;
+            info!("ACL users loaded from {}", path);
+            Arc::new(acl)
+        }
+        None => Arc::new(Acl::new(None)),
+    };
+
+    let clients = Arc::new(Clients::new());
+    clients.set_limits(config.read().await.server.limits.clone());
+    clients.start_cron();
+
+    if let Some(primary) = args.replicaof {
+        let mut client = commands::Interpreter::new(
+            Arc::clone(&db),
+            Arc::clone(&aof),
+            Arc::clone(&config),
+            Arc::clone(&settings),
+            Arc::clone(&pubsub),
+            Arc::clone(&replication),
+            cluster.clone(),
+            None,
+            cdc.clone(),
+            Arc::clone(&acl),
+            Arc::clone(&clients),
+        );
+        let command = std::iter::once("REPLICAOF".to_string())
+            .chain(primary)
+            .map(|arg| RespValue::BulkString(Some(arg)))
+            .collect();
+        if let commands::ExecutionResult::Response(RespValue::Error(e)) =
+            client.execute(RespValue::Array(Some(command))).await
+        {
+            error!("Invalid --replicaof: {}", e);
+        }
+    }
+
+    // Initialize raft mode
+    let raft = match args.raft {
+        Some(addr) => {
+            let raft_config = RaftConfig {
+                addr,
+                peers: args.raft_peers,
+                dir: args.raft_dir.into(),
+                snapshot_entries: args.raft_snapshot_entries,
+                auth: Credentials::new(args.raft_user, args.raft_auth),
+            };
+            let raft = Raft::open(raft_config, Arc::clone(&db)).await?;
+            let client = commands::Interpreter::new(
+                Arc::clone(&db),
+                Arc::clone(&aof),
+                Arc::clone(&config),
+                Arc::clone(&settings),
+                Arc::clone(&pubsub),
+                Arc::clone(&replication),
+                None,
+                Some(Arc::clone(&raft)),
+                cdc.clone(),
+                Arc::clone(&acl),
+                Arc::clone(&clients),
+            );
+            raft.start(client).await;
+            Some(raft)
+        }
+        None => None,
+    };
+
+    let shared = Shared {
+        db: Arc::clone(&db),
+        aof: Arc::clone(&aof),
+        config: Arc::clone(&config),
+        settings: Arc::clone(&settings),
+        pubsub: Arc::clone(&pubsub),
+        replication: Arc::clone(&replication),
+        cluster: cluster.clone(),
+        raft: raft.clone(),
+        cdc: cdc.clone(),
+        acl: Arc::clone(&acl),
+        clients: Arc::clone(&clients),
+    };
+
+    // Start the TLS listener next to the plain TCP one
+    if let (Some(tls), Some(tls_config)) = (&tls, &tls_config) {
+        let bind = config.read().await.server.bind_address.clone();
+        let tls_listener = TcpListener::bind((bind.as_str(), tls_config.port)).await?;
+        info!("TLS listening on {}:{}", bind, tls_config.port);
+        if tls_config.ca_file.is_some() {
+            info!("TLS client certificates are required");
+        }
+        tokio::spawn(serve_tls(
+            tls_listener,
+            Arc::clone(tls),
+            shared.clone(),
+            Arc::clone(&connection_limit),
+        ));
+    }
+
+    // Start the Unix domain socket listener
+    let unix_config = config.read().await.server.unix_socket.clone();
+    if let Some(unix_config) = unix_config {
+        let (unix_listener, socket_file) =
+            unix::bind(&unix_config).map_err(std::io::Error::other)?;
+        info!("Unix socket listening on {}", unix_config.path);
+        // Remove the socket file when stopped by SIGINT or SIGTERM so the next start
+        // does not find a stale one
+        tokio::spawn(async move {
+            use tokio::signal::unix::{signal, SignalKind};
+            let mut terminate = signal(SignalKind::terminate()).unwrap();
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+            info!("Shutting down");
+            socket_file.remove();
+            std::process::exit(0);
+        });
+        tokio::spawn(serve_unix(
+            unix_listener,
+            unix_config.path,
+            shared.clone(),
+            Arc::clone(&connection_limit),
+        ));
+    }
 
     // Spawn signal handler for SIGHUP
     let config_clone = Arc::clone(&config);
     let config_path = args.config.clone();
+    let tls_clone = tls.clone();
+    let settings_clone = Arc::clone(&settings);
+    let clients_clone = Arc::clone(&clients);
     tokio::spawn(async move {
         use tokio::signal::unix::{signal, SignalKind};
         let mut stream = signal(SignalKind::hangup()).unwrap();
@@ -111,6 +395,26 @@ async fn main() -> std::io::Result<()> {
                     cfg.persistence = new_config.persistence;
                     cfg.logging = new_config.logging;
                     cfg.memory = new_config.memory;
+                    cfg.server.max_connections = new_config.server.max_connections;
+                    cfg.server.limits = new_config.server.limits;
+                    if let Err(e) = settings_clone.apply(&cfg, &clients_clone) {
+                        error!("Failed to apply configuration: {}", e);
+                    }
+                    // The TLS port stays the same; certificates are read again from the new
+                    // paths, which CONFIG GET and CONFIG REWRITE then report
+                    if let (Some(tls), Some(tls_config)) = (&tls_clone, &new_config.server.tls) {
+                        match tls.reload(tls_config) {
+                            Ok(()) => {
+                                if let Some(current) = cfg.server.tls.as_mut() {
+                                    current.cert_file = tls_config.cert_file.clone();
+                                    current.key_file = tls_config.key_file.clone();
+                                    current.ca_file = tls_config.ca_file.clone();
+                                }
+                                info!("TLS certificates reloaded")
+                            }
+                            Err(e) => error!("Failed to reload TLS certificates: {}", e),
+                        }
+                    }
                     info!("Configuration reloaded successfully");
                 }
                 Err(e) => error!("Failed to reload configuration: {}", e),
@@ -146,7 +450,9 @@ async fn main() -> std::io::Result<()> {
             if changes > 0 {
                 info!("Auto-save triggered: {} changes since last save", changes);
 
-                match hexagondb::persistence::snapshot::save("dump.rdb", &db_clone).await {
+                let result = hexagondb::persistence::snapshot::save("dump.rdb", &db_clone).await;
+                STATS.saved(result.is_ok());
+                match result {
                     Ok(_) => {
                         let db_guard = db_clone.read().await;
                         db_guard.reset_changes();
@@ -174,11 +480,7 @@ async fn main() -> std::io::Result<()> {
 
         match listener.accept().await {
             Ok((stream, addr)) => {
-                let db_clone = Arc::clone(&db);
-                let aof_clone = Arc::clone(&aof);
-                let info_clone = Arc::clone(&server_info);
-                let config_clone = Arc::clone(&config);
-                let pubsub_clone = Arc::clone(&pubsub);
+                let shared = shared.clone();
                 let limit_clone = Arc::clone(&connection_limit);
 
                 // Try to acquire permit
@@ -189,18 +491,14 @@ async fn main() -> std::io::Result<()> {
                             // permit is held until this block exits
                             let _permit = permit;
                             info!("New client connected: {}", addr);
-                            let mut client = commands::Interpreter::new(
-                                db_clone,
-                                aof_clone,
-                                info_clone,
-                                config_clone,
-                                pubsub_clone,
-                            );
-                            connection::handle_client(stream, &mut client).await;
+                            let mut client = shared.interpreter();
+                            let peer = Peer::tcp(addr, stream.local_addr().ok());
+                            connection::handle_client(stream, peer, &mut client).await;
                             info!("Client disconnected: {}", addr);
                         });
                     }
                     Err(_) => {
+                        STATS.rejected_connections.fetch_add(1, Ordering::Relaxed);
                         error!("Max connections reached. Rejecting client: {}", addr);
                         // Optional: Send error message to client before closing?
                         // stream.write_all(b"-ERR max number of clients reached\r\n").await.ok();
@@ -211,3 +509,177 @@ async fn main() -> std::io::Result<()> {
         }
     }
 }
+
+/// Shared server state every connection needs to build its interpreter
+#[derive(Clone)]
+struct Shared {
+    db: Arc<RwLock<DB>>,
+    aof: Arc<RwLock<Aof>>,
+    config: Arc<RwLock<Config>>,
+    settings: Arc<Settings>,
+    pubsub: Arc<PubSub>,
+    replication: Arc<Replication>,
+    cluster: Option<Arc<Cluster>>,
+    raft: Option<Arc<Raft>>,
+    cdc: Option<Arc<Cdc>>,
+    acl: Arc<Acl>,
+    clients: Arc<Clients>,
+}
+
+impl Shared {
+    fn interpreter(&self) -> commands::Interpreter {
+        commands::Interpreter::new(
+            Arc::clone(&self.db),
+            Arc::clone(&self.aof),
+            Arc::clone(&self.config),
+            Arc::clone(&self.settings),
+            Arc::clone(&self.pubsub),
+            Arc::clone(&self.replication),
+            self.cluster.clone(),
+            self.raft.clone(),
+            self.cdc.clone(),
+            Arc::clone(&self.acl),
+            Arc::clone(&self.clients),
+        )
+    }
+}
+
+/// Accepts TLS connections. With client certificate verification enabled, a client whose
+/// certificate CN names an enabled ACL user is logged in as that user.
+async fn serve_tls(
+    listener: TcpListener,
+    tls: Arc<Tls>,
+    shared: Shared,
+    connection_limit: Arc<Semaphore>,
+) {
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                error!("TLS connection error: {}", e);
+                continue;
+            }
+        };
+        let Ok(permit) = Arc::clone(&connection_limit).try_acquire_owned() else {
+            STATS.rejected_connections.fetch_add(1, Ordering::Relaxed);
+            error!("Max connections reached. Rejecting TLS client: {}", addr);
+            continue;
+        };
+        let peer = Peer::tcp(addr, stream.local_addr().ok());
+        let tls = Arc::clone(&tls);
+        let shared = shared.clone();
+        tokio::spawn(async move {
+            let _permit = permit;
+            let stream = match tls.accept(stream).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    error!("Rejecting TLS client {}: {}", addr, e);
+                    return;
+                }
+            };
+            let mut client = shared.interpreter();
+            // A client certificate must name an enabled ACL user; falling back to the
+            // default user would give any holder of a CA-signed certificate its rights
+            match tls::peer_common_name(&stream) {
+                Ok(Some(name)) if client.login_with_certificate(&name) => {
+                    info!("New TLS client connected: {} as user {}", addr, name)
+                }
+                Ok(Some(name)) => {
+                    error!(
+                        "Rejecting TLS client {}: certificate user {} does not exist or is disabled",
+                        addr, name
+                    );
+                    return;
+                }
+                Ok(None) => info!("New TLS client connected: {}", addr),
+                Err(e) => {
+                    error!("Rejecting TLS client {}: {}", addr, e);
+                    return;
+                }
+            }
+            connection::handle_client(stream, peer, &mut client).await;
+            info!("Client disconnected: {}", addr);
+        });
+    }
+}
+
+/// Accepts connections on the Unix domain socket. Access is controlled by the socket
+/// file's permissions; ACL rules still apply to the commands.
+async fn serve_unix(
+    listener: UnixListener,
+    path: String,
+    shared: Shared,
+    connection_limit: Arc<Semaphore>,
+) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                error!("Unix socket connection error: {}", e);
+                continue;
+            }
+        };
+        let Ok(permit) = Arc::clone(&connection_limit).try_acquire_owned() else {
+            STATS.rejected_connections.fetch_add(1, Ordering::Relaxed);
+            error!("Max connections reached. Rejecting unix socket client");
+            continue;
+        };
+        let peer = Peer::unix(&path);
+        let shared = shared.clone();
+        tokio::spawn(async move {
+            let _permit = permit;
+            info!("New unix socket client connected");
+            let mut client = shared.interpreter();
+            connection::handle_client(stream, peer, &mut client).await;
+            info!("Unix socket client disconnected");
+        });
+    }
+}
+
+/// Runs the sentinel monitor instead of the database server.
+async fn run_sentinel(
+    monitor: Option<&[String]>,
+    down_after: std::time::Duration,
+    failover_timeout: std::time::Duration,
+    auth: Option<Credentials>,
+    config: &Config,
+) -> std::io::Result<()> {
+    let invalid =
+        |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg.to_string());
+    let Some([name, host, port, quorum]) = monitor else {
+        return Err(invalid(
+            "--sentinel requires --monitor NAME HOST PORT QUORUM",
+        ));
+    };
+    let sentinel_config = SentinelConfig {
+        name: name.clone(),
+        master: Addr::new(
+            host.as_str(),
+            port.parse().map_err(|_| invalid("invalid primary port"))?,
+        ),
+        quorum: quorum
+            .parse()
+            .ok()
+            .filter(|&quorum| quorum > 0)
+            .ok_or_else(|| invalid("quorum must be a positive integer"))?,
+        down_after,
+        failover_timeout,
+        announce_port: config.server.port,
+        auth,
+    };
+
+    let listener = TcpListener::bind(config.server_address()).await?;
+    let sentinel = Arc::new(Sentinel::new(&sentinel_config));
+    info!(
+        "Sentinel {} listening on {}, monitoring {} at {} (quorum {})",
+        sentinel.run_id,
+        config.server_address(),
+        sentinel_config.name,
+        sentinel_config.master,
+        sentinel_config.quorum
+    );
+
+    tokio::spawn(sentinel::monitor::run(Arc::clone(&sentinel)));
+    sentinel::server::serve(listener, sentinel).await;
+    Ok(())
+}
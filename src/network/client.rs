@@ -0,0 +1,158 @@
+//! Diğer sunuculara açılan giden RESP bağlantıları (sentinel, raft, cluster bus, MIGRATE).
+
+use crate::network::parser::parse_value;
+use crate::network::resp::RespValue;
+use std::io;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+fn timed_out() -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, "request timed out")
+}
+
+/// Giden bağlantıların `AUTH` ile gönderdiği kullanıcı ve parola. Kullanıcı verilmezse
+/// karşı tarafta `default` kullanıcısıyla giriş yapılır.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Credentials {
+    pub user: Option<String>,
+    pub password: String,
+}
+
+impl Credentials {
+    /// Parola verilmediyse giriş yapılmaz.
+    pub fn new(user: Option<String>, password: Option<String>) -> Option<Self> {
+        password.map(|password| Credentials { user, password })
+    }
+
+    /// `AUTH [user] password`
+    pub fn auth_command(&self) -> Vec<&str> {
+        let mut args = vec!["AUTH"];
+        args.extend(self.user.as_deref());
+        args.push(&self.password);
+        args
+    }
+}
+
+/// Tek bir sunucuya açık bağlantı ve okunmuş ama işlenmemiş baytlar.
+pub struct Connection {
+    stream: TcpStream,
+    buffer: Vec<u8>,
+    /// Bağlantı kurma ve tek bir cevabı bekleme süresi sınırı
+    timeout: Duration,
+}
+
+impl Connection {
+    pub async fn connect(host: &str, port: u16, timeout: Duration) -> io::Result<Self> {
+        let stream = tokio::time::timeout(timeout, TcpStream::connect((host, port)))
+            .await
+            .map_err(|_| timed_out())??;
+        Ok(Connection {
+            stream,
+            buffer: Vec::new(),
+            timeout,
+        })
+    }
+
+    /// Bağlantı kurup kimlik bilgisi verildiyse giriş yapar.
+    pub async fn connect_auth(
+        host: &str,
+        port: u16,
+        timeout: Duration,
+        credentials: Option<&Credentials>,
+    ) -> io::Result<Self> {
+        let mut connection = Connection::connect(host, port, timeout).await?;
+        if let Some(credentials) = credentials {
+            expect_ok(connection.request(&credentials.auth_command()).await?)?;
+        }
+        Ok(connection)
+    }
+
+    /// Bağlantının yerel adresi; hello mesajlarında duyurulan IP budur.
+    pub fn local_ip(&self) -> io::Result<String> {
+        Ok(self.stream.local_addr()?.ip().to_string())
+    }
+
+    pub async fn send(&mut self, args: &[&str]) -> io::Result<()> {
+        let command = RespValue::Array(Some(
+            args.iter()
+                .map(|arg| RespValue::BulkString(Some(arg.to_string())))
+                .collect(),
+        ));
+        self.stream.write_all(command.serialize().as_bytes()).await
+    }
+
+    /// Sıradaki cevabı bekler; süre sınırı yoktur.
+    pub async fn read(&mut self) -> io::Result<RespValue> {
+        let mut temp_buf = [0u8; 4096];
+        loop {
+            if let Some((reply, len)) = parse_value(&self.buffer).map_err(io::Error::other)? {
+                self.buffer.drain(..len);
+                return Ok(reply);
+            }
+            let n = self.stream.read(&mut temp_buf).await?;
+            if n == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "connection closed",
+                ));
+            }
+            self.buffer.extend_from_slice(&temp_buf[..n]);
+        }
+    }
+
+    /// Sıradaki cevabı bağlantının süre sınırı içinde bekler.
+    pub async fn read_timeout(&mut self) -> io::Result<RespValue> {
+        tokio::time::timeout(self.timeout, self.read())
+            .await
+            .map_err(|_| timed_out())?
+    }
+
+    /// Komutu gönderir ve cevabını bağlantının süre sınırı içinde bekler.
+    pub async fn request(&mut self, args: &[&str]) -> io::Result<RespValue> {
+        self.send(args).await?;
+        tokio::time::timeout(self.timeout, self.read())
+            .await
+            .map_err(|_| timed_out())?
+    }
+}
+
+/// Tek seferlik bağlantıyla bir komut çalıştırır.
+pub async fn request(
+    host: &str,
+    port: u16,
+    timeout: Duration,
+    args: &[&str],
+) -> io::Result<RespValue> {
+    Connection::connect(host, port, timeout)
+        .await?
+        .request(args)
+        .await
+}
+
+/// Cevap hata ise `Err` döndürür.
+pub fn expect_ok(reply: RespValue) -> io::Result<RespValue> {
+    match reply {
+        RespValue::Error(e) => Err(io::Error::other(e)),
+        reply => Ok(reply),
+    }
+}
+
+/// Bulk string cevabın içeriği.
+pub fn bulk_text(reply: &RespValue) -> Option<&str> {
+    match reply {
+        RespValue::BulkString(Some(text)) | RespValue::SimpleString(text) => Some(text),
+        _ => None,
+    }
+}
+
+/// Pub/sub push mesajıysa (`message`, kanal, içerik) içeriği döndürür.
+pub fn pushed_message(reply: &RespValue) -> Option<&str> {
+    let RespValue::Array(Some(items)) = reply else {
+        return None;
+    };
+    match &items[..] {
+        [kind, _, message] if bulk_text(kind) == Some("message") => bulk_text(message),
+        _ => None,
+    }
+}
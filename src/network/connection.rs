@@ -1,10 +1,17 @@
+use crate::clients::{Client, ClientKind, Peer};
 use crate::commands::{ExecutionResult, Interpreter};
-use crate::network::resp::{RespHandler, RespValue};
+use crate::network::limits::Limits;
+use crate::network::parser::{Request, RequestParser};
+use crate::network::resp::RespValue;
 use crate::observability::metrics::{METRIC_ACTIVE_CONNECTIONS, METRIC_CONNECTIONS_TOTAL};
+use crate::observability::stats::STATS;
+use crate::replication::primary;
 use metrics::{counter, gauge};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
-use tracing::{debug, error, info, instrument, Instrument};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::broadcast;
+use tracing::{debug, error, info, instrument, warn, Instrument};
 use uuid::Uuid;
 
 struct ConnectionGuard;
@@ -15,56 +22,179 @@ impl Drop for ConnectionGuard {
     }
 }
 
+/// Çıkış sınırını aşan bağlantı yazma beklerken de kapatılabilsin diye yazma, bağlantının
+/// kapatılmasıyla yarışır.
+async fn write<S>(stream: &mut S, conn: &Client, bytes: &[u8]) -> std::io::Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    tokio::select! {
+        result = stream.write_all(bytes) => result,
+        _ = conn.killed() => Err(std::io::Error::other("client killed")),
+    }
+}
+
+/// Abone olunan kanalın mesajlarını bağlantının kuyruğuna aktarır. Kuyruk çıkış sınırını
+/// aştığında bağlantı kapatılır ve aktarma biter.
+async fn forward_messages(
+    channel: String,
+    mut receiver: broadcast::Receiver<String>,
+    conn: Arc<Client>,
+) {
+    loop {
+        tokio::select! {
+            _ = conn.killed() => return,
+            msg = receiver.recv() => match msg {
+                Ok(msg_content) => {
+                    let push_msg = RespValue::Array(Some(vec![
+                        RespValue::BulkString(Some("message".to_string())),
+                        RespValue::BulkString(Some(channel.clone())),
+                        RespValue::BulkString(Some(msg_content)),
+                    ]));
+                    conn.push(push_msg.serialize());
+                }
+                Err(e) => {
+                    // Kanal kapandı ya da mesajlar kaçırıldı
+                    error!("Broadcast receive error: {}", e);
+                    conn.kill();
+                    return;
+                }
+            },
+        }
+    }
+}
+
+/// Abonelik modunda buffer'daki bütün istekleri sırayla işler. Bağlantı abonelikten
+/// çıkacaksa (UNSUBSCRIBE, QUIT, protokol ya da yazma hatası) `false` döner.
+async fn subscribed_requests<S>(stream: &mut S, conn: &Client, parser: &mut RequestParser) -> bool
+where
+    S: AsyncWrite + Unpin,
+{
+    loop {
+        let request = match next_request(parser, &conn.limits()) {
+            Ok(Some(request)) => request,
+            Ok(None) => return true,
+            Err((reason, _)) => {
+                warn!("Closing client: {}", reason);
+                return false;
+            }
+        };
+        if request.is_empty() {
+            continue;
+        }
+        let cmd_upper = String::from_utf8_lossy(request.arg(0)).to_uppercase();
+        if cmd_upper == "UNSUBSCRIBE" || cmd_upper == "QUIT" {
+            // UNSUBSCRIBE sonrası normal moda dönülmez, bağlantı kapanır
+            return false;
+        } else if cmd_upper == "PING" {
+            let pong = RespValue::SimpleString("PONG".to_string()).serialize();
+            if !conn.queue(pong.len()) {
+                return false;
+            }
+            if let Err(e) = write(stream, conn, pong.as_bytes()).await {
+                error!("Failed to send PONG: {}", e);
+                return false;
+            }
+            conn.sent(pong.len());
+        }
+    }
+}
+
+/// Buffer'daki sıradaki tam istek. Girdi buffer'ı sınırın üstündeyse ya da istek
+/// protokole uymuyorsa bağlantının kapatılma sebebi döner; protokol hataları istemciye de
+/// bildirilir.
+fn next_request(
+    parser: &mut RequestParser,
+    limits: &Limits,
+) -> Result<Option<Request>, (String, Option<String>)> {
+    if parser.buffered() > limits.query_buffer {
+        return Err((
+            format!(
+                "query buffer of {} bytes exceeds limit of {} bytes",
+                parser.buffered(),
+                limits.query_buffer
+            ),
+            None,
+        ));
+    }
+    parser
+        .next_request(limits)
+        .map_err(|e| (e.clone(), Some(e)))
+}
+
 /// Her bir istemci bağlantısını işler.
-/// Gelen veriyi buffer'a alır, RESP formatında parse eder, komutu işler ve cevap gönderir.
-#[instrument(skip(stream, client), fields(connection_id = %Uuid::new_v4()))]
-pub async fn handle_client(mut stream: TcpStream, client: &mut Interpreter) {
+/// Gelen veriyi parser'ın buffer'ına alır, RESP formatında parse eder, komutu işler ve cevap gönderir.
+/// Düz TCP, TLS ve Unix socket bağlantıları aynı yoldan geçer. Bağlantı süresince istemci
+/// kaydında yer alır ve `CLIENT KILL` ile kapatılabilir. Girdi ve çıkış buffer'ları
+/// sınırları aştığında bağlantı kapatılır.
+#[instrument(skip(stream, peer, client), fields(client_id))]
+pub async fn handle_client<S>(mut stream: S, peer: Peer, client: &mut Interpreter)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
     counter!(METRIC_CONNECTIONS_TOTAL).increment(1);
+    STATS.connections_received.fetch_add(1, Ordering::Relaxed);
     gauge!(METRIC_ACTIVE_CONNECTIONS).increment(1.0);
     let _guard = ConnectionGuard;
 
+    let peer_ip = peer.ip.clone();
+    let (conn, mut pushes) = client.connect(peer);
+    tracing::Span::current().record("client_id", conn.id);
     info!("New connection established");
 
-    // Sabit buffer yerine dinamik bir buffer kullanıyoruz.
-    // Bu sayede parça parça gelen verileri birleştirebiliriz.
-    let mut buffer = Vec::new();
-    let mut temp_buf = [0u8; 1024]; // Ağdan okuma yapmak için geçici buffer
+    // Okunan veri doğrudan parser'ın buffer'ına eklenir. Parça parça gelen istekler
+    // kaldığı yerden çözülmeye devam eder.
+    let mut parser = RequestParser::new();
 
     loop {
-        match stream.read(&mut temp_buf).await {
+        let read_result = tokio::select! {
+            _ = conn.killed() => {
+                debug!("Client killed");
+                return;
+            }
+            read_result = stream.read_buf(parser.buffer_mut()) => read_result,
+            // CLIENT TRACKING geçersiz kılma mesajları
+            Some(frame) = pushes.recv() => {
+                if let Err(e) = write(&mut stream, &conn, frame.as_bytes()).await {
+                    error!("Failed to send push message: {}", e);
+                    return;
+                }
+                conn.sent(frame.len());
+                continue;
+            }
+        };
+        match read_result {
             Ok(bytes_read) => {
                 if bytes_read == 0 {
                     debug!("Client closed the connection");
                     return;
                 }
 
-                // Okunan veriyi ana buffer'a ekle
-                buffer.extend_from_slice(&temp_buf[..bytes_read]);
+                conn.received(parser.buffered(), parser.capacity());
+                let limits = conn.limits();
 
                 // Pipelining desteği: Tüm mevcut komutları işle
-                let mut responses = Vec::new();
+                let mut responses: Vec<String> = Vec::new();
 
                 loop {
-                    // Buffer boşsa döngüden çık, yeni veri bekle
-                    if buffer.is_empty() {
-                        break;
-                    }
-
                     // Gelen veriyi RESP formatında parse etmeye çalış
-                    match RespHandler::parse_request(&buffer) {
-                        Ok(Some((request, len))) => {
+                    match next_request(&mut parser, &limits) {
+                        Ok(Some(request)) => {
                             // Başarılı bir şekilde tam bir komut parse edildi
 
                             // Komutu çalıştır
                             let request_id = Uuid::new_v4();
                             let span = tracing::info_span!("request", %request_id);
 
-                            match client.execute(request).instrument(span).await {
-                                ExecutionResult::Response(response) => {
-                                    // Cevabı topla (pipelining için)
-                                    responses.push(response);
-                                }
-                                ExecutionResult::Subscribe(channel, mut receiver) => {
+                            let frame = match client
+                                .execute(request.to_resp())
+                                .instrument(span)
+                                .await
+                            {
+                                ExecutionResult::Response(response) => response.serialize(),
+                                ExecutionResult::Raw(frame) => frame,
+                                ExecutionResult::Subscribe(channel, receiver) => {
+                                    conn.set_kind(ClientKind::PubSub);
                                     // Abonelik moduna geç
                                     // İlk olarak abonelik onayını gönder
                                     let success_resp = RespValue::Array(Some(vec![
@@ -74,89 +204,59 @@ pub async fn handle_client(mut stream: TcpStream, client: &mut Interpreter) {
                                     ]));
 
                                     let response_bytes = success_resp.serialize();
+                                    if !conn.queue(response_bytes.len()) {
+                                        return;
+                                    }
                                     if let Err(e) =
-                                        stream.write_all(response_bytes.as_bytes()).await
+                                        write(&mut stream, &conn, response_bytes.as_bytes()).await
                                     {
                                         error!("Failed to send subscribe response: {}", e);
                                         return;
                                     }
+                                    conn.sent(response_bytes.len());
+
+                                    // Kanal mesajları yayınlandıkları anda bağlantının kuyruğuna
+                                    // alınır; yavaş bir istemcinin birikmiş mesajları da çıkış
+                                    // sınırına sayılır
+                                    let forwarder = tokio::spawn(forward_messages(
+                                        channel,
+                                        receiver,
+                                        Arc::clone(&conn),
+                                    ));
 
                                     // Abonelik döngüsü
                                     // Hem kanaldan gelen mesajları hem de istemciden gelen komutları dinliyoruz.
-                                    loop {
+                                    // SUBSCRIBE ile aynı okumada gelen komutlar önce işlenir.
+                                    let mut subscribed =
+                                        subscribed_requests(&mut stream, &conn, &mut parser).await;
+                                    while subscribed {
                                         tokio::select! {
-                                            // 1. Kanaldan gelen mesajlar
-                                            msg = receiver.recv() => {
-                                                match msg {
-                                                    Ok(msg_content) => {
-                                                        let push_msg = RespValue::Array(Some(vec![
-                                                            RespValue::BulkString(Some("message".to_string())),
-                                                            RespValue::BulkString(Some(channel.clone())),
-                                                            RespValue::BulkString(Some(msg_content)),
-                                                        ]));
-
-                                                        let push_bytes = push_msg.serialize();
-                                                        if let Err(e) = stream.write_all(push_bytes.as_bytes()).await {
-                                                            error!("Failed to send push message: {}", e);
-                                                            break;
-                                                        }
-                                                    }
-                                                    Err(e) => {
-                                                        // Kanal kapandı veya hata oluştu
-                                                        error!("Broadcast receive error: {}", e);
-                                                        break;
-                                                    }
+                                            _ = conn.killed() => {
+                                                debug!("Client killed during subscribe");
+                                                break;
+                                            }
+
+                                            // Kanal mesajları ve CLIENT TRACKING REDIRECT ile bu
+                                            // bağlantıya yönlendirilen mesajlar
+                                            Some(frame) = pushes.recv() => {
+                                                if let Err(e) = write(&mut stream, &conn, frame.as_bytes()).await {
+                                                    error!("Failed to send push message: {}", e);
+                                                    break;
                                                 }
+                                                conn.sent(frame.len());
                                             }
 
-                                            // 2. İstemciden gelen veriler (UNSUBSCRIBE, QUIT vb.)
-                                            read_result = stream.read(&mut temp_buf) => {
+                                            // İstemciden gelen veriler (UNSUBSCRIBE, QUIT vb.)
+                                            read_result = stream.read_buf(parser.buffer_mut()) => {
                                                 match read_result {
                                                     Ok(0) => {
                                                         // Bağlantı koptu
                                                         debug!("Client closed connection during subscribe");
                                                         break;
                                                     }
-                                                    Ok(n) => {
-                                                        // Veriyi buffer'a ekle
-                                                        buffer.extend_from_slice(&temp_buf[..n]);
-
-                                                        // Buffer'daki komutları işle
-                                                        // Not: Basitlik için burada sadece buffer'ın başındaki komuta bakıyoruz.
-                                                        // Gerçek bir implementasyonda döngü içinde tüm komutları işlemeliyiz.
-                                                        match RespHandler::parse_request(&buffer) {
-                                                            Ok(Some((request, len))) => {
-                                                                // Buffer'dan işlenen kısmı sil
-                                                                buffer.drain(0..len);
-
-                                                                if let RespValue::Array(Some(tokens)) = &request {
-                                                                    if !tokens.is_empty() {
-                                                                        if let RespValue::BulkString(Some(cmd)) = &tokens[0] {
-                                                                            let cmd_upper = cmd.to_uppercase();
-                                                                            if cmd_upper == "UNSUBSCRIBE" || cmd_upper == "QUIT" {
-                                                                                // Döngüden çık, normal moda dön veya bağlantıyı kapat
-                                                                                // UNSUBSCRIBE durumunda normal moda dönmek gerekebilir ama şimdilik çıkıyoruz.
-                                                                                break;
-                                                                            } else if cmd_upper == "PING" {
-                                                                                // PONG gönder
-                                                                                let pong = RespValue::SimpleString("PONG".to_string());
-                                                                                if let Err(e) = stream.write_all(pong.serialize().as_bytes()).await {
-                                                                                     error!("Failed to send PONG: {}", e);
-                                                                                     break;
-                                                                                }
-                                                                            }
-                                                                        }
-                                                                    }
-                                                                }
-                                                            }
-                                                            Ok(None) => {
-                                                                // Veri eksik, devam et
-                                                            }
-                                                            Err(e) => {
-                                                                error!("Failed to parse request in subscribe mode: {}", e);
-                                                                break;
-                                                            }
-                                                        }
+                                                    Ok(_) => {
+                                                        conn.received(parser.buffered(), parser.capacity());
+                                                        subscribed = subscribed_requests(&mut stream, &conn, &mut parser).await;
                                                     }
                                                     Err(e) => {
                                                         error!("Failed to read from socket in subscribe mode: {}", e);
@@ -166,38 +266,79 @@ pub async fn handle_client(mut stream: TcpStream, client: &mut Interpreter) {
                                             }
                                         }
                                     }
+                                    forwarder.abort();
                                     // Döngüden çıkınca fonksiyon bitiyor ve bağlantı kapanıyor.
                                     // Normalde UNSUBSCRIBE sonrası normal moda dönmek gerekir (recursive call veya loop yapısı değişikliği ile).
                                     return;
                                 }
-                            }
+                                ExecutionResult::Psync(sync_request) => {
+                                    // Bağlantı artık bir replikaya ait: önce bekleyen cevapları
+                                    // gönder, sonra bağlantıyı replikasyon akışına devret
+                                    for response_bytes in responses.drain(..) {
+                                        if let Err(e) =
+                                            write(&mut stream, &conn, response_bytes.as_bytes())
+                                                .await
+                                        {
+                                            error!("Failed to send response: {}", e);
+                                            return;
+                                        }
+                                        conn.sent(response_bytes.len());
+                                    }
+                                    conn.set_kind(ClientKind::Replica);
+                                    tokio::select! {
+                                        _ = primary::serve(
+                                            &mut stream,
+                                            peer_ip,
+                                            parser.take_buffer(),
+                                            sync_request,
+                                            Arc::clone(&conn),
+                                        ) => {}
+                                        _ = conn.killed() => debug!("Replica connection killed"),
+                                    }
+                                    return;
+                                }
+                            };
 
-                            // İşlenen kısmı buffer'dan sil (drain)
-                            buffer.drain(0..len);
+                            // Cevap gönderilene kadar çıkış buffer'ında bekler
+                            if !conn.queue(frame.len()) {
+                                return;
+                            }
+                            // Cevabı topla (pipelining için)
+                            responses.push(frame);
                         }
                         Ok(None) => {
                             // Veri eksik, daha fazla veri bekle
                             break;
                         }
-                        Err(e) => {
-                            error!("Failed to parse request: {}", e);
-                            // Hatalı veriyi temizle veya bağlantıyı kapat
+                        Err((reason, reply)) => {
+                            warn!("Closing client: {}", reason);
+                            // Hatalı istekten önceki komutların cevapları yine gönderilir
+                            if let Some(reply) = reply {
+                                responses.push(RespValue::Error(reply).serialize());
+                                let _ =
+                                    write(&mut stream, &conn, responses.concat().as_bytes()).await;
+                            }
                             return;
                         }
                     }
                 }
 
+                conn.received(parser.buffered(), parser.capacity());
+
                 // Pipelining: Tüm cevapları birlikte gönder
-                if !responses.is_empty() {
-                    for response in responses {
-                        let response_bytes = response.serialize();
-                        if let Err(e) = stream.write_all(response_bytes.as_bytes()).await {
-                            error!("Failed to send pipelined response: {}", e);
-                            return;
-                        }
+                for response_bytes in responses {
+                    if let Err(e) = write(&mut stream, &conn, response_bytes.as_bytes()).await {
+                        error!("Failed to send pipelined response: {}", e);
+                        return;
                     }
+                    conn.sent(response_bytes.len());
                 }
             }
+            // TLS istemcileri bağlantıyı close_notify göndermeden de kapatabilir
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                debug!("Client closed the connection");
+                return;
+            }
             Err(e) => {
                 error!("Failed to read from socket: {}", e);
                 return;
@@ -205,3 +346,90 @@ pub async fn handle_client(mut stream: TcpStream, client: &mut Interpreter) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::{Clients, Peer};
+    use crate::network::limits::OutputLimit;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_kill_blocked_subscriber() {
+        let clients = Arc::new(Clients::new());
+        let mut limits = Limits::default();
+        limits.output.pubsub = OutputLimit {
+            hard: 1024 * 1024,
+            ..Default::default()
+        };
+        clients.set_limits(limits);
+        let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 5000));
+        let (registration, mut pushes) = clients.register(Peer::tcp(addr, None), None);
+        let conn = Arc::clone(&registration);
+        conn.set_kind(ClientKind::PubSub);
+
+        let (publisher, receiver) = broadcast::channel(16);
+        let forwarder = tokio::spawn(forward_messages(
+            "news".to_string(),
+            receiver,
+            Arc::clone(&conn),
+        ));
+        // İstemci soketini hiç okumuyor; yazma ilk mesajda takılır
+        let (mut stream, _reader) = tokio::io::duplex(64);
+        let writer = {
+            let conn = Arc::clone(&conn);
+            tokio::spawn(async move {
+                while let Some(frame) = pushes.recv().await {
+                    write(&mut stream, &conn, frame.as_bytes()).await?;
+                    conn.sent(frame.len());
+                }
+                Ok::<_, std::io::Error>(())
+            })
+        };
+        publisher.send("x".repeat(1024)).unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!writer.is_finished());
+
+        // CLIENT KILL hem yazan döngüyü hem mesaj aktaran görevi bitirir
+        conn.kill();
+        let written = tokio::time::timeout(Duration::from_secs(1), writer)
+            .await
+            .expect("writer is still blocked")
+            .unwrap();
+        assert_eq!(written.unwrap_err().to_string(), "client killed");
+        tokio::time::timeout(Duration::from_secs(1), forwarder)
+            .await
+            .expect("forwarder is still running")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_subscribed_pipeline() {
+        let clients = Arc::new(Clients::new());
+        let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 5000));
+        let (registration, _pushes) = clients.register(Peer::tcp(addr, None), None);
+        let conn = Arc::clone(&registration);
+        let (mut stream, mut reader) = tokio::io::duplex(1024);
+
+        // Tek okumada gelen bütün komutlar işlenir
+        let mut parser = RequestParser::new();
+        parser
+            .buffer_mut()
+            .extend_from_slice(b"PING\r\n*1\r\n$4\r\nPING\r\nPING\r\n");
+        assert!(subscribed_requests(&mut stream, &conn, &mut parser).await);
+        assert_eq!(parser.buffered(), 0);
+        let mut replies = vec![0; 21];
+        reader.read_exact(&mut replies).await.unwrap();
+        assert_eq!(replies, b"+PONG\r\n".repeat(3));
+
+        // UNSUBSCRIBE sonrası kalan komutlar işlenmez
+        parser
+            .buffer_mut()
+            .extend_from_slice(b"PING\r\nUNSUBSCRIBE\r\nPING\r\n");
+        assert!(!subscribed_requests(&mut stream, &conn, &mut parser).await);
+        drop(stream);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, b"+PONG\r\n");
+    }
+}
@@ -0,0 +1,141 @@
+//! Inline istekler: redis-cli ve telnet ile yazılan `SET k "hello world"` biçimindeki tek
+//! satırlık komutlar. Argümanlar boşlukla ayrılır; çift tırnak içinde `\n`, `\r`, `\t`,
+//! `\b`, `\a`, `\xHH` ve `\"` kaçışları, tek tırnak içinde yalnızca `\'` geçerlidir.
+//! Kapanan tırnaktan sonra boşluk ya da satır sonu gelmelidir.
+
+use std::ops::Range;
+
+pub const UNBALANCED_QUOTES: &str = "unbalanced quotes in request";
+
+fn is_space(byte: u8) -> bool {
+    matches!(
+        byte,
+        b' ' | b'\n' | b'\r' | b'\t' | b'\0' | b'\x0b' | b'\x0c'
+    )
+}
+
+fn hex_value(byte: Option<&u8>) -> Option<u8> {
+    (*byte? as char).to_digit(16).map(|digit| digit as u8)
+}
+
+/// Satırı argümanlara böler. Tırnaklar ve kaçışlar çözülürken satırın üzerine yazılır;
+/// çözülmüş hali hiçbir zaman uzun olmadığı için kopya gerekmez. Dönen aralıklar
+/// argümanların `line` içindeki yeridir.
+pub fn split_in_place(line: &mut [u8]) -> Result<Vec<Range<usize>>, String> {
+    let mut args = Vec::new();
+    let (mut read, mut write) = (0, 0);
+    loop {
+        while read < line.len() && is_space(line[read]) {
+            read += 1;
+        }
+        if read == line.len() {
+            return Ok(args);
+        }
+
+        let start = write;
+        let mut quote = None;
+        loop {
+            let Some(&byte) = line.get(read) else {
+                if quote.is_some() {
+                    return Err(UNBALANCED_QUOTES.to_string());
+                }
+                break;
+            };
+            let (value, used) = match quote {
+                None if is_space(byte) => break,
+                None if byte == b'"' || byte == b'\'' => {
+                    quote = Some(byte);
+                    read += 1;
+                    continue;
+                }
+                Some(open) if byte == open => {
+                    if line.get(read + 1).is_some_and(|next| !is_space(*next)) {
+                        return Err(UNBALANCED_QUOTES.to_string());
+                    }
+                    read += 1;
+                    break;
+                }
+                Some(b'"') if byte == b'\\' && read + 1 < line.len() => {
+                    match (
+                        line[read + 1],
+                        hex_value(line.get(read + 2)),
+                        hex_value(line.get(read + 3)),
+                    ) {
+                        (b'x', Some(high), Some(low)) => (high * 16 + low, 4),
+                        (b'n', ..) => (b'\n', 2),
+                        (b'r', ..) => (b'\r', 2),
+                        (b't', ..) => (b'\t', 2),
+                        (b'b', ..) => (0x08, 2),
+                        (b'a', ..) => (0x07, 2),
+                        (other, ..) => (other, 2),
+                    }
+                }
+                Some(b'\'') if byte == b'\\' && line.get(read + 1) == Some(&b'\'') => (b'\'', 2),
+                _ => (byte, 1),
+            };
+            line[write] = value;
+            write += 1;
+            read += used;
+        }
+        args.push(start..write);
+    }
+}
+
+/// Satırı argümanlara böler; gömülü kullanım için kopyalayan sürüm.
+pub fn split_args(line: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+    let mut line = line.to_vec();
+    let args = split_in_place(&mut line)?;
+    Ok(args.into_iter().map(|range| line[range].to_vec()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(line: &str) -> Result<Vec<String>, String> {
+        split_args(line.as_bytes()).map(|args| {
+            args.into_iter()
+                .map(|arg| String::from_utf8_lossy(&arg).into_owned())
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_quotes_and_escapes() {
+        assert_eq!(split("  SET k  v ").unwrap(), vec!["SET", "k", "v"]);
+        assert_eq!(
+            split(r#"SET k "hello world""#).unwrap(),
+            vec!["SET", "k", "hello world"]
+        );
+        assert_eq!(
+            split(r#"SET k "a\"b\\c\n\x41\x4a\xzz""#).unwrap(),
+            vec!["SET", "k", "a\"b\\c\nAJxzz"]
+        );
+        assert_eq!(
+            split(r#"SET k 'it\'s "raw" \n'"#).unwrap(),
+            vec!["SET", "k", r#"it's "raw" \n"#]
+        );
+        assert_eq!(split(r#"SET k "" ''"#).unwrap(), vec!["SET", "k", "", ""]);
+        // Tırnak argümanın ortasında da başlayabilir
+        assert_eq!(split(r#"SET k a"b c""#).unwrap(), vec!["SET", "k", "ab c"]);
+        assert!(split("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unbalanced_quotes() {
+        assert_eq!(split(r#"SET k "hello"#), Err(UNBALANCED_QUOTES.to_string()));
+        assert!(split("SET k 'hello").is_err());
+        assert!(split(r#"SET k "a\"#).is_err());
+        // Kapanan tırnağın hemen ardından argüman devam edemez
+        assert!(split(r#"SET k "a"b"#).is_err());
+        assert!(split("SET k 'a'b").is_err());
+    }
+
+    #[test]
+    fn test_binary_escape() {
+        assert_eq!(
+            split_args(br#""\x00\xff""#).unwrap(),
+            vec![vec![0x00, 0xff]]
+        );
+    }
+}
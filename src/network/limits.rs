@@ -0,0 +1,139 @@
+//! Bağlantı başına bellek sınırları. İstemcinin gönderdiği istek, bildirdiği dizi ve bulk
+//! uzunluklarına göre parser'da kontrol edilir; böylece `*2147483647` gibi bir başlık
+//! bellek ayırtamaz. Cevaplar için sınıf başına (normal, pubsub, replika) sert ve
+//! yumuşak çıkış sınırları vardır; aşan bağlantı kapatılır.
+
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+/// Satır sonu gelmemiş bir inline isteğin ya da başlık satırının en fazla uzunluğu
+pub const INLINE_MAX: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Limits {
+    /// Bağlantının işlenmemiş girdi buffer'ının en fazla boyutu
+    pub query_buffer: usize,
+    /// Tek bir bulk argümanın en fazla uzunluğu
+    pub max_bulk_len: usize,
+    /// Bir istekteki en fazla argüman sayısı
+    pub max_multibulk_len: usize,
+    pub output: OutputLimits,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            query_buffer: 1024 * 1024 * 1024,
+            max_bulk_len: 512 * 1024 * 1024,
+            max_multibulk_len: 1024 * 1024,
+            output: OutputLimits::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct OutputLimits {
+    pub normal: OutputLimit,
+    pub replica: OutputLimit,
+    pub pubsub: OutputLimit,
+}
+
+impl Default for OutputLimits {
+    fn default() -> Self {
+        OutputLimits {
+            normal: OutputLimit::default(),
+            replica: OutputLimit {
+                hard: 256 * 1024 * 1024,
+                soft: 64 * 1024 * 1024,
+                soft_seconds: 60,
+            },
+            pubsub: OutputLimit {
+                hard: 32 * 1024 * 1024,
+                soft: 8 * 1024 * 1024,
+                soft_seconds: 60,
+            },
+        }
+    }
+}
+
+/// Gönderilmeyi bekleyen cevapların sınırı; 0 sınır yok demektir.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct OutputLimit {
+    /// Aşıldığı anda bağlantı kapatılır
+    pub hard: usize,
+    /// `soft_seconds` boyunca aralıksız aşılırsa bağlantı kapatılır
+    pub soft: usize,
+    pub soft_seconds: u64,
+}
+
+impl OutputLimit {
+    /// Bekleyen çıktı sınırı aşıyorsa bağlantının kapatılma sebebi. `soft_since`, yumuşak
+    /// sınırın ne zamandan beri aşıldığını tutar.
+    pub fn check(
+        &self,
+        pending: usize,
+        soft_since: &mut Option<Instant>,
+        now: Instant,
+    ) -> Option<String> {
+        if self.hard > 0 && pending > self.hard {
+            return Some(format!(
+                "output buffer of {} bytes exceeds hard limit of {} bytes",
+                pending, self.hard
+            ));
+        }
+        if self.soft == 0 || pending <= self.soft {
+            *soft_since = None;
+            return None;
+        }
+        let since = *soft_since.get_or_insert(now);
+        if now.duration_since(since) >= Duration::from_secs(self.soft_seconds) {
+            return Some(format!(
+                "output buffer of {} bytes over soft limit of {} bytes for {} seconds",
+                pending, self.soft, self.soft_seconds
+            ));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_limit() {
+        let limit = OutputLimit {
+            hard: 100,
+            soft: 50,
+            soft_seconds: 10,
+        };
+        let now = Instant::now();
+        let mut since = None;
+        assert!(limit.check(40, &mut since, now).is_none());
+        assert!(limit.check(101, &mut since, now).is_some());
+
+        // Yumuşak sınır süre dolana kadar tolere edilir, altına inince sayaç sıfırlanır
+        assert!(limit.check(60, &mut since, now).is_none());
+        assert_eq!(since, Some(now));
+        assert!(limit
+            .check(60, &mut since, now + Duration::from_secs(5))
+            .is_none());
+        assert!(limit
+            .check(10, &mut since, now + Duration::from_secs(6))
+            .is_none());
+        assert_eq!(since, None);
+        assert!(limit
+            .check(60, &mut since, now + Duration::from_secs(7))
+            .is_none());
+        assert!(limit
+            .check(60, &mut since, now + Duration::from_secs(17))
+            .is_some());
+
+        assert!(OutputLimit::default()
+            .check(usize::MAX, &mut None, now)
+            .is_none());
+    }
+}
@@ -0,0 +1,633 @@
+//! Akış halinde çalışan istek parser'ı. Bağlantının girdi buffer'ı parser'ın içindedir;
+//! okunan veri buraya eklenir ve parser, yarım kalan isteği bir sonraki okumada kaldığı
+//! yerden çözmeye devam eder. Tamamlanan istek buffer'dan kopyalanmadan ayrılır ve
+//! argümanları isteğin baytları üzerinde dilim olarak verilir.
+//!
+//! Bildirilen dizi ve bulk uzunlukları başlık okunur okunmaz sınırlarla karşılaştırılır;
+//! sınırı aşan bir istek için bellek ayrılmaz.
+
+use crate::network::inline;
+use crate::network::limits::{Limits, INLINE_MAX};
+use crate::network::resp::RespValue;
+use bytes::{Bytes, BytesMut};
+use std::ops::Range;
+
+/// Okumadan önce buffer'da en az bu kadar boş yer açılır
+const READ_SIZE: usize = 16 * 1024;
+
+/// Argüman listesi için baştan ayrılan en fazla yer; gerisi argümanlar geldikçe büyür
+const PREALLOCATED_ARGS: usize = 1024;
+
+/// Tam bir istek.
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// İsteğin ağdan geldiği haliyle baytları
+    frame: Bytes,
+    args: Vec<Range<usize>>,
+}
+
+impl Request {
+    pub fn len(&self) -> usize {
+        self.args.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+
+    pub fn arg(&self, index: usize) -> &[u8] {
+        &self.frame[self.args[index].clone()]
+    }
+
+    pub fn args(&self) -> impl Iterator<Item = &[u8]> {
+        self.args.iter().map(|range| &self.frame[range.clone()])
+    }
+
+    /// İsteğin ağdaki boyutu
+    pub fn frame_len(&self) -> usize {
+        self.frame.len()
+    }
+
+    /// Yorumlayıcının beklediği biçim; argümanlar burada kopyalanır.
+    pub fn to_resp(&self) -> RespValue {
+        RespValue::Array(Some(
+            self.args()
+                .map(|arg| RespValue::BulkString(Some(String::from_utf8_lossy(arg).into_owned())))
+                .collect(),
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    /// Yeni bir isteğin ilk baytı bekleniyor
+    Start,
+    /// Sıradaki `$` başlığı `pos`'ta bekleniyor
+    Args { remaining: usize },
+    /// `len` baytlık argüman `pos`'ta başlıyor
+    Bulk { remaining: usize, len: usize },
+    /// Inline istek; `pos`'a kadar satır sonu yok
+    Inline,
+}
+
+pub struct RequestParser {
+    buffer: BytesMut,
+    state: State,
+    /// Buffer'ın başındaki isteğin çözümlenmiş kısmının sonu
+    pos: usize,
+    args: Vec<Range<usize>>,
+}
+
+impl Default for RequestParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RequestParser {
+    pub fn new() -> Self {
+        RequestParser {
+            buffer: BytesMut::new(),
+            state: State::Start,
+            pos: 0,
+            args: Vec::new(),
+        }
+    }
+
+    /// Soketten okunacak verinin ekleneceği buffer; yeterince boş yer açılmış olarak döner.
+    pub fn buffer_mut(&mut self) -> &mut BytesMut {
+        self.buffer.reserve(READ_SIZE);
+        &mut self.buffer
+    }
+
+    pub fn extend(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Henüz istek olarak ayrılmamış bayt sayısı
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buffer.capacity()
+    }
+
+    /// Ayrılmamış baytları alır ve parser'ı sıfırlar. Bağlantı başka bir protokole
+    /// (replikasyon akışı) geçerken kullanılır.
+    pub fn take_buffer(&mut self) -> Vec<u8> {
+        self.state = State::Start;
+        self.pos = 0;
+        self.args.clear();
+        self.buffer.split().to_vec()
+    }
+
+    /// Buffer'daki sıradaki tam isteği ayırır. İstek henüz tamamlanmadıysa `None` döner;
+    /// o ana kadar çözümlenen kısım bir daha taranmaz.
+    pub fn next_request(&mut self, limits: &Limits) -> Result<Option<Request>, String> {
+        loop {
+            match self.state {
+                State::Start => {
+                    match self.buffer.first() {
+                        None => return Ok(None),
+                        Some(b'*') => {}
+                        Some(_) => {
+                            self.state = State::Inline;
+                            continue;
+                        }
+                    }
+                    let Some((count, next)) = self.header(0, "mbulk")? else {
+                        return Ok(None);
+                    };
+                    if count > limits.max_multibulk_len as i64 {
+                        return Err("Protocol error: invalid multibulk length".to_string());
+                    }
+                    self.pos = next;
+                    if count <= 0 {
+                        // Boş istek yok sayılır
+                        self.finish();
+                        continue;
+                    }
+                    let count = count as usize;
+                    self.args.reserve(count.min(PREALLOCATED_ARGS));
+                    self.state = State::Args { remaining: count };
+                }
+                State::Args { remaining: 0 } => return Ok(Some(self.finish())),
+                State::Args { remaining } => {
+                    match self.buffer.get(self.pos) {
+                        None => return Ok(None),
+                        Some(b'$') => {}
+                        Some(&byte) => {
+                            return Err(format!(
+                                "Protocol error: expected '$', got '{}'",
+                                byte as char
+                            ))
+                        }
+                    }
+                    let Some((len, next)) = self.header(self.pos, "bulk")? else {
+                        return Ok(None);
+                    };
+                    if len < 0 || len > limits.max_bulk_len as i64 {
+                        return Err("Protocol error: invalid bulk length".to_string());
+                    }
+                    self.pos = next;
+                    self.state = State::Bulk {
+                        remaining,
+                        len: len as usize,
+                    };
+                }
+                State::Bulk { remaining, len } => {
+                    let end = self.pos + len;
+                    if self.buffer.len() < end + 2 {
+                        return Ok(None);
+                    }
+                    if &self.buffer[end..end + 2] != b"\r\n" {
+                        return Err("Protocol error: expected CRLF after bulk".to_string());
+                    }
+                    self.args.push(self.pos..end);
+                    self.pos = end + 2;
+                    self.state = State::Args {
+                        remaining: remaining - 1,
+                    };
+                }
+                State::Inline => {
+                    let Some(newline) = self.buffer[self.pos..].iter().position(|b| *b == b'\n')
+                    else {
+                        self.pos = self.buffer.len();
+                        if self.pos > INLINE_MAX {
+                            return Err("Protocol error: too big inline request".to_string());
+                        }
+                        return Ok(None);
+                    };
+                    let end = self.pos + newline;
+                    let line = match self.buffer[..end].last() {
+                        Some(b'\r') => end - 1,
+                        _ => end,
+                    };
+                    // Tırnaklar ve kaçışlar satırın üzerinde çözülür
+                    self.args = inline::split_in_place(&mut self.buffer[..line])
+                        .map_err(|e| format!("Protocol error: {}", e))?;
+                    self.pos = end + 1;
+                    let request = self.finish();
+                    if !request.is_empty() {
+                        return Ok(Some(request));
+                    }
+                }
+            }
+        }
+    }
+
+    /// `pos`'taki `*n` ya da `$n` satırının sayısı ve sonraki satırın başı.
+    fn header(&self, pos: usize, kind: &str) -> Result<Option<(i64, usize)>, String> {
+        let line = &self.buffer[pos + 1..];
+        let Some(end) = line.windows(2).position(|w| w == b"\r\n") else {
+            if line.len() > INLINE_MAX {
+                return Err(format!("Protocol error: too big {} count string", kind));
+            }
+            return Ok(None);
+        };
+        let number = std::str::from_utf8(&line[..end])
+            .ok()
+            .and_then(|number| number.parse::<i64>().ok());
+        match number {
+            Some(number) => Ok(Some((number, pos + 1 + end + 2))),
+            None if kind == "mbulk" => Err("Protocol error: invalid multibulk length".to_string()),
+            None => Err("Protocol error: invalid bulk length".to_string()),
+        }
+    }
+
+    /// `pos`'a kadar olan baytları istek olarak ayırır.
+    fn finish(&mut self) -> Request {
+        let frame = self.buffer.split_to(self.pos).freeze();
+        self.pos = 0;
+        self.state = State::Start;
+        Request {
+            frame,
+            args: std::mem::take(&mut self.args),
+        }
+    }
+}
+
+/// [`parse_value`]'nun kabul ettiği en derin dizi iç içeliği
+const MAX_NESTING: usize = 128;
+
+/// Buffer'ın başındaki tam RESP değerini ve uzunluğunu döndürür; değer henüz
+/// tamamlanmadıysa `Ok(None)` döner. Replikasyon, cluster bus, sentinel ve istemci
+/// bağlantıları gibi ağdan gelen ve istek olmayan değerler de içerebilen akışlar
+/// bunu kullanır. `RespHandler::parse_request` ile aynı değerleri okur; ancak
+/// tamsayı olmayan başlıklar, -1'den küçük uzunluklar ve [`MAX_NESTING`]'i aşan
+/// diziler hata döner.
+pub fn parse_value(buffer: &[u8]) -> Result<Option<(RespValue, usize)>, String> {
+    value_at(buffer, 0)
+}
+
+fn value_at(buffer: &[u8], depth: usize) -> Result<Option<(RespValue, usize)>, String> {
+    let Some(&kind) = buffer.first() else {
+        return Ok(None);
+    };
+    if !matches!(kind, b'+' | b'-' | b':' | b'$' | b'*') {
+        // Inline: boşluklarla ayrılmış argümanlar
+        let Some((line, len)) = line(buffer) else {
+            return Ok(None);
+        };
+        let args = String::from_utf8_lossy(line)
+            .split_whitespace()
+            .map(|arg| RespValue::BulkString(Some(arg.to_string())))
+            .collect();
+        return Ok(Some((RespValue::Array(Some(args)), len)));
+    }
+
+    let Some((line, len)) = line(&buffer[1..]) else {
+        return Ok(None);
+    };
+    let mut pos = 1 + len;
+    let value = match kind {
+        b'+' => RespValue::SimpleString(String::from_utf8_lossy(line).into_owned()),
+        b'-' => RespValue::Error(String::from_utf8_lossy(line).into_owned()),
+        b':' => RespValue::Integer(
+            integer(line).ok_or_else(|| "Protocol error: invalid integer".to_string())?,
+        ),
+        b'$' => match length(line, "bulk")? {
+            None => RespValue::BulkString(None),
+            Some(len) => {
+                let end = pos.checked_add(len);
+                let Some(end) = end.filter(|&end| buffer.len().saturating_sub(end) >= 2) else {
+                    return Ok(None);
+                };
+                if &buffer[end..end + 2] != b"\r\n" {
+                    return Err("Protocol error: expected CRLF after bulk".to_string());
+                }
+                let bulk = String::from_utf8_lossy(&buffer[pos..end]).into_owned();
+                pos = end + 2;
+                RespValue::BulkString(Some(bulk))
+            }
+        },
+        _ => match length(line, "multibulk")? {
+            None => RespValue::Array(None),
+            Some(count) => {
+                if depth >= MAX_NESTING {
+                    return Err("Protocol error: too many nested arrays".to_string());
+                }
+                // Eleman sayısı ağdan geldiği için önceden yer ayrılmaz
+                let mut items = Vec::new();
+                for _ in 0..count {
+                    let Some((item, len)) = value_at(&buffer[pos..], depth + 1)? else {
+                        return Ok(None);
+                    };
+                    items.push(item);
+                    pos += len;
+                }
+                RespValue::Array(Some(items))
+            }
+        },
+    };
+    Ok(Some((value, pos)))
+}
+
+/// CRLF'e kadar olan satır ve CRLF dahil uzunluğu.
+fn line(buffer: &[u8]) -> Option<(&[u8], usize)> {
+    let end = buffer.windows(2).position(|w| w == b"\r\n")?;
+    Some((&buffer[..end], end + 2))
+}
+
+fn integer(line: &[u8]) -> Option<i64> {
+    std::str::from_utf8(line).ok()?.parse().ok()
+}
+
+/// `$` ve `*` uzunluğu; -1 null değerdir.
+fn length(line: &[u8], kind: &str) -> Result<Option<usize>, String> {
+    match integer(line) {
+        Some(-1) => Ok(None),
+        Some(len) => usize::try_from(len)
+            .map(Some)
+            .map_err(|_| format!("Protocol error: invalid {} length", kind)),
+        None => Err(format!("Protocol error: invalid {} length", kind)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::resp::RespHandler;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use std::path::Path;
+
+    fn args(request: &Request) -> Vec<&[u8]> {
+        request.args().collect()
+    }
+
+    /// Girdi `chunk` baytlık okumalarla gelir; ilk protokol hatasında durulur.
+    fn parse_chunks(input: &[u8], chunk: usize, limits: &Limits) -> (Vec<Vec<Vec<u8>>>, bool) {
+        let mut parser = RequestParser::new();
+        let mut requests = Vec::new();
+        for piece in input.chunks(chunk) {
+            parser.extend(piece);
+            loop {
+                match parser.next_request(limits) {
+                    Ok(Some(request)) => {
+                        requests.push(request.args().map(<[u8]>::to_vec).collect())
+                    }
+                    Ok(None) => break,
+                    Err(_) => return (requests, true),
+                }
+            }
+        }
+        (requests, false)
+    }
+
+    fn parse(bytes: &[u8], limits: &Limits) -> Result<Option<Request>, String> {
+        let mut parser = RequestParser::new();
+        parser.extend(bytes);
+        parser.next_request(limits)
+    }
+
+    #[test]
+    fn test_partial_reads() {
+        let limits = Limits::default();
+        let input = b"*3\r\n$3\r\nSET\r\n$1\r\nk\r\n$5\r\nvalue\r\n*1\r\n$4\r\nPING\r\n";
+        let mut parser = RequestParser::new();
+        let mut requests = Vec::new();
+        // Her bayt ayrı bir okumayla gelir
+        for byte in input {
+            parser.extend(&[*byte]);
+            while let Some(request) = parser.next_request(&limits).unwrap() {
+                requests.push(request);
+            }
+        }
+        assert_eq!(requests.len(), 2);
+        assert_eq!(args(&requests[0]), vec![&b"SET"[..], b"k", b"value"]);
+        assert_eq!(requests[0].frame_len(), 31);
+        assert_eq!(args(&requests[1]), vec![&b"PING"[..]]);
+        assert_eq!(parser.buffered(), 0);
+    }
+
+    #[test]
+    fn test_inline_and_empty_requests() {
+        let limits = Limits::default();
+        let mut parser = RequestParser::new();
+        parser.extend(b"\r\n*0\r\n  SET  k v\r\nSET k \"hello world\"\r\nPING\n");
+        let request = parser.next_request(&limits).unwrap().unwrap();
+        assert_eq!(args(&request), vec![&b"SET"[..], b"k", b"v"]);
+        assert_eq!(
+            request.to_resp(),
+            RespValue::Array(Some(vec![
+                RespValue::BulkString(Some("SET".to_string())),
+                RespValue::BulkString(Some("k".to_string())),
+                RespValue::BulkString(Some("v".to_string())),
+            ]))
+        );
+        let request = parser.next_request(&limits).unwrap().unwrap();
+        assert_eq!(args(&request), vec![&b"SET"[..], b"k", b"hello world"]);
+        assert_eq!(request.frame_len(), 21);
+        let request = parser.next_request(&limits).unwrap().unwrap();
+        assert_eq!(args(&request), vec![&b"PING"[..]]);
+        assert!(parser.next_request(&limits).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_limits() {
+        let limits = Limits {
+            max_bulk_len: 10,
+            max_multibulk_len: 3,
+            ..Default::default()
+        };
+        // Eksik istek: gelen başlıklar sınırın içindeyse beklenir
+        assert!(parse(b"*2\r\n$3\r\nGE", &limits).unwrap().is_none());
+        assert!(parse(b"*2147483647\r\n", &limits).is_err());
+        assert!(parse(b"*2\r\n$3\r\nGET\r\n$2147483647\r\n", &limits).is_err());
+        assert!(parse(b"*1\r\n$-1\r\n", &limits).is_err());
+        assert!(parse(b"*x\r\n", &limits).is_err());
+        assert!(parse(b"*1\r\n+PING\r\n", &limits).is_err());
+        assert!(parse(b"*1\r\n$4\r\nPINGxx", &limits).is_err());
+        assert_eq!(
+            parse(b"SET k \"hello\r\n", &limits).unwrap_err(),
+            "Protocol error: unbalanced quotes in request"
+        );
+
+        let long = vec![b'1'; INLINE_MAX + 2];
+        assert!(parse(&long, &limits).is_err());
+        let mut header = b"*".to_vec();
+        header.extend_from_slice(&long);
+        assert!(parse(&header, &limits).is_err());
+    }
+
+    #[test]
+    fn test_take_buffer() {
+        let limits = Limits::default();
+        let mut parser = RequestParser::new();
+        parser.extend(b"*1\r\n$4\r\nPING\r\n+FULLRESYNC");
+        assert!(parser.next_request(&limits).unwrap().is_some());
+        assert_eq!(parser.take_buffer(), b"+FULLRESYNC".to_vec());
+        assert_eq!(parser.buffered(), 0);
+    }
+
+    /// Fuzz hedefinin derlenmiş girdileri (`fuzz/corpus/request_parser`) her test koşusunda
+    /// yeniden denenir.
+    #[test]
+    fn test_fuzz_corpus() {
+        let limits = Limits {
+            max_bulk_len: 1024,
+            max_multibulk_len: 64,
+            ..Default::default()
+        };
+        let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus/request_parser");
+        for entry in std::fs::read_dir(corpus).unwrap() {
+            let input = std::fs::read(entry.unwrap().path()).unwrap();
+            let whole = parse_chunks(&input, input.len().max(1), &limits);
+            for chunk in 1..=8 {
+                assert_eq!(parse_chunks(&input, chunk, &limits), whole);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_value() {
+        let data = b"*3\r\n$3\r\nSET\r\n:-5\r\n*2\r\n$-1\r\n*-1\r\n+OK";
+        let (value, len) = parse_value(data).unwrap().unwrap();
+        assert_eq!(len, data.len() - 3);
+        assert_eq!(
+            value,
+            RespValue::Array(Some(vec![
+                RespValue::BulkString(Some("SET".to_string())),
+                RespValue::Integer(-5),
+                RespValue::Array(Some(vec![
+                    RespValue::BulkString(None),
+                    RespValue::Array(None)
+                ])),
+            ]))
+        );
+        // Eksik veri
+        for end in 0..len {
+            assert_eq!(parse_value(&data[..end]), Ok(None));
+        }
+        assert_eq!(parse_value(b"+"), Ok(None));
+        assert_eq!(parse_value(b"*1\r\n+"), Ok(None));
+        assert_eq!(parse_value(b"$9223372036854775807\r\nab"), Ok(None));
+
+        let (value, len) = parse_value(b"GET  key\r\n").unwrap().unwrap();
+        assert_eq!(len, 10);
+        assert_eq!(
+            value,
+            RespValue::Array(Some(vec![
+                RespValue::BulkString(Some("GET".to_string())),
+                RespValue::BulkString(Some("key".to_string())),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_parse_value_errors() {
+        assert!(parse_value(b"$-2\r\n").is_err());
+        assert!(parse_value(b"*1\r\n$-5\r\nab\r\n").is_err());
+        assert!(parse_value(b"*-2\r\n").is_err());
+        assert!(parse_value(b"$x\r\n").is_err());
+        assert!(parse_value(b":1.5\r\n").is_err());
+        assert!(parse_value(b"$2\r\nabcd\r\n").is_err());
+        let nested = "*1\r\n".repeat(MAX_NESTING + 1);
+        assert!(parse_value(nested.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_value_fuzz_corpus() {
+        let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus/resp_value");
+        for entry in std::fs::read_dir(corpus).unwrap() {
+            let input = std::fs::read(entry.unwrap().path()).unwrap();
+            if let Ok(Some((value, len))) = parse_value(&input) {
+                assert!(len <= input.len());
+                let serialized = value.serialize();
+                assert_eq!(
+                    parse_value(serialized.as_bytes()),
+                    Ok(Some((value, serialized.len())))
+                );
+            }
+        }
+    }
+
+    /// `RespHandler`'ın okuyabildiği değerler; satır tipleri CR ve LF içeremez
+    fn resp_value() -> impl Strategy<Value = RespValue> {
+        let leaf = prop_oneof![
+            "[^\r\n]*".prop_map(RespValue::SimpleString),
+            "[^\r\n]*".prop_map(RespValue::Error),
+            any::<i64>().prop_map(RespValue::Integer),
+            any::<String>().prop_map(|s| RespValue::BulkString(Some(s))),
+            Just(RespValue::BulkString(None)),
+            Just(RespValue::Array(None)),
+        ];
+        leaf.prop_recursive(4, 64, 8, |inner| {
+            vec(inner, 0..8).prop_map(|items| RespValue::Array(Some(items)))
+        })
+    }
+
+    proptest! {
+        /// `serialize` edilen her değer `RespHandler` ile aynen geri okunur
+        #[test]
+        fn prop_resp_handler_round_trip(value in resp_value()) {
+            let serialized = value.serialize();
+            let parsed = RespHandler::parse_request(serialized.as_bytes());
+            prop_assert_eq!(parsed, Ok(Some((value, serialized.len()))));
+        }
+
+        /// `serialize` edilen her değer `parse_value` ile aynen geri okunur
+        #[test]
+        fn prop_parse_value_round_trip(value in resp_value()) {
+            let serialized = value.serialize();
+            prop_assert_eq!(parse_value(serialized.as_bytes()), Ok(Some((value, serialized.len()))));
+        }
+
+        /// Rastgele girdi `parse_value`'yu panikletmez
+        #[test]
+        fn prop_parse_value_arbitrary(input in vec(any::<u8>(), 0..256)) {
+            if let Ok(Some((_, len))) = parse_value(&input) {
+                prop_assert!(len <= input.len());
+            }
+        }
+
+        /// `serialize` edilen komut, parça parça okunsa da aynı değer olarak geri gelir
+        #[test]
+        fn prop_serialize_round_trip(args in vec(any::<String>(), 1..8), chunk in 1usize..32) {
+            let value = RespValue::Array(Some(
+                args.into_iter().map(|arg| RespValue::BulkString(Some(arg))).collect(),
+            ));
+            let serialized = value.serialize();
+            let mut parser = RequestParser::new();
+            let mut requests = Vec::new();
+            for piece in serialized.as_bytes().chunks(chunk) {
+                parser.extend(piece);
+                while let Some(request) = parser.next_request(&Limits::default()).unwrap() {
+                    requests.push(request);
+                }
+            }
+            prop_assert_eq!(requests.len(), 1);
+            prop_assert_eq!(requests[0].to_resp(), value);
+            prop_assert_eq!(requests[0].frame_len(), serialized.len());
+            prop_assert_eq!(parser.buffered(), 0);
+        }
+
+        /// UTF-8 olmayan argümanlar da bayt bayt aynen verilir
+        #[test]
+        fn prop_binary_args(args in vec(vec(any::<u8>(), 0..64), 1..8), chunk in 1usize..32) {
+            let mut input = format!("*{}\r\n", args.len()).into_bytes();
+            for arg in &args {
+                input.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+                input.extend_from_slice(arg);
+                input.extend_from_slice(b"\r\n");
+            }
+            let (requests, failed) = parse_chunks(&input, chunk, &Limits::default());
+            prop_assert!(!failed);
+            prop_assert_eq!(requests, vec![args]);
+        }
+
+        /// Rastgele girdi parser'ı paniğe sokmaz ve okumaların boyutu sonucu değiştirmez
+        #[test]
+        fn prop_arbitrary_input(input in vec(any::<u8>(), 0..512), chunk in 1usize..16) {
+            let limits = Limits {
+                max_bulk_len: 64,
+                max_multibulk_len: 8,
+                ..Default::default()
+            };
+            let whole = parse_chunks(&input, input.len().max(1), &limits);
+            prop_assert_eq!(parse_chunks(&input, chunk, &limits), whole);
+        }
+    }
+}
@@ -0,0 +1,323 @@
+//! TLS dinleyicisi. Sertifika ve anahtar `config.server.tls`'ten okunur; `ca_file`
+//! verilirse istemcilerden bu CA'nın imzaladığı bir sertifika istenir (mutual TLS) ve
+//! sertifikanın CN'i aynı adlı açık bir ACL kullanıcısı olmalıdır; bağlantı o kullanıcıyla
+//! başlar, eşleşmeyen sertifikalar reddedilir.
+
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{crypto, RootCertStore, ServerConfig};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+/// TLS el sıkışmasının tamamlanması için beklenen en uzun süre
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TlsConfig {
+    /// TLS dinleyicisinin portu; düz TCP portu da açık kalır
+    pub port: u16,
+    /// PEM sertifika zinciri
+    pub cert_file: String,
+    /// PEM özel anahtar
+    pub key_file: String,
+    /// Verilirse istemci sertifikaları bu CA'ya göre doğrulanır ve zorunlu olur
+    #[serde(default)]
+    pub ca_file: Option<String>,
+}
+
+fn load_certs(path: &str) -> Result<Vec<CertificateDer<'static>>, String> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("failed to read certificates from {}: {}", path, e))?;
+    if certs.is_empty() {
+        return Err(format!("no certificates found in {}", path));
+    }
+    Ok(certs)
+}
+
+fn server_config(config: &TlsConfig) -> Result<ServerConfig, String> {
+    let certs = load_certs(&config.cert_file)?;
+    let key = PrivateKeyDer::from_pem_file(&config.key_file)
+        .map_err(|e| format!("failed to read private key from {}: {}", config.key_file, e))?;
+
+    // Sağlayıcı açıkça seçilir; bağımlılıklardan biri başka bir sağlayıcıyı da açtığında
+    // rustls varsayılanı kendisi seçemez
+    let provider = Arc::new(crypto::ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(Arc::clone(&provider))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("invalid TLS settings: {}", e))?;
+    let builder = match &config.ca_file {
+        Some(ca_file) => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(ca_file)? {
+                roots
+                    .add(cert)
+                    .map_err(|e| format!("invalid CA certificate in {}: {}", ca_file, e))?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+                .build()
+                .map_err(|e| format!("invalid client certificate settings: {}", e))?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+    builder
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("invalid certificate or key: {}", e))
+}
+
+/// Yeniden yüklenebilen TLS ayarları. Yeni sertifikalar yalnızca sonraki bağlantılarda
+/// kullanılır; açık bağlantılar etkilenmez.
+pub struct Tls {
+    acceptor: parking_lot::RwLock<TlsAcceptor>,
+}
+
+impl Tls {
+    pub fn new(config: &TlsConfig) -> Result<Self, String> {
+        Ok(Tls {
+            acceptor: parking_lot::RwLock::new(TlsAcceptor::from(Arc::new(server_config(config)?))),
+        })
+    }
+
+    /// Sertifikaları yeniden okur (SIGHUP). Hata olursa eski sertifikalar kullanılmaya devam eder.
+    pub fn reload(&self, config: &TlsConfig) -> Result<(), String> {
+        let acceptor = TlsAcceptor::from(Arc::new(server_config(config)?));
+        *self.acceptor.write() = acceptor;
+        Ok(())
+    }
+
+    /// El sıkışmayı yapar.
+    pub async fn accept(&self, stream: TcpStream) -> Result<TlsStream<TcpStream>, String> {
+        let acceptor = self.acceptor.read().clone();
+        match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+            Ok(Ok(stream)) => Ok(stream),
+            Ok(Err(e)) => Err(format!("TLS handshake failed: {}", e)),
+            Err(_) => Err("TLS handshake timed out".to_string()),
+        }
+    }
+}
+
+/// İstemcinin doğrulanmış sertifikasındaki CN; istemci sertifika göstermediyse `None`.
+/// Sertifika okunamıyor ya da CN içermiyorsa hata döner.
+pub fn peer_common_name(stream: &TlsStream<TcpStream>) -> Result<Option<String>, String> {
+    let Some(cert) = stream
+        .get_ref()
+        .1
+        .peer_certificates()
+        .and_then(|certs| certs.first())
+    else {
+        return Ok(None);
+    };
+    let (_, cert) = x509_parser::parse_x509_certificate(cert)
+        .map_err(|e| format!("invalid client certificate: {}", e))?;
+    let name = cert
+        .subject()
+        .iter_common_name()
+        .next()
+        .and_then(|name| name.as_str().ok())
+        .ok_or("client certificate has no common name")?;
+    Ok(Some(name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::acl::Acl;
+    use rcgen::{BasicConstraints, CertificateParams, CertifiedIssuer, DnType, IsCa, KeyPair};
+    use tempfile::TempDir;
+    use tokio::net::TcpListener;
+    use tokio_rustls::rustls::pki_types::ServerName;
+    use tokio_rustls::rustls::ClientConfig;
+    use tokio_rustls::TlsConnector;
+
+    /// Geçici bir dizinde test CA'sı ve onun imzaladığı sertifikalar
+    struct Pki {
+        dir: TempDir,
+        ca: CertifiedIssuer<'static, KeyPair>,
+    }
+
+    impl Pki {
+        fn new() -> Self {
+            let mut params = CertificateParams::default();
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            params
+                .distinguished_name
+                .push(DnType::CommonName, "test ca");
+            let ca = CertifiedIssuer::self_signed(params, KeyPair::generate().unwrap()).unwrap();
+            let pki = Pki {
+                dir: tempfile::tempdir().unwrap(),
+                ca,
+            };
+            std::fs::write(pki.path("ca.crt"), pki.ca.pem()).unwrap();
+            pki
+        }
+
+        fn path(&self, file: &str) -> String {
+            self.dir.path().join(file).to_string_lossy().into_owned()
+        }
+
+        /// CA'nın imzaladığı `<name>.crt` ve `<name>.key` dosyalarını yazar.
+        fn issue(&self, name: &str, common_name: Option<&str>) {
+            let mut params = CertificateParams::new(vec!["localhost".to_string()]).unwrap();
+            params.distinguished_name = Default::default();
+            if let Some(common_name) = common_name {
+                params
+                    .distinguished_name
+                    .push(DnType::CommonName, common_name);
+            }
+            let key = KeyPair::generate().unwrap();
+            let cert = params.signed_by(&key, &self.ca).unwrap();
+            std::fs::write(self.path(&format!("{}.crt", name)), cert.pem()).unwrap();
+            std::fs::write(self.path(&format!("{}.key", name)), key.serialize_pem()).unwrap();
+        }
+
+        fn config(&self, require_client_cert: bool) -> TlsConfig {
+            TlsConfig {
+                port: 0,
+                cert_file: self.path("server.crt"),
+                key_file: self.path("server.key"),
+                ca_file: require_client_cert.then(|| self.path("ca.crt")),
+            }
+        }
+
+        /// Test CA'sına güvenen, istenirse `<name>` sertifikasını gösteren istemci
+        fn connector(&self, client_cert: Option<&str>) -> TlsConnector {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(&self.path("ca.crt")).unwrap() {
+                roots.add(cert).unwrap();
+            }
+            let builder =
+                ClientConfig::builder_with_provider(Arc::new(crypto::ring::default_provider()))
+                    .with_safe_default_protocol_versions()
+                    .unwrap()
+                    .with_root_certificates(roots);
+            let config = match client_cert {
+                Some(name) => {
+                    let certs = load_certs(&self.path(&format!("{}.crt", name))).unwrap();
+                    let key =
+                        PrivateKeyDer::from_pem_file(self.path(&format!("{}.key", name))).unwrap();
+                    builder.with_client_auth_cert(certs, key).unwrap()
+                }
+                None => builder.with_no_client_auth(),
+            };
+            TlsConnector::from(Arc::new(config))
+        }
+    }
+
+    /// Bir istemciyle el sıkışır; sunucu tarafının sonucu ve istemcinin CN'i döner.
+    async fn handshake(tls: &Tls, connector: TlsConnector) -> Result<Option<String>, String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = tokio::spawn(async move {
+            let stream = TcpStream::connect(addr).await.unwrap();
+            let name = ServerName::try_from("localhost").unwrap();
+            connector.connect(name, stream).await.is_ok()
+        });
+        let (stream, _) = listener.accept().await.unwrap();
+        let result = tls.accept(stream).await;
+        client.await.unwrap();
+        peer_common_name(&result?)
+    }
+
+    #[tokio::test]
+    async fn test_handshake() {
+        let pki = Pki::new();
+        pki.issue("server", Some("localhost"));
+        let tls = Tls::new(&pki.config(false)).unwrap();
+
+        assert_eq!(handshake(&tls, pki.connector(None)).await, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn test_client_certificate_required() {
+        let pki = Pki::new();
+        pki.issue("server", Some("localhost"));
+        pki.issue("alice", Some("alice"));
+        pki.issue("anonymous", None);
+        let tls = Tls::new(&pki.config(true)).unwrap();
+
+        assert!(handshake(&tls, pki.connector(None)).await.is_err());
+        assert_eq!(
+            handshake(&tls, pki.connector(Some("alice"))).await,
+            Ok(Some("alice".to_string()))
+        );
+        assert!(handshake(&tls, pki.connector(Some("anonymous")))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_client_certificate_rejects_foreign_ca() {
+        let pki = Pki::new();
+        pki.issue("server", Some("localhost"));
+        let other = Pki::new();
+        other.issue("alice", Some("alice"));
+        let tls = Tls::new(&pki.config(true)).unwrap();
+
+        // İstemci sunucuya güvenir ama sertifikası başka bir CA'dan
+        std::fs::copy(other.path("alice.crt"), pki.path("alice.crt")).unwrap();
+        std::fs::copy(other.path("alice.key"), pki.path("alice.key")).unwrap();
+        assert!(handshake(&tls, pki.connector(Some("alice"))).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_certificate_login() {
+        let pki = Pki::new();
+        pki.issue("server", Some("localhost"));
+        pki.issue("alice", Some("alice"));
+        pki.issue("mallory", Some("mallory"));
+        let tls = Tls::new(&pki.config(true)).unwrap();
+        let acl = Acl::new(None);
+        let setuser = |rules: &[&str]| {
+            let mut args = vec!["SETUSER".to_string(), "alice".to_string()];
+            args.extend(rules.iter().map(|rule| rule.to_string()));
+            acl.command("default", &args);
+        };
+        setuser(&["on", "nopass", "~*", "+@all"]);
+
+        let name = handshake(&tls, pki.connector(Some("alice")))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(acl.certificate_login(&name));
+        let name = handshake(&tls, pki.connector(Some("mallory")))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!acl.certificate_login(&name));
+
+        setuser(&["off"]);
+        assert!(!acl.certificate_login("alice"));
+    }
+
+    #[tokio::test]
+    async fn test_reload() {
+        let pki = Pki::new();
+        pki.issue("server", Some("localhost"));
+        let tls = Tls::new(&pki.config(false)).unwrap();
+
+        // Okunamayan dosyalarla eski sertifikalar kullanılmaya devam eder
+        let mut broken = pki.config(true);
+        broken.cert_file = pki.path("missing.crt");
+        assert!(tls.reload(&broken).is_err());
+        std::fs::write(pki.path("broken.key"), "not a key").unwrap();
+        let mut broken = pki.config(true);
+        broken.key_file = pki.path("broken.key");
+        assert!(tls.reload(&broken).is_err());
+        assert_eq!(handshake(&tls, pki.connector(None)).await, Ok(None));
+
+        // Geçerli ayarlar sonraki bağlantılarda kullanılır
+        pki.issue("alice", Some("alice"));
+        tls.reload(&pki.config(true)).unwrap();
+        assert!(handshake(&tls, pki.connector(None)).await.is_err());
+        assert_eq!(
+            handshake(&tls, pki.connector(Some("alice"))).await,
+            Ok(Some("alice".to_string()))
+        );
+    }
+}
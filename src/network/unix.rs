@@ -0,0 +1,200 @@
+//! Unix domain socket dinleyicisi. Aynı makinedeki istemciler TCP'ye uğramadan bağlanır;
+//! erişim, socket dosyasının izinleriyle sınırlanır.
+
+use serde::Deserialize;
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use tokio::net::UnixListener;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UnixSocketConfig {
+    /// Socket dosyasının yolu
+    pub path: String,
+    /// Socket dosyasının sekizlik izinleri (`"770"` gibi); verilmezse umask geçerlidir
+    #[serde(default)]
+    pub permissions: Option<String>,
+}
+
+/// `bind`'ın oluşturduğu socket dosyası. Sunucu kapanırken silinir.
+#[derive(Debug)]
+pub struct SocketFile {
+    path: PathBuf,
+    inode: u64,
+}
+
+impl SocketFile {
+    /// Dosyayı siler; yol bu arada başka bir sürecin socket'ine geçtiyse ona dokunmaz.
+    pub fn remove(&self) {
+        let ours = std::fs::symlink_metadata(&self.path)
+            .is_ok_and(|metadata| metadata.file_type().is_socket() && metadata.ino() == self.inode);
+        if ours {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Socket'i açar ve izinlerini ayarlar. Socket yalnızca sahibinin erişebildiği geçici bir
+/// dizinde oluşturulur ve izinleri ayarlandıktan sonra yerine taşınır; dosya hiçbir an
+/// istenenden geniş izinlerle görünmez. Önceki bir çalışmadan kalan socket dosyasının
+/// yerine geçilir; yolda socket olmayan bir dosya ya da hâlâ bağlantı kabul eden bir
+/// socket varsa hata döner.
+pub fn bind(config: &UnixSocketConfig) -> Result<(UnixListener, SocketFile), String> {
+    let permissions = config
+        .permissions
+        .as_deref()
+        .map(|mode| {
+            u32::from_str_radix(mode, 8)
+                .ok()
+                .filter(|mode| *mode <= 0o777)
+                .ok_or_else(|| format!("invalid unix socket permissions '{}'", mode))
+        })
+        .transpose()?;
+
+    let path = Path::new(&config.path);
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(format!("{} exists and is not a socket", config.path));
+        }
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            return Err(format!("{} is in use by another process", config.path));
+        }
+    }
+
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let staging = parent.join(format!(".hexagondb-{}", std::process::id()));
+    // Aynı pid'le çökmüş bir çalışmadan kalmış olabilir
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .map_err(|e| format!("failed to create {}: {}", staging.display(), e))?;
+    let result = bind_in(&staging, path, permissions);
+    let _ = std::fs::remove_dir_all(&staging);
+    result.map_err(|e| format!("failed to bind unix socket {}: {}", config.path, e))
+}
+
+fn bind_in(
+    staging: &Path,
+    path: &Path,
+    permissions: Option<u32>,
+) -> std::io::Result<(UnixListener, SocketFile)> {
+    let staged = staging.join("s");
+    let listener = UnixListener::bind(&staged)?;
+    if let Some(mode) = permissions {
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(mode))?;
+    }
+    let inode = std::fs::symlink_metadata(&staged)?.ino();
+    // Taşıma, kalmış eski socket dosyasının yerine tek adımda geçer
+    std::fs::rename(&staged, path)?;
+    Ok((
+        listener,
+        SocketFile {
+            path: path.to_path_buf(),
+            inode,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config(dir: &TempDir, permissions: Option<&str>) -> UnixSocketConfig {
+        UnixSocketConfig {
+            path: dir
+                .path()
+                .join("hexagondb.sock")
+                .to_string_lossy()
+                .into_owned(),
+            permissions: permissions.map(str::to_string),
+        }
+    }
+
+    fn mode(path: &str) -> u32 {
+        std::fs::symlink_metadata(path)
+            .unwrap()
+            .permissions()
+            .mode()
+            & 0o777
+    }
+
+    /// Dizinde socket dışında bir şey (geçici dizin) kalmamalı
+    fn entries(dir: &TempDir) -> Vec<String> {
+        std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_bind_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        for permissions in ["600", "770"] {
+            let config = config(&dir, Some(permissions));
+            let (_listener, file) = bind(&config).unwrap();
+            assert_eq!(
+                mode(&config.path),
+                u32::from_str_radix(permissions, 8).unwrap()
+            );
+            assert_eq!(entries(&dir), vec!["hexagondb.sock"]);
+            file.remove();
+        }
+
+        let config = config(&dir, Some("999"));
+        assert_eq!(
+            bind(&config).unwrap_err(),
+            "invalid unix socket permissions '999'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bind_replaces_stale_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(&dir, None);
+        // Kapanmış bir sürecin bıraktığı socket
+        drop(std::os::unix::net::UnixListener::bind(&config.path).unwrap());
+
+        let (listener, _file) = bind(&config).unwrap();
+        let _client = tokio::net::UnixStream::connect(&config.path).await.unwrap();
+        listener.accept().await.unwrap();
+        assert_eq!(entries(&dir), vec!["hexagondb.sock"]);
+    }
+
+    #[tokio::test]
+    async fn test_bind_refuses_live_socket_and_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(&dir, None);
+        let _other = std::os::unix::net::UnixListener::bind(&config.path).unwrap();
+        assert_eq!(
+            bind(&config).unwrap_err(),
+            format!("{} is in use by another process", config.path)
+        );
+
+        std::fs::remove_file(&config.path).unwrap();
+        std::fs::write(&config.path, "data").unwrap();
+        assert_eq!(
+            bind(&config).unwrap_err(),
+            format!("{} exists and is not a socket", config.path)
+        );
+        assert_eq!(std::fs::read(&config.path).unwrap(), b"data");
+    }
+
+    #[tokio::test]
+    async fn test_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(&dir, None);
+        let (listener, old) = bind(&config).unwrap();
+        drop(listener);
+
+        // Yol başka bir dinleyiciye geçtiyse eski kayıt onu silmez
+        let (_listener, new) = bind(&config).unwrap();
+        old.remove();
+        assert!(std::fs::symlink_metadata(&config.path).is_ok());
+        new.remove();
+        assert!(entries(&dir).is_empty());
+    }
+}
@@ -0,0 +1,64 @@
+//! Log output. The level sits behind a reload layer so `CONFIG SET loglevel` and SIGHUP can
+//! change it while the server runs.
+
+use std::sync::OnceLock;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, Registry};
+
+static LEVEL: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+/// Parses a log level. Besides the tracing names, the Redis `loglevel` names are accepted.
+pub fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    match level.to_lowercase().as_str() {
+        "verbose" => Ok(LevelFilter::DEBUG),
+        "notice" => Ok(LevelFilter::INFO),
+        "warning" => Ok(LevelFilter::WARN),
+        "nothing" => Ok(LevelFilter::OFF),
+        "" => Err("log level is empty".to_string()),
+        other => other
+            .parse()
+            .map_err(|_| format!("invalid log level '{}'", level)),
+    }
+}
+
+/// Installs the global subscriber. An invalid or empty level falls back to `info`.
+pub fn init(level: &str) {
+    let (filter, handle) = reload::Layer::new(parse_level(level).unwrap_or(LevelFilter::INFO));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            fmt::layer()
+                .with_target(false)
+                .with_thread_ids(true)
+                .with_file(true)
+                .with_line_number(true)
+                .compact(),
+        )
+        .init();
+    let _ = LEVEL.set(handle);
+}
+
+/// Changes the level of the running subscriber. Before `init` only the level is validated.
+pub fn set_level(level: &str) -> Result<(), String> {
+    let filter = parse_level(level)?;
+    if let Some(handle) = LEVEL.get() {
+        handle.reload(filter).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("info"), Ok(LevelFilter::INFO));
+        assert_eq!(parse_level("DEBUG"), Ok(LevelFilter::DEBUG));
+        assert_eq!(parse_level("warning"), Ok(LevelFilter::WARN));
+        assert_eq!(parse_level("nothing"), Ok(LevelFilter::OFF));
+        assert!(parse_level("loud").is_err());
+        assert!(parse_level("").is_err());
+    }
+}
@@ -1,11 +1,14 @@
 use metrics_exporter_prometheus::PrometheusBuilder;
 use std::net::SocketAddr;
 
-pub fn init_metrics() {
-    // Initialize Prometheus recorder
-    // By default, it listens on 0.0.0.0:9000, but we can customize it.
-    // We'll use port 9100 which is standard for node_exporter/prometheus metrics.
-    let addr: SocketAddr = "0.0.0.0:9100".parse().expect("Invalid metrics address");
+/// Default port for the Prometheus endpoint, standard for node_exporter/prometheus metrics.
+pub const DEFAULT_METRICS_PORT: u16 = 9100;
+
+pub fn init_metrics(port: u16) {
+    // Initialize Prometheus recorder.
+    // The port is configurable so that several servers (e.g. a primary and its replica)
+    // can run on the same host.
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
 
     let builder = PrometheusBuilder::new().with_http_listener(addr);
 
@@ -20,3 +23,4 @@ pub const METRIC_COMMANDS_TOTAL: &str = "hexagondb_commands_total";
 pub const METRIC_COMMAND_LATENCY: &str = "hexagondb_command_latency_seconds";
 pub const METRIC_ACTIVE_CONNECTIONS: &str = "hexagondb_active_connections";
 pub const METRIC_KEYS_TOTAL: &str = "hexagondb_keys_total";
+pub const METRIC_ACL_DENIED_TOTAL: &str = "hexagondb_acl_denied_total";
@@ -0,0 +1,250 @@
+//! Server counters reported by INFO. CONFIG RESETSTAT sets them back to zero; the Prometheus
+//! metrics keep counting.
+
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Interval of the ops/sec samples; the reported value averages the last `OPS_SAMPLES`
+const OPS_SAMPLE_PERIOD: Duration = Duration::from_millis(100);
+const OPS_SAMPLES: usize = 16;
+
+/// Distinct error codes tracked in `errorstats`; later codes only count in the total
+const MAX_ERROR_CODES: usize = 128;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CommandStat {
+    pub calls: u64,
+    pub usec: u64,
+    /// Refused before running: arity, ACL, READONLY, cluster redirection
+    pub rejected_calls: u64,
+    /// Ran and replied with an error
+    pub failed_calls: u64,
+}
+
+#[derive(Default)]
+struct OpsSamples {
+    last_commands: u64,
+    samples: [u64; OPS_SAMPLES],
+    next: usize,
+}
+
+pub struct Stats {
+    pub started: Instant,
+    pub connections_received: AtomicU64,
+    pub rejected_connections: AtomicU64,
+    pub commands_processed: AtomicU64,
+    pub keyspace_hits: AtomicU64,
+    pub keyspace_misses: AtomicU64,
+    pub expired_keys: AtomicU64,
+    pub error_replies: AtomicU64,
+    /// Unix time of the last snapshot attempt, and whether it succeeded
+    pub last_save_time: AtomicU64,
+    pub last_save_ok: AtomicBool,
+    pub bgsave_in_progress: AtomicBool,
+    pub aof_last_write_ok: AtomicBool,
+    commands: Mutex<HashMap<&'static str, CommandStat>>,
+    errors: Mutex<BTreeMap<String, u64>>,
+    ops: Mutex<OpsSamples>,
+}
+
+pub static STATS: LazyLock<Stats> = LazyLock::new(Stats::new);
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+impl Stats {
+    fn new() -> Self {
+        Stats {
+            started: Instant::now(),
+            connections_received: AtomicU64::new(0),
+            rejected_connections: AtomicU64::new(0),
+            commands_processed: AtomicU64::new(0),
+            keyspace_hits: AtomicU64::new(0),
+            keyspace_misses: AtomicU64::new(0),
+            expired_keys: AtomicU64::new(0),
+            error_replies: AtomicU64::new(0),
+            last_save_time: AtomicU64::new(unix_time()),
+            last_save_ok: AtomicBool::new(true),
+            bgsave_in_progress: AtomicBool::new(false),
+            aof_last_write_ok: AtomicBool::new(true),
+            commands: Mutex::new(HashMap::new()),
+            errors: Mutex::new(BTreeMap::new()),
+            ops: Mutex::new(OpsSamples::default()),
+        }
+    }
+
+    /// Starts sampling the command rate for `instantaneous_ops_per_sec`.
+    pub fn start_cron(&'static self) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(OPS_SAMPLE_PERIOD);
+            loop {
+                interval.tick().await;
+                self.sample_ops();
+            }
+        });
+    }
+
+    fn sample_ops(&self) {
+        let commands = self.commands_processed.load(Ordering::Relaxed);
+        let mut ops = self.ops.lock();
+        let index = ops.next;
+        ops.samples[index] = commands.saturating_sub(ops.last_commands);
+        ops.last_commands = commands;
+        ops.next = (index + 1) % OPS_SAMPLES;
+    }
+
+    pub fn ops_per_sec(&self) -> u64 {
+        let total: u64 = self.ops.lock().samples.iter().sum();
+        total * 1000 / (OPS_SAMPLE_PERIOD.as_millis() as u64 * OPS_SAMPLES as u64)
+    }
+
+    /// A command ran for `elapsed`; `failed` if it replied with an error.
+    pub fn command_called(&self, name: &'static str, elapsed: Duration, failed: bool) {
+        let mut commands = self.commands.lock();
+        let stat = commands.entry(name).or_default();
+        stat.calls += 1;
+        stat.usec += elapsed.as_micros() as u64;
+        stat.failed_calls += failed as u64;
+    }
+
+    pub fn command_rejected(&self, name: &'static str) {
+        self.commands.lock().entry(name).or_default().rejected_calls += 1;
+    }
+
+    /// An error reply was sent; `code` is its first word, such as `ERR` or `WRONGTYPE`.
+    pub fn error_replied(&self, code: &str) {
+        self.error_replies.fetch_add(1, Ordering::Relaxed);
+        let mut errors = self.errors.lock();
+        if let Some(count) = errors.get_mut(code) {
+            *count += 1;
+        } else if errors.len() < MAX_ERROR_CODES {
+            errors.insert(code.to_string(), 1);
+        }
+    }
+
+    /// A snapshot was written, or failed to be.
+    pub fn saved(&self, ok: bool) {
+        self.last_save_time.store(unix_time(), Ordering::Relaxed);
+        self.last_save_ok.store(ok, Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        for counter in [
+            &self.connections_received,
+            &self.rejected_connections,
+            &self.commands_processed,
+            &self.keyspace_hits,
+            &self.keyspace_misses,
+            &self.expired_keys,
+            &self.error_replies,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+        self.commands.lock().clear();
+        self.errors.lock().clear();
+        *self.ops.lock() = OpsSamples {
+            last_commands: self.commands_processed.load(Ordering::Relaxed),
+            ..OpsSamples::default()
+        };
+    }
+
+    /// The `# Stats` section of INFO. Keys are never evicted, so `evicted_keys` stays 0.
+    pub fn info(&self) -> String {
+        format!(
+            "# Stats\r\ntotal_connections_received:{}\r\ntotal_commands_processed:{}\r\ninstantaneous_ops_per_sec:{}\r\nrejected_connections:{}\r\nexpired_keys:{}\r\nevicted_keys:0\r\nkeyspace_hits:{}\r\nkeyspace_misses:{}\r\ntotal_error_replies:{}\r\n",
+            self.connections_received.load(Ordering::Relaxed),
+            self.commands_processed.load(Ordering::Relaxed),
+            self.ops_per_sec(),
+            self.rejected_connections.load(Ordering::Relaxed),
+            self.expired_keys.load(Ordering::Relaxed),
+            self.keyspace_hits.load(Ordering::Relaxed),
+            self.keyspace_misses.load(Ordering::Relaxed),
+            self.error_replies.load(Ordering::Relaxed),
+        )
+    }
+
+    /// The `# Commandstats` section, one line per command that has been called or rejected
+    pub fn commandstats_info(&self) -> String {
+        let commands = self.commands.lock();
+        let mut names: Vec<&&str> = commands.keys().collect();
+        names.sort();
+        let mut info = "# Commandstats\r\n".to_string();
+        for name in names {
+            let stat = &commands[*name];
+            let per_call = if stat.calls == 0 {
+                0.0
+            } else {
+                stat.usec as f64 / stat.calls as f64
+            };
+            info.push_str(&format!(
+                "cmdstat_{}:calls={},usec={},usec_per_call={:.2},rejected_calls={},failed_calls={}\r\n",
+                name.to_lowercase(),
+                stat.calls,
+                stat.usec,
+                per_call,
+                stat.rejected_calls,
+                stat.failed_calls
+            ));
+        }
+        info
+    }
+
+    /// The `# Errorstats` section, one line per error code
+    pub fn errorstats_info(&self) -> String {
+        let mut info = "# Errorstats\r\n".to_string();
+        for (code, count) in self.errors.lock().iter() {
+            info.push_str(&format!("errorstat_{}:count={}\r\n", code, count));
+        }
+        info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commandstats() {
+        let stats = Stats::new();
+        stats.command_called("GET", Duration::from_micros(10), false);
+        stats.command_called("GET", Duration::from_micros(5), true);
+        stats.command_rejected("SET");
+        assert_eq!(
+            stats.commandstats_info(),
+            "# Commandstats\r\n\
+             cmdstat_get:calls=2,usec=15,usec_per_call=7.50,rejected_calls=0,failed_calls=1\r\n\
+             cmdstat_set:calls=0,usec=0,usec_per_call=0.00,rejected_calls=1,failed_calls=0\r\n"
+        );
+
+        stats.error_replied("ERR");
+        stats.error_replied("WRONGTYPE");
+        stats.error_replied("ERR");
+        assert_eq!(
+            stats.errorstats_info(),
+            "# Errorstats\r\nerrorstat_ERR:count=2\r\nerrorstat_WRONGTYPE:count=1\r\n"
+        );
+
+        stats.reset();
+        assert_eq!(stats.commandstats_info(), "# Commandstats\r\n");
+        assert_eq!(stats.error_replies.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_ops_per_sec() {
+        let stats = Stats::new();
+        stats.sample_ops();
+        stats.commands_processed.store(160, Ordering::Relaxed);
+        stats.sample_ops();
+        // 160 commands in 16 samples of 100ms
+        assert_eq!(stats.ops_per_sec(), 100);
+        stats.reset();
+        stats.sample_ops();
+        assert_eq!(stats.ops_per_sec(), 0);
+    }
+}
@@ -1,27 +1,11 @@
+/// Eski string sorgu API'si. Ayrıştırma, ağdan gelen inline isteklerle aynı kurallarla
+/// `network::inline` tarafından yapılır; tırnaklar dengesizse boş liste döner.
 pub fn parse_query(query: String) -> Vec<String> {
-    let mut parsed: Vec<String> = Vec::new();
-    let mut in_quotes = false;
-    let mut keyword = String::from("");
-    for chr in query.chars() {
-        if chr == '\''{
-            in_quotes = !in_quotes;
-            continue;
-        }
-
-        if chr == ' ' && !in_quotes{
-            if keyword != ""{
-                parsed.push(keyword);
-                keyword = String::from("");
-            }
-        }
-        else {
-            keyword.push(chr);
-        }
-       
-    }
-    if keyword != ""{
-        parsed.push(keyword);
-    }
-
-   parsed
-}
\ No newline at end of file
+    crate::network::inline::split_args(query.as_bytes())
+        .map(|args| {
+            args.into_iter()
+                .map(|arg| String::from_utf8_lossy(&arg).into_owned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,185 @@
+//! Raft log'u. Snapshot'a alınmış girdiler atılır; log yalnızca snapshot'tan sonraki
+//! girdileri tutar.
+
+use serde::{Deserialize, Serialize};
+
+/// Log girdisinin içeriği.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum EntryKind {
+    /// Uygulanacak yazma komutu
+    Command(Vec<String>),
+    /// Yeni üye listesi; log'a eklendiği anda geçerli olur
+    Members(Vec<String>),
+    /// Yeni lider, kendi döneminde bir girdi commit edebilmek için ekler
+    Noop,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub term: u64,
+    pub index: u64,
+    #[serde(flatten)]
+    pub kind: EntryKind,
+}
+
+#[derive(Debug, Default)]
+pub struct Log {
+    /// Snapshot'ın kapsadığı son girdi ve dönemi
+    pub snapshot_index: u64,
+    pub snapshot_term: u64,
+    /// Snapshot anındaki üye listesi
+    pub snapshot_members: Vec<String>,
+    /// `entries[i].index == snapshot_index + 1 + i`
+    entries: Vec<Entry>,
+}
+
+impl Log {
+    pub fn last_index(&self) -> u64 {
+        self.snapshot_index + self.entries.len() as u64
+    }
+
+    pub fn last_term(&self) -> u64 {
+        self.entries
+            .last()
+            .map_or(self.snapshot_term, |entry| entry.term)
+    }
+
+    /// Girdinin dönemi; girdi log'da yoksa (ya da snapshot'tan önceyse) `None`.
+    pub fn term_at(&self, index: u64) -> Option<u64> {
+        if index == self.snapshot_index {
+            return Some(self.snapshot_term);
+        }
+        self.get(index).map(|entry| entry.term)
+    }
+
+    pub fn get(&self, index: u64) -> Option<&Entry> {
+        let offset = index.checked_sub(self.snapshot_index + 1)?;
+        self.entries.get(offset as usize)
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// `from` dahil en fazla `max` girdi.
+    pub fn entries_from(&self, from: u64, max: usize) -> Vec<Entry> {
+        let start = from.saturating_sub(self.snapshot_index + 1) as usize;
+        self.entries.iter().skip(start).take(max).cloned().collect()
+    }
+
+    pub fn append(&mut self, entry: Entry) {
+        debug_assert_eq!(entry.index, self.last_index() + 1);
+        self.entries.push(entry);
+    }
+
+    /// `index` ve sonrasındaki girdileri siler.
+    pub fn truncate_from(&mut self, index: u64) {
+        let keep = index.saturating_sub(self.snapshot_index + 1) as usize;
+        self.entries.truncate(keep);
+    }
+
+    /// `index`'e kadar olan girdiler snapshot'a alındı; log'dan atılır.
+    pub fn compact(&mut self, index: u64, term: u64, members: Vec<String>) {
+        let drop = (index.min(self.last_index()) - self.snapshot_index) as usize;
+        self.entries.drain(..drop);
+        self.snapshot_index = index;
+        self.snapshot_term = term;
+        self.snapshot_members = members;
+    }
+
+    /// Liderden gelen snapshot kuruldu. Log snapshot'la çelişmiyorsa sonraki girdiler
+    /// korunur, çelişiyorsa tamamen atılır.
+    pub fn install_snapshot(&mut self, index: u64, term: u64, members: Vec<String>) {
+        if self.term_at(index) != Some(term) {
+            self.entries.clear();
+            self.snapshot_index = index;
+        }
+        self.compact(index, term, members);
+    }
+
+    /// `index` itibarıyla geçerli üye listesi ve onu belirleyen girdinin index'i.
+    pub fn members_at(&self, index: u64) -> (Vec<String>, u64) {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.index <= index)
+            .find_map(|entry| match &entry.kind {
+                EntryKind::Members(members) => Some((members.clone(), entry.index)),
+                _ => None,
+            })
+            .unwrap_or_else(|| (self.snapshot_members.clone(), self.snapshot_index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(term: u64, index: u64) -> Entry {
+        Entry {
+            term,
+            index,
+            kind: EntryKind::Noop,
+        }
+    }
+
+    #[test]
+    fn test_append_truncate_compact() {
+        let mut log = Log::default();
+        for index in 1..=5 {
+            log.append(entry(1, index));
+        }
+        assert_eq!(log.last_index(), 5);
+        assert_eq!(log.term_at(0), Some(0));
+        assert_eq!(log.term_at(6), None);
+
+        log.truncate_from(4);
+        assert_eq!(log.last_index(), 3);
+
+        log.compact(2, 1, vec!["a".to_string()]);
+        assert_eq!(log.last_index(), 3);
+        assert_eq!(log.term_at(2), Some(1));
+        assert_eq!(log.term_at(1), None);
+        assert_eq!(log.entries_from(1, 10), vec![entry(1, 3)]);
+        assert_eq!(log.members_at(3), (vec!["a".to_string()], 2));
+    }
+
+    #[test]
+    fn test_install_snapshot() {
+        let mut log = Log::default();
+        for index in 1..=4 {
+            log.append(entry(1, index));
+        }
+        // Aynı dönemdeki snapshot sonraki girdileri korur
+        log.install_snapshot(2, 1, Vec::new());
+        assert_eq!(log.last_index(), 4);
+
+        // Çelişen snapshot log'u siler
+        log.install_snapshot(3, 2, Vec::new());
+        assert_eq!(log.last_index(), 3);
+        assert!(log.entries().is_empty());
+
+        // Log'un ötesindeki snapshot
+        log.install_snapshot(10, 3, Vec::new());
+        assert_eq!((log.last_index(), log.last_term()), (10, 3));
+    }
+
+    #[test]
+    fn test_members_at() {
+        let mut log = Log::default();
+        log.append(Entry {
+            term: 0,
+            index: 1,
+            kind: EntryKind::Members(vec!["a".to_string()]),
+        });
+        log.append(entry(1, 2));
+        log.append(Entry {
+            term: 1,
+            index: 3,
+            kind: EntryKind::Members(vec!["a".to_string(), "b".to_string()]),
+        });
+        assert_eq!(log.members_at(2), (vec!["a".to_string()], 1));
+        assert_eq!(log.members_at(3).1, 3);
+    }
+}
@@ -0,0 +1,244 @@
+//! Node'lar arası Raft RPC'leri. İstekler normal istemci bağlantısı üzerinden
+//! `RAFT VOTE|APPEND|SNAPSHOT ...` komutu olarak gönderilir.
+
+use crate::network::resp::RespValue;
+use crate::raft::log::Entry;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoteRequest {
+    pub term: u64,
+    pub candidate: String,
+    pub last_index: u64,
+    pub last_term: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoteReply {
+    pub term: u64,
+    pub granted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppendRequest {
+    pub term: u64,
+    pub leader: String,
+    pub prev_index: u64,
+    pub prev_term: u64,
+    pub leader_commit: u64,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppendReply {
+    pub term: u64,
+    pub success: bool,
+    /// Başarılıysa eşleşen son index; değilse liderin deneyeceği bir önceki index
+    pub index: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotRequest {
+    pub term: u64,
+    pub leader: String,
+    pub last_index: u64,
+    pub last_term: u64,
+    pub members: Vec<String>,
+    pub data: Vec<u8>,
+}
+
+fn hex_encode(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn hex_decode(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn integers(reply: &RespValue) -> Option<Vec<u64>> {
+    match reply {
+        RespValue::Array(Some(items)) => items
+            .iter()
+            .map(|item| match item {
+                RespValue::Integer(n) => u64::try_from(*n).ok(),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+fn integer_reply(values: &[u64]) -> RespValue {
+    RespValue::Array(Some(
+        values
+            .iter()
+            .map(|&value| RespValue::Integer(value as i64))
+            .collect(),
+    ))
+}
+
+impl VoteRequest {
+    pub fn to_args(&self) -> Vec<String> {
+        vec![
+            "RAFT".to_string(),
+            "VOTE".to_string(),
+            self.term.to_string(),
+            self.candidate.clone(),
+            self.last_index.to_string(),
+            self.last_term.to_string(),
+        ]
+    }
+
+    /// `args`, `RAFT VOTE`'tan sonraki argümanlardır.
+    pub fn parse(args: &[String]) -> Option<Self> {
+        let [term, candidate, last_index, last_term] = args else {
+            return None;
+        };
+        Some(VoteRequest {
+            term: term.parse().ok()?,
+            candidate: candidate.clone(),
+            last_index: last_index.parse().ok()?,
+            last_term: last_term.parse().ok()?,
+        })
+    }
+}
+
+impl VoteReply {
+    pub fn to_resp(&self) -> RespValue {
+        integer_reply(&[self.term, self.granted as u64])
+    }
+
+    pub fn from_resp(reply: &RespValue) -> Option<Self> {
+        let [term, granted] = integers(reply)?[..] else {
+            return None;
+        };
+        Some(VoteReply {
+            term,
+            granted: granted == 1,
+        })
+    }
+}
+
+impl AppendRequest {
+    pub fn to_args(&self) -> Vec<String> {
+        vec![
+            "RAFT".to_string(),
+            "APPEND".to_string(),
+            self.term.to_string(),
+            self.leader.clone(),
+            self.prev_index.to_string(),
+            self.prev_term.to_string(),
+            self.leader_commit.to_string(),
+            serde_json::to_string(&self.entries).unwrap_or_default(),
+        ]
+    }
+
+    pub fn parse(args: &[String]) -> Option<Self> {
+        let [term, leader, prev_index, prev_term, leader_commit, entries] = args else {
+            return None;
+        };
+        Some(AppendRequest {
+            term: term.parse().ok()?,
+            leader: leader.clone(),
+            prev_index: prev_index.parse().ok()?,
+            prev_term: prev_term.parse().ok()?,
+            leader_commit: leader_commit.parse().ok()?,
+            entries: serde_json::from_str(entries).ok()?,
+        })
+    }
+}
+
+impl AppendReply {
+    pub fn to_resp(&self) -> RespValue {
+        integer_reply(&[self.term, self.success as u64, self.index])
+    }
+
+    pub fn from_resp(reply: &RespValue) -> Option<Self> {
+        let [term, success, index] = integers(reply)?[..] else {
+            return None;
+        };
+        Some(AppendReply {
+            term,
+            success: success == 1,
+            index,
+        })
+    }
+}
+
+impl SnapshotRequest {
+    pub fn to_args(&self) -> Vec<String> {
+        vec![
+            "RAFT".to_string(),
+            "SNAPSHOT".to_string(),
+            self.term.to_string(),
+            self.leader.clone(),
+            self.last_index.to_string(),
+            self.last_term.to_string(),
+            serde_json::to_string(&self.members).unwrap_or_default(),
+            hex_encode(&self.data),
+        ]
+    }
+
+    pub fn parse(args: &[String]) -> Option<Self> {
+        let [term, leader, last_index, last_term, members, data] = args else {
+            return None;
+        };
+        Some(SnapshotRequest {
+            term: term.parse().ok()?,
+            leader: leader.clone(),
+            last_index: last_index.parse().ok()?,
+            last_term: last_term.parse().ok()?,
+            members: serde_json::from_str(members).ok()?,
+            data: hex_decode(data)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raft::log::EntryKind;
+
+    #[test]
+    fn test_round_trip() {
+        let append = AppendRequest {
+            term: 3,
+            leader: "127.0.0.1:7001".to_string(),
+            prev_index: 4,
+            prev_term: 2,
+            leader_commit: 4,
+            entries: vec![Entry {
+                term: 3,
+                index: 5,
+                kind: EntryKind::Command(vec!["SET".to_string(), "k".to_string()]),
+            }],
+        };
+        assert_eq!(AppendRequest::parse(&append.to_args()[2..]), Some(append));
+
+        let snapshot = SnapshotRequest {
+            term: 1,
+            leader: "a".to_string(),
+            last_index: 9,
+            last_term: 1,
+            members: vec!["a".to_string(), "b".to_string()],
+            data: vec![0, 255, 16],
+        };
+        assert_eq!(
+            SnapshotRequest::parse(&snapshot.to_args()[2..]),
+            Some(snapshot)
+        );
+
+        let reply = AppendReply {
+            term: 2,
+            success: false,
+            index: 7,
+        };
+        assert_eq!(AppendReply::from_resp(&reply.to_resp()), Some(reply));
+        assert_eq!(hex_decode("0g"), None);
+    }
+}
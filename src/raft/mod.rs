@@ -0,0 +1,667 @@
+//! Raft tabanlı tutarlı replikasyon modu.
+//!
+//! Yazma komutları önce liderin log'una eklenir ve üyelerin çoğunluğuna kopyalanıp
+//! commit edildikten sonra uygulanır; istemci cevabı ancak o zaman alır. Okumalar
+//! read-index ile yapılır: lider, okumadan önce çoğunluğun kendisini hâlâ lider
+//! olarak tanıdığını bir heartbeat turuyla doğrular. Uygulanan girdiler belirli bir
+//! sayıyı geçince veri seti `persistence::snapshot` ile kaydedilir ve log sıkıştırılır.
+//! Üyelik değişiklikleri tek seferde bir node eklenerek ya da çıkarılarak yapılır.
+
+pub mod log;
+pub mod message;
+pub mod state;
+pub mod storage;
+
+use crate::commands::{ExecutionResult, Interpreter};
+use crate::db::DB;
+use crate::network::client::{Connection, Credentials};
+use crate::network::resp::RespValue;
+use crate::persistence::snapshot;
+use log::EntryKind;
+use message::{AppendReply, AppendRequest, SnapshotRequest, VoteReply, VoteRequest};
+use parking_lot::Mutex;
+use state::{Replicate, Role, State};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use storage::{SnapshotMeta, Storage};
+use tokio::sync::{oneshot, Notify, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+/// Seçim zamanlayıcısının ve replikatörlerin kontrol aralığı
+const TICK: Duration = Duration::from_millis(50);
+/// Lider, gönderecek girdi olmasa da takipçilere bu aralıkla APPEND gönderir
+const HEARTBEAT_PERIOD: Duration = Duration::from_millis(200);
+/// Oy ve APPEND istekleri için bağlantı ve cevap süresi sınırı
+const RPC_TIMEOUT: Duration = Duration::from_millis(500);
+/// Snapshot gönderimi için süre sınırı
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Yazmanın commit edilmesi ya da okumanın doğrulanması için beklenen en uzun süre
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Log'dan geçmeyen ve okuma doğrulaması gerektirmeyen komutlar.
+const LOCAL_COMMANDS: &[&str] = &[
+    "PING",
+    "ECHO",
+    "INFO",
+    "COMMAND",
+    "CONFIG",
+    "RAFT",
+    "CDC",
+    "AUTH",
+    "HELLO",
+    "ACL",
+    "CLIENT",
+    "ROLE",
+    "PUBLISH",
+    "SUBSCRIBE",
+    "SAVE",
+    "BGSAVE",
+    "REPLCONF",
+    "PSYNC",
+    "SYNC",
+    "WAIT",
+];
+
+pub fn is_local_command(cmd: &str) -> bool {
+    LOCAL_COMMANDS.contains(&cmd)
+}
+
+pub struct RaftConfig {
+    /// Diğer node'ların bu node'a ulaştığı `host:port`
+    pub addr: String,
+    /// İlk üye listesi; yalnızca log boşken kullanılır
+    pub peers: Vec<String>,
+    pub dir: PathBuf,
+    /// Log bu kadar uygulanmış girdi biriktirince snapshot alınır
+    pub snapshot_entries: u64,
+    /// Diğer üyelere RPC göndermeden önce `AUTH` ile giriş yapılır
+    pub auth: Option<Credentials>,
+}
+
+/// Commit edilmesi beklenen bir yazma.
+struct Pending {
+    term: u64,
+    reply: oneshot::Sender<RespValue>,
+}
+
+pub struct Raft {
+    state: Mutex<State>,
+    /// Durum her değiştiğinde replikatörleri, uygulayıcıyı ve bekleyen okumaları uyandırır
+    changed: Notify,
+    pending: Mutex<HashMap<u64, Pending>>,
+    /// Commit edilen komutları uygulayan istemci. Snapshot alınırken ve kurulurken de
+    /// kilitlenir; böylece veri seti her zaman `last_applied` ile tutarlıdır.
+    applier: tokio::sync::Mutex<Option<Interpreter>>,
+    db: Arc<RwLock<DB>>,
+    snapshot_entries: u64,
+    auth: Option<Credentials>,
+}
+
+fn split_addr(addr: &str) -> Option<(&str, u16)> {
+    let (host, port) = addr.rsplit_once(':')?;
+    Some((host, port.parse().ok()?))
+}
+
+fn log_error(context: &str, result: io::Result<()>) {
+    if let Err(e) = result {
+        error!("Raft {} failed: {}", context, e);
+    }
+}
+
+/// Tek seferlik RPC.
+async fn rpc(raft: &Raft, addr: &str, args: &[String], timeout: Duration) -> io::Result<RespValue> {
+    let (host, port) =
+        split_addr(addr).ok_or_else(|| io::Error::other(format!("bad address {}", addr)))?;
+    let mut connection = Connection::connect_auth(host, port, timeout, raft.auth.as_ref()).await?;
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    connection.request(&args).await
+}
+
+impl Raft {
+    /// Diskteki durumu ve varsa son snapshot'ı yükler.
+    pub async fn open(config: RaftConfig, db: Arc<RwLock<DB>>) -> io::Result<Arc<Raft>> {
+        let storage = Storage::open(&config.dir)?;
+        let state = State::open(config.addr, &config.peers, storage, Instant::now())?;
+        let snapshot_index = state.log.snapshot_index;
+        if snapshot_index > 0 {
+            if let Some(path) = state.snapshot_path(snapshot_index) {
+                db.write().await.clear();
+                snapshot::load(&path, &db).await?;
+            }
+        }
+        info!(
+            "Raft state loaded: term {}, log {}..{}, members {:?}",
+            state.term,
+            snapshot_index,
+            state.log.last_index(),
+            state.members
+        );
+        Ok(Arc::new(Raft {
+            state: Mutex::new(state),
+            changed: Notify::new(),
+            pending: Mutex::new(HashMap::new()),
+            applier: tokio::sync::Mutex::new(None),
+            db,
+            snapshot_entries: config.snapshot_entries.max(1),
+            auth: config.auth,
+        }))
+    }
+
+    /// Seçim zamanlayıcısını, replikatörleri ve uygulayıcıyı başlatır.
+    pub async fn start(self: &Arc<Self>, client: Interpreter) {
+        *self.applier.lock().await = Some(client.raft_applier());
+        tokio::spawn(run(Arc::clone(self)));
+        tokio::spawn(apply_loop(Arc::clone(self)));
+    }
+
+    /// Lider değilsek istemciye dönülecek hata.
+    fn check_leader(state: &State) -> Result<(), String> {
+        match (&state.role, &state.leader) {
+            (Role::Leader, _) => Ok(()),
+            (_, Some(leader)) => Err(format!("NOTLEADER {}", leader)),
+            (_, None) => Err("TRYAGAIN no raft leader elected yet".to_string()),
+        }
+    }
+
+    /// Girdiyi log'a ekler; commit edilip uygulandığında cevabı alacak kanal döner.
+    fn submit(
+        &self,
+        state: &mut State,
+        kind: EntryKind,
+    ) -> Result<oneshot::Receiver<RespValue>, String> {
+        let index = state
+            .propose(kind)
+            .map_err(|e| format!("raft log write failed: {}", e))?;
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().insert(
+            index,
+            Pending {
+                term: state.term,
+                reply: sender,
+            },
+        );
+        Ok(receiver)
+    }
+
+    async fn wait_applied(
+        &self,
+        receiver: oneshot::Receiver<RespValue>,
+    ) -> Result<RespValue, String> {
+        self.changed.notify_waiters();
+        match tokio::time::timeout(REQUEST_TIMEOUT, receiver).await {
+            Ok(Ok(reply)) => Ok(reply),
+            _ => Err(
+                "TRYAGAIN the write was not committed in time, its outcome is unknown".to_string(),
+            ),
+        }
+    }
+
+    /// Yazma komutunu log üzerinden çalıştırır ve uygulandığındaki cevabını döndürür.
+    pub async fn propose(&self, command: Vec<String>) -> Result<RespValue, String> {
+        let receiver = {
+            let mut state = self.state.lock();
+            Self::check_leader(&state)?;
+            self.submit(&mut state, EntryKind::Command(command))?
+        };
+        self.wait_applied(receiver).await
+    }
+
+    /// Okumadan önce liderliği doğrular ve okuma anındaki commit index'inin
+    /// uygulanmasını bekler.
+    pub async fn read_index(&self) -> Result<(), String> {
+        let (term, index, round) = {
+            let mut state = self.state.lock();
+            Self::check_leader(&state)?;
+            // Yeni lider hangi girdilerin commit edildiğini ancak kendi döneminden
+            // bir girdiyi commit edince bilir
+            if state.log.term_at(state.commit_index) != Some(state.term) {
+                return Err("TRYAGAIN the new raft leader is not ready yet".to_string());
+            }
+            state.round += 1;
+            (state.term, state.commit_index, state.round)
+        };
+        self.changed.notify_waiters();
+
+        let deadline = tokio::time::Instant::now() + REQUEST_TIMEOUT;
+        loop {
+            let changed = self.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+            {
+                let state = self.state.lock();
+                if state.role != Role::Leader || state.term != term {
+                    return Self::check_leader(&state);
+                }
+                if state.quorum_round() >= round && state.last_applied >= index {
+                    return Ok(());
+                }
+            }
+            if tokio::time::timeout_at(deadline, changed).await.is_err() {
+                return Err("TRYAGAIN raft leadership could not be confirmed in time".to_string());
+            }
+        }
+    }
+
+    /// Üye ekler ya da çıkarır. Önceki değişiklik commit edilmeden yenisi yapılamaz.
+    async fn change_members(&self, addr: &str, add: bool) -> Result<RespValue, String> {
+        if split_addr(addr).is_none() {
+            return Err(format!(
+                "invalid node address '{}', expected host:port",
+                addr
+            ));
+        }
+        let receiver = {
+            let mut state = self.state.lock();
+            Self::check_leader(&state)?;
+            if state.members_index > state.commit_index {
+                return Err("TRYAGAIN a membership change is already in progress".to_string());
+            }
+            let mut members = state.members.clone();
+            if add == members.iter().any(|member| member == addr) {
+                return Ok(RespValue::SimpleString("OK".to_string()));
+            }
+            if add {
+                members.push(addr.to_string());
+                members.sort();
+            } else {
+                members.retain(|member| member != addr);
+                if members.is_empty() {
+                    return Err("cannot remove the last raft member".to_string());
+                }
+            }
+            info!("Proposing raft members {:?}", members);
+            self.submit(&mut state, EntryKind::Members(members))?
+        };
+        self.wait_applied(receiver).await
+    }
+
+    /// INFO'daki `# Raft` bölümü.
+    pub fn info(&self) -> String {
+        let state = self.state.lock();
+        format!(
+            "# Raft\r\nraft_node:{}\r\nraft_role:{}\r\nraft_term:{}\r\nraft_leader:{}\r\nraft_commit_index:{}\r\nraft_last_applied:{}\r\nraft_log_first_index:{}\r\nraft_log_last_index:{}\r\nraft_members:{}\r\n",
+            state.id,
+            state.role.name(),
+            state.term,
+            state.leader.as_deref().unwrap_or(""),
+            state.commit_index,
+            state.last_applied,
+            state.log.snapshot_index + 1,
+            state.log.last_index(),
+            state.members.join(","),
+        )
+    }
+
+    /// RAFT komutu: istemci alt komutları ve node'lar arası RPC'ler.
+    pub async fn command(&self, args: &[String]) -> RespValue {
+        match self.try_command(args).await {
+            Ok(reply) => reply,
+            Err(e) => RespValue::Error(e),
+        }
+    }
+
+    async fn try_command(&self, args: &[String]) -> Result<RespValue, String> {
+        let subcommand = args.first().map(|s| s.to_uppercase()).unwrap_or_default();
+        let rest = args.get(1..).unwrap_or_default();
+        let bad_request = || format!("invalid RAFT {} request", subcommand);
+        let now = Instant::now();
+        match subcommand.as_str() {
+            "VOTE" => {
+                let request = VoteRequest::parse(rest).ok_or_else(bad_request)?;
+                let reply = self
+                    .state
+                    .lock()
+                    .handle_vote(&request, now)
+                    .map_err(|e| e.to_string())?;
+                Ok(reply.to_resp())
+            }
+            "APPEND" => {
+                let request = AppendRequest::parse(rest).ok_or_else(bad_request)?;
+                let reply = self
+                    .state
+                    .lock()
+                    .handle_append(request, now)
+                    .map_err(|e| e.to_string())?;
+                self.changed.notify_waiters();
+                Ok(reply.to_resp())
+            }
+            "SNAPSHOT" => {
+                let request = SnapshotRequest::parse(rest).ok_or_else(bad_request)?;
+                self.install_snapshot(request)
+                    .await
+                    .map_err(|e| format!("snapshot install failed: {}", e))
+            }
+            "INFO" => Ok(RespValue::BulkString(Some(self.info()))),
+            "LEADER" => Ok(RespValue::BulkString(self.state.lock().leader.clone())),
+            "ADDNODE" | "REMOVENODE" => {
+                let [addr] = rest else {
+                    return Err(format!(
+                        "wrong number of arguments for 'RAFT {}' command",
+                        subcommand
+                    ));
+                };
+                self.change_members(addr, subcommand == "ADDNODE").await
+            }
+            _ => Err(format!(
+                "unknown RAFT subcommand '{}'",
+                args.first().map(String::as_str).unwrap_or("")
+            )),
+        }
+    }
+
+    /// Liderden gelen snapshot'ı veri setinin yerine yükler.
+    async fn install_snapshot(&self, request: SnapshotRequest) -> io::Result<RespValue> {
+        if !self
+            .state
+            .lock()
+            .accept_snapshot(&request, Instant::now())?
+        {
+            return Ok(RespValue::Integer(self.state.lock().term as i64));
+        }
+        let applier = self.applier.lock().await;
+        let path = self
+            .state
+            .lock()
+            .snapshot_path(request.last_index)
+            .ok_or_else(|| io::Error::other("raft storage is not on disk"))?;
+        tokio::fs::write(&path, &request.data).await?;
+        self.db.write().await.clear();
+        snapshot::load(&path, &self.db).await?;
+        if let Some(client) = applier.as_ref() {
+            client.invalidate_all();
+        }
+
+        let mut state = self.state.lock();
+        state.install_snapshot(SnapshotMeta {
+            index: request.last_index,
+            term: request.last_term,
+            members: request.members,
+        })?;
+        info!("Installed raft snapshot up to index {}", request.last_index);
+        drop(state);
+        self.changed.notify_waiters();
+        Ok(RespValue::Integer(self.state.lock().term as i64))
+    }
+
+    /// Commit edilmiş ama uygulanmamış girdileri uygular. Uygulanacak girdi yoksa `false`.
+    async fn apply_committed(&self) -> bool {
+        let mut applier = self.applier.lock().await;
+        let Some(client) = applier.as_mut() else {
+            return false;
+        };
+        let entries = {
+            let state = self.state.lock();
+            if state.commit_index <= state.last_applied {
+                return false;
+            }
+            let count = (state.commit_index - state.last_applied) as usize;
+            state.log.entries_from(state.last_applied + 1, count)
+        };
+
+        for entry in entries {
+            let reply = match entry.kind {
+                EntryKind::Command(command) => {
+                    let request = RespValue::Array(Some(
+                        command
+                            .into_iter()
+                            .map(|arg| RespValue::BulkString(Some(arg)))
+                            .collect(),
+                    ));
+                    match client.execute(request).await {
+                        ExecutionResult::Response(reply) => reply,
+                        _ => RespValue::Error("command is not supported in raft mode".to_string()),
+                    }
+                }
+                EntryKind::Members(_) | EntryKind::Noop => {
+                    RespValue::SimpleString("OK".to_string())
+                }
+            };
+            if let Some(pending) = self.pending.lock().remove(&entry.index) {
+                let reply = if pending.term == entry.term {
+                    reply
+                } else {
+                    RespValue::Error(
+                        "TRYAGAIN raft leadership changed before the write was committed"
+                            .to_string(),
+                    )
+                };
+                let _ = pending.reply.send(reply);
+            }
+            self.state.lock().last_applied = entry.index;
+        }
+        self.changed.notify_waiters();
+
+        let (applied, snapshot_index, path) = {
+            let state = self.state.lock();
+            (
+                state.last_applied,
+                state.log.snapshot_index,
+                state.snapshot_path(state.last_applied),
+            )
+        };
+        if applied - snapshot_index >= self.snapshot_entries {
+            if let Some(path) = path {
+                match snapshot::save(&path, &self.db).await {
+                    Ok(()) => {
+                        log_error("log compaction", self.state.lock().compact(applied));
+                        info!("Raft log compacted up to index {}", applied);
+                    }
+                    Err(e) => error!("Raft snapshot failed: {}", e),
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Commit edilen girdileri sırayla uygular.
+async fn apply_loop(raft: Arc<Raft>) {
+    loop {
+        let changed = raft.changed.notified();
+        tokio::pin!(changed);
+        changed.as_mut().enable();
+        if !raft.apply_committed().await {
+            changed.await;
+        }
+    }
+}
+
+/// Seçim zamanlayıcısı; liderken her takipçi için bir replikatör çalıştırır.
+async fn run(raft: Arc<Raft>) {
+    let mut replicators: HashMap<String, (u64, JoinHandle<()>)> = HashMap::new();
+    let mut interval = tokio::time::interval(TICK);
+    loop {
+        interval.tick().await;
+        let (vote, peers, leader_term) = {
+            let mut state = raft.state.lock();
+            let vote = match state.tick(Instant::now()) {
+                Ok(vote) => vote,
+                Err(e) => {
+                    error!("Raft election failed: {}", e);
+                    None
+                }
+            };
+            let leader_term = (state.role == Role::Leader).then_some(state.term);
+            (vote, state.peers(), leader_term)
+        };
+
+        if let Some(request) = vote {
+            info!("Starting raft election for term {}", request.term);
+            for peer in &peers {
+                tokio::spawn(request_vote(
+                    Arc::clone(&raft),
+                    peer.clone(),
+                    request.clone(),
+                ));
+            }
+        }
+
+        replicators.retain(|peer, (term, task)| {
+            let keep = Some(*term) == leader_term && peers.contains(peer) && !task.is_finished();
+            if !keep {
+                task.abort();
+            }
+            keep
+        });
+        if let Some(term) = leader_term {
+            for peer in peers {
+                replicators.entry(peer.clone()).or_insert_with(|| {
+                    (term, tokio::spawn(replicate(Arc::clone(&raft), peer, term)))
+                });
+            }
+        }
+    }
+}
+
+async fn request_vote(raft: Arc<Raft>, peer: String, request: VoteRequest) {
+    let reply = match rpc(&raft, &peer, &request.to_args(), RPC_TIMEOUT).await {
+        Ok(reply) => VoteReply::from_resp(&reply),
+        Err(e) => {
+            debug!("Raft vote request to {} failed: {}", peer, e);
+            return;
+        }
+    };
+    let Some(reply) = reply else {
+        warn!("Invalid raft vote reply from {}", peer);
+        return;
+    };
+    let mut state = raft.state.lock();
+    let was_leader = state.role == Role::Leader;
+    log_error("vote", state.handle_vote_reply(&peer, request.term, &reply));
+    if !was_leader && state.role == Role::Leader {
+        info!("Elected raft leader for term {}", state.term);
+    }
+    drop(state);
+    raft.changed.notify_waiters();
+}
+
+/// Liderken tek bir takipçiye girdileri, heartbeat'leri ve gerekirse snapshot'ı gönderir.
+async fn replicate(raft: Arc<Raft>, peer: String, term: u64) {
+    let Some((host, port)) = split_addr(&peer) else {
+        warn!("Invalid raft member address {}", peer);
+        return;
+    };
+    let mut connection: Option<Connection> = None;
+    let mut last_sent: Option<tokio::time::Instant> = None;
+    let mut sent_round = 0;
+
+    loop {
+        let changed = raft.changed.notified();
+        tokio::pin!(changed);
+        changed.as_mut().enable();
+
+        let (next, round) = {
+            let state = raft.state.lock();
+            if state.role != Role::Leader || state.term != term {
+                return;
+            }
+            (state.replicate(&peer), state.round)
+        };
+        let Some(next) = next else {
+            return;
+        };
+        let heartbeat_due = last_sent.is_none_or(|sent| sent.elapsed() >= HEARTBEAT_PERIOD);
+        if let Replicate::Append(request) = &next {
+            if request.entries.is_empty() && round <= sent_round && !heartbeat_due {
+                let wake = last_sent.unwrap_or_else(tokio::time::Instant::now) + HEARTBEAT_PERIOD;
+                tokio::select! {
+                    _ = changed => {}
+                    _ = tokio::time::sleep_until(wake) => {}
+                }
+                continue;
+            }
+        }
+        last_sent = Some(tokio::time::Instant::now());
+        sent_round = round;
+
+        let result = match next {
+            Replicate::Append(request) => {
+                send_append(&raft, &mut connection, host, port, &peer, round, request).await
+            }
+            Replicate::Snapshot { index } => send_snapshot(&raft, &peer, round, index).await,
+        };
+        if let Err(e) = result {
+            debug!("Raft replication to {} failed: {}", peer, e);
+            connection = None;
+            tokio::time::sleep(HEARTBEAT_PERIOD).await;
+        }
+    }
+}
+
+async fn send_append(
+    raft: &Raft,
+    connection: &mut Option<Connection>,
+    host: &str,
+    port: u16,
+    peer: &str,
+    round: u64,
+    request: AppendRequest,
+) -> io::Result<()> {
+    if connection.is_none() {
+        *connection =
+            Some(Connection::connect_auth(host, port, RPC_TIMEOUT, raft.auth.as_ref()).await?);
+    }
+    let Some(connection) = connection.as_mut() else {
+        return Ok(());
+    };
+    let args = request.to_args();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let reply = connection.request(&args).await?;
+    let reply = AppendReply::from_resp(&reply)
+        .ok_or_else(|| io::Error::other(format!("invalid append reply: {:?}", reply)))?;
+
+    let mut state = raft.state.lock();
+    log_error(
+        "append",
+        state.handle_append_reply(peer, request.term, round, &reply),
+    );
+    if reply.term > request.term {
+        info!(
+            "Stepped down as raft leader, {} has term {}",
+            peer, reply.term
+        );
+    }
+    drop(state);
+    raft.changed.notify_waiters();
+    Ok(())
+}
+
+async fn send_snapshot(raft: &Raft, peer: &str, round: u64, index: u64) -> io::Result<()> {
+    let (path, mut request) = {
+        let state = raft.state.lock();
+        let request = SnapshotRequest {
+            term: state.term,
+            leader: state.id.clone(),
+            last_index: index,
+            last_term: state.log.snapshot_term,
+            members: state.log.snapshot_members.clone(),
+            data: Vec::new(),
+        };
+        (state.snapshot_path(index), request)
+    };
+    let path = path.ok_or_else(|| io::Error::other("raft storage is not on disk"))?;
+    // Bu arada yeni bir snapshot alındıysa dosya silinmiş olabilir; bir sonraki turda
+    // yenisi gönderilir
+    request.data = tokio::fs::read(&path).await?;
+
+    let reply = rpc(raft, peer, &request.to_args(), SNAPSHOT_TIMEOUT).await?;
+    let RespValue::Integer(reply_term) = reply else {
+        return Err(io::Error::other(format!(
+            "invalid snapshot reply: {:?}",
+            reply
+        )));
+    };
+    info!("Sent raft snapshot up to index {} to {}", index, peer);
+    let mut state = raft.state.lock();
+    log_error(
+        "snapshot",
+        state.handle_snapshot_reply(peer, request.term, round, index, reply_term as u64),
+    );
+    drop(state);
+    raft.changed.notify_waiters();
+    Ok(())
+}
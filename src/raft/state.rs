@@ -0,0 +1,684 @@
+//! Raft algoritmasının ağdan ve zamanlayıcılardan bağımsız kısmı. Mesajları işler,
+//! gönderilecek istekleri üretir; kalıcı durumu cevap vermeden önce diske yazar.
+
+use crate::raft::log::{Entry, EntryKind, Log};
+use crate::raft::message::{AppendReply, AppendRequest, SnapshotRequest, VoteReply, VoteRequest};
+use crate::raft::storage::{HardState, SnapshotMeta, Storage};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::time::{Duration, Instant};
+
+/// Bu süre boyunca liderden haber alınmazsa seçim başlatılır (rastgele uzatılır)
+pub const ELECTION_TIMEOUT: Duration = Duration::from_millis(1000);
+/// Tek bir APPEND isteğinde gönderilen en fazla girdi sayısı
+const MAX_APPEND_ENTRIES: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Follower,
+    Candidate,
+    Leader,
+}
+
+impl Role {
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::Follower => "follower",
+            Role::Candidate => "candidate",
+            Role::Leader => "leader",
+        }
+    }
+}
+
+/// Liderin bir takipçiye ilişkin bildikleri.
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    pub next_index: u64,
+    pub match_index: u64,
+    /// Takipçinin onayladığı en son heartbeat turu
+    pub acked_round: u64,
+}
+
+/// Lider bir takipçiye ne göndermeli.
+pub enum Replicate {
+    Append(AppendRequest),
+    /// Takipçinin ihtiyaç duyduğu girdiler snapshot'a alınmış
+    Snapshot {
+        index: u64,
+    },
+}
+
+pub struct State {
+    /// Bu node'un adresi; üye listesinde de bu adresle yer alır
+    pub id: String,
+    pub term: u64,
+    pub voted_for: Option<String>,
+    pub role: Role,
+    pub leader: Option<String>,
+    pub log: Log,
+    pub commit_index: u64,
+    pub last_applied: u64,
+    pub members: Vec<String>,
+    /// Üye listesini belirleyen girdinin index'i
+    pub members_index: u64,
+    votes: HashSet<String>,
+    pub progress: HashMap<String, Progress>,
+    /// Lider her read-index isteğinde turu ilerletir; çoğunluk bu turu onaylayınca
+    /// liderliğin sürdüğü kesinleşir
+    pub round: u64,
+    election_deadline: Instant,
+    last_leader_contact: Option<Instant>,
+    storage: Storage,
+}
+
+/// Seçim zaman aşımını node'lar aynı anda aday olmasın diye rastgele uzatır.
+fn election_deadline(now: Instant) -> Instant {
+    let jitter = uuid::Uuid::new_v4().as_u128() % ELECTION_TIMEOUT.as_millis();
+    now + ELECTION_TIMEOUT + Duration::from_millis(jitter as u64)
+}
+
+impl State {
+    /// Diskteki durumu yükler. Log boşsa ve `peers` verildiyse ilk üye listesi log'a yazılır;
+    /// `peers` boşsa node bir lidere eklenmeyi bekler.
+    pub fn open(id: String, peers: &[String], storage: Storage, now: Instant) -> io::Result<Self> {
+        let (hard_state, log) = storage.load()?;
+        let mut state = State {
+            id,
+            term: hard_state.term,
+            voted_for: hard_state.voted_for,
+            role: Role::Follower,
+            leader: None,
+            commit_index: log.snapshot_index,
+            last_applied: log.snapshot_index,
+            log,
+            members: Vec::new(),
+            members_index: 0,
+            votes: HashSet::new(),
+            progress: HashMap::new(),
+            round: 0,
+            election_deadline: election_deadline(now),
+            last_leader_contact: None,
+            storage,
+        };
+        if state.log.last_index() == 0 && !peers.is_empty() {
+            let mut members = peers.to_vec();
+            if !members.contains(&state.id) {
+                members.push(state.id.clone());
+            }
+            members.sort();
+            members.dedup();
+            // Tüm node'lar aynı girdiyle başladığından log'ları tutarlıdır
+            state.append_entries(vec![Entry {
+                term: 0,
+                index: 1,
+                kind: EntryKind::Members(members),
+            }])?;
+        }
+        state.refresh_members();
+        Ok(state)
+    }
+
+    fn quorum(&self) -> usize {
+        self.members.len() / 2 + 1
+    }
+
+    pub fn is_member(&self) -> bool {
+        self.members.contains(&self.id)
+    }
+
+    /// Üye listesindeki diğer node'lar.
+    pub fn peers(&self) -> Vec<String> {
+        self.members
+            .iter()
+            .filter(|member| **member != self.id)
+            .cloned()
+            .collect()
+    }
+
+    fn save_hard_state(&self) -> io::Result<()> {
+        self.storage.save_hard_state(&HardState {
+            term: self.term,
+            voted_for: self.voted_for.clone(),
+        })
+    }
+
+    /// Üye listesini log'daki son yapılandırmadan yeniden hesaplar.
+    fn refresh_members(&mut self) {
+        let (members, index) = self.log.members_at(self.log.last_index());
+        self.members = members;
+        self.members_index = index;
+        if self.role == Role::Leader {
+            let next_index = self.log.last_index() + 1;
+            self.progress
+                .retain(|peer, _| self.members.contains(peer) && *peer != self.id);
+            for peer in self.members.iter().filter(|peer| **peer != self.id) {
+                self.progress.entry(peer.clone()).or_insert(Progress {
+                    next_index,
+                    match_index: 0,
+                    acked_round: 0,
+                });
+            }
+        }
+    }
+
+    fn append_entries(&mut self, entries: Vec<Entry>) -> io::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        self.storage.append(&entries)?;
+        let changes_members = entries
+            .iter()
+            .any(|entry| matches!(entry.kind, EntryKind::Members(_)));
+        for entry in entries {
+            self.log.append(entry);
+        }
+        if changes_members {
+            self.refresh_members();
+        }
+        Ok(())
+    }
+
+    fn step_down(&mut self, term: u64) -> io::Result<()> {
+        if term > self.term {
+            self.term = term;
+            self.voted_for = None;
+            self.save_hard_state()?;
+        }
+        if self.role == Role::Leader {
+            self.leader = None;
+        }
+        self.role = Role::Follower;
+        self.votes.clear();
+        self.progress.clear();
+        Ok(())
+    }
+
+    /// Zamanlayıcı: seçim zaman aşımı dolduysa aday olur ve diğer üyelere gönderilecek
+    /// oy isteğini döndürür.
+    pub fn tick(&mut self, now: Instant) -> io::Result<Option<VoteRequest>> {
+        if self.role == Role::Leader || now < self.election_deadline || !self.is_member() {
+            return Ok(None);
+        }
+        self.term += 1;
+        self.voted_for = Some(self.id.clone());
+        self.save_hard_state()?;
+        self.role = Role::Candidate;
+        self.leader = None;
+        self.votes = HashSet::from([self.id.clone()]);
+        self.election_deadline = election_deadline(now);
+        if self.votes.len() >= self.quorum() {
+            self.become_leader()?;
+            return Ok(None);
+        }
+        Ok(Some(VoteRequest {
+            term: self.term,
+            candidate: self.id.clone(),
+            last_index: self.log.last_index(),
+            last_term: self.log.last_term(),
+        }))
+    }
+
+    fn become_leader(&mut self) -> io::Result<()> {
+        self.role = Role::Leader;
+        self.leader = Some(self.id.clone());
+        self.votes.clear();
+        self.progress.clear();
+        self.refresh_members();
+        let entry = Entry {
+            term: self.term,
+            index: self.log.last_index() + 1,
+            kind: EntryKind::Noop,
+        };
+        self.append_entries(vec![entry])?;
+        self.maybe_commit();
+        Ok(())
+    }
+
+    pub fn handle_vote(&mut self, request: &VoteRequest, now: Instant) -> io::Result<VoteReply> {
+        // Liderden yakın zamanda haber aldıysak oylamayı yok sayarız; böylece üyelikten
+        // çıkarılan bir node seçim başlatarak kümeyi bozamaz
+        let leader_alive = self.role == Role::Leader
+            || self
+                .last_leader_contact
+                .is_some_and(|contact| now < contact + ELECTION_TIMEOUT);
+        if request.term > self.term && !leader_alive {
+            self.step_down(request.term)?;
+        }
+
+        let up_to_date = (request.last_term, request.last_index)
+            >= (self.log.last_term(), self.log.last_index());
+        let granted = request.term == self.term
+            && up_to_date
+            && self
+                .voted_for
+                .as_ref()
+                .is_none_or(|voted| *voted == request.candidate);
+        if granted {
+            self.voted_for = Some(request.candidate.clone());
+            self.save_hard_state()?;
+            self.election_deadline = election_deadline(now);
+        }
+        Ok(VoteReply {
+            term: self.term,
+            granted,
+        })
+    }
+
+    pub fn handle_vote_reply(
+        &mut self,
+        from: &str,
+        term: u64,
+        reply: &VoteReply,
+    ) -> io::Result<()> {
+        if reply.term > self.term {
+            return self.step_down(reply.term);
+        }
+        if self.role != Role::Candidate || self.term != term || !reply.granted {
+            return Ok(());
+        }
+        self.votes.insert(from.to_string());
+        let votes = self
+            .votes
+            .iter()
+            .filter(|voter| self.members.contains(voter))
+            .count();
+        if votes >= self.quorum() {
+            self.become_leader()?;
+        }
+        Ok(())
+    }
+
+    /// Liderden gelen her istek: dönemi kontrol eder ve lideri kaydeder.
+    /// İstek eski bir dönemdense `false` döner.
+    fn accept_leader(&mut self, term: u64, leader: &str, now: Instant) -> io::Result<bool> {
+        if term < self.term {
+            return Ok(false);
+        }
+        if term > self.term || self.role != Role::Follower {
+            self.step_down(term)?;
+        }
+        self.leader = Some(leader.to_string());
+        self.last_leader_contact = Some(now);
+        self.election_deadline = election_deadline(now);
+        Ok(true)
+    }
+
+    pub fn handle_append(
+        &mut self,
+        request: AppendRequest,
+        now: Instant,
+    ) -> io::Result<AppendReply> {
+        let reject = |state: &State, index: u64| AppendReply {
+            term: state.term,
+            success: false,
+            index,
+        };
+        if !self.accept_leader(request.term, &request.leader, now)? {
+            return Ok(reject(self, 0));
+        }
+        if request.prev_index > self.log.last_index() {
+            return Ok(reject(self, self.log.last_index()));
+        }
+        // Snapshot'a alınmış girdiler zaten commit edilmiştir; eşleştiği kabul edilir
+        if request.prev_index >= self.log.snapshot_index
+            && self.log.term_at(request.prev_index) != Some(request.prev_term)
+        {
+            return Ok(reject(self, request.prev_index.saturating_sub(1)));
+        }
+
+        let last_new = request.prev_index + request.entries.len() as u64;
+        let mut new_entries = Vec::new();
+        for entry in request.entries {
+            if entry.index <= self.log.snapshot_index {
+                continue;
+            }
+            if new_entries.is_empty() {
+                match self.log.term_at(entry.index) {
+                    Some(term) if term == entry.term => continue,
+                    Some(_) => {
+                        // Çelişen girdi ve sonrası silinir (henüz commit edilmemiştir)
+                        self.log.truncate_from(entry.index);
+                        self.storage.rewrite_log(&self.log)?;
+                        self.refresh_members();
+                    }
+                    None => {}
+                }
+            }
+            new_entries.push(entry);
+        }
+        self.append_entries(new_entries)?;
+
+        if request.leader_commit > self.commit_index {
+            self.commit_index = request.leader_commit.min(last_new).max(self.commit_index);
+        }
+        Ok(AppendReply {
+            term: self.term,
+            success: true,
+            index: last_new,
+        })
+    }
+
+    /// Takipçiye gönderilecek sonraki istek; lider değilsek ya da takipçi üye değilse `None`.
+    pub fn replicate(&self, peer: &str) -> Option<Replicate> {
+        if self.role != Role::Leader {
+            return None;
+        }
+        let progress = self.progress.get(peer)?;
+        if progress.next_index <= self.log.snapshot_index {
+            return Some(Replicate::Snapshot {
+                index: self.log.snapshot_index,
+            });
+        }
+        let prev_index = progress.next_index - 1;
+        Some(Replicate::Append(AppendRequest {
+            term: self.term,
+            leader: self.id.clone(),
+            prev_index,
+            prev_term: self.log.term_at(prev_index)?,
+            leader_commit: self.commit_index,
+            entries: self
+                .log
+                .entries_from(progress.next_index, MAX_APPEND_ENTRIES),
+        }))
+    }
+
+    /// Takipçinin cevabını işler. `term` ve `round`, isteğin gönderildiği andaki değerlerdir.
+    pub fn handle_append_reply(
+        &mut self,
+        peer: &str,
+        term: u64,
+        round: u64,
+        reply: &AppendReply,
+    ) -> io::Result<()> {
+        if reply.term > self.term {
+            return self.step_down(reply.term);
+        }
+        if self.role != Role::Leader || self.term != term {
+            return Ok(());
+        }
+        let Some(progress) = self.progress.get_mut(peer) else {
+            return Ok(());
+        };
+        progress.acked_round = progress.acked_round.max(round);
+        if reply.success {
+            progress.match_index = progress.match_index.max(reply.index);
+            progress.next_index = progress.match_index + 1;
+            self.maybe_commit();
+        } else {
+            progress.next_index = (reply.index + 1)
+                .min(progress.next_index.saturating_sub(1))
+                .max(1);
+        }
+        Ok(())
+    }
+
+    /// Takipçi snapshot'ı kurdu; `index`'e kadar her şeye sahip.
+    pub fn handle_snapshot_reply(
+        &mut self,
+        peer: &str,
+        term: u64,
+        round: u64,
+        index: u64,
+        reply_term: u64,
+    ) -> io::Result<()> {
+        if reply_term > self.term {
+            return self.step_down(reply_term);
+        }
+        if self.role != Role::Leader || self.term != term {
+            return Ok(());
+        }
+        if let Some(progress) = self.progress.get_mut(peer) {
+            progress.acked_round = progress.acked_round.max(round);
+            progress.match_index = progress.match_index.max(index);
+            progress.next_index = progress.match_index + 1;
+        }
+        self.maybe_commit();
+        Ok(())
+    }
+
+    /// Çoğunlukta bulunan ve bu döneme ait en yüksek girdiyi commit eder.
+    fn maybe_commit(&mut self) {
+        let mut matched: Vec<u64> = self
+            .members
+            .iter()
+            .map(|member| {
+                if *member == self.id {
+                    self.log.last_index()
+                } else {
+                    self.progress.get(member).map_or(0, |p| p.match_index)
+                }
+            })
+            .collect();
+        if matched.is_empty() {
+            return;
+        }
+        matched.sort_unstable_by(|a, b| b.cmp(a));
+        let candidate = matched[self.quorum() - 1];
+        if candidate > self.commit_index && self.log.term_at(candidate) == Some(self.term) {
+            self.commit_index = candidate;
+        }
+        // Kendini üyelikten çıkaran lider, değişiklik commit edilince görevi bırakır
+        if !self.is_member() && self.commit_index >= self.members_index {
+            self.role = Role::Follower;
+            self.leader = None;
+            self.progress.clear();
+        }
+    }
+
+    /// Çoğunluğun onayladığı en son heartbeat turu.
+    pub fn quorum_round(&self) -> u64 {
+        let mut rounds: Vec<u64> = self
+            .members
+            .iter()
+            .map(|member| {
+                if *member == self.id {
+                    self.round
+                } else {
+                    self.progress.get(member).map_or(0, |p| p.acked_round)
+                }
+            })
+            .collect();
+        if rounds.is_empty() {
+            return 0;
+        }
+        rounds.sort_unstable_by(|a, b| b.cmp(a));
+        rounds[self.quorum() - 1]
+    }
+
+    /// Lider olarak yeni bir girdi ekler; girdinin index'ini döndürür.
+    pub fn propose(&mut self, kind: EntryKind) -> io::Result<u64> {
+        let index = self.log.last_index() + 1;
+        self.append_entries(vec![Entry {
+            term: self.term,
+            index,
+            kind,
+        }])?;
+        self.maybe_commit();
+        Ok(index)
+    }
+
+    /// Snapshot isteğini kabul edip etmeyeceğimiz. Veri kurulduktan sonra
+    /// `install_snapshot` çağrılır.
+    pub fn accept_snapshot(&mut self, request: &SnapshotRequest, now: Instant) -> io::Result<bool> {
+        Ok(self.accept_leader(request.term, &request.leader, now)?
+            && request.last_index > self.commit_index)
+    }
+
+    pub fn install_snapshot(&mut self, meta: SnapshotMeta) -> io::Result<()> {
+        let previous = self.log.snapshot_index;
+        self.log
+            .install_snapshot(meta.index, meta.term, meta.members.clone());
+        self.storage.commit_snapshot(&meta, previous)?;
+        self.storage.rewrite_log(&self.log)?;
+        self.commit_index = self.commit_index.max(meta.index);
+        self.last_applied = meta.index;
+        self.refresh_members();
+        Ok(())
+    }
+
+    /// `index`'e kadar uygulanmış girdiler snapshot'a alındı; log sıkıştırılır.
+    pub fn compact(&mut self, index: u64) -> io::Result<()> {
+        let Some(term) = self.log.term_at(index) else {
+            return Ok(());
+        };
+        let previous = self.log.snapshot_index;
+        let (members, _) = self.log.members_at(index);
+        let meta = SnapshotMeta {
+            index,
+            term,
+            members: members.clone(),
+        };
+        self.storage.commit_snapshot(&meta, previous)?;
+        self.log.compact(index, term, members);
+        self.storage.rewrite_log(&self.log)
+    }
+
+    pub fn snapshot_path(&self, index: u64) -> Option<std::path::PathBuf> {
+        self.storage.snapshot_path(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, peers: &[&str]) -> State {
+        let peers: Vec<String> = peers.iter().map(|p| p.to_string()).collect();
+        State::open(id.to_string(), &peers, Storage::memory(), Instant::now()).unwrap()
+    }
+
+    /// `leader`'ın tüm takipçilere göndereceklerini iletir ve cevapları işler.
+    fn replicate_all(leader: &mut State, followers: &mut [&mut State]) {
+        for follower in followers.iter_mut() {
+            while let Some(Replicate::Append(request)) = leader.replicate(&follower.id) {
+                let heartbeat = request.entries.is_empty();
+                let term = leader.term;
+                let round = leader.round;
+                let reply = follower.handle_append(request, Instant::now()).unwrap();
+                leader
+                    .handle_append_reply(&follower.id.clone(), term, round, &reply)
+                    .unwrap();
+                if heartbeat && reply.success {
+                    break;
+                }
+            }
+        }
+    }
+
+    fn elect(candidate: &mut State, voters: &mut [&mut State]) {
+        let later = Instant::now() + ELECTION_TIMEOUT * 3;
+        let request = candidate.tick(later).unwrap().unwrap();
+        for voter in voters.iter_mut() {
+            let reply = voter.handle_vote(&request, later).unwrap();
+            candidate
+                .handle_vote_reply(&voter.id.clone(), request.term, &reply)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_single_node_commits_immediately() {
+        let mut a = node("a", &["a"]);
+        assert!(a
+            .tick(Instant::now() + ELECTION_TIMEOUT * 3)
+            .unwrap()
+            .is_none());
+        assert_eq!(a.role, Role::Leader);
+        let index = a.propose(EntryKind::Command(vec!["SET".into()])).unwrap();
+        assert_eq!(a.commit_index, index);
+    }
+
+    #[test]
+    fn test_election_and_replication() {
+        let peers = ["a", "b", "c"];
+        let (mut a, mut b, mut c) = (node("a", &peers), node("b", &peers), node("c", &peers));
+        elect(&mut a, &mut [&mut b, &mut c]);
+        assert_eq!(a.role, Role::Leader);
+        assert_eq!(a.term, 1);
+
+        let index = a.propose(EntryKind::Command(vec!["SET".into()])).unwrap();
+        assert!(a.commit_index < index);
+        replicate_all(&mut a, &mut [&mut b]);
+        assert_eq!(a.commit_index, index);
+
+        // Commit index bir sonraki istekle takipçilere ulaşır
+        replicate_all(&mut a, &mut [&mut b, &mut c]);
+        assert_eq!(b.commit_index, index);
+        assert_eq!(c.log.last_index(), index);
+    }
+
+    #[test]
+    fn test_vote_requires_up_to_date_log() {
+        let peers = ["a", "b", "c"];
+        let (mut a, mut b, mut c) = (node("a", &peers), node("b", &peers), node("c", &peers));
+        elect(&mut a, &mut [&mut b, &mut c]);
+        a.propose(EntryKind::Noop).unwrap();
+        replicate_all(&mut a, &mut [&mut b]);
+
+        // c eksik log'la aday olursa b oy vermez
+        let later = Instant::now() + ELECTION_TIMEOUT * 6;
+        let request = c.tick(later).unwrap().unwrap();
+        let reply = b.handle_vote(&request, later).unwrap();
+        assert!(!reply.granted);
+        assert_eq!(b.term, request.term);
+    }
+
+    #[test]
+    fn test_conflicting_entries_are_replaced() {
+        let peers = ["a", "b", "c"];
+        let (mut a, mut b, mut c) = (node("a", &peers), node("b", &peers), node("c", &peers));
+        elect(&mut a, &mut [&mut b, &mut c]);
+        replicate_all(&mut a, &mut [&mut b, &mut c]);
+        // a commit edilmemiş bir girdi ekleyip ayrılır
+        a.propose(EntryKind::Command(vec!["LOST".into()])).unwrap();
+
+        elect(&mut b, &mut [&mut c]);
+        assert_eq!(b.role, Role::Leader);
+        b.propose(EntryKind::Command(vec!["KEPT".into()])).unwrap();
+        replicate_all(&mut b, &mut [&mut a, &mut c]);
+
+        assert_eq!(a.role, Role::Follower);
+        assert_eq!(a.log.entries(), b.log.entries());
+        assert!(a
+            .log
+            .entries()
+            .iter()
+            .all(|entry| entry.kind != EntryKind::Command(vec!["LOST".into()])));
+    }
+
+    #[test]
+    fn test_membership_change() {
+        let mut a = node("a", &["a"]);
+        a.tick(Instant::now() + ELECTION_TIMEOUT * 3).unwrap();
+        let mut b = node("b", &[]);
+        assert!(!b.is_member());
+
+        let index = a
+            .propose(EntryKind::Members(vec!["a".into(), "b".into()]))
+            .unwrap();
+        assert_eq!(a.members.len(), 2);
+        // Yeni üye onaylamadan commit edilmez
+        assert!(a.commit_index < index);
+        replicate_all(&mut a, &mut [&mut b]);
+        assert_eq!(a.commit_index, index);
+        assert!(b.is_member());
+
+        // Lider kendini çıkarınca değişiklik commit edilene kadar liderliği sürer
+        let index = a.propose(EntryKind::Members(vec!["b".into()])).unwrap();
+        assert_eq!(a.role, Role::Leader);
+        replicate_all(&mut a, &mut [&mut b]);
+        assert!(a.commit_index >= index);
+        assert_eq!(a.role, Role::Follower);
+    }
+
+    #[test]
+    fn test_quorum_round() {
+        let peers = ["a", "b", "c"];
+        let (mut a, mut b, mut c) = (node("a", &peers), node("b", &peers), node("c", &peers));
+        elect(&mut a, &mut [&mut b, &mut c]);
+        a.round += 1;
+        assert_eq!(a.quorum_round(), 0);
+        replicate_all(&mut a, &mut [&mut b]);
+        assert_eq!(a.quorum_round(), a.round);
+    }
+}
@@ -0,0 +1,153 @@
+//! Raft durumunun diskteki kopyası. Bir isteğe cevap vermeden önce dönem, oy ve log
+//! diske yazılır; böylece yeniden başlayan node verdiği sözleri unutmaz.
+//!
+//! Dizin içeriği:
+//! - `state.json`: dönem ve verilen oy
+//! - `log.jsonl`: snapshot'tan sonraki girdiler, satır başına bir girdi
+//! - `snapshot.json`: son snapshot'ın index'i, dönemi ve üye listesi
+//! - `snapshot-<index>.rdb`: `persistence::snapshot` ile kaydedilmiş veri seti
+
+use crate::raft::log::{Entry, Log};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HardState {
+    pub term: u64,
+    pub voted_for: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SnapshotMeta {
+    pub index: u64,
+    pub term: u64,
+    pub members: Vec<String>,
+}
+
+/// `dir` `None` ise hiçbir şey diske yazılmaz (testler için).
+pub struct Storage {
+    dir: Option<PathBuf>,
+}
+
+/// Dosyayı geçici bir dosyaya yazıp yerine taşır; yarım yazılmış dosya kalmaz.
+fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    let mut file = File::create(&tmp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+}
+
+fn to_line(entry: &Entry) -> io::Result<Vec<u8>> {
+    let mut line = serde_json::to_vec(entry)?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+impl Storage {
+    pub fn open(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Storage { dir: Some(dir) })
+    }
+
+    pub fn memory() -> Self {
+        Storage { dir: None }
+    }
+
+    fn path(&self, name: &str) -> Option<PathBuf> {
+        self.dir.as_ref().map(|dir| dir.join(name))
+    }
+
+    pub fn snapshot_path(&self, index: u64) -> Option<PathBuf> {
+        self.path(&format!("snapshot-{}.rdb", index))
+    }
+
+    /// Kayıtlı durumu okur; dizin boşsa varsayılanlar döner.
+    pub fn load(&self) -> io::Result<(HardState, Log)> {
+        let Some(dir) = &self.dir else {
+            return Ok((HardState::default(), Log::default()));
+        };
+        let read_json = |name: &str| -> io::Result<Option<Vec<u8>>> {
+            match fs::read(dir.join(name)) {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e),
+            }
+        };
+
+        let hard_state = match read_json("state.json")? {
+            Some(bytes) => serde_json::from_slice(&bytes)?,
+            None => HardState::default(),
+        };
+        let meta: SnapshotMeta = match read_json("snapshot.json")? {
+            Some(bytes) => serde_json::from_slice(&bytes)?,
+            None => SnapshotMeta::default(),
+        };
+
+        let mut log = Log::default();
+        log.compact(meta.index, meta.term, meta.members);
+        if let Ok(file) = File::open(dir.join("log.jsonl")) {
+            for line in BufReader::new(file).lines() {
+                let line = line?;
+                // Son satır yazılırken kesilmiş olabilir; cevabı verilmemiş bir girdidir
+                let Ok(entry) = serde_json::from_str::<Entry>(&line) else {
+                    break;
+                };
+                // Snapshot'tan sonra log yeniden yazılamadan kapanmış olabilir
+                if entry.index <= log.last_index() {
+                    continue;
+                }
+                log.append(entry);
+            }
+        }
+        Ok((hard_state, log))
+    }
+
+    pub fn save_hard_state(&self, state: &HardState) -> io::Result<()> {
+        match self.path("state.json") {
+            Some(path) => write_atomic(&path, &serde_json::to_vec(state)?),
+            None => Ok(()),
+        }
+    }
+
+    pub fn append(&self, entries: &[Entry]) -> io::Result<()> {
+        let Some(path) = self.path("log.jsonl") else {
+            return Ok(());
+        };
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        for entry in entries {
+            file.write_all(&to_line(entry)?)?;
+        }
+        file.sync_data()
+    }
+
+    /// Log dosyasını baştan yazar (girdi silindiğinde ya da log sıkıştırıldığında).
+    pub fn rewrite_log(&self, log: &Log) -> io::Result<()> {
+        let Some(path) = self.path("log.jsonl") else {
+            return Ok(());
+        };
+        let mut contents = Vec::new();
+        for entry in log.entries() {
+            contents.extend(to_line(entry)?);
+        }
+        write_atomic(&path, &contents)
+    }
+
+    /// `snapshot-<index>.rdb` kaydedildikten sonra çağrılır: snapshot'ı geçerli kılar
+    /// ve eskisini siler.
+    pub fn commit_snapshot(&self, meta: &SnapshotMeta, previous: u64) -> io::Result<()> {
+        let Some(path) = self.path("snapshot.json") else {
+            return Ok(());
+        };
+        write_atomic(&path, &serde_json::to_vec(meta)?)?;
+        if previous != meta.index {
+            if let Some(old) = self.snapshot_path(previous) {
+                let _ = fs::remove_file(old);
+            }
+        }
+        Ok(())
+    }
+}
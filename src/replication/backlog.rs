@@ -0,0 +1,105 @@
+//! Replikasyon backlog'u: primary'nin yaydığı son baytları tutan halka tampon.
+//! Kısa süre kopan bir replika, kaldığı offset hâlâ burada ise tam senkronizasyon
+//! yerine yalnızca eksik baytları alır (kısmi senkronizasyon).
+
+use std::collections::VecDeque;
+
+pub const DEFAULT_BACKLOG_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug)]
+pub struct Backlog {
+    buffer: VecDeque<u8>,
+    capacity: usize,
+    /// Şimdiye kadar yayılan toplam bayt sayısı (`master_repl_offset`)
+    offset: u64,
+}
+
+impl Backlog {
+    pub fn new(capacity: usize) -> Self {
+        Backlog {
+            buffer: VecDeque::new(),
+            capacity: capacity.max(1),
+            offset: 0,
+        }
+    }
+
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Tampondaki ilk baytın offset'i. Offset'ler Redis'teki gibi 1'den başlar.
+    pub fn first_byte_offset(&self) -> u64 {
+        self.offset + 1 - self.buffer.len() as u64
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.offset += bytes.len() as u64;
+        let skip = bytes.len().saturating_sub(self.capacity);
+        self.buffer.extend(&bytes[skip..]);
+        let overflow = self.buffer.len().saturating_sub(self.capacity);
+        self.buffer.drain(..overflow);
+    }
+
+    /// Offset'i `next` olan bayttan itibaren tampondakileri döndürür.
+    /// `next == offset + 1` ise replika günceldir ve boş vektör döner;
+    /// istenen baytlar artık tamponda değilse `None` döner.
+    pub fn range_from(&self, next: u64) -> Option<Vec<u8>> {
+        if next < self.first_byte_offset() || next > self.offset + 1 {
+            return None;
+        }
+        let start = (next - self.first_byte_offset()) as usize;
+        Some(self.buffer.range(start..).copied().collect())
+    }
+
+    /// Tamponu boşaltır ve offset'i verilen değere ayarlar (tam senkronizasyon sonrası).
+    pub fn reset(&mut self, offset: u64) {
+        self.buffer.clear();
+        self.offset = offset;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_from() {
+        let mut backlog = Backlog::new(8);
+        backlog.push(b"abc");
+        assert_eq!(backlog.offset(), 3);
+        assert_eq!(backlog.first_byte_offset(), 1);
+        assert_eq!(backlog.range_from(1), Some(b"abc".to_vec()));
+        assert_eq!(backlog.range_from(3), Some(b"c".to_vec()));
+        assert_eq!(backlog.range_from(4), Some(Vec::new()));
+        assert_eq!(backlog.range_from(5), None);
+    }
+
+    #[test]
+    fn test_overflow_drops_oldest_bytes() {
+        let mut backlog = Backlog::new(4);
+        backlog.push(b"abc");
+        backlog.push(b"defgh");
+        assert_eq!(backlog.offset(), 8);
+        assert_eq!(backlog.len(), 4);
+        assert_eq!(backlog.first_byte_offset(), 5);
+        assert_eq!(backlog.range_from(4), None);
+        assert_eq!(backlog.range_from(5), Some(b"efgh".to_vec()));
+
+        backlog.reset(100);
+        assert!(backlog.is_empty());
+        assert_eq!(backlog.range_from(101), Some(Vec::new()));
+        assert_eq!(backlog.range_from(100), None);
+    }
+}
@@ -0,0 +1,532 @@
+//! Primary–replika replikasyonu.
+//!
+//! Primary, AOF'a yazılan her komutu RESP olarak backlog'a ve bağlı replikalara yayar.
+//! Replika `PSYNC replid offset` ile bağlanır: istenen offset backlog'da ise yalnızca
+//! eksik baytlar gönderilir (`+CONTINUE`), değilse snapshot ile tam senkronizasyon
+//! yapılır (`+FULLRESYNC`). Offset'ler primary'nin yaydığı toplam bayt sayısıdır.
+
+pub mod backlog;
+pub mod primary;
+pub mod replica;
+
+use crate::clients::Client;
+use crate::commands::Interpreter;
+use crate::db::DB;
+use crate::network::client::Credentials;
+use crate::network::resp::RespValue;
+use backlog::{Backlog, DEFAULT_BACKLOG_SIZE};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Notify, RwLock};
+use tokio::task::JoinHandle;
+
+/// RESP dizisindeki bulk string argümanları.
+pub(crate) fn command_args(request: &RespValue) -> Vec<&str> {
+    match request {
+        RespValue::Array(Some(tokens)) => tokens
+            .iter()
+            .filter_map(|token| match token {
+                RespValue::BulkString(Some(s)) => Some(s.as_str()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Primary'nin bağlantıyı canlı tutmak için replikalara PING gönderme aralığı
+pub const PING_PERIOD: Duration = Duration::from_secs(10);
+/// Replikanın primary'ye ACK gönderme aralığı
+pub const ACK_PERIOD: Duration = Duration::from_secs(1);
+/// Bu süre boyunca primary'den veri gelmezse bağlantı kopmuş sayılır
+pub const REPL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 40 karakterlik rastgele kimlik üretir (replikasyon kimliği, sentinel run id).
+pub(crate) fn random_id() -> String {
+    let mut id = uuid::Uuid::new_v4().simple().to_string();
+    id.push_str(&uuid::Uuid::new_v4().simple().to_string());
+    id.truncate(40);
+    id
+}
+
+/// Yeni bir kimliğe geçer; eski kimlik şu ana kadarki geçmiş için geçerli kalır.
+fn shift_replid(state: &mut State) {
+    state.replid2 = std::mem::replace(&mut state.replid, random_id());
+    state.second_replid_offset = Some(state.backlog.offset() + 1);
+}
+
+/// Bir replikaya gönderilecek akış parçaları.
+pub type ReplicaStream = mpsc::UnboundedReceiver<Arc<[u8]>>;
+
+/// Replikanın primary bağlantısının durumu (ROLE çıktısındaki adlarıyla).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkState {
+    Connect,
+    Connecting,
+    Sync,
+    Connected,
+}
+
+impl LinkState {
+    pub fn name(self) -> &'static str {
+        match self {
+            LinkState::Connect => "connect",
+            LinkState::Connecting => "connecting",
+            LinkState::Sync => "sync",
+            LinkState::Connected => "connected",
+        }
+    }
+}
+
+/// Replika olduğumuzda bağlı olduğumuz primary.
+struct PrimaryLink {
+    host: String,
+    port: u16,
+    state: LinkState,
+    last_io: Instant,
+    task: JoinHandle<()>,
+}
+
+/// Primary olduğumuzda bize bağlı bir replika.
+struct ReplicaLink {
+    id: u64,
+    ip: String,
+    port: u16,
+    ack_offset: u64,
+    last_ack: Instant,
+    sender: mpsc::UnboundedSender<Arc<[u8]>>,
+    /// Replika bağlantısı; gönderilmeyi bekleyen akış çıkış sınırına sayılır
+    client: Arc<Client>,
+}
+
+struct State {
+    replid: String,
+    /// Önceki primary'nin kimliği; terfi eden replika eski replikaları kısmi senkronize edebilsin diye
+    replid2: String,
+    /// `replid2` ile kabul edilen son offset (+1)
+    second_replid_offset: Option<u64>,
+    backlog: Backlog,
+    replicas: Vec<ReplicaLink>,
+    next_replica_id: u64,
+    primary: Option<PrimaryLink>,
+}
+
+/// Sunucunun replikasyon durumu. Tüm bağlantılar aynı örneği paylaşır.
+pub struct Replication {
+    state: Mutex<State>,
+    /// Bir replika ACK gönderdiğinde WAIT bekleyenleri uyandırır
+    acks: Notify,
+    /// Bu sunucunun dinlediği port; replika olunca primary'ye bildirilir
+    listening_port: u16,
+    /// Replika olunca primary'ye `AUTH` ile gönderilir (`--masteruser`, `--masterauth`)
+    primary_auth: Option<Credentials>,
+}
+
+impl Replication {
+    pub fn new(listening_port: u16, primary_auth: Option<Credentials>) -> Self {
+        Replication {
+            state: Mutex::new(State {
+                replid: random_id(),
+                replid2: "0".repeat(40),
+                second_replid_offset: None,
+                backlog: Backlog::new(DEFAULT_BACKLOG_SIZE),
+                replicas: Vec::new(),
+                next_replica_id: 1,
+                primary: None,
+            }),
+            acks: Notify::new(),
+            listening_port,
+            primary_auth,
+        }
+    }
+
+    pub fn listening_port(&self) -> u16 {
+        self.listening_port
+    }
+
+    pub fn primary_auth(&self) -> Option<&Credentials> {
+        self.primary_auth.as_ref()
+    }
+
+    pub fn is_replica(&self) -> bool {
+        self.state.lock().primary.is_some()
+    }
+
+    /// `master_repl_offset`
+    pub fn offset(&self) -> u64 {
+        self.state.lock().backlog.offset()
+    }
+
+    pub fn replid(&self) -> String {
+        self.state.lock().replid.clone()
+    }
+
+    /// Replika olduğumuz primary'nin adresi.
+    pub fn primary_addr(&self) -> Option<(String, u16)> {
+        let state = self.state.lock();
+        state.primary.as_ref().map(|p| (p.host.clone(), p.port))
+    }
+
+    /// Yazma komutunu backlog'a ve replikalara yayar.
+    pub fn feed(&self, command: &[String]) {
+        let resp = RespValue::Array(Some(
+            command
+                .iter()
+                .map(|arg| RespValue::BulkString(Some(arg.clone())))
+                .collect(),
+        ));
+        self.feed_raw(resp.serialize().as_bytes());
+    }
+
+    /// Baytları olduğu gibi yayar. Replikalar primary'den gelen akışı bununla
+    /// aktarır; böylece zincirdeki tüm sunucuların offset'leri aynı kalır.
+    pub fn feed_raw(&self, bytes: &[u8]) {
+        let mut state = self.state.lock();
+        state.backlog.push(bytes);
+        if state.replicas.is_empty() {
+            return;
+        }
+        let chunk: Arc<[u8]> = Arc::from(bytes);
+        // Çıkış sınırını aşan replika bırakılır; akışı biter ve bağlantısı kapanır
+        state.replicas.retain(|replica| {
+            replica.client.queue(chunk.len()) && replica.sender.send(Arc::clone(&chunk)).is_ok()
+        });
+    }
+
+    /// `PSYNC replid next` isteğinin kısmi senkronizasyonla karşılanıp karşılanamayacağı.
+    pub fn can_continue(&self, replid: &str, next: i64) -> bool {
+        let state = self.state.lock();
+        let Ok(next) = u64::try_from(next) else {
+            return false;
+        };
+        let known = replid == state.replid
+            || (replid == state.replid2
+                && state
+                    .second_replid_offset
+                    .is_some_and(|limit| next <= limit));
+        known && state.backlog.range_from(next).is_some()
+    }
+
+    /// Replikayı kaydeder. Offset'i `next` olan bayttan bu yana yayılanlar ve sonraki
+    /// yayınları alacak kanal döner; bu baytlar artık backlog'da değilse `None` döner.
+    /// Kanala giren baytlar `client`'ın çıkış buffer'ına sayılır.
+    pub fn attach(
+        &self,
+        ip: String,
+        port: u16,
+        next: u64,
+        client: Arc<Client>,
+    ) -> Option<(u64, Vec<u8>, ReplicaStream)> {
+        let mut state = self.state.lock();
+        let pending = state.backlog.range_from(next)?;
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let id = state.next_replica_id;
+        state.next_replica_id += 1;
+        state.replicas.push(ReplicaLink {
+            id,
+            ip,
+            port,
+            ack_offset: next.saturating_sub(1),
+            last_ack: Instant::now(),
+            sender,
+            client,
+        });
+        Some((id, pending, receiver))
+    }
+
+    pub fn detach(&self, id: u64) {
+        self.state
+            .lock()
+            .replicas
+            .retain(|replica| replica.id != id);
+    }
+
+    /// Replikanın `REPLCONF ACK offset` bildirimini kaydeder.
+    pub fn ack(&self, id: u64, offset: u64) {
+        let mut state = self.state.lock();
+        if let Some(replica) = state.replicas.iter_mut().find(|r| r.id == id) {
+            replica.ack_offset = replica.ack_offset.max(offset);
+            replica.last_ack = Instant::now();
+        }
+        drop(state);
+        self.acks.notify_waiters();
+    }
+
+    fn acked(&self, offset: u64) -> usize {
+        let state = self.state.lock();
+        state
+            .replicas
+            .iter()
+            .filter(|replica| replica.ack_offset >= offset)
+            .count()
+    }
+
+    /// WAIT: şu ana kadarki yazmaları en az `numreplicas` replika onaylayana ya da
+    /// süre dolana kadar bekler; onaylayan replika sayısını döndürür.
+    pub async fn wait(&self, numreplicas: usize, timeout: Option<Duration>) -> usize {
+        let target = self.offset();
+        let acked = self.acked(target);
+        if acked >= numreplicas {
+            return acked;
+        }
+        self.feed(&[
+            "REPLCONF".to_string(),
+            "GETACK".to_string(),
+            "*".to_string(),
+        ]);
+
+        let deadline = timeout.map(|t| tokio::time::Instant::now() + t);
+        loop {
+            let notified = self.acks.notified();
+            let acked = self.acked(target);
+            if acked >= numreplicas {
+                return acked;
+            }
+            match deadline {
+                Some(deadline) => {
+                    if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                        return self.acked(target);
+                    }
+                }
+                None => notified.await,
+            }
+        }
+    }
+
+    /// REPLICAOF host port: mevcut replikasyonu bırakıp verilen primary'yi izlemeye başlar.
+    /// Zaten o primary'ye bağlıysak `false` döner.
+    pub fn replicate_from(
+        self: &Arc<Self>,
+        host: String,
+        port: u16,
+        db: Arc<RwLock<DB>>,
+        client: Interpreter,
+    ) -> bool {
+        let mut state = self.state.lock();
+        if let Some(primary) = &state.primary {
+            if primary.host == host && primary.port == port {
+                return false;
+            }
+            primary.task.abort();
+        }
+        // Alt replikalar bağlantıları kapanınca yeniden PSYNC ile bağlanır
+        state.replicas.clear();
+        let task = tokio::spawn(replica::run(
+            Arc::clone(self),
+            host.clone(),
+            port,
+            db,
+            client,
+        ));
+        state.primary = Some(PrimaryLink {
+            host,
+            port,
+            state: LinkState::Connect,
+            last_io: Instant::now(),
+            task,
+        });
+        true
+    }
+
+    /// REPLICAOF NO ONE: replikasyonu bırakıp primary olur. Eski kimlik `replid2` olarak
+    /// saklanır; böylece aynı primary'nin diğer replikaları kısmi senkronize olabilir.
+    pub fn become_primary(&self) {
+        let mut state = self.state.lock();
+        let Some(primary) = state.primary.take() else {
+            return;
+        };
+        primary.task.abort();
+        shift_replid(&mut state);
+    }
+
+    pub(crate) fn set_link_state(&self, link_state: LinkState) {
+        if let Some(primary) = self.state.lock().primary.as_mut() {
+            primary.state = link_state;
+            primary.last_io = Instant::now();
+        }
+    }
+
+    /// Primary'den veri geldiğini kaydeder.
+    pub(crate) fn touch(&self) {
+        if let Some(primary) = self.state.lock().primary.as_mut() {
+            primary.last_io = Instant::now();
+        }
+    }
+
+    /// `+FULLRESYNC replid offset` sonrası primary'nin kimliğini ve offset'ini devralır.
+    pub(crate) fn finish_full_sync(&self, replid: String, offset: u64) {
+        let mut state = self.state.lock();
+        state.replid = replid;
+        state.replid2 = "0".repeat(40);
+        state.second_replid_offset = None;
+        state.backlog.reset(offset);
+    }
+
+    /// `+CONTINUE [replid]`: primary terfi etmiş bir replikaysa kimliği değişmiştir.
+    pub(crate) fn continue_sync(&self, replid: Option<String>) {
+        let mut state = self.state.lock();
+        if let Some(replid) = replid.filter(|id| *id != state.replid) {
+            state.replid2 = std::mem::replace(&mut state.replid, replid);
+            state.second_replid_offset = Some(state.backlog.offset() + 1);
+        }
+    }
+
+    /// Replikalar varsa onlara düzenli aralıklarla PING yayan görevi başlatır.
+    pub fn start_cron(self: &Arc<Self>) {
+        let replication = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PING_PERIOD);
+            loop {
+                interval.tick().await;
+                let idle = {
+                    let state = replication.state.lock();
+                    state.primary.is_some() || state.replicas.is_empty()
+                };
+                if !idle {
+                    replication.feed(&["PING".to_string()]);
+                }
+            }
+        });
+    }
+
+    /// ROLE cevabı.
+    pub fn role_reply(&self) -> RespValue {
+        let state = self.state.lock();
+        let offset = state.backlog.offset() as i64;
+        match &state.primary {
+            Some(primary) => RespValue::Array(Some(vec![
+                RespValue::BulkString(Some("slave".to_string())),
+                RespValue::BulkString(Some(primary.host.clone())),
+                RespValue::Integer(primary.port as i64),
+                RespValue::BulkString(Some(primary.state.name().to_string())),
+                RespValue::Integer(offset),
+            ])),
+            None => RespValue::Array(Some(vec![
+                RespValue::BulkString(Some("master".to_string())),
+                RespValue::Integer(offset),
+                RespValue::Array(Some(
+                    state
+                        .replicas
+                        .iter()
+                        .map(|replica| {
+                            RespValue::Array(Some(vec![
+                                RespValue::BulkString(Some(replica.ip.clone())),
+                                RespValue::BulkString(Some(replica.port.to_string())),
+                                RespValue::BulkString(Some(replica.ack_offset.to_string())),
+                            ]))
+                        })
+                        .collect(),
+                )),
+            ])),
+        }
+    }
+
+    /// INFO çıktısının `# Replication` bölümü.
+    pub fn info(&self) -> String {
+        let state = self.state.lock();
+        let mut lines = vec!["# Replication".to_string()];
+        match &state.primary {
+            Some(primary) => {
+                lines.push("role:slave".to_string());
+                lines.push(format!("master_host:{}", primary.host));
+                lines.push(format!("master_port:{}", primary.port));
+                let up = primary.state == LinkState::Connected;
+                lines.push(format!(
+                    "master_link_status:{}",
+                    if up { "up" } else { "down" }
+                ));
+                lines.push(format!(
+                    "master_last_io_seconds_ago:{}",
+                    primary.last_io.elapsed().as_secs()
+                ));
+                lines.push(format!(
+                    "master_sync_in_progress:{}",
+                    (primary.state == LinkState::Sync) as u8
+                ));
+                lines.push(format!("slave_repl_offset:{}", state.backlog.offset()));
+                lines.push("slave_read_only:1".to_string());
+            }
+            None => lines.push("role:master".to_string()),
+        }
+        lines.push(format!("connected_slaves:{}", state.replicas.len()));
+        for (i, replica) in state.replicas.iter().enumerate() {
+            lines.push(format!(
+                "slave{}:ip={},port={},state=online,offset={},lag={}",
+                i,
+                replica.ip,
+                replica.port,
+                replica.ack_offset,
+                replica.last_ack.elapsed().as_secs()
+            ));
+        }
+        lines.push(format!("master_replid:{}", state.replid));
+        lines.push(format!("master_replid2:{}", state.replid2));
+        lines.push(format!("master_repl_offset:{}", state.backlog.offset()));
+        lines.push(format!(
+            "second_repl_offset:{}",
+            state
+                .second_replid_offset
+                .map_or(-1, |offset| offset as i64)
+        ));
+        lines.push("repl_backlog_active:1".to_string());
+        lines.push(format!("repl_backlog_size:{}", state.backlog.capacity()));
+        lines.push(format!(
+            "repl_backlog_first_byte_offset:{}",
+            state.backlog.first_byte_offset()
+        ));
+        lines.push(format!("repl_backlog_histlen:{}", state.backlog.len()));
+        lines.join("\r\n") + "\r\n"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::{ClientKind, Clients, Peer};
+
+    #[test]
+    fn test_partial_sync_decision() {
+        let replication = Replication::new(0, None);
+        replication.feed(&["SET".to_string(), "a".to_string(), "1".to_string()]);
+        let offset = replication.offset();
+        let replid = replication.replid();
+
+        assert!(replication.can_continue(&replid, 1));
+        assert!(replication.can_continue(&replid, offset as i64 + 1));
+        assert!(!replication.can_continue(&replid, offset as i64 + 2));
+        assert!(!replication.can_continue("?", -1));
+        assert!(!replication.can_continue(&random_id(), 1));
+
+        let clients = Arc::new(Clients::new());
+        let (client, _) = clients.register(Peer::unix("replica.sock"), None);
+        client.set_kind(ClientKind::Replica);
+        let (_, pending, mut receiver) = replication
+            .attach("127.0.0.1".into(), 6380, 1, Arc::clone(&client))
+            .unwrap();
+        assert_eq!(
+            pending,
+            b"*3\r\n$3\r\nSET\r\n$1\r\na\r\n$1\r\n1\r\n".to_vec()
+        );
+        replication.feed(&["DEL".to_string(), "a".to_string()]);
+        assert_eq!(
+            &*receiver.try_recv().unwrap(),
+            b"*2\r\n$3\r\nDEL\r\n$1\r\na\r\n"
+        );
+    }
+
+    #[test]
+    fn test_promoted_replica_keeps_previous_history() {
+        let replication = Replication::new(0, None);
+        replication.finish_full_sync("a".repeat(40), 100);
+        replication.feed_raw(b"*1\r\n$4\r\nPING\r\n");
+        shift_replid(&mut replication.state.lock());
+        replication.feed(&["SET".to_string(), "k".to_string(), "v".to_string()]);
+
+        let old = "a".repeat(40);
+        assert!(replication.can_continue(&old, 101));
+        assert!(replication.can_continue(&old, 115));
+        // Eski primary'nin terfiden sonra yazdıkları bizde yok
+        assert!(!replication.can_continue(&old, 116));
+    }
+}
@@ -0,0 +1,177 @@
+//! Primary tarafı: PSYNC gönderen bağlantıyı replika akışına çevirir.
+
+use crate::clients::Client;
+use crate::db::DB;
+use crate::network::parser::parse_value;
+use crate::network::resp::RespValue;
+use crate::persistence::snapshot;
+use crate::replication::{command_args, Replication};
+use std::io;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Snapshot alınırken veri değişirse yeniden denenir; bu kadar denemeden sonra vazgeçilir.
+const SNAPSHOT_ATTEMPTS: usize = 10;
+
+/// PSYNC isteği ve onu karşılamak için gerekenler.
+pub struct SyncRequest {
+    /// Replikanın bildiği replikasyon kimliği (`?` = hiçbiri)
+    pub replid: String,
+    /// Replikanın istediği ilk baytın offset'i (`-1` = tam senkronizasyon)
+    pub offset: i64,
+    /// `REPLCONF listening-port` ile bildirilen port
+    pub listening_port: u16,
+    pub db: Arc<RwLock<DB>>,
+    pub replication: Arc<Replication>,
+}
+
+/// Veri setinin snapshot'ını alır ve hangi offset'e karşılık geldiğini döndürür.
+/// Yazmalar snapshot sırasında da sürebildiği için offset kaydın öncesinde ve
+/// sonrasında aynı kalana kadar tekrar denenir.
+async fn snapshot_at_offset(
+    db: &Arc<RwLock<DB>>,
+    replication: &Replication,
+) -> io::Result<(u64, Vec<u8>)> {
+    let path = std::env::temp_dir().join(format!("hexagondb-sync-{}.rdb", Uuid::new_v4()));
+    let mut result = Err(io::Error::other("dataset kept changing during snapshot"));
+    for _ in 0..SNAPSHOT_ATTEMPTS {
+        let before = replication.offset();
+        if let Err(e) = snapshot::save(&path, db).await {
+            result = Err(e);
+            break;
+        }
+        if replication.offset() == before {
+            result = tokio::fs::read(&path).await.map(|bytes| (before, bytes));
+            break;
+        }
+    }
+    let _ = tokio::fs::remove_file(&path).await;
+    result
+}
+
+/// Replikaya akışı gönderir; bağlantı kapanana kadar döner.
+/// `buffer`, PSYNC'ten sonra bağlantıdan okunmuş ama işlenmemiş baytlardır. Akış,
+/// `client`'ın replika çıkış sınırına tabidir.
+pub async fn serve<S>(
+    stream: &mut S,
+    peer_ip: String,
+    mut buffer: Vec<u8>,
+    request: SyncRequest,
+    client: Arc<Client>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let replication = &request.replication;
+
+    let (next, header, payload) = if replication.can_continue(&request.replid, request.offset) {
+        let header = format!("+CONTINUE {}\r\n", replication.replid());
+        (request.offset as u64, header, None)
+    } else {
+        match snapshot_at_offset(&request.db, replication).await {
+            Ok((offset, payload)) => {
+                let header = format!("+FULLRESYNC {} {}\r\n", replication.replid(), offset);
+                (offset + 1, header, Some(payload))
+            }
+            Err(e) => {
+                error!("Full resync failed: {}", e);
+                let reply = RespValue::Error(format!("full resync failed: {}", e));
+                let _ = stream.write_all(reply.serialize().as_bytes()).await;
+                return;
+            }
+        }
+    };
+
+    let Some((id, pending, mut receiver)) = replication.attach(
+        peer_ip.clone(),
+        request.listening_port,
+        next,
+        client.clone(),
+    ) else {
+        warn!(
+            "Replication backlog overflowed before replica {} attached",
+            peer_ip
+        );
+        return;
+    };
+    info!(
+        "Replica {}:{} attached ({})",
+        peer_ip,
+        request.listening_port,
+        if payload.is_some() {
+            "full resync"
+        } else {
+            "partial resync"
+        }
+    );
+
+    let mut initial = header.into_bytes();
+    if let Some(payload) = payload {
+        initial.extend_from_slice(format!("${}\r\n", payload.len()).as_bytes());
+        initial.extend_from_slice(&payload);
+    }
+    initial.extend_from_slice(&pending);
+
+    if stream.write_all(&initial).await.is_ok() {
+        let mut temp_buf = [0u8; 1024];
+        loop {
+            tokio::select! {
+                chunk = receiver.recv() => {
+                    // Kanal kapandıysa replika listeden çıkarılmıştır
+                    let Some(chunk) = chunk else { break };
+                    if let Err(e) = stream.write_all(&chunk).await {
+                        warn!("Failed to send replication stream: {}", e);
+                        break;
+                    }
+                    client.sent(chunk.len());
+                }
+                read = stream.read(&mut temp_buf) => {
+                    match read {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => buffer.extend_from_slice(&temp_buf[..n]),
+                    }
+                    // Replikadan yalnızca REPLCONF ACK beklenir
+                    if !read_acks(&mut buffer, |offset| replication.ack(id, offset)) {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    replication.detach(id);
+    info!("Replica {}:{} detached", peer_ip, request.listening_port);
+}
+
+/// Tampondaki tam komutları işler; bozuk veri gelirse `false` döner.
+fn read_acks(buffer: &mut Vec<u8>, mut on_ack: impl FnMut(u64)) -> bool {
+    loop {
+        match parse_value(buffer) {
+            Ok(Some((request, len))) => {
+                buffer.drain(..len);
+                if let Some(offset) = parse_ack(&request) {
+                    on_ack(offset);
+                }
+            }
+            Ok(None) => return true,
+            Err(e) => {
+                warn!("Invalid data from replica: {}", e);
+                return false;
+            }
+        }
+    }
+}
+
+/// `REPLCONF ACK offset` ise offset'i döndürür.
+fn parse_ack(request: &RespValue) -> Option<u64> {
+    match command_args(request)[..] {
+        [cmd, sub, offset]
+            if cmd.eq_ignore_ascii_case("REPLCONF") && sub.eq_ignore_ascii_case("ACK") =>
+        {
+            offset.parse().ok()
+        }
+        _ => None,
+    }
+}
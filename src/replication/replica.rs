@@ -0,0 +1,228 @@
+//! Replika tarafı: primary'ye bağlanır, senkronize olur ve komut akışını uygular.
+//! Bağlantı koptuğunda kaldığı offset'ten PSYNC ile yeniden bağlanır.
+
+use crate::commands::Interpreter;
+use crate::db::DB;
+use crate::network::parser::parse_value;
+use crate::network::resp::RespValue;
+use crate::persistence::snapshot;
+use crate::replication::{command_args, LinkState, Replication, ACK_PERIOD, REPL_TIMEOUT};
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Bağlantı koptuktan sonra yeniden denemeden önce beklenen süre
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Primary bağlantısı ve okunmuş ama işlenmemiş baytlar.
+struct PrimaryConnection {
+    stream: TcpStream,
+    buffer: Vec<u8>,
+}
+
+impl PrimaryConnection {
+    /// Bağlantıdan yeni veri okur; bağlantı kapandıysa hata döner.
+    async fn fill(&mut self) -> io::Result<()> {
+        let mut temp_buf = [0u8; 16 * 1024];
+        let n = tokio::time::timeout(REPL_TIMEOUT, self.stream.read(&mut temp_buf))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "primary timed out"))??;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "primary closed the connection",
+            ));
+        }
+        self.buffer.extend_from_slice(&temp_buf[..n]);
+        Ok(())
+    }
+
+    async fn read_line(&mut self) -> io::Result<String> {
+        loop {
+            if let Some(end) = self.buffer.windows(2).position(|w| w == b"\r\n") {
+                let line = String::from_utf8_lossy(&self.buffer[..end]).to_string();
+                self.buffer.drain(..end + 2);
+                return Ok(line);
+            }
+            self.fill().await?;
+        }
+    }
+
+    async fn read_bytes(&mut self, len: usize) -> io::Result<Vec<u8>> {
+        while self.buffer.len() < len {
+            self.fill().await?;
+        }
+        Ok(self.buffer.drain(..len).collect())
+    }
+
+    async fn send(&mut self, args: &[&str]) -> io::Result<()> {
+        let command = RespValue::Array(Some(
+            args.iter()
+                .map(|arg| RespValue::BulkString(Some(arg.to_string())))
+                .collect(),
+        ));
+        self.stream.write_all(command.serialize().as_bytes()).await
+    }
+
+    /// Komutu gönderir ve tek satırlık cevabı döndürür; hata cevabı `Err` olur.
+    async fn request(&mut self, args: &[&str]) -> io::Result<String> {
+        self.send(args).await?;
+        let line = self.read_line().await?;
+        match line.strip_prefix('-') {
+            Some(error) => Err(io::Error::other(format!(
+                "primary replied to {} with: {}",
+                args[0], error
+            ))),
+            None => Ok(line),
+        }
+    }
+}
+
+/// Replikasyon döngüsü. REPLICAOF NO ONE ya da yeni bir REPLICAOF görevi iptal edene kadar sürer.
+pub async fn run(
+    replication: Arc<Replication>,
+    host: String,
+    port: u16,
+    db: Arc<RwLock<DB>>,
+    mut client: Interpreter,
+) {
+    loop {
+        replication.set_link_state(LinkState::Connecting);
+        if let Err(e) = sync(&replication, &host, port, &db, &mut client).await {
+            warn!("Replication link to {}:{} lost: {}", host, port, e);
+        }
+        replication.set_link_state(LinkState::Connect);
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Tek bir bağlantı ömrü: el sıkışma, senkronizasyon ve komut akışı.
+async fn sync(
+    replication: &Replication,
+    host: &str,
+    port: u16,
+    db: &Arc<RwLock<DB>>,
+    client: &mut Interpreter,
+) -> io::Result<()> {
+    let stream = TcpStream::connect((host, port)).await?;
+    let mut primary = PrimaryConnection {
+        stream,
+        buffer: Vec::new(),
+    };
+
+    if let Some(credentials) = replication.primary_auth() {
+        primary.request(&credentials.auth_command()).await?;
+    }
+    primary.request(&["PING"]).await?;
+    let listening_port = replication.listening_port().to_string();
+    primary
+        .request(&["REPLCONF", "listening-port", &listening_port])
+        .await?;
+    primary.request(&["REPLCONF", "capa", "psync2"]).await?;
+
+    replication.set_link_state(LinkState::Sync);
+    let replid = replication.replid();
+    let next = (replication.offset() + 1).to_string();
+    let reply = primary.request(&["PSYNC", &replid, &next]).await?;
+    let mut parts = reply.trim_start_matches('+').split_whitespace();
+    match parts.next() {
+        Some("FULLRESYNC") => {
+            let (Some(replid), Some(Ok(offset))) = (parts.next(), parts.next().map(str::parse))
+            else {
+                return Err(io::Error::other(format!("bad PSYNC reply: {}", reply)));
+            };
+            load_snapshot(&mut primary, db).await?;
+            client.invalidate_all();
+            replication.finish_full_sync(replid.to_string(), offset);
+            info!(
+                "Full resync with {}:{} done at offset {}",
+                host, port, offset
+            );
+        }
+        Some("CONTINUE") => {
+            replication.continue_sync(parts.next().map(str::to_string));
+            info!("Partial resync with {}:{} accepted", host, port);
+        }
+        _ => return Err(io::Error::other(format!("bad PSYNC reply: {}", reply))),
+    }
+    replication.set_link_state(LinkState::Connected);
+
+    let mut ack_interval = tokio::time::interval(ACK_PERIOD);
+    loop {
+        while let Some((request, len)) = parse_value(&primary.buffer).map_err(io::Error::other)? {
+            let raw: Vec<u8> = primary.buffer.drain(..len).collect();
+            apply(replication, &mut primary, client, request, &raw).await?;
+        }
+        tokio::select! {
+            _ = ack_interval.tick() => send_ack(&mut primary, replication).await?,
+            read = primary.fill() => {
+                read?;
+                replication.touch();
+            }
+        }
+    }
+}
+
+/// `$len\r\n` ile başlayan snapshot'ı okur ve mevcut veri setinin yerine yükler.
+async fn load_snapshot(primary: &mut PrimaryConnection, db: &Arc<RwLock<DB>>) -> io::Result<()> {
+    let header = primary.read_line().await?;
+    let len = header
+        .strip_prefix('$')
+        .and_then(|len| len.parse::<usize>().ok())
+        .ok_or_else(|| io::Error::other(format!("bad snapshot header: {}", header)))?;
+    let payload = primary.read_bytes(len).await?;
+
+    let path = std::env::temp_dir().join(format!("hexagondb-sync-{}.rdb", Uuid::new_v4()));
+    tokio::fs::write(&path, &payload).await?;
+    db.write().await.clear();
+    let result = snapshot::load(&path, db).await;
+    let _ = tokio::fs::remove_file(&path).await;
+    result
+}
+
+/// Primary'den gelen tek bir komutu uygular ve offset'i ilerletir.
+async fn apply(
+    replication: &Replication,
+    primary: &mut PrimaryConnection,
+    client: &mut Interpreter,
+    request: RespValue,
+    raw: &[u8],
+) -> io::Result<()> {
+    let (command, is_getack) = {
+        let args = command_args(&request);
+        let command = args
+            .first()
+            .map(|cmd| cmd.to_uppercase())
+            .unwrap_or_default();
+        let is_getack = command == "REPLCONF"
+            && args
+                .get(1)
+                .is_some_and(|sub| sub.eq_ignore_ascii_case("GETACK"));
+        (command, is_getack)
+    };
+
+    match command.as_str() {
+        // Bağlantıyı canlı tutmak içindir, uygulanacak bir şey yok
+        "PING" | "REPLCONF" => {}
+        _ => {
+            client.execute(request).await;
+        }
+    }
+    // Akışı alt replikalara aynen aktar; offset primary'ninkiyle aynı kalır
+    replication.feed_raw(raw);
+
+    if is_getack {
+        send_ack(primary, replication).await?;
+    }
+    Ok(())
+}
+
+async fn send_ack(primary: &mut PrimaryConnection, replication: &Replication) -> io::Result<()> {
+    let offset = replication.offset().to_string();
+    primary.send(&["REPLCONF", "ACK", &offset]).await
+}
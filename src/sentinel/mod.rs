@@ -0,0 +1,774 @@
+//! Sentinel: bir primary'yi ve replikalarını izleyen, primary çöktüğünde diğer
+//! sentinel'lerle anlaşıp bir replikayı terfi ettiren izleme modu.
+//!
+//! Akış Redis Sentinel'e benzer:
+//! - Her sentinel izlenen sunuculara saniyede bir INFO gönderir. Primary `down_after`
+//!   boyunca geçerli cevap vermezse öznel olarak çökmüş sayılır (SDOWN).
+//! - Diğer sentinel'lere `SENTINEL is-master-down-by-addr` sorulur; en az `quorum`
+//!   sentinel aynı fikirdeyse çöküş nesneldir (ODOWN).
+//! - Dönem (epoch) artırılarak lider seçimi başlar; her sentinel bir dönemde tek oy
+//!   verir. Quorum'u ve çoğunluğu toplayan lider en iyi replikayı terfi ettirir, diğer
+//!   replikaları ona bağlar ve yeni yapılandırmayı `config_epoch` ile duyurur.
+//! - Sentinel'ler birbirini izlenen sunuculardaki `__sentinel__:hello` kanalından bulur.
+
+pub mod monitor;
+pub mod server;
+
+use crate::network::client::Credentials;
+use crate::network::resp::RespValue;
+use crate::replication::random_id;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Sentinel'lerin birbirini bulduğu kanal
+pub const HELLO_CHANNEL: &str = "__sentinel__:hello";
+
+/// Bir sunucunun adresi.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Addr {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Addr {
+    pub fn new(host: impl Into<String>, port: u16) -> Self {
+        Addr {
+            host: host.into(),
+            port,
+        }
+    }
+}
+
+impl fmt::Display for Addr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.host, self.port)
+    }
+}
+
+/// `--monitor` ile verilen izleme ayarları.
+#[derive(Debug, Clone)]
+pub struct SentinelConfig {
+    pub name: String,
+    pub master: Addr,
+    pub quorum: usize,
+    pub down_after: Duration,
+    pub failover_timeout: Duration,
+    /// Diğer sentinel'lere duyurulan port
+    pub announce_port: u16,
+    /// İzlenen sunuculara `AUTH` ile gönderilir; diğer sentinel'ler giriş istemez
+    pub auth: Option<Credentials>,
+}
+
+/// INFO cevabından okunan, sentinel'i ilgilendiren alanlar.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstanceInfo {
+    pub role_master: bool,
+    /// Replikanın bağlı olduğu primary
+    pub master: Option<Addr>,
+    pub repl_offset: u64,
+    /// Primary'nin bildirdiği replikalar
+    pub replicas: Vec<Addr>,
+}
+
+impl InstanceInfo {
+    pub fn parse(info: &str) -> Self {
+        let fields: HashMap<&str, &str> = info
+            .lines()
+            .filter_map(|line| line.trim_end().split_once(':'))
+            .collect();
+        let master = match (fields.get("master_host"), fields.get("master_port")) {
+            (Some(host), Some(port)) => port.parse().ok().map(|port| Addr::new(*host, port)),
+            _ => None,
+        };
+        let offset_field = if fields.get("role") == Some(&"master") {
+            "master_repl_offset"
+        } else {
+            "slave_repl_offset"
+        };
+        // slave0:ip=127.0.0.1,port=6380,state=online,offset=42,lag=0
+        let mut replicas: Vec<(usize, Addr)> = fields
+            .iter()
+            .filter_map(|(name, value)| {
+                let index = name.strip_prefix("slave")?.parse::<usize>().ok()?;
+                let attrs: HashMap<&str, &str> = value
+                    .split(',')
+                    .filter_map(|kv| kv.split_once('='))
+                    .collect();
+                let port = attrs.get("port")?.parse().ok()?;
+                Some((index, Addr::new(*attrs.get("ip")?, port)))
+            })
+            .collect();
+        replicas.sort();
+        InstanceInfo {
+            role_master: fields.get("role") == Some(&"master"),
+            master,
+            repl_offset: fields
+                .get(offset_field)
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            replicas: replicas.into_iter().map(|(_, addr)| addr).collect(),
+        }
+    }
+}
+
+/// Sentinel'lerin hello kanalına yayınladığı duyuru:
+/// `ip,port,runid,current_epoch,master_name,master_ip,master_port,config_epoch`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hello {
+    pub addr: Addr,
+    pub run_id: String,
+    pub current_epoch: u64,
+    pub master_name: String,
+    pub master: Addr,
+    pub config_epoch: u64,
+}
+
+impl Hello {
+    pub fn parse(message: &str) -> Option<Hello> {
+        let parts: Vec<&str> = message.split(',').collect();
+        let [ip, port, run_id, current_epoch, name, master_ip, master_port, config_epoch] =
+            parts[..]
+        else {
+            return None;
+        };
+        Some(Hello {
+            addr: Addr::new(ip, port.parse().ok()?),
+            run_id: run_id.to_string(),
+            current_epoch: current_epoch.parse().ok()?,
+            master_name: name.to_string(),
+            master: Addr::new(master_ip, master_port.parse().ok()?),
+            config_epoch: config_epoch.parse().ok()?,
+        })
+    }
+}
+
+impl fmt::Display for Hello {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{},{},{},{},{},{},{},{}",
+            self.addr.host,
+            self.addr.port,
+            self.run_id,
+            self.current_epoch,
+            self.master_name,
+            self.master.host,
+            self.master.port,
+            self.config_epoch
+        )
+    }
+}
+
+/// İzlenen bir replika.
+#[derive(Debug, Clone, Default)]
+pub struct ReplicaState {
+    pub last_ok: Option<Instant>,
+    pub info: InstanceInfo,
+    /// Replikanın yanlış primary'ye bağlı (ya da kendini primary) bildirmeye başladığı an
+    pub misconfigured_since: Option<Instant>,
+}
+
+/// Hello mesajlarıyla bulunan başka bir sentinel.
+#[derive(Debug, Clone)]
+pub struct Peer {
+    pub addr: Addr,
+    pub last_hello: Instant,
+    /// Son sorduğumuzda primary'yi çökmüş görüyor muydu?
+    pub master_down: bool,
+    /// Verdiği oy: `(lider run id, dönem)`
+    pub leader: Option<(String, u64)>,
+}
+
+/// Sürmekte olan failover.
+#[derive(Debug, Clone)]
+pub struct Failover {
+    pub epoch: u64,
+    pub started: Instant,
+    /// `SENTINEL FAILOVER` ile istendi; oylama yapılmaz
+    pub forced: bool,
+}
+
+/// İzlenen primary ve onunla ilgili tüm bilgiler.
+#[derive(Debug)]
+pub struct MasterState {
+    pub name: String,
+    pub addr: Addr,
+    pub quorum: usize,
+    pub down_after: Duration,
+    pub failover_timeout: Duration,
+    /// Bu yapılandırmayı üreten failover'ın dönemi
+    pub config_epoch: u64,
+    pub last_ok: Instant,
+    pub replicas: BTreeMap<Addr, ReplicaState>,
+    /// Run id'ye göre diğer sentinel'ler
+    pub peers: HashMap<String, Peer>,
+    /// Verdiğimiz son oy: `(lider run id, dönem)`
+    pub vote: Option<(String, u64)>,
+    pub failover: Option<Failover>,
+    /// Bu andan önce kendimiz failover başlatmayız; başka bir sentinel'e oy
+    /// verdiğimizde ya da son denememiz sonuçsuz kaldığında ileri atılır.
+    pub failover_not_before: Option<Instant>,
+    pub failover_requested: bool,
+}
+
+impl MasterState {
+    pub fn new(config: &SentinelConfig, now: Instant) -> Self {
+        MasterState {
+            name: config.name.clone(),
+            addr: config.master.clone(),
+            quorum: config.quorum,
+            down_after: config.down_after,
+            failover_timeout: config.failover_timeout,
+            config_epoch: 0,
+            last_ok: now,
+            replicas: BTreeMap::new(),
+            peers: HashMap::new(),
+            vote: None,
+            failover: None,
+            failover_not_before: None,
+            failover_requested: false,
+        }
+    }
+
+    /// Öznel çöküş: primary `down_after` süresince cevap vermedi.
+    pub fn sdown(&self, now: Instant) -> bool {
+        now.duration_since(self.last_ok) > self.down_after
+    }
+
+    /// Nesnel çöküş: bizimle birlikte en az `quorum` sentinel primary'yi çökmüş görüyor.
+    pub fn odown(&self, now: Instant) -> bool {
+        self.sdown(now) && 1 + self.peers.values().filter(|p| p.master_down).count() >= self.quorum
+    }
+
+    /// Lider seçilmek için gereken oy: quorum ve bilinen sentinel'lerin çoğunluğu.
+    pub fn needed_votes(&self) -> usize {
+        let voters = 1 + self.peers.len();
+        self.quorum.max(voters / 2 + 1)
+    }
+
+    pub fn votes_for(&self, run_id: &str, epoch: u64) -> usize {
+        let is_vote = |vote: &Option<(String, u64)>| {
+            vote.as_ref()
+                .is_some_and(|(leader, e)| leader == run_id && *e == epoch)
+        };
+        is_vote(&self.vote) as usize + self.peers.values().filter(|p| is_vote(&p.leader)).count()
+    }
+
+    /// Terfi için en iyi replika: yakın zamanda cevap vermiş olanlar arasında
+    /// replikasyon offset'i en büyük olan, eşitlikte adresi küçük olan.
+    pub fn select_replica(&self, now: Instant) -> Option<Addr> {
+        self.replicas
+            .iter()
+            .filter(|(_, replica)| {
+                !replica.info.role_master
+                    && replica
+                        .last_ok
+                        .is_some_and(|at| now.duration_since(at) <= self.down_after)
+            })
+            .max_by(|(a, ra), (b, rb)| {
+                ra.info
+                    .repl_offset
+                    .cmp(&rb.info.repl_offset)
+                    .then_with(|| b.cmp(a))
+            })
+            .map(|(addr, _)| addr.clone())
+    }
+
+    /// Kendi failover'ımızı en az `until` anına kadar erteler.
+    pub fn postpone_failover(&mut self, until: Instant) {
+        self.failover_not_before = Some(self.failover_not_before.map_or(until, |t| t.max(until)));
+    }
+
+    /// Yeni primary'ye geçer; eski primary geri döndüğünde replikaya çevrilmek üzere
+    /// replikalar arasına eklenir.
+    pub fn switch_master(&mut self, new: Addr, config_epoch: u64, now: Instant) {
+        let old = std::mem::replace(&mut self.addr, new);
+        self.replicas.remove(&self.addr);
+        self.replicas.entry(old).or_default();
+        self.config_epoch = config_epoch;
+        self.last_ok = now;
+        self.failover = None;
+        self.failover_requested = false;
+        for peer in self.peers.values_mut() {
+            peer.master_down = false;
+        }
+    }
+
+    fn flags(&self, now: Instant) -> String {
+        let mut flags = vec!["master"];
+        if self.sdown(now) {
+            flags.push("s_down");
+        }
+        if self.odown(now) {
+            flags.push("o_down");
+        }
+        if self.failover.is_some() {
+            flags.push("failover_in_progress");
+        }
+        flags.join(",")
+    }
+}
+
+#[derive(Debug)]
+pub struct State {
+    pub current_epoch: u64,
+    pub master: MasterState,
+}
+
+impl State {
+    /// `is-master-down-by-addr` ile gelen oy isteği. Her dönemde yalnızca ilk isteyene
+    /// oy verilir; verilen (ya da daha önce verilmiş) oy döner.
+    pub fn vote(&mut self, run_id: &str, epoch: u64) -> (String, u64) {
+        self.current_epoch = self.current_epoch.max(epoch);
+        if self.master.vote.as_ref().is_none_or(|(_, e)| *e < epoch) {
+            self.master.vote = Some((run_id.to_string(), epoch));
+        }
+        self.master.vote.clone().expect("vote was just set")
+    }
+
+    /// Başka bir sentinel'in duyurusunu işler. Duyurudaki yapılandırma bizimkinden
+    /// yeniyse ona geçilir ve `true` döner.
+    pub fn process_hello(&mut self, hello: Hello, my_run_id: &str, now: Instant) -> bool {
+        if hello.run_id == my_run_id || hello.master_name != self.master.name {
+            return false;
+        }
+        self.current_epoch = self.current_epoch.max(hello.current_epoch);
+        let peer = self
+            .master
+            .peers
+            .entry(hello.run_id.clone())
+            .or_insert_with(|| Peer {
+                addr: hello.addr.clone(),
+                last_hello: now,
+                master_down: false,
+                leader: None,
+            });
+        peer.addr = hello.addr;
+        peer.last_hello = now;
+
+        if hello.config_epoch > self.master.config_epoch && hello.master != self.master.addr {
+            self.master
+                .switch_master(hello.master, hello.config_epoch, now);
+            return true;
+        }
+        false
+    }
+
+    fn hello(&self, addr: Addr, run_id: &str) -> Hello {
+        Hello {
+            addr,
+            run_id: run_id.to_string(),
+            current_epoch: self.current_epoch,
+            master_name: self.master.name.clone(),
+            master: self.master.addr.clone(),
+            config_epoch: self.master.config_epoch,
+        }
+    }
+}
+
+/// Sentinel sürecinin paylaşılan durumu.
+pub struct Sentinel {
+    pub run_id: String,
+    pub announce_port: u16,
+    pub auth: Option<Credentials>,
+    pub state: Mutex<State>,
+}
+
+fn bulk(value: impl ToString) -> RespValue {
+    RespValue::BulkString(Some(value.to_string()))
+}
+
+/// Alan-değer çiftlerini düz bir diziye çevirir (SENTINEL MASTER/REPLICAS biçimi).
+fn pairs(pairs: Vec<(&str, String)>) -> RespValue {
+    RespValue::Array(Some(
+        pairs
+            .into_iter()
+            .flat_map(|(field, value)| [bulk(field), bulk(value)])
+            .collect(),
+    ))
+}
+
+impl Sentinel {
+    pub fn new(config: &SentinelConfig) -> Self {
+        Sentinel {
+            run_id: random_id(),
+            announce_port: config.announce_port,
+            auth: config.auth.clone(),
+            state: Mutex::new(State {
+                current_epoch: 0,
+                master: MasterState::new(config, Instant::now()),
+            }),
+        }
+    }
+
+    /// Verilen adresle yayınlanacak hello mesajı.
+    pub fn hello(&self, announce_ip: &str) -> String {
+        let addr = Addr::new(announce_ip, self.announce_port);
+        self.state.lock().hello(addr, &self.run_id).to_string()
+    }
+
+    /// Sentinel bağlantısına gelen komutu çalıştırır.
+    pub fn execute(&self, args: &[String]) -> RespValue {
+        let Some(command) = args.first().map(|c| c.to_uppercase()) else {
+            return RespValue::Error("empty command".to_string());
+        };
+        match command.as_str() {
+            "PING" => RespValue::SimpleString("PONG".to_string()),
+            "ROLE" => {
+                let state = self.state.lock();
+                RespValue::Array(Some(vec![
+                    bulk("sentinel"),
+                    RespValue::Array(Some(vec![bulk(&state.master.name)])),
+                ]))
+            }
+            "INFO" => RespValue::BulkString(Some(self.info())),
+            "SENTINEL" => self.sentinel_command(&args[1..]),
+            _ => RespValue::Error(format!("unknown command '{}'", args[0])),
+        }
+    }
+
+    fn info(&self) -> String {
+        let state = self.state.lock();
+        let master = &state.master;
+        let status = if master.sdown(Instant::now()) {
+            "sdown"
+        } else {
+            "ok"
+        };
+        format!(
+            "# Sentinel\r\nsentinel_masters:1\r\nsentinel_running_scripts:0\r\n\
+             master0:name={},status={},address={},slaves={},sentinels={}\r\n",
+            master.name,
+            status,
+            master.addr,
+            master.replicas.len(),
+            master.peers.len() + 1
+        )
+    }
+
+    fn master_reply(&self, master: &MasterState, now: Instant) -> RespValue {
+        pairs(vec![
+            ("name", master.name.clone()),
+            ("ip", master.addr.host.clone()),
+            ("port", master.addr.port.to_string()),
+            ("flags", master.flags(now)),
+            (
+                "last-ok-ping-reply",
+                now.duration_since(master.last_ok).as_millis().to_string(),
+            ),
+            (
+                "down-after-milliseconds",
+                master.down_after.as_millis().to_string(),
+            ),
+            ("num-slaves", master.replicas.len().to_string()),
+            ("num-other-sentinels", master.peers.len().to_string()),
+            ("quorum", master.quorum.to_string()),
+            (
+                "failover-timeout",
+                master.failover_timeout.as_millis().to_string(),
+            ),
+            ("config-epoch", master.config_epoch.to_string()),
+        ])
+    }
+
+    fn sentinel_command(&self, args: &[String]) -> RespValue {
+        let Some(subcommand) = args.first().map(|s| s.to_lowercase()) else {
+            return RespValue::Error(
+                "wrong number of arguments for 'SENTINEL' command".to_string(),
+            );
+        };
+        let now = Instant::now();
+        let mut state = self.state.lock();
+
+        // Ad alan alt komutlar izlenen primary'nin adını doğrular
+        let named = matches!(
+            subcommand.as_str(),
+            "get-master-addr-by-name"
+                | "master"
+                | "replicas"
+                | "slaves"
+                | "sentinels"
+                | "failover"
+                | "ckquorum"
+        );
+        if named {
+            match args.get(1) {
+                Some(name) if *name == state.master.name => {}
+                Some(_) => return RespValue::Error("no such master with that name".to_string()),
+                None => {
+                    return RespValue::Error(format!(
+                        "wrong number of arguments for 'SENTINEL {}' command",
+                        subcommand
+                    ))
+                }
+            }
+        }
+
+        match subcommand.as_str() {
+            "myid" => bulk(&self.run_id),
+            "get-master-addr-by-name" => RespValue::Array(Some(vec![
+                bulk(&state.master.addr.host),
+                bulk(state.master.addr.port),
+            ])),
+            "master" => self.master_reply(&state.master, now),
+            "masters" => RespValue::Array(Some(vec![self.master_reply(&state.master, now)])),
+            "replicas" | "slaves" => RespValue::Array(Some(
+                state
+                    .master
+                    .replicas
+                    .iter()
+                    .map(|(addr, replica)| {
+                        let down = replica
+                            .last_ok
+                            .is_none_or(|at| now.duration_since(at) > state.master.down_after);
+                        pairs(vec![
+                            ("name", addr.to_string()),
+                            ("ip", addr.host.clone()),
+                            ("port", addr.port.to_string()),
+                            (
+                                "flags",
+                                if down { "slave,s_down" } else { "slave" }.to_string(),
+                            ),
+                            ("slave-repl-offset", replica.info.repl_offset.to_string()),
+                        ])
+                    })
+                    .collect(),
+            )),
+            "sentinels" => RespValue::Array(Some(
+                state
+                    .master
+                    .peers
+                    .iter()
+                    .map(|(run_id, peer)| {
+                        pairs(vec![
+                            ("name", peer.addr.to_string()),
+                            ("ip", peer.addr.host.clone()),
+                            ("port", peer.addr.port.to_string()),
+                            ("runid", run_id.clone()),
+                            (
+                                "last-hello-message",
+                                now.duration_since(peer.last_hello).as_millis().to_string(),
+                            ),
+                        ])
+                    })
+                    .collect(),
+            )),
+            "is-master-down-by-addr" => {
+                // ip port current_epoch runid; runid `*` ise yalnızca durum sorulur
+                let (Some(ip), Some(Ok(port)), Some(Ok(epoch)), Some(run_id)) = (
+                    args.get(1),
+                    args.get(2).map(|p| p.parse::<u16>()),
+                    args.get(3).map(|e| e.parse::<u64>()),
+                    args.get(4),
+                ) else {
+                    return RespValue::Error(
+                        "wrong number of arguments for 'SENTINEL is-master-down-by-addr' command"
+                            .to_string(),
+                    );
+                };
+                let is_master = state.master.addr == Addr::new(ip.as_str(), port);
+                let down = is_master && state.master.sdown(now);
+                let (leader, leader_epoch) = if is_master && run_id != "*" {
+                    let vote = state.vote(run_id, epoch);
+                    if vote.0 != self.run_id {
+                        // Lider olarak başka bir sentinel'i destekliyoruz; onunla yarışma
+                        let until = now + 2 * state.master.failover_timeout;
+                        state.master.postpone_failover(until);
+                    }
+                    vote
+                } else {
+                    ("*".to_string(), 0)
+                };
+                RespValue::Array(Some(vec![
+                    RespValue::Integer(down as i64),
+                    bulk(leader),
+                    RespValue::Integer(leader_epoch as i64),
+                ]))
+            }
+            "failover" => {
+                if state.master.failover.is_some() {
+                    return RespValue::Error("INPROG failover already in progress".to_string());
+                }
+                if state.master.select_replica(now).is_none() {
+                    return RespValue::Error(
+                        "NOGOODSLAVE no suitable replica to promote".to_string(),
+                    );
+                }
+                state.master.failover_requested = true;
+                RespValue::SimpleString("OK".to_string())
+            }
+            "ckquorum" => {
+                let usable = 1 + state
+                    .master
+                    .peers
+                    .values()
+                    .filter(|peer| now.duration_since(peer.last_hello) <= state.master.down_after)
+                    .count();
+                if usable >= state.master.needed_votes() {
+                    RespValue::SimpleString(format!(
+                        "OK {} usable Sentinels. Quorum and failover authorization can be reached",
+                        usable
+                    ))
+                } else {
+                    RespValue::Error(format!(
+                        "NOQUORUM {} usable Sentinels. Not enough available Sentinels to reach the majority and authorize a failover",
+                        usable
+                    ))
+                }
+            }
+            _ => RespValue::Error(format!("unknown sentinel subcommand '{}'", subcommand)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SentinelConfig {
+        SentinelConfig {
+            name: "mymaster".to_string(),
+            master: Addr::new("127.0.0.1", 6379),
+            quorum: 2,
+            down_after: Duration::from_secs(5),
+            failover_timeout: Duration::from_secs(60),
+            announce_port: 26379,
+            auth: None,
+        }
+    }
+
+    fn peer(now: Instant) -> Peer {
+        Peer {
+            addr: Addr::new("127.0.0.1", 26380),
+            last_hello: now,
+            master_down: false,
+            leader: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_info() {
+        let master = InstanceInfo::parse(
+            "# Replication\r\nrole:master\r\nconnected_slaves:2\r\n\
+             slave1:ip=10.0.0.2,port=6381,state=online,offset=90,lag=0\r\n\
+             slave0:ip=10.0.0.1,port=6380,state=online,offset=100,lag=0\r\n\
+             master_repl_offset:100\r\n",
+        );
+        assert!(master.role_master);
+        assert_eq!(master.repl_offset, 100);
+        assert_eq!(
+            master.replicas,
+            vec![Addr::new("10.0.0.1", 6380), Addr::new("10.0.0.2", 6381)]
+        );
+
+        let replica = InstanceInfo::parse(
+            "role:slave\r\nmaster_host:10.0.0.9\r\nmaster_port:6379\r\nslave_repl_offset:77\r\n",
+        );
+        assert!(!replica.role_master);
+        assert_eq!(replica.master, Some(Addr::new("10.0.0.9", 6379)));
+        assert_eq!(replica.repl_offset, 77);
+    }
+
+    #[test]
+    fn test_hello_round_trip() {
+        let hello = Hello {
+            addr: Addr::new("127.0.0.1", 26379),
+            run_id: "a".repeat(40),
+            current_epoch: 3,
+            master_name: "mymaster".to_string(),
+            master: Addr::new("127.0.0.1", 6380),
+            config_epoch: 2,
+        };
+        assert_eq!(Hello::parse(&hello.to_string()), Some(hello));
+        assert_eq!(Hello::parse("1,2,3"), None);
+    }
+
+    #[test]
+    fn test_odown_and_votes() {
+        let now = Instant::now();
+        let mut master = MasterState::new(&config(), now);
+        let later = now + Duration::from_secs(6);
+        master.peers.insert("b".to_string(), peer(now));
+        master.peers.insert("c".to_string(), peer(now));
+
+        assert!(master.sdown(later));
+        assert!(!master.odown(later));
+        master.peers.get_mut("b").unwrap().master_down = true;
+        assert!(master.odown(later));
+
+        // Üç sentinel, quorum 2: çoğunluk da 2
+        assert_eq!(master.needed_votes(), 2);
+        master.vote = Some(("a".to_string(), 1));
+        master.peers.get_mut("b").unwrap().leader = Some(("a".to_string(), 1));
+        master.peers.get_mut("c").unwrap().leader = Some(("c".to_string(), 1));
+        assert_eq!(master.votes_for("a", 1), 2);
+        assert_eq!(master.votes_for("a", 2), 0);
+    }
+
+    #[test]
+    fn test_one_vote_per_epoch() {
+        let mut state = State {
+            current_epoch: 0,
+            master: MasterState::new(&config(), Instant::now()),
+        };
+        assert_eq!(state.vote("a", 1), ("a".to_string(), 1));
+        assert_eq!(state.vote("b", 1), ("a".to_string(), 1));
+        assert_eq!(state.vote("b", 2), ("b".to_string(), 2));
+        assert_eq!(state.current_epoch, 2);
+        // Eski döneme ait istek oyu değiştirmez
+        assert_eq!(state.vote("c", 1), ("b".to_string(), 2));
+    }
+
+    #[test]
+    fn test_select_replica_and_switch() {
+        let now = Instant::now();
+        let mut master = MasterState::new(&config(), now);
+        for (port, offset, seen) in [(6380, 100, true), (6381, 120, true), (6382, 500, false)] {
+            master.replicas.insert(
+                Addr::new("127.0.0.1", port),
+                ReplicaState {
+                    last_ok: seen.then_some(now),
+                    info: InstanceInfo {
+                        repl_offset: offset,
+                        ..Default::default()
+                    },
+                    misconfigured_since: None,
+                },
+            );
+        }
+        let best = master.select_replica(now).unwrap();
+        assert_eq!(best, Addr::new("127.0.0.1", 6381));
+
+        master.switch_master(best.clone(), 1, now);
+        assert_eq!(master.addr, best);
+        assert_eq!(master.config_epoch, 1);
+        assert!(master.replicas.contains_key(&Addr::new("127.0.0.1", 6379)));
+        assert!(!master.replicas.contains_key(&best));
+    }
+
+    #[test]
+    fn test_hello_with_newer_config_switches_master() {
+        let now = Instant::now();
+        let mut state = State {
+            current_epoch: 0,
+            master: MasterState::new(&config(), now),
+        };
+        let hello = |config_epoch, port| Hello {
+            addr: Addr::new("127.0.0.1", 26380),
+            run_id: "b".repeat(40),
+            current_epoch: 4,
+            master_name: "mymaster".to_string(),
+            master: Addr::new("127.0.0.1", port),
+            config_epoch,
+        };
+        assert!(!state.process_hello(hello(0, 6379), "a", now));
+        assert_eq!(state.master.peers.len(), 1);
+        assert_eq!(state.current_epoch, 4);
+        assert!(state.process_hello(hello(3, 6380), "a", now));
+        assert_eq!(state.master.addr, Addr::new("127.0.0.1", 6380));
+        // Kendi mesajımız yok sayılır
+        assert!(!state.process_hello(hello(9, 6381), &"b".repeat(40), now));
+    }
+}
@@ -0,0 +1,433 @@
+//! İzleme döngüsü: sunucuları yoklar, diğer sentinel'lerle haberleşir ve gerekirse
+//! failover'ı yürütür.
+
+use crate::network::client::{self, bulk_text, expect_ok, pushed_message, Connection};
+use crate::network::resp::RespValue;
+use crate::sentinel::{Addr, Failover, Hello, InstanceInfo, Sentinel, HELLO_CHANNEL};
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::{JoinHandle, JoinSet};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// İzleme döngüsünün periyodu
+const TICK: Duration = Duration::from_millis(1000);
+/// Bağlantı kurma ve tek bir cevabı bekleme süresi sınırı
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(1000);
+/// Hello mesajları bu kadar turda bir yayınlanır
+const HELLO_EVERY: u64 = 2;
+/// Yanlış yapılandırılmış bir sunucu düzeltilmeden önce beklenen süre; başka bir
+/// sentinel'in yaptığı failover'ın duyurusunun bize ulaşmasına zaman tanır.
+const RECONFIGURE_GRACE: Duration = Duration::from_secs(8);
+/// Terfi edilen replikanın primary olduğunu bildirmesi beklenirken yoklama aralığı
+const PROMOTION_POLL: Duration = Duration::from_millis(200);
+/// Failover başlatılmadan önce beklenen rastgele sürenin üst sınırı
+const MAX_DESYNC: Duration = Duration::from_millis(2000);
+
+fn jitter() -> Duration {
+    let millis = Uuid::new_v4().as_u128() % MAX_DESYNC.as_millis();
+    Duration::from_millis(millis as u64)
+}
+
+/// Sentinel'in ana döngüsü.
+pub async fn run(sentinel: Arc<Sentinel>) {
+    let mut subscriptions: HashMap<Addr, JoinHandle<()>> = HashMap::new();
+    let mut interval = tokio::time::interval(TICK);
+    let mut tick: u64 = 0;
+    loop {
+        interval.tick().await;
+        tick += 1;
+
+        let instances = {
+            let state = sentinel.state.lock();
+            std::iter::once(state.master.addr.clone())
+                .chain(state.master.replicas.keys().cloned())
+                .collect::<Vec<_>>()
+        };
+
+        // Her sunucunun hello kanalını dinle
+        subscriptions.retain(|addr, task| instances.contains(addr) && !task.is_finished());
+        for addr in &instances {
+            if !subscriptions.contains_key(addr) {
+                let task = tokio::spawn(listen_hello(Arc::clone(&sentinel), addr.clone()));
+                subscriptions.insert(addr.clone(), task);
+            }
+        }
+
+        poll_instances(&sentinel, instances, tick.is_multiple_of(HELLO_EVERY)).await;
+        ask_peers(&sentinel).await;
+        if start_failover(&sentinel) {
+            // Oyları bir sonraki turu beklemeden iste
+            ask_peers(&sentinel).await;
+        }
+        if let Some((epoch, promote)) = failover_step(&sentinel) {
+            failover(&sentinel, epoch, promote).await;
+        }
+        fix_config(&sentinel).await;
+    }
+}
+
+/// İzlenen bir sunucuya (primary ya da replika) bağlanır; `--auth-pass` verildiyse giriş yapar.
+async fn connect(sentinel: &Sentinel, addr: &Addr) -> io::Result<Connection> {
+    Connection::connect_auth(
+        &addr.host,
+        addr.port,
+        REQUEST_TIMEOUT,
+        sentinel.auth.as_ref(),
+    )
+    .await
+}
+
+/// İzlenen bir sunucuda tek seferlik bağlantıyla bir komut çalıştırır.
+async fn instance_request(
+    sentinel: &Sentinel,
+    addr: &Addr,
+    args: &[&str],
+) -> io::Result<RespValue> {
+    connect(sentinel, addr).await?.request(args).await
+}
+
+/// Diğer bir sentinel'e tek seferlik bağlantıyla bir komut gönderir.
+async fn request(addr: &Addr, args: &[&str]) -> io::Result<RespValue> {
+    client::request(&addr.host, addr.port, REQUEST_TIMEOUT, args).await
+}
+
+/// Bir sunucuya INFO gönderir; istenirse aynı bağlantıdan hello mesajını yayınlar.
+async fn check_instance(
+    sentinel: &Sentinel,
+    addr: &Addr,
+    publish_hello: bool,
+) -> io::Result<InstanceInfo> {
+    let mut connection = connect(sentinel, addr).await?;
+    let reply = expect_ok(connection.request(&["INFO"]).await?)?;
+    let info = bulk_text(&reply)
+        .map(InstanceInfo::parse)
+        .ok_or_else(|| io::Error::other("unexpected INFO reply"))?;
+    if publish_hello {
+        let hello = sentinel.hello(&connection.local_ip()?);
+        expect_ok(
+            connection
+                .request(&["PUBLISH", HELLO_CHANNEL, &hello])
+                .await?,
+        )?;
+    }
+    Ok(info)
+}
+
+/// Primary'yi ve replikaları paralel yoklar, sonuçları duruma işler.
+async fn poll_instances(sentinel: &Arc<Sentinel>, instances: Vec<Addr>, publish_hello: bool) {
+    let mut checks = JoinSet::new();
+    for addr in instances {
+        let sentinel = Arc::clone(sentinel);
+        checks.spawn(async move {
+            let result = check_instance(&sentinel, &addr, publish_hello).await;
+            (addr, result)
+        });
+    }
+
+    while let Some(joined) = checks.join_next().await {
+        let Ok((addr, result)) = joined else { continue };
+        let now = Instant::now();
+        let mut state = sentinel.state.lock();
+        let master = &mut state.master;
+        let Ok(info) = result else { continue };
+
+        if addr == master.addr {
+            // Kendini replika olarak bildiren primary sağlıklı sayılmaz
+            if info.role_master {
+                master.last_ok = now;
+            }
+            for replica in &info.replicas {
+                if *replica != master.addr && !master.replicas.contains_key(replica) {
+                    info!("+slave {} @ {} {}", replica, master.name, master.addr);
+                    master.replicas.entry(replica.clone()).or_default();
+                }
+            }
+        } else if let Some(replica) = master.replicas.get_mut(&addr) {
+            let misconfigured = info.role_master || info.master.as_ref() != Some(&master.addr);
+            replica.misconfigured_since = match replica.misconfigured_since {
+                since @ Some(_) if misconfigured => since,
+                _ if misconfigured => Some(now),
+                _ => None,
+            };
+            replica.last_ok = Some(now);
+            replica.info = info;
+        }
+    }
+}
+
+/// Hello kanalına abone olur ve gelen duyuruları işler; bağlantı koparsa döner.
+async fn listen_hello(sentinel: Arc<Sentinel>, addr: Addr) {
+    let Ok(mut connection) = connect(&sentinel, &addr).await else {
+        return;
+    };
+    if connection
+        .send(&["SUBSCRIBE", HELLO_CHANNEL])
+        .await
+        .is_err()
+    {
+        return;
+    }
+    while let Ok(message) = connection.read().await {
+        let Some(hello) = pushed_message(&message).and_then(Hello::parse) else {
+            continue;
+        };
+        let mut state = sentinel.state.lock();
+        let new_master = hello.master.clone();
+        let config_epoch = hello.config_epoch;
+        if state.process_hello(hello, &sentinel.run_id, Instant::now()) {
+            info!(
+                "+switch-master {} -> {} (config epoch {})",
+                state.master.name, new_master, config_epoch
+            );
+        }
+    }
+}
+
+/// Primary öznel olarak çökmüşse diğer sentinel'lere sorar. Failover sürüyorsa
+/// aynı istekle oy da istenir.
+async fn ask_peers(sentinel: &Arc<Sentinel>) {
+    let (command, peers) = {
+        let mut state = sentinel.state.lock();
+        if !state.master.sdown(Instant::now()) {
+            for peer in state.master.peers.values_mut() {
+                peer.master_down = false;
+            }
+            return;
+        }
+        let master = &state.master;
+        let (epoch, run_id) = match &master.failover {
+            Some(failover) if !failover.forced => (failover.epoch, sentinel.run_id.clone()),
+            _ => (state.current_epoch, "*".to_string()),
+        };
+        let command = vec![
+            "SENTINEL".to_string(),
+            "is-master-down-by-addr".to_string(),
+            master.addr.host.clone(),
+            master.addr.port.to_string(),
+            epoch.to_string(),
+            run_id,
+        ];
+        let peers: Vec<(String, Addr)> = master
+            .peers
+            .iter()
+            .map(|(run_id, peer)| (run_id.clone(), peer.addr.clone()))
+            .collect();
+        (command, peers)
+    };
+
+    let mut replies = JoinSet::new();
+    for (run_id, addr) in peers {
+        let command = command.clone();
+        replies.spawn(async move {
+            let args: Vec<&str> = command.iter().map(String::as_str).collect();
+            (run_id, request(&addr, &args).await)
+        });
+    }
+    while let Some(joined) = replies.join_next().await {
+        let Ok((run_id, Ok(RespValue::Array(Some(reply))))) = joined else {
+            continue;
+        };
+        let [RespValue::Integer(down), leader, RespValue::Integer(leader_epoch)] = &reply[..]
+        else {
+            continue;
+        };
+        let mut state = sentinel.state.lock();
+        let leader = bulk_text(leader)
+            .filter(|leader| *leader != "*")
+            .map(|leader| (leader.to_string(), *leader_epoch as u64));
+        if let Some((_, epoch)) = &leader {
+            state.current_epoch = state.current_epoch.max(*epoch);
+        }
+        if let Some(peer) = state.master.peers.get_mut(&run_id) {
+            peer.master_down = *down == 1;
+            if leader.is_some() {
+                peer.leader = leader;
+            }
+        }
+    }
+}
+
+/// Primary nesnel olarak çökmüşse (ya da SENTINEL FAILOVER istendiyse) yeni bir
+/// dönemde failover başlatır ve kendimize oy veririz. Başlatıldıysa `true` döner.
+fn start_failover(sentinel: &Sentinel) -> bool {
+    let now = Instant::now();
+    let mut state = sentinel.state.lock();
+    let master = &mut state.master;
+    let odown = master.odown(now);
+    if !odown && master.failover_not_before.is_some_and(|at| at <= now) {
+        master.failover_not_before = None;
+    }
+    if master.failover.is_some() {
+        return false;
+    }
+
+    let requested = master.failover_requested;
+    if !requested {
+        if !odown {
+            return false;
+        }
+        match master.failover_not_before {
+            // Çöküşü aynı anda fark eden sentinel'ler aynı anda aday olup oyları
+            // bölmesin diye rastgele bir süre beklenir
+            None => {
+                master.failover_not_before = Some(now + jitter());
+                return false;
+            }
+            Some(at) if now < at => return false,
+            Some(_) => {}
+        }
+    }
+
+    state.current_epoch += 1;
+    let epoch = state.current_epoch;
+    state.vote(&sentinel.run_id, epoch);
+    let master = &mut state.master;
+    master.failover_requested = false;
+    master.failover_not_before = Some(now + 2 * master.failover_timeout + jitter());
+    master.failover = Some(Failover {
+        epoch,
+        started: now,
+        forced: requested,
+    });
+    info!(
+        "+try-failover {} {} (epoch {})",
+        master.name, master.addr, epoch
+    );
+    true
+}
+
+/// Sürmekte olan failover'ın bir adımı. Lider seçildiysek terfi edilecek replikayı
+/// ve failover dönemini döndürür.
+fn failover_step(sentinel: &Sentinel) -> Option<(u64, Addr)> {
+    let now = Instant::now();
+    let mut state = sentinel.state.lock();
+    let master = &mut state.master;
+    let failover = master.failover.clone()?;
+    if now.duration_since(failover.started) > master.failover_timeout {
+        warn!("-failover-abort-timeout {} {}", master.name, master.addr);
+        master.failover = None;
+        return None;
+    }
+    if !failover.forced {
+        if !master.odown(now) {
+            info!("-failover-abort-not-odown {} {}", master.name, master.addr);
+            master.failover = None;
+            return None;
+        }
+        let votes = master.votes_for(&sentinel.run_id, failover.epoch);
+        if votes < master.needed_votes() {
+            return None;
+        }
+        info!(
+            "+elected-leader {} {} ({} votes, epoch {})",
+            master.name, master.addr, votes, failover.epoch
+        );
+    }
+    match master.select_replica(now) {
+        Some(replica) => Some((failover.epoch, replica)),
+        None => {
+            warn!(
+                "-failover-abort-no-good-slave {} {}",
+                master.name, master.addr
+            );
+            master.failover = None;
+            None
+        }
+    }
+}
+
+/// Seçilen replikayı primary yapar, yapılandırmayı günceller ve diğer replikaları
+/// yeni primary'ye bağlar.
+async fn failover(sentinel: &Arc<Sentinel>, epoch: u64, promote: Addr) {
+    info!("+selected-slave {}", promote);
+    let timeout = sentinel.state.lock().master.failover_timeout;
+    let promoted = tokio::time::timeout(timeout, async {
+        loop {
+            if instance_request(sentinel, &promote, &["REPLICAOF", "NO", "ONE"])
+                .await
+                .and_then(expect_ok)
+                .is_ok()
+            {
+                let role = check_instance(sentinel, &promote, false).await;
+                if role.is_ok_and(|info| info.role_master) {
+                    return;
+                }
+            }
+            tokio::time::sleep(PROMOTION_POLL).await;
+        }
+    })
+    .await;
+    if promoted.is_err() {
+        let mut state = sentinel.state.lock();
+        warn!("-failover-abort-slave-timeout {}", promote);
+        state.master.failover = None;
+        return;
+    }
+    info!("+promoted-slave {}", promote);
+
+    let replicas: Vec<Addr> = {
+        let mut state = sentinel.state.lock();
+        state
+            .master
+            .switch_master(promote.clone(), epoch, Instant::now());
+        info!(
+            "+switch-master {} -> {} (config epoch {})",
+            state.master.name, promote, epoch
+        );
+        state.master.replicas.keys().cloned().collect()
+    };
+
+    // Eski primary de listede; çökmüş olduğu için şimdilik başarısız olur ve
+    // geri döndüğünde fix_config tarafından replikaya çevrilir.
+    let port = promote.port.to_string();
+    for replica in replicas {
+        match instance_request(sentinel, &replica, &["REPLICAOF", &promote.host, &port])
+            .await
+            .and_then(expect_ok)
+        {
+            Ok(_) => info!("+slave-reconf-sent {} -> {}", replica, promote),
+            Err(e) => warn!("Failed to reconfigure {}: {}", replica, e),
+        }
+    }
+}
+
+/// Uzun süredir yanlış primary'yi gösteren replikaları (ya da geri dönen eski
+/// primary'yi) güncel primary'ye bağlar.
+async fn fix_config(sentinel: &Arc<Sentinel>) {
+    let now = Instant::now();
+    let (master, targets) = {
+        let mut state = sentinel.state.lock();
+        let master = &mut state.master;
+        if master.failover.is_some() || master.sdown(now) {
+            return;
+        }
+        let targets: Vec<Addr> = master
+            .replicas
+            .iter_mut()
+            .filter(|(_, replica)| {
+                replica
+                    .misconfigured_since
+                    .is_some_and(|since| now.duration_since(since) > RECONFIGURE_GRACE)
+            })
+            .map(|(addr, replica)| {
+                replica.misconfigured_since = None;
+                addr.clone()
+            })
+            .collect();
+        (master.addr.clone(), targets)
+    };
+
+    let port = master.port.to_string();
+    for replica in targets {
+        match instance_request(sentinel, &replica, &["REPLICAOF", &master.host, &port])
+            .await
+            .and_then(expect_ok)
+        {
+            Ok(_) => info!("+fix-slave-config {} -> {}", replica, master),
+            Err(e) => warn!("Failed to reconfigure {}: {}", replica, e),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Sentinel'in istemcilere ve diğer sentinel'lere açtığı RESP sunucusu.
+
+use crate::network::parser::parse_value;
+use crate::replication::command_args;
+use crate::sentinel::Sentinel;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, error};
+
+/// Bağlantıları kabul eder; dinleme sonlanmaz.
+pub async fn serve(listener: TcpListener, sentinel: Arc<Sentinel>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, addr)) => {
+                let sentinel = Arc::clone(&sentinel);
+                tokio::spawn(async move {
+                    debug!("Sentinel client connected: {}", addr);
+                    handle_client(stream, &sentinel).await;
+                });
+            }
+            Err(e) => error!("Connection error: {}", e),
+        }
+    }
+}
+
+async fn handle_client(mut stream: TcpStream, sentinel: &Sentinel) {
+    let mut buffer = Vec::new();
+    let mut temp_buf = [0u8; 1024];
+    loop {
+        match stream.read(&mut temp_buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => buffer.extend_from_slice(&temp_buf[..n]),
+        }
+
+        let mut responses = String::new();
+        loop {
+            match parse_value(&buffer) {
+                Ok(Some((request, len))) => {
+                    buffer.drain(..len);
+                    let args: Vec<String> = command_args(&request)
+                        .into_iter()
+                        .map(str::to_string)
+                        .collect();
+                    responses.push_str(&sentinel.execute(&args).serialize());
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    error!("Failed to parse request: {}", e);
+                    return;
+                }
+            }
+        }
+        if !responses.is_empty() && stream.write_all(responses.as_bytes()).await.is_err() {
+            return;
+        }
+    }
+}